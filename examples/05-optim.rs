@@ -26,6 +26,7 @@ fn main() {
         lr: 1e-1,
         momentum: Some(Momentum::Nesterov(0.9)),
         weight_decay: None,
+        weight_decay_1d: true,
     });
 
     // let's initialize our model and some dummy data
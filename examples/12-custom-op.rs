@@ -0,0 +1,73 @@
+//! Demonstrates how to implement custom differentiable operations with [dfdx::tensor_ops::utils]
+
+use dfdx::arrays::HasArrayData;
+use dfdx::devices::{Device, ForEachElement};
+use dfdx::gradients::{OwnedTape, Tape};
+use dfdx::tensor::{tensor, Tensor, Tensor1D, TensorCreator};
+use dfdx::tensor_ops::utils::{binary_map, map, move_tape_and_add_backward_op};
+use dfdx::tensor_ops::{backward, sum};
+
+/// An element wise function only needs its derivative.
+fn softplus<T: Tensor<Dtype = f32>>(t: T) -> T {
+    map(t, |x| (1.0 + x.exp()).ln(), |x| 1.0 / (1.0 + (-x).exp()))
+}
+
+/// So does an element wise function of two tensors, with respect to each input.
+fn huber<T: Tensor<Dtype = f32>>(pred: T, target: T::NoTape) -> T {
+    binary_map(
+        pred,
+        target,
+        |p, t| {
+            let d = p - t;
+            if d.abs() < 1.0 {
+                0.5 * d * d
+            } else {
+                d.abs() - 0.5
+            }
+        },
+        |p, t| (p - t).clamp(-1.0, 1.0),
+        |p, t| (t - p).clamp(-1.0, 1.0),
+    )
+}
+
+/// Anything else computes its output, and then writes the backward op, which adds
+/// to the gradient of the input using the gradient of the output.
+fn cumsum<const N: usize, H: Tape>(t: Tensor1D<N, H>) -> Tensor1D<N, H> {
+    let mut result: Tensor1D<N> = TensorCreator::zeros();
+    let mut total = 0.0;
+    for (r, x) in result.mut_data().iter_mut().zip(t.data().iter()) {
+        total += x;
+        *r = total;
+    }
+    // the output type can't always be inferred before the closure, so it is given explicitly
+    move_tape_and_add_backward_op::<_, Tensor1D<N, H>, _>(t, result, |t, result, grads| {
+        let (t_grad, result_grad) = grads.mut_and_ref(&t, &result);
+        // each x[i] contributes to result[i..], so its gradient is a reversed cumsum
+        let mut total = 0.0;
+        for (g, r) in t_grad.iter_mut().zip(result_grad.iter()).rev() {
+            total += r;
+            *g += total;
+        }
+    })
+}
+
+/// Generic over the array type, using [Device] to iterate over the elements.
+fn scale<T: Tensor<Dtype = f32>>(t: T, factor: f32) -> T {
+    let result = T::NoTape::new_boxed(T::Device::map(t.data(), |x| x * factor));
+    move_tape_and_add_backward_op(t, result, move |t, result, grads| {
+        let (t_grad, result_grad) = grads.mut_and_ref(&t, &result);
+        T::Device::foreach_mr(t_grad, result_grad, &mut |g, r| *g += factor * r);
+    })
+}
+
+fn main() {
+    let x: Tensor1D<4> = tensor([-2.0, -0.5, 0.5, 2.0]);
+    let target: Tensor1D<4> = tensor([0.0, 0.0, 0.0, 0.0]);
+
+    let y: Tensor1D<4, OwnedTape> = cumsum(scale(softplus(x.trace()), 2.0));
+    println!("y={:?}", y.data());
+
+    let loss = sum(huber(y, target));
+    let gradients = backward(loss);
+    println!("dloss/dx={:?}", gradients.ref_gradient(&x));
+}
@@ -33,6 +33,7 @@ fn main() {
         lr: 1e-1,
         momentum: Some(Momentum::Nesterov(0.9)),
         weight_decay: None,
+        weight_decay_1d: true,
     });
 
     // run through training data
@@ -0,0 +1,138 @@
+//! Opt-in detection of non-finite values (`NaN`, `inf` and `-inf`) in the forward & backward pass,
+//! to find which operation produced them.
+//!
+//! Between [enable_anomaly_detection()] and [disable_anomaly_detection()], every operation that puts an
+//! [crate::gradients::OwnedTape] on its output:
+//! 1. Panics right away if its output contains a non-finite value.
+//! 2. Records a check on the tape, which panics during backward if the gradient of its output
+//!    contains a non-finite value. This means the backward of an operation that used the output
+//!    produced it.
+//!
+//! The panic message contains where in dfdx the operation was called from (the same locations as
+//! [crate::profiler]), and the shape of its output:
+//!
+//! ```rust
+//! # use dfdx::prelude::*;
+//! # use dfdx::anomaly::*;
+//! let x = tensor([1.0, 0.0]);
+//! enable_anomaly_detection();
+//! let y = x.trace().sqrt().sum(); // sqrt of 0.0 has an infinite derivative
+//! let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| y.backward()));
+//! disable_anomaly_detection();
+//! assert!(result.is_err());
+//! ```
+//!
+//! Anomaly detection is enabled per thread, but the checks recorded on a tape run on whichever thread
+//! runs backward. When it is not enabled, this only costs a thread local load per operation.
+//!
+//! Requires the "std" feature.
+
+use crate::arrays::{CountElements, HasShape};
+use crate::gradients::{NoneTape, Tape};
+use crate::tensor::Tensor;
+use std::cell::Cell;
+use std::panic::Location;
+
+std::thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Starts checking operations on this thread for non-finite values.
+pub fn enable_anomaly_detection() {
+    ENABLED.with(|enabled| enabled.set(true));
+}
+
+/// Stops checking operations on this thread for non-finite values.
+/// Checks already recorded on a tape still run during backward.
+pub fn disable_anomaly_detection() {
+    ENABLED.with(|enabled| enabled.set(false));
+}
+
+/// Whether [enable_anomaly_detection()] was called on this thread.
+pub fn is_detecting_anomalies() -> bool {
+    ENABLED.with(|enabled| enabled.get())
+}
+
+/// Checks the data of `t`, which was just output by the operation that called this,
+/// and records a check of its gradient on `tape`.
+#[track_caller]
+pub(crate) fn check<T, H: Tape>(t: T, tape: &mut H)
+where
+    T: 'static + Send + Tensor<Dtype = f32, Tape = NoneTape>,
+{
+    let location = Location::caller();
+    if !all_finite(t.data()) {
+        panic!(
+            "Anomaly detected: the operation at {location} output a non-finite value, with shape {:?}",
+            T::Array::shape()
+        );
+    }
+    tape.add_backward_op(move |grads| {
+        if let Some(g) = grads.try_ref_gradient(&t) {
+            if !all_finite(g) {
+                panic!(
+                    "Anomaly detected: the gradient of the output of the operation at {location} is non-finite, with shape {:?}. It was produced by the backward of an operation that used this output.",
+                    T::Array::shape()
+                );
+            }
+        }
+    });
+}
+
+fn all_finite<A: CountElements<Dtype = f32>>(a: &A) -> bool {
+    if A::NUM_ELEMENTS == 0 {
+        return true;
+    }
+    let data = unsafe { std::slice::from_raw_parts(a.ref_first_elem(), A::NUM_ELEMENTS) };
+    data.iter().all(|x| x.is_finite())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::string::String;
+
+    fn panic_message<F: FnOnce()>(f: F) -> String {
+        let err = catch_unwind(AssertUnwindSafe(f)).unwrap_err();
+        err.downcast::<String>().map(|s| *s).unwrap()
+    }
+
+    #[test]
+    fn test_anomaly_in_forward() {
+        let x = tensor([[1.0, 2.0], [-1.0, 3.0]]);
+        enable_anomaly_detection();
+        let msg = panic_message(|| {
+            let _ = x.trace().ln();
+        });
+        disable_anomaly_detection();
+        assert!(msg.contains("map.rs"), "{msg}");
+        assert!(msg.contains("[2, 2]"), "{msg}");
+    }
+
+    #[test]
+    fn test_anomaly_in_backward() {
+        let x = tensor([1.0, 0.0, 4.0]);
+        enable_anomaly_detection();
+        let y = x.trace().sqrt().sum();
+        disable_anomaly_detection();
+        let msg = panic_message(|| {
+            let _ = y.backward();
+        });
+        assert!(msg.contains("gradient"), "{msg}");
+        assert!(msg.contains("[3]"), "{msg}");
+    }
+
+    #[test]
+    fn test_no_anomaly() {
+        let x = tensor([1.0, 2.0, 3.0]);
+        enable_anomaly_detection();
+        let g = x.trace().sqrt().sum().backward();
+        disable_anomaly_detection();
+        assert!(all_finite(g.ref_gradient(&x)));
+
+        let y = x.trace().ln() * f32::NAN;
+        assert!(y.data()[0].is_nan());
+    }
+}
@@ -247,14 +247,49 @@ impl<T: ZeroElements, const M: usize> ZeroElements for [T; M] {
     const ZEROS: Self = [T::ZEROS; M];
 }
 
+/// Something that has a compile time known number of dimensions, each with a compile time known size.
+pub trait HasShape {
+    /// The number of dimensions, which is `0` for scalars.
+    const NUM_DIMS: usize;
+
+    /// The size of each dimension, starting with the outermost one. This is empty for scalars.
+    fn shape() -> std::vec::Vec<usize>;
+}
+
+impl HasShape for f32 {
+    const NUM_DIMS: usize = 0;
+    fn shape() -> std::vec::Vec<usize> {
+        std::vec::Vec::new()
+    }
+}
+
+impl HasShape for usize {
+    const NUM_DIMS: usize = 0;
+    fn shape() -> std::vec::Vec<usize> {
+        std::vec::Vec::new()
+    }
+}
+
+impl<T: HasShape, const M: usize> HasShape for [T; M] {
+    const NUM_DIMS: usize = T::NUM_DIMS + 1;
+    fn shape() -> std::vec::Vec<usize> {
+        let mut s = T::shape();
+        s.insert(0, M);
+        s
+    }
+}
+
 /// Has an associated type that implemented [CountElements] and [ZeroElements].
 pub trait HasArrayType {
     type Dtype;
     type Array: 'static
         + Sized
         + Clone
+        + Send
+        + Sync
         + CountElements<Dtype = Self::Dtype>
         + ZeroElements
+        + HasShape
         + HasAxes<Axis<0>>
         + HasAxes<AllAxes>
         + HasLastAxis;
@@ -270,6 +305,15 @@ pub trait HasArrayData: HasArrayType {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_shape() {
+        assert_eq!(f32::NUM_DIMS, 0);
+        assert!(f32::shape().is_empty());
+        assert_eq!(<[f32; 5]>::shape(), [5]);
+        assert_eq!(<[[[f32; 2]; 3]; 5]>::NUM_DIMS, 3);
+        assert_eq!(<[[[f32; 2]; 3]; 5]>::shape(), [5, 3, 2]);
+    }
+
     #[test]
     fn test_0d_count() {
         assert_eq!(1, f32::NUM_ELEMENTS);
@@ -0,0 +1,401 @@
+use crate::prelude::*;
+use rand::Rng;
+
+/// A random transformation of images, like [RandomCrop] or [ColorJitter], for data augmentation.
+///
+/// Implemented for [Tensor3D]`<C, H, W>` images, and [Tensor4D]`<B, C, H, W>` batches of images,
+/// which transform each image independently. Images are expected to have values in `[0, 1]`.
+///
+/// Tuples of transforms apply each transform in order, so transforms can be applied to the batches
+/// of a [super::DataLoader] like so:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::data::*;
+/// # use rand::prelude::*;
+/// let images: Vec<Tensor3D<3, 8, 8>> = vec![TensorCreator::ones(); 4];
+/// let loader = DataLoader::<_, 2>::new(images, Remainder::Drop);
+/// let augment = (
+///     RandomCrop::<6, 6>::default(),
+///     RandomHorizontalFlip::default(),
+///     Normalize { mean: [0.5; 3], std: [0.25; 3] },
+/// );
+/// let mut rng = StdRng::seed_from_u64(0);
+/// for batch in loader.iter().map(|x| augment.apply(x, &mut rng)) {
+///     let batch: Tensor4D<2, 3, 6, 6> = batch;
+/// }
+/// ```
+pub trait Transform<T> {
+    /// The type of the transformed images.
+    type Output;
+
+    /// Transforms `x`, using `rng` for any random choices.
+    fn apply<R: Rng + ?Sized>(&self, x: T, rng: &mut R) -> Self::Output;
+}
+
+macro_rules! tuple_impls {
+    ([$($name:ident: $input:ty),+] [$($idx:tt),+], $last:ident) => {
+        impl<T, $($name: Transform<$input>),+> Transform<T> for ($($name,)+) {
+            type Output = $last::Output;
+            fn apply<R: Rng + ?Sized>(&self, x: T, rng: &mut R) -> Self::Output {
+                $(let x = self.$idx.apply(x, rng);)+
+                x
+            }
+        }
+    };
+}
+
+tuple_impls!([A: T, B: A::Output] [0, 1], B);
+tuple_impls!([A: T, B: A::Output, C: B::Output] [0, 1, 2], C);
+tuple_impls!([A: T, B: A::Output, C: B::Output, D: C::Output] [0, 1, 2, 3], D);
+
+/// Implements [Transform] for images & batches of images with a method that transforms
+/// a single image in place.
+macro_rules! in_place_impls {
+    ($typename:ty, [$($consts:tt),*], $C:tt) => {
+        impl<$(const $consts: usize, )* const H: usize, const W: usize> Transform<Tensor3D<$C, H, W>> for $typename {
+            type Output = Tensor3D<$C, H, W>;
+            fn apply<R: Rng + ?Sized>(&self, mut x: Tensor3D<$C, H, W>, rng: &mut R) -> Self::Output {
+                self.apply_image(x.mut_data(), rng);
+                x
+            }
+        }
+
+        impl<const B: usize, $(const $consts: usize, )* const H: usize, const W: usize> Transform<Tensor4D<B, $C, H, W>> for $typename {
+            type Output = Tensor4D<B, $C, H, W>;
+            fn apply<R: Rng + ?Sized>(&self, mut x: Tensor4D<B, $C, H, W>, rng: &mut R) -> Self::Output {
+                for img in x.mut_data().iter_mut() {
+                    self.apply_image(img, rng);
+                }
+                x
+            }
+        }
+    };
+}
+
+/// Crops a random `H2 x W2` window out of each image, after padding its borders with `padding` zeros.
+///
+/// **Pytorch equivalent**: `torchvision.transforms.RandomCrop((H2, W2), padding)`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomCrop<const H2: usize, const W2: usize> {
+    pub padding: usize,
+}
+
+impl<const H2: usize, const W2: usize> RandomCrop<H2, W2> {
+    fn crop_image<R, const C: usize, const H: usize, const W: usize>(
+        &self,
+        out: &mut [[[f32; W2]; H2]; C],
+        x: &[[[f32; W]; H]; C],
+        rng: &mut R,
+    ) where
+        R: Rng + ?Sized,
+    {
+        let p = self.padding;
+        assert!(
+            H2 <= H + 2 * p && W2 <= W + 2 * p,
+            "crop is bigger than the image"
+        );
+        let oy = rng.gen_range(0..=H + 2 * p - H2);
+        let ox = rng.gen_range(0..=W + 2 * p - W2);
+        for (out_c, x_c) in out.iter_mut().zip(x.iter()) {
+            for (y, out_row) in out_c.iter_mut().enumerate() {
+                let row = (oy + y).checked_sub(p).and_then(|y| x_c.get(y));
+                for (x, o) in out_row.iter_mut().enumerate() {
+                    let v = row.and_then(|row| (ox + x).checked_sub(p).and_then(|x| row.get(x)));
+                    *o = v.copied().unwrap_or(0.0);
+                }
+            }
+        }
+    }
+}
+
+impl<const C: usize, const H: usize, const W: usize, const H2: usize, const W2: usize>
+    Transform<Tensor3D<C, H, W>> for RandomCrop<H2, W2>
+{
+    type Output = Tensor3D<C, H2, W2>;
+    fn apply<R: Rng + ?Sized>(&self, x: Tensor3D<C, H, W>, rng: &mut R) -> Self::Output {
+        let mut out: Self::Output = TensorCreator::zeros();
+        self.crop_image(out.mut_data(), x.data(), rng);
+        out
+    }
+}
+
+impl<
+        const B: usize,
+        const C: usize,
+        const H: usize,
+        const W: usize,
+        const H2: usize,
+        const W2: usize,
+    > Transform<Tensor4D<B, C, H, W>> for RandomCrop<H2, W2>
+{
+    type Output = Tensor4D<B, C, H2, W2>;
+    fn apply<R: Rng + ?Sized>(&self, x: Tensor4D<B, C, H, W>, rng: &mut R) -> Self::Output {
+        let mut out: Self::Output = TensorCreator::zeros();
+        for (o, img) in out.mut_data().iter_mut().zip(x.data().iter()) {
+            self.crop_image(o, img, rng);
+        }
+        out
+    }
+}
+
+/// Mirrors each image left to right with probability `p`.
+///
+/// **Pytorch equivalent**: `torchvision.transforms.RandomHorizontalFlip(p)`
+#[derive(Debug, Clone, Copy)]
+pub struct RandomHorizontalFlip {
+    pub p: f32,
+}
+
+impl Default for RandomHorizontalFlip {
+    /// Sets `p` to `0.5`.
+    fn default() -> Self {
+        Self { p: 0.5 }
+    }
+}
+
+impl RandomHorizontalFlip {
+    fn apply_image<R, const C: usize, const H: usize, const W: usize>(
+        &self,
+        img: &mut [[[f32; W]; H]; C],
+        rng: &mut R,
+    ) where
+        R: Rng + ?Sized,
+    {
+        if rng.gen::<f32>() < self.p {
+            for row in img.iter_mut().flat_map(|c| c.iter_mut()) {
+                row.reverse();
+            }
+        }
+    }
+}
+
+in_place_impls!(RandomHorizontalFlip, [C], C);
+
+/// Subtracts `mean[c]` from channel `c` of each image, and then divides it by `std[c]`.
+///
+/// **Pytorch equivalent**: `torchvision.transforms.Normalize(mean, std)`
+#[derive(Debug, Clone, Copy)]
+pub struct Normalize<const C: usize> {
+    pub mean: [f32; C],
+    pub std: [f32; C],
+}
+
+impl<const C: usize> Normalize<C> {
+    fn apply_image<R, const H: usize, const W: usize>(
+        &self,
+        img: &mut [[[f32; W]; H]; C],
+        _: &mut R,
+    ) where
+        R: Rng + ?Sized,
+    {
+        for (c, channel) in img.iter_mut().enumerate() {
+            for v in channel.iter_mut().flat_map(|row| row.iter_mut()) {
+                *v = (*v - self.mean[c]) / self.std[c];
+            }
+        }
+    }
+}
+
+in_place_impls!(Normalize<C>, [C], C);
+
+/// Randomly changes the brightness, contrast, and saturation of each image, in that order.
+/// Each is scaled by a factor chosen uniformly from `[1 - x, 1 + x]`, and values are clamped to `[0, 1]`.
+///
+/// - brightness scales the values.
+/// - contrast blends the image with the mean of its grayscale version.
+/// - saturation blends each pixel with its grayscale value. Only applies to RGB images.
+///
+/// **Pytorch equivalent**: `torchvision.transforms.ColorJitter(brightness, contrast, saturation)`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ColorJitter {
+    pub brightness: f32,
+    pub contrast: f32,
+    pub saturation: f32,
+}
+
+impl ColorJitter {
+    fn apply_image<R, const C: usize, const H: usize, const W: usize>(
+        &self,
+        img: &mut [[[f32; W]; H]; C],
+        rng: &mut R,
+    ) where
+        R: Rng + ?Sized,
+    {
+        let mut factor = |x: f32| {
+            if x > 0.0 {
+                rng.gen_range((1.0 - x).max(0.0)..=1.0 + x)
+            } else {
+                1.0
+            }
+        };
+        let brightness = factor(self.brightness);
+        let contrast = factor(self.contrast);
+        let saturation = factor(self.saturation);
+
+        let blend =
+            |v: &mut f32, other: f32, f: f32| *v = (f * *v + (1.0 - f) * other).clamp(0.0, 1.0);
+
+        for v in img
+            .iter_mut()
+            .flat_map(|c| c.iter_mut())
+            .flat_map(|r| r.iter_mut())
+        {
+            blend(v, 0.0, brightness);
+        }
+
+        if contrast != 1.0 {
+            let mut mean = 0.0;
+            for y in 0..H {
+                for x in 0..W {
+                    mean += gray(img, y, x);
+                }
+            }
+            mean /= (H * W) as f32;
+            for v in img
+                .iter_mut()
+                .flat_map(|c| c.iter_mut())
+                .flat_map(|r| r.iter_mut())
+            {
+                blend(v, mean, contrast);
+            }
+        }
+
+        if C == 3 && saturation != 1.0 {
+            for y in 0..H {
+                for x in 0..W {
+                    let g = gray(img, y, x);
+                    for channel in img.iter_mut() {
+                        blend(&mut channel[y][x], g, saturation);
+                    }
+                }
+            }
+        }
+    }
+}
+
+in_place_impls!(ColorJitter, [C], C);
+
+/// The grayscale value of a pixel: the ITU-R 601-2 luma of RGB images, or the mean of the channels.
+fn gray<const C: usize, const H: usize, const W: usize>(
+    img: &[[[f32; W]; H]; C],
+    y: usize,
+    x: usize,
+) -> f32 {
+    if C == 3 {
+        0.299 * img[0][y][x] + 0.587 * img[1][y][x] + 0.114 * img[2][y][x]
+    } else {
+        img.iter().map(|c| c[y][x]).sum::<f32>() / C as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+    use rand::{prelude::StdRng, SeedableRng};
+
+    #[test]
+    fn test_random_crop() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let x: Tensor3D<1, 3, 3> = tensor([[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]]);
+        let crop = RandomCrop::<2, 2>::default();
+        let mut seen = std::vec::Vec::new();
+        for _ in 0..50 {
+            let y = crop.apply(x.clone(), &mut rng);
+            let [[[a, b], [c, d]]] = *y.data();
+            assert_eq!((b - a, c - a, d - a), (1.0, 3.0, 4.0));
+            if !seen.contains(&a) {
+                seen.push(a);
+            }
+        }
+        seen.sort_by(|a, b| a.total_cmp(b));
+        assert_eq!(seen, [1.0, 2.0, 4.0, 5.0]);
+    }
+
+    #[test]
+    fn test_random_crop_padding() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let x: Tensor4D<8, 1, 1, 1> = TensorCreator::ones();
+        let crop = RandomCrop::<3, 3> { padding: 1 };
+        let y: Tensor4D<8, 1, 3, 3> = crop.apply(x, &mut rng);
+        for img in y.data().iter() {
+            assert_eq!(img, &[[[0.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 0.0]]]);
+        }
+    }
+
+    #[test]
+    fn test_horizontal_flip() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let x: Tensor4D<2, 1, 1, 3> = tensor([[[[1.0, 2.0, 3.0]]], [[[4.0, 5.0, 6.0]]]]);
+        let always = RandomHorizontalFlip { p: 1.0 };
+        let y = always.apply(x.clone(), &mut rng);
+        assert_eq!(y.data(), &[[[[3.0, 2.0, 1.0]]], [[[6.0, 5.0, 4.0]]]]);
+        let never = RandomHorizontalFlip { p: 0.0 };
+        let y = never.apply(x.clone(), &mut rng);
+        assert_eq!(y.data(), x.data());
+    }
+
+    #[test]
+    fn test_normalize() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let x: Tensor3D<2, 1, 2> = tensor([[[0.5, 1.0]], [[0.0, 0.25]]]);
+        let normalize = Normalize {
+            mean: [0.5, 0.25],
+            std: [0.5, 0.25],
+        };
+        let y = normalize.apply(x, &mut rng);
+        assert_eq!(y.data(), &[[[0.0, 1.0]], [[-1.0, 0.0]]]);
+    }
+
+    #[test]
+    fn test_color_jitter() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let x: Tensor3D<3, 1, 2> = tensor([[[0.2, 0.4]], [[0.3, 0.5]], [[0.1, 0.6]]]);
+        let y = ColorJitter::default().apply(x.clone(), &mut rng);
+        assert_eq!(y.data(), x.data());
+
+        let brightness = ColorJitter {
+            brightness: 0.5,
+            ..Default::default()
+        };
+        let y = brightness.apply(x.clone(), &mut rng);
+        let f = y.data()[0][0][0] / x.data()[0][0][0];
+        assert_ne!(f, 1.0);
+        assert_close(y.data(), mul_scalar(x.clone(), f).data());
+
+        // contrast keeps the mean grayscale value, and saturation keeps the grayscale value of each pixel
+        let grays = |img: &[[[f32; 2]; 1]; 3]| [gray(img, 0, 0), gray(img, 0, 1)];
+        let contrast = ColorJitter {
+            contrast: 0.5,
+            ..Default::default()
+        };
+        let y = contrast.apply(x.clone(), &mut rng);
+        assert_ne!(y.data(), x.data());
+        let [a, b] = grays(x.data());
+        let [c, d] = grays(y.data());
+        assert!((a + b - c - d).abs() < 1e-6);
+
+        let saturation = ColorJitter {
+            saturation: 0.5,
+            ..Default::default()
+        };
+        let y = saturation.apply(x.clone(), &mut rng);
+        assert_ne!(y.data(), x.data());
+        assert_close(&grays(y.data()), &grays(x.data()));
+    }
+
+    #[test]
+    fn test_compose() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let x: Tensor3D<1, 2, 2> = TensorCreator::ones();
+        let augment = (
+            RandomCrop::<1, 1>::default(),
+            Normalize {
+                mean: [0.5],
+                std: [0.5],
+            },
+        );
+        let y: Tensor3D<1, 1, 1> = augment.apply(x, &mut rng);
+        assert_eq!(y.data(), &[[[1.0]]]);
+    }
+}
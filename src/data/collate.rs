@@ -0,0 +1,91 @@
+use super::{pad_sequences, Stack};
+use crate::prelude::*;
+use std::vec::Vec;
+
+/// Combines the `B` items of a batch. [super::DataLoader] uses [StackItems] by default, and
+/// [super::DataLoader::with_collate()] changes it, e.g. to [PadSequences] for items of different
+/// lengths.
+///
+/// **Pytorch equivalent**: the `collate_fn` of `torch.utils.data.DataLoader`
+pub trait Collate<T, const B: usize> {
+    /// The type of a batch.
+    type Batch;
+
+    /// Combines `items` into a batch.
+    fn collate(&self, items: [T; B]) -> Self::Batch;
+}
+
+/// Stacks the items of a batch with [Stack]. The default [Collate] of [super::DataLoader].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StackItems;
+
+impl<T: Stack<B>, const B: usize> Collate<T, B> for StackItems {
+    type Batch = T::Stacked;
+    fn collate(&self, items: [T; B]) -> Self::Batch {
+        T::stack(items)
+    }
+}
+
+/// Pads sequences of token ids of different lengths to length `L` with [pad_sequences()],
+/// returning the padded ids & the mask that is `1.0` for tokens and `0.0` for padding.
+/// Since tensors have a fixed size, `L` is the maximum length of a sequence, and longer
+/// sequences are truncated.
+///
+/// Collates `Vec<usize>` items, and `(Vec<usize>, Y)` items where `Y` is stacked with [Stack],
+/// like the label of a sequence.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::data::*;
+/// let sequences = vec![vec![5, 6], vec![7, 8, 9], vec![4]];
+/// let loader = DataLoader::<_, 3>::new(sequences, Remainder::Drop).with_collate(PadSequences::<4>::new(0));
+/// let (ids, mask): ([[usize; 4]; 3], Tensor2D<3, 4>) = loader.iter().next().unwrap();
+/// assert_eq!(ids, [[5, 6, 0, 0], [7, 8, 9, 0], [4, 0, 0, 0]]);
+/// assert_eq!(mask.data(), &[[1.0, 1.0, 0.0, 0.0], [1.0, 1.0, 1.0, 0.0], [1.0, 0.0, 0.0, 0.0]]);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PadSequences<const L: usize> {
+    pub pad_id: usize,
+}
+
+impl<const L: usize> PadSequences<L> {
+    /// Pads with `pad_id`, like [super::CharTokenizer::PAD].
+    pub fn new(pad_id: usize) -> Self {
+        Self { pad_id }
+    }
+}
+
+impl<const B: usize, const L: usize> Collate<Vec<usize>, B> for PadSequences<L> {
+    type Batch = ([[usize; L]; B], Tensor2D<B, L>);
+    fn collate(&self, items: [Vec<usize>; B]) -> Self::Batch {
+        pad_sequences(core::array::from_fn(|i| items[i].as_slice()), self.pad_id)
+    }
+}
+
+impl<Y: Stack<B>, const B: usize, const L: usize> Collate<(Vec<usize>, Y), B> for PadSequences<L> {
+    type Batch = (([[usize; L]; B], Tensor2D<B, L>), Y::Stacked);
+    fn collate(&self, items: [(Vec<usize>, Y); B]) -> Self::Batch {
+        let mut items = items.map(|(seq, y)| (seq, Some(y)));
+        let sequences = core::array::from_fn(|i| items[i].0.as_slice());
+        let ids_and_mask = pad_sequences(sequences, self.pad_id);
+        let ys = core::array::from_fn(|i| items[i].1.take().unwrap());
+        (ids_and_mask, Y::stack(ys))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_pad_sequences_with_labels() {
+        let items = [(vec![1, 2, 3, 4], 0), (vec![], 1), (vec![5], 1)];
+        let ((ids, mask), labels): (([[usize; 3]; 3], Tensor2D<3, 3>), [usize; 3]) =
+            PadSequences::<3>::new(9).collate(items);
+        assert_eq!(ids, [[1, 2, 3], [9; 3], [5, 9, 9]]);
+        assert_eq!(mask.data(), &[[1.0; 3], [0.0; 3], [1.0, 0.0, 0.0]]);
+        assert_eq!(labels, [0, 1, 1]);
+    }
+}
@@ -0,0 +1,96 @@
+use std::vec::Vec;
+
+/// A collection of items that can be accessed by index, like the images & labels of MNIST.
+///
+/// Implemented for [Vec] & slices of [Clone] items, and for tuples of datasets, which
+/// zip their items together.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::data::Dataset;
+/// let images = vec![[0.0f32; 4], [1.0; 4], [2.0; 4]];
+/// let labels = vec![0usize, 1, 1];
+/// let dataset = (images, labels);
+/// assert_eq!(dataset.len(), 3);
+/// assert_eq!(dataset.get(1), ([1.0; 4], 1));
+/// ```
+pub trait Dataset {
+    /// The type of a single item.
+    type Item;
+
+    /// The number of items.
+    fn len(&self) -> usize;
+
+    /// Returns the item at `index`, which is less than [Dataset::len()].
+    fn get(&self, index: usize) -> Self::Item;
+
+    /// Whether there are no items.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<D: Dataset + ?Sized> Dataset for &D {
+    type Item = D::Item;
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+    fn get(&self, index: usize) -> Self::Item {
+        (**self).get(index)
+    }
+}
+
+impl<T: Clone> Dataset for [T] {
+    type Item = T;
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+    fn get(&self, index: usize) -> Self::Item {
+        self[index].clone()
+    }
+}
+
+impl<T: Clone> Dataset for Vec<T> {
+    type Item = T;
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+    fn get(&self, index: usize) -> Self::Item {
+        self[index].clone()
+    }
+}
+
+macro_rules! tuple_impls {
+    ([$($name:ident),+] [$($idx:tt),+]) => {
+        /// Zips the items of each dataset. The length is the length of the shortest dataset.
+        impl<$($name: Dataset),+> Dataset for ($($name,)+) {
+            type Item = ($($name::Item,)+);
+            fn len(&self) -> usize {
+                let len = usize::MAX;
+                $(let len = len.min(self.$idx.len());)+
+                len
+            }
+            fn get(&self, index: usize) -> Self::Item {
+                ($(self.$idx.get(index),)+)
+            }
+        }
+    };
+}
+
+tuple_impls!([A, B] [0, 1]);
+tuple_impls!([A, B, C] [0, 1, 2]);
+tuple_impls!([A, B, C, D] [0, 1, 2, 3]);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zipped_dataset() {
+        let dataset = (alloc::vec![1.0f32, 2.0, 3.0], [0usize, 1].as_slice());
+        assert_eq!(dataset.len(), 2);
+        assert!(!dataset.is_empty());
+        assert_eq!(dataset.get(0), (1.0, 0));
+        assert_eq!(dataset.get(1), (2.0, 1));
+    }
+}
@@ -0,0 +1,246 @@
+use super::{Collate, Dataset, Sampler, StackItems};
+use std::vec::Vec;
+
+/// What [DataLoader] does with the items left over when the length of the dataset
+/// isn't a multiple of the batch size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Remainder {
+    /// Skip the left over items.
+    #[default]
+    Drop,
+
+    /// Fill the last batch with items from the start of the epoch, so every item is used.
+    Pad,
+}
+
+/// Iterates a [Dataset] in batches of `B` items, combining the items of each batch with a
+/// [Collate]. By default that's [StackItems], which stacks them with [super::Stack].
+///
+/// **Pytorch equivalent**: `torch.utils.data.DataLoader`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::data::{DataLoader, Remainder};
+/// let images: Vec<Tensor1D<4>> = vec![Tensor1D::ones(); 10];
+/// let labels: Vec<usize> = vec![1; 10];
+/// let loader = DataLoader::<_, 3>::new((images, labels), Remainder::Drop);
+/// assert_eq!(loader.len(), 3);
+/// for (x, y) in loader.iter() {
+///     let x: Tensor2D<3, 4> = x;
+///     let y: [usize; 3] = y;
+/// }
+/// ```
+///
+/// Use [DataLoader::iter_with()] and a [Sampler], like [super::RandomSampler], to change the order
+/// of each epoch, and [DataLoader::with_collate()] to change how batches are combined, like
+/// padding sequences of different lengths with [super::PadSequences].
+#[derive(Debug, Clone)]
+pub struct DataLoader<D, const B: usize, C = StackItems> {
+    dataset: D,
+    remainder: Remainder,
+    collate: C,
+}
+
+impl<D: Dataset, const B: usize> DataLoader<D, B> {
+    /// Iterates `dataset` in batches of `B`, handling the left over items with `remainder`.
+    pub fn new(dataset: D, remainder: Remainder) -> Self {
+        Self {
+            dataset,
+            remainder,
+            collate: StackItems,
+        }
+    }
+}
+
+impl<D: Dataset, const B: usize, C> DataLoader<D, B, C> {
+    /// Combines the items of each batch with `collate` instead.
+    pub fn with_collate<C2: Collate<D::Item, B>>(self, collate: C2) -> DataLoader<D, B, C2> {
+        DataLoader {
+            dataset: self.dataset,
+            remainder: self.remainder,
+            collate,
+        }
+    }
+}
+
+impl<D: Dataset, const B: usize, C: Collate<D::Item, B>> DataLoader<D, B, C> {
+    /// The dataset being iterated.
+    pub fn dataset(&self) -> &D {
+        &self.dataset
+    }
+
+    /// The number of batches in an epoch.
+    pub fn len(&self) -> usize {
+        num_batches::<B>(self.dataset.len(), self.remainder)
+    }
+
+    /// Whether an epoch has no batches.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates one epoch of batches in order.
+    pub fn iter(&self) -> Batches<'_, D, B, C> {
+        self.iter_with(super::SequentialSampler)
+    }
+
+    /// Iterates one epoch of batches in the order given by `sampler`. The number of batches depends
+    /// on how many indices `sampler` returns, so [DataLoader::len()] only applies to samplers that
+    /// visit every item once.
+    pub fn iter_with<S: Sampler>(&self, mut sampler: S) -> Batches<'_, D, B, C> {
+        let indices = sampler.indices(self.dataset.len());
+        Batches::new(&self.dataset, &self.collate, indices, self.remainder)
+    }
+}
+
+impl<'a, D: Dataset, const B: usize, C: Collate<D::Item, B>> IntoIterator
+    for &'a DataLoader<D, B, C>
+{
+    type Item = C::Batch;
+    type IntoIter = Batches<'a, D, B, C>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+fn num_batches<const B: usize>(n: usize, remainder: Remainder) -> usize {
+    match remainder {
+        Remainder::Drop => n / B,
+        Remainder::Pad => n.div_ceil(B),
+    }
+}
+
+/// An iterator over one epoch of batches from a [DataLoader].
+#[derive(Debug, Clone)]
+pub struct Batches<'a, D, const B: usize, C = StackItems> {
+    dataset: &'a D,
+    collate: &'a C,
+    indices: Vec<usize>,
+    i: usize,
+    num_batches: usize,
+}
+
+impl<'a, D: Dataset, const B: usize, C> Batches<'a, D, B, C> {
+    pub(super) fn new(
+        dataset: &'a D,
+        collate: &'a C,
+        indices: Vec<usize>,
+        remainder: Remainder,
+    ) -> Self {
+        let num_batches = num_batches::<B>(indices.len(), remainder);
+        Self {
+            dataset,
+            collate,
+            indices,
+            i: 0,
+            num_batches,
+        }
+    }
+}
+
+impl<'a, D: Dataset, const B: usize, C: Collate<D::Item, B>> Iterator for Batches<'a, D, B, C> {
+    type Item = C::Batch;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.i >= self.num_batches {
+            return None;
+        }
+        let start = self.i * B;
+        self.i += 1;
+        let n = self.indices.len();
+        let items = core::array::from_fn(|j| self.dataset.get(self.indices[(start + j) % n]));
+        Some(self.collate.collate(items))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.num_batches - self.i;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, D: Dataset, const B: usize, C: Collate<D::Item, B>> ExactSizeIterator
+    for Batches<'a, D, B, C>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{PadSequences, RandomSampler};
+    use crate::prelude::*;
+
+    #[test]
+    fn test_drop_remainder() {
+        let dataset: Vec<f32> = (0..7).map(|i| i as f32).collect();
+        let loader = DataLoader::<_, 3>::new(dataset, Remainder::Drop);
+        assert_eq!(loader.len(), 2);
+        let batches: Vec<Tensor1D<3>> = loader.iter().collect();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].data(), &[0.0, 1.0, 2.0]);
+        assert_eq!(batches[1].data(), &[3.0, 4.0, 5.0]);
+    }
+
+    #[test]
+    fn test_pad_remainder() {
+        let dataset: Vec<usize> = (0..7).collect();
+        let loader = DataLoader::<_, 3>::new(dataset, Remainder::Pad);
+        assert_eq!(loader.len(), 3);
+        let batches: Vec<[usize; 3]> = loader.iter().collect();
+        assert_eq!(batches, [[0, 1, 2], [3, 4, 5], [6, 0, 1]]);
+    }
+
+    #[test]
+    fn test_loader_batches() {
+        let xs: Vec<Tensor1D<2>> = (0..4).map(|i| tensor([i as f32, -(i as f32)])).collect();
+        let ys: Vec<usize> = (0..4).collect();
+        let loader = DataLoader::<_, 2>::new((xs, ys), Default::default());
+        let mut batches = loader.iter();
+        assert_eq!(batches.len(), 2);
+        let (x, y) = batches.next().unwrap();
+        assert_eq!(x.data(), &[[0.0, 0.0], [1.0, -1.0]]);
+        assert_eq!(y, [0, 1]);
+        let (x, y) = batches.next().unwrap();
+        assert_eq!(x.data(), &[[2.0, -2.0], [3.0, -3.0]]);
+        assert_eq!(y, [2, 3]);
+        assert!(batches.next().is_none());
+    }
+
+    #[test]
+    fn test_empty_dataset() {
+        let loader = DataLoader::<_, 2>::new(Vec::<usize>::new(), Remainder::Pad);
+        assert!(loader.is_empty());
+        assert!(loader.iter().next().is_none());
+    }
+
+    #[test]
+    fn test_iter_with_sampler() {
+        let dataset: Vec<usize> = (0..10).collect();
+        let loader = DataLoader::<_, 4>::new(dataset, Remainder::Pad);
+        let mut sampler = RandomSampler::new(0);
+        let epoch_0: Vec<[usize; 4]> = loader.iter_with(&mut sampler).collect();
+        let epoch_1: Vec<[usize; 4]> = loader.iter_with(&mut sampler).collect();
+        assert_eq!(epoch_0.len(), 3);
+        assert_ne!(epoch_0, epoch_1);
+        let mut seen: Vec<usize> = epoch_0.iter().flatten().copied().collect();
+        seen.sort_unstable();
+        seen.dedup();
+        assert_eq!(seen, loader.dataset().clone());
+    }
+
+    #[test]
+    fn test_with_collate() {
+        let sequences = alloc::vec![
+            (alloc::vec![1, 2], 0),
+            (alloc::vec![3], 1),
+            (alloc::vec![4, 5, 6], 2)
+        ];
+        let loader = DataLoader::<_, 2>::new(sequences, Remainder::Drop)
+            .with_collate(PadSequences::<3>::new(0));
+        assert_eq!(loader.len(), 1);
+        let ((ids, mask), labels): (([[usize; 3]; 2], Tensor2D<2, 3>), [usize; 2]) =
+            loader.iter().next().unwrap();
+        assert_eq!(ids, [[1, 2, 0], [3, 0, 0]]);
+        assert_eq!(mask.data(), &[[1.0, 1.0, 0.0], [1.0, 0.0, 0.0]]);
+        assert_eq!(labels, [0, 1]);
+    }
+}
@@ -1,4 +1,31 @@
 //! A collection of data utility classes such as [one_hot_encode()] and [SubsetIterator].
+//!
+//! [Dataset] is a collection of items, and [DataLoader] iterates a dataset in batches,
+//! stacking the items of each batch into tensors with [Stack]. A [Sampler] decides the order
+//! of the items in each epoch. [random_split()] and [stratified_split()] split a dataset into
+//! [Subset]s, like training & validation sets.
+//!
+//! [Transform]s like [RandomCrop] augment images & batches of images. For text, a [Tokenizer]
+//! encodes text as token ids, and [pad_sequences()] batches them. [DataLoader::with_collate()]
+//! and [PadSequences] pad the sequences of each batch.
+
+mod augment;
+mod collate;
+mod dataset;
+mod loader;
+mod sampler;
+mod split;
+mod stack;
+mod text;
+
+pub use augment::*;
+pub use collate::*;
+pub use dataset::*;
+pub use loader::*;
+pub use sampler::*;
+pub use split::*;
+pub use stack::*;
+pub use text::*;
 
 use rand::prelude::SliceRandom;
 use std::vec::Vec;
@@ -0,0 +1,302 @@
+use crate::rng::default_rng;
+use rand::prelude::*;
+use std::vec::Vec;
+
+/// Decides the order [super::DataLoader] visits the items of a dataset in each epoch.
+/// Pass one to [super::DataLoader::iter_with()].
+///
+/// Example:
+/// ```rust
+/// # use dfdx::data::*;
+/// let loader = DataLoader::<_, 2>::new(vec![0usize, 1, 2, 3], Remainder::Drop);
+/// let mut sampler = RandomSampler::new(0);
+/// for epoch in 0..3 {
+///     for batch in loader.iter_with(&mut sampler) {
+///         let batch: [usize; 2] = batch;
+///     }
+/// }
+/// ```
+pub trait Sampler {
+    /// Returns the indices of the items of a dataset of length `len` to visit in the next epoch.
+    fn indices(&mut self, len: usize) -> Vec<usize>;
+}
+
+impl<S: Sampler + ?Sized> Sampler for &mut S {
+    fn indices(&mut self, len: usize) -> Vec<usize> {
+        (**self).indices(len)
+    }
+}
+
+/// Visits every item in order.
+///
+/// **Pytorch equivalent**: `torch.utils.data.SequentialSampler`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SequentialSampler;
+
+impl Sampler for SequentialSampler {
+    fn indices(&mut self, len: usize) -> Vec<usize> {
+        (0..len).collect()
+    }
+}
+
+/// Visits every item in a random order, which is different for each epoch.
+/// Two samplers made with the same seed visit items in the same orders.
+///
+/// **Pytorch equivalent**: `torch.utils.data.RandomSampler`
+#[derive(Debug, Clone)]
+pub struct RandomSampler {
+    rng: StdRng,
+}
+
+impl RandomSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Default for RandomSampler {
+    /// Uses [default_rng()], so the rng is different every time this is called.
+    fn default() -> Self {
+        Self { rng: default_rng() }
+    }
+}
+
+impl Sampler for RandomSampler {
+    fn indices(&mut self, len: usize) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..len).collect();
+        indices.shuffle(&mut self.rng);
+        indices
+    }
+}
+
+/// Visits every item in a random order, with the items of each class spread evenly through
+/// the epoch. So every batch has about the same proportion of each class as the whole dataset,
+/// even when some classes are rare.
+///
+/// `labels[i]` is the class of item `i`.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::data::*;
+/// let labels = vec![0, 0, 0, 0, 0, 0, 1, 1];
+/// let loader = DataLoader::<_, 4>::new(labels.clone(), Remainder::Drop);
+/// let mut sampler = StratifiedSampler::new(labels, 0);
+/// for batch in loader.iter_with(&mut sampler) {
+///     assert_eq!(batch.iter().filter(|&&c| c == 1).count(), 1);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct StratifiedSampler {
+    labels: Vec<usize>,
+    rng: StdRng,
+}
+
+impl StratifiedSampler {
+    pub fn new(labels: Vec<usize>, seed: u64) -> Self {
+        Self {
+            labels,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Sampler for StratifiedSampler {
+    fn indices(&mut self, len: usize) -> Vec<usize> {
+        assert_eq!(len, self.labels.len(), "expected one label per item");
+        let num_classes = self.labels.iter().max().map_or(0, |c| c + 1);
+        let mut classes: Vec<Vec<usize>> = (0..num_classes).map(|_| Vec::new()).collect();
+        for (i, &c) in self.labels.iter().enumerate() {
+            classes[c].push(i);
+        }
+
+        // the k-th of n items of a class goes at (k + u) / n through the epoch, with u in [0, 1)
+        let mut keyed: Vec<(f64, usize)> = Vec::with_capacity(len);
+        for class in classes.iter_mut() {
+            class.shuffle(&mut self.rng);
+            let n = class.len() as f64;
+            for (k, &i) in class.iter().enumerate() {
+                let u: f64 = self.rng.gen();
+                keyed.push(((k as f64 + u) / n, i));
+            }
+        }
+        keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+        keyed.into_iter().map(|(_, i)| i).collect()
+    }
+}
+
+/// Visits items at random, where item `i` is picked with probability proportional to
+/// `weights[i]`. Each epoch visits `num_samples` items, and with `replacement` an item can
+/// be visited more than once.
+///
+/// Oversamples rare classes without copying their items, see [WeightedRandomSampler::balanced()].
+///
+/// **Pytorch equivalent**: `torch.utils.data.WeightedRandomSampler`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::data::*;
+/// let mut sampler = WeightedRandomSampler::new(vec![1.0, 0.0, 3.0], 6, true, 0);
+/// let indices = sampler.indices(3);
+/// assert_eq!(indices.len(), 6);
+/// assert!(!indices.contains(&1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct WeightedRandomSampler {
+    weights: Vec<f32>,
+    num_samples: usize,
+    replacement: bool,
+    rng: StdRng,
+}
+
+impl WeightedRandomSampler {
+    /// Panics if a weight is negative or not finite, or if `num_samples` is more than the number
+    /// of non zero weights without `replacement`.
+    pub fn new(weights: Vec<f32>, num_samples: usize, replacement: bool, seed: u64) -> Self {
+        assert!(
+            weights.iter().all(|w| w.is_finite() && *w >= 0.0),
+            "weights must be finite and non negative"
+        );
+        let num_nonzero = weights.iter().filter(|&&w| w > 0.0).count();
+        if replacement {
+            assert!(
+                num_samples == 0 || num_nonzero > 0,
+                "expected a non zero weight"
+            );
+        } else {
+            assert!(
+                num_samples <= num_nonzero,
+                "can't take {num_samples} samples of {num_nonzero} non zero weights without replacement"
+            );
+        }
+        Self {
+            weights,
+            num_samples,
+            replacement,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Visits as many items per epoch as there are `labels`, with replacement, and weights each
+    /// item by one over the size of its class, so every class is visited equally often.
+    ///
+    /// `labels[i]` is the class of item `i`.
+    pub fn balanced(labels: &[usize], seed: u64) -> Self {
+        let num_classes = labels.iter().max().map_or(0, |c| c + 1);
+        let mut counts: Vec<usize> = alloc::vec![0; num_classes];
+        for &c in labels.iter() {
+            counts[c] += 1;
+        }
+        let weights = labels.iter().map(|&c| 1.0 / counts[c] as f32).collect();
+        Self::new(weights, labels.len(), true, seed)
+    }
+}
+
+impl Sampler for WeightedRandomSampler {
+    fn indices(&mut self, len: usize) -> Vec<usize> {
+        assert_eq!(len, self.weights.len(), "expected one weight per item");
+        if self.replacement {
+            let mut cumulative: Vec<f64> = Vec::with_capacity(len);
+            let mut total = 0.0;
+            for &w in self.weights.iter() {
+                total += w as f64;
+                cumulative.push(total);
+            }
+            (0..self.num_samples)
+                .map(|_| {
+                    let u = self.rng.gen::<f64>() * total;
+                    cumulative.partition_point(|&c| c <= u).min(len - 1)
+                })
+                .collect()
+        } else {
+            // picks the items with the largest ln(u) / w, with u uniform in (0, 1]
+            let mut keyed: Vec<(f64, usize)> = Vec::with_capacity(len);
+            for (i, &w) in self.weights.iter().enumerate() {
+                if w > 0.0 {
+                    let u = 1.0 - self.rng.gen::<f64>();
+                    keyed.push((num_traits::Float::ln(u) / w as f64, i));
+                }
+            }
+            keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+            keyed.truncate(self.num_samples);
+            keyed.into_iter().map(|(_, i)| i).collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequential_sampler() {
+        assert_eq!(SequentialSampler.indices(4), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_random_sampler_reproducible() {
+        let mut a = RandomSampler::new(0);
+        let mut b = RandomSampler::new(0);
+        let epoch_0 = a.indices(100);
+        let epoch_1 = a.indices(100);
+        assert_ne!(epoch_0, epoch_1);
+        assert_eq!(b.indices(100), epoch_0);
+        assert_eq!(b.indices(100), epoch_1);
+
+        let mut sorted = epoch_0.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, SequentialSampler.indices(100));
+    }
+
+    #[test]
+    fn test_stratified_sampler() {
+        let mut labels = alloc::vec![0; 90];
+        labels.extend([1; 10]);
+        let mut sampler = StratifiedSampler::new(labels.clone(), 0);
+        for _ in 0..5 {
+            let indices = sampler.indices(100);
+            let mut sorted = indices.clone();
+            sorted.sort_unstable();
+            assert_eq!(sorted, SequentialSampler.indices(100));
+            for chunk in indices.chunks(20) {
+                let ones = chunk.iter().filter(|&&i| labels[i] == 1).count();
+                assert!((1..=3).contains(&ones), "{ones}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_weighted_sampler_with_replacement() {
+        let mut sampler = WeightedRandomSampler::new(alloc::vec![1.0, 0.0, 3.0], 4000, true, 0);
+        let indices = sampler.indices(3);
+        let counts: Vec<usize> = (0..3)
+            .map(|i| indices.iter().filter(|&&j| j == i).count())
+            .collect();
+        assert_eq!(counts[1], 0);
+        assert!((900..1100).contains(&counts[0]), "{counts:?}");
+        assert_eq!(counts[0] + counts[2], 4000);
+    }
+
+    #[test]
+    fn test_weighted_sampler_without_replacement() {
+        let mut sampler = WeightedRandomSampler::new(alloc::vec![1.0, 0.0, 1e6, 1.0], 3, false, 0);
+        for _ in 0..10 {
+            let mut indices = sampler.indices(4);
+            assert_eq!(indices[0], 2);
+            indices.sort_unstable();
+            assert_eq!(indices, [0, 2, 3]);
+        }
+    }
+
+    #[test]
+    fn test_balanced_sampler() {
+        let mut labels = alloc::vec![0; 90];
+        labels.extend([1; 10]);
+        let mut sampler = WeightedRandomSampler::balanced(&labels, 0);
+        let indices = sampler.indices(100);
+        assert_eq!(indices.len(), 100);
+        let ones = indices.iter().filter(|&&i| labels[i] == 1).count();
+        assert!((35..65).contains(&ones), "{ones}");
+    }
+}
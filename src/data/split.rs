@@ -0,0 +1,163 @@
+use super::Dataset;
+use rand::prelude::*;
+use std::vec::Vec;
+
+/// A view of some of the items of a dataset, without copying them.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::data::*;
+/// let data = vec![10, 11, 12, 13];
+/// let subset = Subset::new(&data, vec![3, 1]);
+/// assert_eq!(subset.len(), 2);
+/// assert_eq!(subset.get(0), 13);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Subset<D> {
+    dataset: D,
+    indices: Vec<usize>,
+}
+
+impl<D: Dataset> Subset<D> {
+    /// The items of `dataset` at `indices`, in that order.
+    pub fn new(dataset: D, indices: Vec<usize>) -> Self {
+        let len = dataset.len();
+        assert!(indices.iter().all(|&i| i < len), "index out of bounds");
+        Self { dataset, indices }
+    }
+
+    /// The indices of the items in the original dataset.
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+}
+
+impl<D: Dataset> Dataset for Subset<D> {
+    type Item = D::Item;
+    fn len(&self) -> usize {
+        self.indices.len()
+    }
+    fn get(&self, index: usize) -> Self::Item {
+        self.dataset.get(self.indices[index])
+    }
+}
+
+/// Randomly splits `dataset` into two [Subset]s, with `fraction` of the items in the first.
+/// The same `seed` always gives the same split.
+///
+/// Pass a reference to split a dataset without moving it.
+///
+/// **Pytorch equivalent**: `torch.utils.data.random_split(dataset, [fraction, 1 - fraction])`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::data::*;
+/// let data: Vec<usize> = (0..10).collect();
+/// let (train, valid) = random_split(&data, 0.8, 0);
+/// assert_eq!(train.len(), 8);
+/// assert_eq!(valid.len(), 2);
+/// ```
+pub fn random_split<D: Dataset + Clone>(
+    dataset: D,
+    fraction: f32,
+    seed: u64,
+) -> (Subset<D>, Subset<D>) {
+    let mut indices: Vec<usize> = (0..dataset.len()).collect();
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (a, b) = split_indices(&mut indices, fraction, &mut rng);
+    (Subset::new(dataset.clone(), a), Subset::new(dataset, b))
+}
+
+/// Same as [random_split()], but splits the items of each class separately, so both
+/// [Subset]s have the same proportion of each class. `labels[i]` is the class of item `i`.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::data::*;
+/// let labels = vec![0, 0, 0, 0, 0, 0, 0, 0, 1, 1];
+/// let (train, valid) = stratified_split(&labels, &labels, 0.5, 0);
+/// assert_eq!((0..5).filter(|&i| train.get(i) == 1).count(), 1);
+/// assert_eq!((0..5).filter(|&i| valid.get(i) == 1).count(), 1);
+/// ```
+pub fn stratified_split<D: Dataset + Clone>(
+    dataset: D,
+    labels: &[usize],
+    fraction: f32,
+    seed: u64,
+) -> (Subset<D>, Subset<D>) {
+    assert_eq!(dataset.len(), labels.len(), "expected one label per item");
+    let num_classes = labels.iter().max().map_or(0, |c| c + 1);
+    let mut classes: Vec<Vec<usize>> = (0..num_classes).map(|_| Vec::new()).collect();
+    for (i, &c) in labels.iter().enumerate() {
+        classes[c].push(i);
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (mut a, mut b) = (Vec::new(), Vec::new());
+    for class in classes.iter_mut() {
+        let (class_a, class_b) = split_indices(class, fraction, &mut rng);
+        a.extend(class_a);
+        b.extend(class_b);
+    }
+    a.sort_unstable();
+    b.sort_unstable();
+    (Subset::new(dataset.clone(), a), Subset::new(dataset, b))
+}
+
+/// Shuffles `indices` and splits off `fraction` of them (rounded), returning both parts in sorted order.
+fn split_indices<R: Rng>(
+    indices: &mut [usize],
+    fraction: f32,
+    rng: &mut R,
+) -> (Vec<usize>, Vec<usize>) {
+    assert!(
+        (0.0..=1.0).contains(&fraction),
+        "fraction must be in [0, 1]"
+    );
+    indices.shuffle(rng);
+    let n = num_traits::Float::round(indices.len() as f32 * fraction) as usize;
+    let (a, b) = indices.split_at_mut(n);
+    a.sort_unstable();
+    b.sort_unstable();
+    (a.to_vec(), b.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_split() {
+        let data: Vec<usize> = (0..100).collect();
+        let (a, b) = random_split(&data, 0.75, 0);
+        assert_eq!(a.len(), 75);
+        assert_eq!(b.len(), 25);
+        let mut all: Vec<usize> = a.indices().iter().chain(b.indices()).copied().collect();
+        all.sort_unstable();
+        assert_eq!(all, data);
+        assert_eq!(a.get(3), a.indices()[3]);
+
+        let (c, _) = random_split(&data, 0.75, 0);
+        assert_eq!(c.indices(), a.indices());
+        let (d, _) = random_split(&data, 0.75, 1);
+        assert_ne!(d.indices(), a.indices());
+    }
+
+    #[test]
+    fn test_stratified_split() {
+        let mut labels = alloc::vec![0; 90];
+        labels.extend([1; 10]);
+        labels.extend([2; 20]);
+        let (a, b) = stratified_split(&labels, &labels, 0.8, 0);
+        let count = |s: &Subset<&Vec<usize>>, c| (0..s.len()).filter(|&i| s.get(i) == c).count();
+        assert_eq!([count(&a, 0), count(&a, 1), count(&a, 2)], [72, 8, 16]);
+        assert_eq!([count(&b, 0), count(&b, 1), count(&b, 2)], [18, 2, 4]);
+    }
+
+    #[test]
+    fn test_empty_split() {
+        let data: Vec<usize> = Vec::new();
+        let (a, b) = random_split(data, 0.5, 0);
+        assert!(a.is_empty() && b.is_empty());
+    }
+}
@@ -0,0 +1,119 @@
+use crate::devices::{Cpu, ForEachElement};
+use crate::prelude::*;
+
+/// Combines `B` items into a batch, where each item becomes one index of the first dimension.
+/// Used by [super::DataLoader] to build batches.
+///
+/// - Tensors are stacked into a tensor with one more dimension, e.g. `B` [Tensor1D]`<M>`
+///   become a [Tensor2D]`<B, M>`.
+/// - `f32`s become a [Tensor1D]`<B>`.
+/// - `usize`s, like class labels, become a `[usize; B]`.
+/// - Tuples stack each of their elements.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::data::Stack;
+/// let items = [(tensor([1.0, 2.0]), 0), (tensor([3.0, 4.0]), 1)];
+/// let (x, y): (Tensor2D<2, 2>, [usize; 2]) = Stack::stack(items);
+/// assert_eq!(x.data(), &[[1.0, 2.0], [3.0, 4.0]]);
+/// assert_eq!(y, [0, 1]);
+/// ```
+pub trait Stack<const B: usize>: Sized {
+    /// The type of `B` stacked items.
+    type Stacked;
+
+    /// Stacks `items` along a new first dimension.
+    fn stack(items: [Self; B]) -> Self::Stacked;
+}
+
+impl<const B: usize> Stack<B> for usize {
+    type Stacked = [usize; B];
+    fn stack(items: [Self; B]) -> Self::Stacked {
+        items
+    }
+}
+
+impl<const B: usize> Stack<B> for f32 {
+    type Stacked = Tensor1D<B>;
+    fn stack(items: [Self; B]) -> Self::Stacked {
+        TensorCreator::new(items)
+    }
+}
+
+impl<const B: usize> Stack<B> for Tensor0D {
+    type Stacked = Tensor1D<B>;
+    fn stack(items: [Self; B]) -> Self::Stacked {
+        let mut batch: Self::Stacked = TensorCreator::zeros();
+        for (b, item) in batch.mut_data().iter_mut().zip(items.iter()) {
+            *b = *item.data();
+        }
+        batch
+    }
+}
+
+macro_rules! tensor_impl {
+    ($typename:ident [$($Vs:tt),*], $stacked:ident) => {
+        impl<const B: usize, $(const $Vs: usize, )*> Stack<B> for $typename<$($Vs, )* NoneTape> {
+            type Stacked = $stacked<B, $($Vs, )* NoneTape>;
+            fn stack(items: [Self; B]) -> Self::Stacked {
+                let mut batch: Self::Stacked = TensorCreator::zeros();
+                for (b, item) in batch.mut_data().iter_mut().zip(items.iter()) {
+                    Cpu::foreach_mr(b, item.data(), &mut |b, x| *b = *x);
+                }
+                batch
+            }
+        }
+    };
+}
+
+tensor_impl!(Tensor1D[M], Tensor2D);
+tensor_impl!(Tensor2D[M, N], Tensor3D);
+tensor_impl!(Tensor3D[M, N, O], Tensor4D);
+
+macro_rules! tuple_impls {
+    ([$($name:ident),+] [$($idx:tt),+]) => {
+        impl<const B: usize, $($name: Stack<B>),+> Stack<B> for ($($name,)+) {
+            type Stacked = ($($name::Stacked,)+);
+            fn stack(items: [Self; B]) -> Self::Stacked {
+                let mut items = items.map(|item| ($(Some(item.$idx),)+));
+                ($($name::stack(core::array::from_fn(|i| items[i].$idx.take().unwrap())),)+)
+            }
+        }
+    };
+}
+
+tuple_impls!([A, Z] [0, 1]);
+tuple_impls!([A, Z, C] [0, 1, 2]);
+tuple_impls!([A, Z, C, D] [0, 1, 2, 3]);
+tuple_impls!([A, Z, C, D, E] [0, 1, 2, 3, 4]);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stack_tensors() {
+        let x: Tensor1D<3> =
+            Stack::stack([Tensor0D::new(1.0), Tensor0D::new(2.0), Tensor0D::new(3.0)]);
+        assert_eq!(x.data(), &[1.0, 2.0, 3.0]);
+
+        let items = [tensor([[1.0, 2.0]]), tensor([[3.0, 4.0]])];
+        let x: Tensor3D<2, 1, 2> = Stack::stack(items);
+        assert_eq!(x.data(), &[[[1.0, 2.0]], [[3.0, 4.0]]]);
+
+        let items = [Tensor3D::<2, 3, 4>::ones(), Tensor3D::zeros()];
+        let x: Tensor4D<2, 2, 3, 4> = Stack::stack(items);
+        assert_eq!(x.data()[0], [[[1.0; 4]; 3]; 2]);
+        assert_eq!(x.data()[1], [[[0.0; 4]; 3]; 2]);
+    }
+
+    #[test]
+    fn test_stack_tuples() {
+        let (x, y, z): (Tensor1D<2>, [usize; 2], Tensor2D<2, 1>) =
+            Stack::stack([(1.0, 3, tensor([5.0])), (2.0, 4, tensor([6.0]))]);
+        assert_eq!(x.data(), &[1.0, 2.0]);
+        assert_eq!(y, [3, 4]);
+        assert_eq!(z.data(), &[[5.0], [6.0]]);
+    }
+}
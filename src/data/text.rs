@@ -0,0 +1,135 @@
+use crate::prelude::*;
+use std::{string::String, vec::Vec};
+
+/// Converts text to a sequence of token ids, and back.
+///
+/// Implement this for the tokenizer of a pretrained model to use it with [pad_sequences()]. [CharTokenizer]
+/// is a simple implementation for character level models.
+pub trait Tokenizer {
+    /// The ids of the tokens of `text`.
+    fn encode(&self, text: &str) -> Vec<usize>;
+
+    /// The text of the tokens `ids`.
+    fn decode(&self, ids: &[usize]) -> String;
+}
+
+/// A [Tokenizer] with one token per character. Id `0` is padding, id `1` is for characters
+/// that aren't in the vocabulary, and the characters of the vocabulary start at id `2`.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::data::*;
+/// let tokenizer = CharTokenizer::from_text("hello world");
+/// assert_eq!(tokenizer.vocab_size(), 2 + 8);
+/// let ids = tokenizer.encode("hole!");
+/// assert_eq!(ids, [5, 7, 6, 4, CharTokenizer::UNKNOWN]);
+/// assert_eq!(tokenizer.decode(&ids), "hole?");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CharTokenizer {
+    chars: Vec<char>,
+}
+
+impl CharTokenizer {
+    /// The id of padding.
+    pub const PAD: usize = 0;
+
+    /// The id of characters that aren't in the vocabulary.
+    pub const UNKNOWN: usize = 1;
+
+    /// A tokenizer with every distinct character of `text` as its vocabulary, in sorted order.
+    pub fn from_text(text: &str) -> Self {
+        let mut chars: Vec<char> = text.chars().collect();
+        chars.sort_unstable();
+        chars.dedup();
+        Self { chars }
+    }
+
+    /// The number of ids, including [CharTokenizer::PAD] and [CharTokenizer::UNKNOWN].
+    pub fn vocab_size(&self) -> usize {
+        self.chars.len() + 2
+    }
+}
+
+impl Tokenizer for CharTokenizer {
+    fn encode(&self, text: &str) -> Vec<usize> {
+        text.chars()
+            .map(|c| match self.chars.binary_search(&c) {
+                Ok(i) => i + 2,
+                Err(_) => Self::UNKNOWN,
+            })
+            .collect()
+    }
+
+    /// Skips padding, and decodes unknown ids as `?`.
+    fn decode(&self, ids: &[usize]) -> String {
+        ids.iter()
+            .filter(|&&id| id != Self::PAD)
+            .map(
+                |&id| match id.checked_sub(2).and_then(|i| self.chars.get(i)) {
+                    Some(&c) => c,
+                    None => '?',
+                },
+            )
+            .collect()
+    }
+}
+
+/// Pads (or truncates) each of `B` sequences of token ids to length `L`, filling the end of each
+/// sequence with `pad_id`. Returns the padded ids, which can be passed to
+/// [crate::tensor_ops::SelectTo::select()] to look up embeddings, and an attention mask that is `1.0` for
+/// tokens and `0.0` for padding.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::data::pad_sequences;
+/// let (ids, mask): ([[usize; 3]; 2], Tensor2D<2, 3>) = pad_sequences([&[5, 6], &[7, 8, 9, 10]], 0);
+/// assert_eq!(ids, [[5, 6, 0], [7, 8, 9]]);
+/// assert_eq!(mask.data(), &[[1.0, 1.0, 0.0], [1.0, 1.0, 1.0]]);
+/// ```
+pub fn pad_sequences<const B: usize, const L: usize>(
+    sequences: [&[usize]; B],
+    pad_id: usize,
+) -> ([[usize; L]; B], Tensor2D<B, L>) {
+    let mut ids = [[pad_id; L]; B];
+    let mut mask: Tensor2D<B, L> = TensorCreator::zeros();
+    for ((seq, ids), mask) in sequences
+        .iter()
+        .zip(ids.iter_mut())
+        .zip(mask.mut_data().iter_mut())
+    {
+        let n = seq.len().min(L);
+        ids[..n].copy_from_slice(&seq[..n]);
+        mask[..n].fill(1.0);
+    }
+    (ids, mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_char_tokenizer() {
+        let tokenizer = CharTokenizer::from_text("abcab");
+        assert_eq!(tokenizer.vocab_size(), 5);
+        assert_eq!(tokenizer.encode("cabd"), [4, 2, 3, CharTokenizer::UNKNOWN]);
+        assert_eq!(tokenizer.decode(&[4, 2, 0, 0, 9]), "ca?");
+        assert_eq!(
+            CharTokenizer::default().encode("a"),
+            [CharTokenizer::UNKNOWN]
+        );
+    }
+
+    #[test]
+    fn test_pad_sequences() {
+        let tokenizer = CharTokenizer::from_text("abc");
+        let a = tokenizer.encode("abcabc");
+        let b = tokenizer.encode("");
+        let c = tokenizer.encode("ba");
+        let (ids, mask): ([[usize; 4]; 3], Tensor2D<3, 4>) = pad_sequences([&a, &b, &c], 0);
+        assert_eq!(ids, [[2, 3, 4, 2], [0; 4], [3, 2, 0, 0]]);
+        assert_eq!(mask.data(), &[[1.0; 4], [0.0; 4], [1.0, 1.0, 0.0, 0.0]]);
+    }
+}
@@ -6,6 +6,7 @@ pub trait Accumulator<T> {
     /// The initial value to set the accumulator to.
     const INIT: T;
 
+    /// Accumulates `item` into `accum`.
     fn accum(accum: &mut T, item: &T);
 }
 
@@ -1,15 +1,15 @@
-use super::{AllocateZeros, Cpu};
+use super::{AllocateZeros, Cpu, PaddingMode, ZeroPadding};
 #[cfg(feature = "cblas")]
 use cblas_sys::{
     cblas_sgemm as sgemm, CblasNoTrans as NoTr, CblasRowMajor as RowMajor, CblasTrans as Tr,
 };
 use std::boxed::Box;
 
-/// **Requires nightly** 2d convolution with stride and padding specified at trait level.
+/// **Requires nightly** 2d convolution with stride, padding, and padding mode specified at trait level.
 ///
 /// This allows the rest of the parameters to be inferred by inputs.
-pub trait DeviceConv2D<const S: usize, const P: usize> {
-    /// Forward operation that modifies the `out` image.
+pub trait DeviceConv2D<const S: usize, const P: usize, M: PaddingMode = ZeroPadding> {
+    /// Forward operation that modifies the `out` image. Adds `bias` if it is `Some`.
     fn conv_forward<
         const C: usize,
         const O: usize,
@@ -19,11 +19,11 @@ pub trait DeviceConv2D<const S: usize, const P: usize> {
     >(
         img: &[[[f32; W]; H]; C],
         weight: &[[[[f32; K]; K]; C]; O],
-        bias: &[f32; O],
+        bias: Option<&[f32; O]>,
         out: &mut [[[f32; (W + 2 * P - K) / S + 1]; (H + 2 * P - K) / S + 1]; O],
     );
 
-    /// Backward operation that modifies the gradients of img, weight, and bias.
+    /// Backward operation that modifies the gradients of img, weight, and bias if it is `Some`.
     fn conv_backward<
         const C: usize,
         const O: usize,
@@ -36,11 +36,11 @@ pub trait DeviceConv2D<const S: usize, const P: usize> {
         out_g: &[[[f32; (W + 2 * P - K) / S + 1]; (H + 2 * P - K) / S + 1]; O],
         img_g: &mut [[[f32; W]; H]; C],
         weight_g: &mut [[[[f32; K]; K]; C]; O],
-        bias_g: &mut [f32; O],
+        bias_g: Option<&mut [f32; O]>,
     );
 }
 
-impl<const S: usize, const P: usize> DeviceConv2D<S, P> for Cpu
+impl<const S: usize, const P: usize, M: PaddingMode> DeviceConv2D<S, P, M> for Cpu
 where
     Self: AllocateZeros,
 {
@@ -53,7 +53,7 @@ where
     >(
         img: &[[[f32; W]; H]; C],
         weight: &[[[[f32; K]; K]; C]; O],
-        bias: &[f32; O],
+        bias: Option<&[f32; O]>,
         out: &mut [[[f32; (W + 2 * P - K) / S + 1]; (H + 2 * P - K) / S + 1]; O],
     ) {
         let mut patches: Box<
@@ -65,9 +65,9 @@ where
                 for k2 in 0..K {
                     for oh in 0..(H + 2 * P - K) / S + 1 {
                         for ow in 0..(W + 2 * P - K) / S + 1 {
-                            let y = (oh * S + k1).wrapping_sub(P);
-                            let x = (ow * S + k2).wrapping_sub(P);
-                            if y < H && x < W {
+                            let y = M::unpadded_index(oh * S + k1, P, H);
+                            let x = M::unpadded_index(ow * S + k2, P, W);
+                            if let (Some(y), Some(x)) = (y, x) {
                                 patches[c][k1][k2][oh][ow] = img[c][y][x];
                             }
                         }
@@ -96,10 +96,12 @@ where
             sgemm(RowMajor, NoTr, NoTr, m, n, k, 1.0, a, k, b, n, 1.0, c, n)
         }
 
-        for oc in 0..O {
-            for oh in 0..((H + 2 * P - K) / S + 1) {
-                for ow in 0..((W + 2 * P - K) / S + 1) {
-                    out[oc][oh][ow] += bias[oc];
+        if let Some(bias) = bias {
+            for oc in 0..O {
+                for oh in 0..((H + 2 * P - K) / S + 1) {
+                    for ow in 0..((W + 2 * P - K) / S + 1) {
+                        out[oc][oh][ow] += bias[oc];
+                    }
                 }
             }
         }
@@ -117,12 +119,14 @@ where
         out_g: &[[[f32; (W + 2 * P - K) / S + 1]; (H + 2 * P - K) / S + 1]; O],
         img_g: &mut [[[f32; W]; H]; C],
         weight_g: &mut [[[[f32; K]; K]; C]; O],
-        bias_g: &mut [f32; O],
+        bias_g: Option<&mut [f32; O]>,
     ) {
-        for oc in 0..O {
-            for oh in 0..((H + 2 * P - K) / S + 1) {
-                for ow in 0..((W + 2 * P - K) / S + 1) {
-                    bias_g[oc] += out_g[oc][oh][ow];
+        if let Some(bias_g) = bias_g {
+            for oc in 0..O {
+                for oh in 0..((H + 2 * P - K) / S + 1) {
+                    for ow in 0..((W + 2 * P - K) / S + 1) {
+                        bias_g[oc] += out_g[oc][oh][ow];
+                    }
                 }
             }
         }
@@ -141,10 +145,10 @@ where
                     let g = out_g[o][oh][ow];
                     for k1 in 0..K {
                         for k2 in 0..K {
-                            let y = (oh * S + k1).wrapping_sub(P);
-                            let x = (ow * S + k2).wrapping_sub(P);
-                            if y < H && x < W {
-                                patches[o][k1][k2][y][x] = g;
+                            let y = M::unpadded_index(oh * S + k1, P, H);
+                            let x = M::unpadded_index(ow * S + k2, P, W);
+                            if let (Some(y), Some(x)) = (y, x) {
+                                patches[o][k1][k2][y][x] += g;
                             }
                         }
                     }
@@ -214,7 +218,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::devices::{AllocateZeros, FillElements};
+    use crate::devices::{AllocateZeros, FillElements, ReflectPadding};
     use crate::tests::assert_close;
     use rand::prelude::*;
     use rand_distr::StandardNormal;
@@ -232,7 +236,7 @@ mod tests {
         <Cpu as DeviceConv2D<4, 3>>::conv_forward(
             x.as_ref(),
             weight.as_ref(),
-            bias.as_ref(),
+            Some(bias.as_ref()),
             &mut out,
         );
 
@@ -252,11 +256,44 @@ mod tests {
             &out,
             xg.as_mut(),
             wg.as_mut(),
-            bg.as_mut(),
+            Some(bg.as_mut()),
         );
 
         assert_ne!(wg.as_ref(), &[[[[0.0; 2]; 2]; 5]; 3]);
         assert_ne!(bg.as_ref(), &[0.0; 3]);
         assert_ne!(xg.as_ref(), &[[[0.0; 6]; 7]; 5]);
     }
+
+    #[test]
+    fn test_conv2d_reflect_padding() {
+        let x = [[[1.0, 2.0], [3.0, 4.0]]];
+        let weight = [[[[1.0]]]];
+        let mut out = [[[0.0; 4]; 4]];
+        <Cpu as DeviceConv2D<1, 1, ReflectPadding>>::conv_forward(&x, &weight, None, &mut out);
+        assert_eq!(
+            out,
+            [[
+                [4.0, 3.0, 4.0, 3.0],
+                [2.0, 1.0, 2.0, 1.0],
+                [4.0, 3.0, 4.0, 3.0],
+                [2.0, 1.0, 2.0, 1.0]
+            ]]
+        );
+
+        let mut wg = [[[[0.0]]]];
+        let mut bg = [0.0];
+        let mut xg = [[[0.0; 2]; 2]];
+        let out_g = [[[1.0; 4]; 4]];
+        <Cpu as DeviceConv2D<1, 1, ReflectPadding>>::conv_backward(
+            &x,
+            &weight,
+            &out_g,
+            &mut xg,
+            &mut wg,
+            Some(&mut bg),
+        );
+        assert_eq!(xg, [[[4.0; 2]; 2]]);
+        assert_eq!(wg, [[[[40.0]]]]);
+        assert_eq!(bg, [16.0]);
+    }
 }
@@ -4,8 +4,10 @@ use std::boxed::Box;
 
 /// Fills all elements with the specified function
 pub trait FillElements<T: CountElements>: Sized + AllocateZeros {
+    /// Calls `f` on every element of `out`.
     fn fill<F: FnMut(&mut T::Dtype)>(out: &mut T, f: &mut F);
 
+    /// Allocates a new `T` with [AllocateZeros::zeros()], then calls [FillElements::fill()] on it.
     fn filled<F: FnMut(&mut T::Dtype)>(f: &mut F) -> Box<T> {
         let mut out = Self::zeros();
         Self::fill(&mut out, f);
@@ -6,7 +6,9 @@ use cblas_sys::{
     CblasRowMajor as RowMajor, CblasTrans as Tr,
 };
 
+/// An array type whose last two axes can be swapped.
 pub trait Transpose {
+    /// `Self` with its last two axes swapped.
     type T: Transpose<T = Self>;
 }
 
@@ -18,10 +20,19 @@ impl<Inner: Transpose, const B: usize> Transpose for [Inner; B] {
     type T = [Inner::T; B];
 }
 
+/// Matrix multiplication of `A` and `B` into `C`. Every method adds its result into `c`,
+/// so `c` must be zeroed first to compute only the product.
 pub trait MatMul<A: Transpose, B: Transpose, C: Transpose> {
+    /// `c += a * b`
     fn mm(a: &A, b: &B, c: &mut C);
+
+    /// `c += trans(a) * b`
     fn mm_at(a: &A::T, b: &B, c: &mut C);
+
+    /// `c += a * trans(b)`
     fn mm_bt(a: &A, b: &B::T, c: &mut C);
+
+    /// `trans(c) += trans(a) * b`
     fn mm_atct(a: &A::T, b: &B, c: &mut C::T);
 }
 
@@ -216,6 +227,7 @@ where
     }
 }
 
+/// The [MatMul]s needed for the forward & backward of `C = A * B`.
 pub trait MatMulOp<A: Transpose, B: Transpose, C: Transpose>:
     MatMul<A, B, C> + MatMul<C, B::T, A> + MatMul<A::T, C, B>
 {
@@ -1,10 +1,34 @@
 //! Provides implementations for modifying Nd arrays on the [Cpu].
+//!
+//! # Kernel traits
+//!
+//! Each kind of kernel is a trait, generic over the array types it acts on, and implemented
+//! for a unit struct that represents the device (e.g. [Cpu]). None of the methods take `self`,
+//! and all arrays are passed as plain Rust arrays (e.g. `[[f32; N]; M]`).
+//!
+//! | Trait | Kernels |
+//! | --- | --- |
+//! | [AllocateZeros] | Allocating zeroed arrays |
+//! | [FillElements] | Setting every element of an array |
+//! | [ForEachElement] | Elementwise ops over 1 to 3 arrays |
+//! | [DeviceReduce] | Reducing & broadcasting along axes with an [Accumulator] |
+//! | [DeviceSelect] | Selecting & gathering along an axis |
+//! | [DevicePermute] | Permuting axes |
+//! | [MatMul] | Matrix multiplication, with either operand transposed |
+//! | `DeviceConv2D` | **Requires nightly** 2d convolutions |
+//! | `DevicePool2D` | **Requires nightly** 2d pooling |
+//! | `DevicePixelShuffle` | **Requires nightly** pixel shuffle |
+//!
+//! [Device] bundles the traits that every tensor needs, and [HasDevice] picks the device
+//! that a tensor uses. Every tensor in [crate::tensor] uses [Cpu], so these traits describe the
+//! kernels dfdx needs, but other devices can't be plugged into tensors, ops or modules.
 
 mod allocate;
 mod broadcast_reduce;
 mod fill;
 mod foreach;
 mod matmul;
+mod padding;
 mod permute;
 mod select;
 
@@ -13,6 +37,7 @@ pub use broadcast_reduce::*;
 pub use fill::*;
 pub use foreach::*;
 pub use matmul::*;
+pub use padding::*;
 pub use permute::*;
 pub use select::*;
 
@@ -21,6 +46,10 @@ mod conv;
 #[cfg(feature = "nightly")]
 pub use conv::*;
 #[cfg(feature = "nightly")]
+mod pixel_shuffle;
+#[cfg(feature = "nightly")]
+pub use pixel_shuffle::*;
+#[cfg(feature = "nightly")]
 mod pool2d;
 #[cfg(feature = "nightly")]
 pub use pool2d::*;
@@ -0,0 +1,90 @@
+/// How to fill in the values outside of an image when it is padded, e.g. by 2d convolutions.
+pub trait PaddingMode: 'static + Default + Clone + Copy + std::fmt::Debug {
+    /// Maps index `i` of an axis padded by `pad` on both sides into an index of the unpadded axis
+    /// of length `len`. Returns `None` if the padded value should be `0.0`.
+    fn unpadded_index(i: usize, pad: usize, len: usize) -> Option<usize>;
+}
+
+/// Pads with `0.0`.
+///
+/// **Pytorch Equivalent**: `padding_mode="zeros"`
+#[derive(Default, Debug, Clone, Copy)]
+pub struct ZeroPadding;
+
+impl PaddingMode for ZeroPadding {
+    fn unpadded_index(i: usize, pad: usize, len: usize) -> Option<usize> {
+        let i = i.wrapping_sub(pad);
+        (i < len).then_some(i)
+    }
+}
+
+/// Pads with the reflection of the values along the edge, not including the edge itself.
+/// E.g. padding `[1, 2, 3]` by 2 gives `[3, 2, 1, 2, 3, 2, 1]`.
+///
+/// **Pytorch Equivalent**: `padding_mode="reflect"`
+#[derive(Default, Debug, Clone, Copy)]
+pub struct ReflectPadding;
+
+impl PaddingMode for ReflectPadding {
+    fn unpadded_index(i: usize, pad: usize, len: usize) -> Option<usize> {
+        if len == 1 {
+            return Some(0);
+        }
+        let period = 2 * (len - 1) as isize;
+        let i = (i as isize - pad as isize).rem_euclid(period);
+        Some(if i < len as isize { i } else { period - i } as usize)
+    }
+}
+
+/// Pads with copies of the values along the edge.
+/// E.g. padding `[1, 2, 3]` by 2 gives `[1, 1, 1, 2, 3, 3, 3]`.
+///
+/// **Pytorch Equivalent**: `padding_mode="replicate"`
+#[derive(Default, Debug, Clone, Copy)]
+pub struct ReplicatePadding;
+
+impl PaddingMode for ReplicatePadding {
+    fn unpadded_index(i: usize, pad: usize, len: usize) -> Option<usize> {
+        Some(i.saturating_sub(pad).min(len - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pad<M: PaddingMode>(x: [f32; 3], p: usize) -> std::vec::Vec<f32> {
+        (0..3 + 2 * p)
+            .map(|i| M::unpadded_index(i, p, 3).map(|j| x[j]).unwrap_or(0.0))
+            .collect()
+    }
+
+    #[test]
+    fn test_zero_padding() {
+        assert_eq!(pad::<ZeroPadding>([1.0, 2.0, 3.0], 0), [1.0, 2.0, 3.0]);
+        assert_eq!(
+            pad::<ZeroPadding>([1.0, 2.0, 3.0], 2),
+            [0.0, 0.0, 1.0, 2.0, 3.0, 0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn test_reflect_padding() {
+        assert_eq!(
+            pad::<ReflectPadding>([1.0, 2.0, 3.0], 2),
+            [3.0, 2.0, 1.0, 2.0, 3.0, 2.0, 1.0]
+        );
+        assert_eq!(
+            pad::<ReflectPadding>([1.0, 2.0, 3.0], 3),
+            [2.0, 3.0, 2.0, 1.0, 2.0, 3.0, 2.0, 1.0, 2.0]
+        );
+    }
+
+    #[test]
+    fn test_replicate_padding() {
+        assert_eq!(
+            pad::<ReplicatePadding>([1.0, 2.0, 3.0], 2),
+            [1.0, 1.0, 1.0, 2.0, 3.0, 3.0, 3.0]
+        );
+    }
+}
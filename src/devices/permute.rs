@@ -22,7 +22,10 @@ use crate::arrays::{Axes2, Axes3, Axes4};
 
 /// Permutes axes of `A` resulting in `B`.
 pub trait DevicePermute<A, B, Axes> {
+    /// Sets `b` to `a` with its axes permuted.
     fn permute(a: &A, b: &mut B);
+
+    /// Adds `b` into `a`, undoing the permutation. Used for backward.
     fn inverse_permute(a: &mut A, b: &B);
 }
 
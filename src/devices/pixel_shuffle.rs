@@ -0,0 +1,74 @@
+use super::Cpu;
+
+/// **Requires nightly** Pixel shuffle with the upscale factor specified at trait level.
+///
+/// This allows the rest of the parameters to be inferred by inputs.
+pub trait DevicePixelShuffle<const R: usize> {
+    /// Forward operation that moves blocks of `R * R` channels of `inp` into `R x R` spatial blocks of `out`.
+    fn shuffle_forward<const C: usize, const H: usize, const W: usize>(
+        inp: &[[[f32; W]; H]; C],
+        out: &mut [[[f32; W * R]; H * R]; C / (R * R)],
+    );
+
+    /// Backward operation that accumulates `out_g` into `inp_g`.
+    fn shuffle_backward<const C: usize, const H: usize, const W: usize>(
+        out_g: &[[[f32; W * R]; H * R]; C / (R * R)],
+        inp_g: &mut [[[f32; W]; H]; C],
+    );
+}
+
+impl<const R: usize> DevicePixelShuffle<R> for Cpu {
+    fn shuffle_forward<const C: usize, const H: usize, const W: usize>(
+        inp: &[[[f32; W]; H]; C],
+        out: &mut [[[f32; W * R]; H * R]; C / (R * R)],
+    ) {
+        for (c, inp_c) in inp.iter().enumerate() {
+            let (oc, offset) = (c / (R * R), c % (R * R));
+            let (i, j) = (offset / R, offset % R);
+            for (h, inp_h) in inp_c.iter().enumerate() {
+                for (w, x) in inp_h.iter().enumerate() {
+                    out[oc][h * R + i][w * R + j] = *x;
+                }
+            }
+        }
+    }
+
+    fn shuffle_backward<const C: usize, const H: usize, const W: usize>(
+        out_g: &[[[f32; W * R]; H * R]; C / (R * R)],
+        inp_g: &mut [[[f32; W]; H]; C],
+    ) {
+        for (c, inp_g_c) in inp_g.iter_mut().enumerate() {
+            let (oc, offset) = (c / (R * R), c % (R * R));
+            let (i, j) = (offset / R, offset % R);
+            for (h, inp_g_h) in inp_g_c.iter_mut().enumerate() {
+                for (w, g) in inp_g_h.iter_mut().enumerate() {
+                    *g += out_g[oc][h * R + i][w * R + j];
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shuffle_forward() {
+        let inp = [[[1.0, 2.0]], [[3.0, 4.0]], [[5.0, 6.0]], [[7.0, 8.0]]];
+        let mut out = [[[0.0; 4]; 2]; 1];
+        <Cpu as DevicePixelShuffle<2>>::shuffle_forward(&inp, &mut out);
+        assert_eq!(out, [[[1.0, 3.0, 2.0, 4.0], [5.0, 7.0, 6.0, 8.0]]]);
+    }
+
+    #[test]
+    fn test_shuffle_backward() {
+        let out_g = [[[1.0, 3.0, 2.0, 4.0], [5.0, 7.0, 6.0, 8.0]]];
+        let mut inp_g = [[[1.0; 2]; 1]; 4];
+        <Cpu as DevicePixelShuffle<2>>::shuffle_backward(&out_g, &mut inp_g);
+        assert_eq!(
+            inp_g,
+            [[[2.0, 3.0]], [[4.0, 5.0]], [[6.0, 7.0]], [[8.0, 9.0]]]
+        );
+    }
+}
@@ -26,7 +26,7 @@ use crate::arrays::CountElements;
 
 /// Used to disambiguate trait implementations. Callees
 /// must specify what kind of selection is occurring.
-pub(crate) mod select_modes {
+pub mod select_modes {
     use std::marker::PhantomData;
 
     /// Select the current axis.
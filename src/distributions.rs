@@ -0,0 +1,372 @@
+//! Probability distributions parameterized by tensors, such as [Normal], [Categorical], and [Bernoulli].
+//!
+//! [Normal::log_prob()], [Normal::entropy()], [Normal::rsample()], and the same methods of the other
+//! distributions are differentiable, so they can be used in losses, like policy gradient losses
+//! and the KL term of a VAE.
+//!
+//! ```rust
+//! # use dfdx::prelude::*;
+//! # use dfdx::distributions::Categorical;
+//! # use rand::{rngs::StdRng, SeedableRng};
+//! let mut rng = StdRng::seed_from_u64(0);
+//! let logits: Tensor2D<2, 3> = Tensor2D::randn(&mut rng);
+//! let pi = Categorical::new(logits.traced());
+//! let actions: [usize; 2] = pi.sample(&mut rng);
+//! let log_prob: Tensor1D<2, OwnedTape> = pi.log_prob(&actions);
+//! let loss = -log_prob.mean();
+//! let gradients = loss.backward();
+//! ```
+//!
+//! The methods that return tensors take `self`, so the tape ends up in the result. To use more than
+//! one of them in a loss, make the other distributions from copies of the parameters with
+//! [Tensor::with_empty_tape()], like `Categorical::new(logits.with_empty_tape())`.
+
+use crate::devices::ForEachElement;
+use crate::gradients::{Merge, Tape};
+use crate::prelude::*;
+use crate::tensor_ops::utils::{binary_map, map};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+use rand::Rng;
+use rand_distr::{Distribution, StandardNormal};
+
+/// `ln(sqrt(2 * pi))`
+const LN_SQRT_2PI: f32 = 0.918_938_5;
+
+/// A normal distribution with mean `loc` and standard deviation `scale`, element wise.
+///
+/// `loc` & `scale` have the same type, so if only one of them is traced, call `.traced()` on the other
+/// one too.
+///
+/// **Pytorch equivalent**: `torch.distributions.Normal`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::distributions::Normal;
+/// let dist = Normal::new(tensor([0.0, 1.0]), tensor([1.0, 2.0]));
+/// let log_prob = dist.log_prob(&tensor([0.0, 0.0]));
+/// assert_eq!(log_prob.data(), &[-0.9189385, -1.7370857]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Normal<T> {
+    pub loc: T,
+    pub scale: T,
+}
+
+impl<T: Tensor<Dtype = f32>> Normal<T> {
+    pub fn new(loc: T, scale: T) -> Self {
+        Self { loc, scale }
+    }
+
+    /// The log of the probability density of `value`:
+    /// `-(value - loc)^2 / (2 * scale^2) - ln(scale) - ln(sqrt(2 * pi))`
+    pub fn log_prob(self, value: &T::NoTape) -> T {
+        let (loc, scale) = join_tapes(self.loc, self.scale);
+        let var2 = mul_scalar(square(scale.clone().put_tape(Default::default())), 2.0);
+        let sq_err = square(sub(loc, value.clone()));
+        let log_scale = ln(scale.put_tape(Default::default()));
+        negate(add_scalar(add(div(sq_err, var2), log_scale), LN_SQRT_2PI))
+    }
+
+    /// The entropy: `0.5 + ln(sqrt(2 * pi)) + ln(scale)`
+    pub fn entropy(self) -> T {
+        let (loc, scale) = join_tapes(self.loc, self.scale);
+        let (_, tape) = loc.split_tape();
+        let (result, result_tape) =
+            add_scalar(ln(scale.put_tape(Default::default())), 0.5 + LN_SQRT_2PI).split_tape();
+        result.put_tape(tape.merge(result_tape))
+    }
+
+    /// Samples with the reparameterization trick: `loc + scale * eps` where `eps` is sampled from
+    /// a standard normal distribution, so gradients flow back to `loc` and `scale`.
+    pub fn rsample<R: Rng>(self, rng: &mut R) -> T {
+        let (loc, scale) = join_tapes(self.loc, self.scale);
+        let eps = T::NoTape::randn(rng);
+        add(loc, mul(scale.put_tape(Default::default()), eps))
+    }
+
+    /// Samples without tracking gradients.
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> T::NoTape {
+        let mut x = T::NoTape::zeros();
+        T::Device::foreach_mrr(
+            x.mut_data(),
+            self.loc.data(),
+            self.scale.data(),
+            &mut |x, loc, scale| {
+                let eps: f32 = StandardNormal.sample(rng);
+                *x = loc + scale * eps;
+            },
+        );
+        x
+    }
+}
+
+/// A categorical distribution over the last axis of `logits`, which are un-normalized log
+/// probabilities. Implemented for [Tensor1D] (a single distribution) and [Tensor2D] (a batch of
+/// distributions).
+///
+/// **Pytorch equivalent**: `torch.distributions.Categorical(logits=logits)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::distributions::Categorical;
+/// let dist = Categorical::new(tensor([[0.0, 0.0], [0.0, 100.0]]));
+/// let log_prob: Tensor1D<2> = dist.log_prob(&[0, 1]);
+/// assert_eq!(log_prob.data(), &[-0.6931472, 0.0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Categorical<T> {
+    pub logits: T,
+}
+
+impl<T> Categorical<T> {
+    pub fn new(logits: T) -> Self {
+        Self { logits }
+    }
+}
+
+impl<const N: usize, H: Tape> Categorical<Tensor1D<N, H>> {
+    /// The log of the probability of `action`.
+    pub fn log_prob(self, action: &usize) -> Tensor0D<H> {
+        self.logits.log_softmax::<AllAxes>().select(action)
+    }
+
+    /// The entropy: `-(probs * log_probs).sum()`. See [entropy_with_logits()].
+    pub fn entropy(self) -> Tensor0D<H> {
+        self.logits.entropy_with_logits()
+    }
+
+    /// Samples an action.
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> usize {
+        sample_logits(self.logits.data(), rng)
+    }
+}
+
+impl<const B: usize, const N: usize, H: Tape> Categorical<Tensor2D<B, N, H>> {
+    /// The log of the probability of each of `actions`.
+    pub fn log_prob(self, actions: &[usize; B]) -> Tensor1D<B, H> {
+        self.logits.log_softmax::<Axis<1>>().select(actions)
+    }
+
+    /// The entropy of each distribution: `-(probs * log_probs).sum(-1)`. See [entropy_with_logits()].
+    pub fn entropy(self) -> Tensor1D<B, H> {
+        self.logits.entropy_with_logits::<_, Axis<1>>()
+    }
+
+    /// Samples an action for each distribution.
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> [usize; B] {
+        let logits = self.logits.data();
+        core::array::from_fn(|b| sample_logits(&logits[b], rng))
+    }
+}
+
+/// A bernoulli distribution with probability `sigmoid(logits)` of being `1.0`, element wise.
+///
+/// **Pytorch equivalent**: `torch.distributions.Bernoulli(logits=logits)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::distributions::Bernoulli;
+/// let dist = Bernoulli::new(tensor([0.0, 100.0]));
+/// let log_prob = dist.log_prob(&tensor([1.0, 1.0]));
+/// assert_eq!(log_prob.data(), &[-0.6931472, 0.0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Bernoulli<T> {
+    pub logits: T,
+}
+
+impl<T: Tensor<Dtype = f32>> Bernoulli<T> {
+    pub fn new(logits: T) -> Self {
+        Self { logits }
+    }
+
+    /// The log of the probability of `value`, which is `0.0` or `1.0`:
+    /// `value * logits - ln(1 + exp(logits))`
+    pub fn log_prob(self, value: &T::NoTape) -> T {
+        binary_map(
+            self.logits,
+            value.clone(),
+            |x, y| y * x - softplus(*x),
+            |x, y| y - sigmoid(*x),
+            |x, _| *x,
+        )
+    }
+
+    /// The entropy: `ln(1 + exp(logits)) - logits * sigmoid(logits)`
+    pub fn entropy(self) -> T {
+        map(
+            self.logits,
+            |x| softplus(*x) - x * sigmoid(*x),
+            |x| {
+                let p = sigmoid(*x);
+                -x * p * (1.0 - p)
+            },
+        )
+    }
+
+    /// Samples `0.0` or `1.0` for each element.
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> T::NoTape {
+        let mut x = T::NoTape::zeros();
+        T::Device::foreach_mr(x.mut_data(), self.logits.data(), &mut |x, logit| {
+            *x = if rng.gen::<f32>() < sigmoid(*logit) {
+                1.0
+            } else {
+                0.0
+            };
+        });
+        x
+    }
+}
+
+/// Moves the tapes of `a` and `b` into `a`, and removes the tape from `b`. Any ops on `b`
+/// afterwards start with an empty tape, so their backward ops run before the backward ops
+/// that `a` & `b` came from.
+fn join_tapes<T: Tensor>(a: T, b: T) -> (T, T::NoTape) {
+    let (a, a_tape) = a.split_tape();
+    let (b, b_tape) = b.split_tape();
+    (a.put_tape(a_tape.merge(b_tape)), b)
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+/// `ln(1 + exp(x))` without overflowing
+fn softplus(x: f32) -> f32 {
+    x.max(0.0) + (-x.abs()).exp().ln_1p()
+}
+
+fn sample_logits<R: Rng, const N: usize>(logits: &[f32; N], rng: &mut R) -> usize {
+    let max = logits.iter().fold(f32::NEG_INFINITY, |a, &b| a.max(b));
+    let total: f32 = logits.iter().map(|x| (x - max).exp()).sum();
+    let mut u = rng.gen::<f32>() * total;
+    for (i, x) in logits.iter().enumerate() {
+        u -= (x - max).exp();
+        if u < 0.0 {
+            return i;
+        }
+    }
+    N - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+    use core::f32::consts::LN_2;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_normal_log_prob() {
+        let loc = tensor([0.0, 1.0]);
+        let scale = tensor([1.0, 2.0]);
+        let dist = Normal::new(loc.trace(), scale.trace());
+        let r = dist.log_prob(&tensor([0.0, 0.0]));
+        assert_close(r.data(), &[-0.9189385, -1.7370857]);
+        let g = r.sum().backward();
+        assert_close(g.ref_gradient(&loc), &[0.0, -0.25]);
+        assert_close(g.ref_gradient(&scale), &[-1.0, -0.375]);
+    }
+
+    #[test]
+    fn test_normal_log_prob_scale_history() {
+        let a = tensor([0.0]);
+        let scale = exp(a.trace());
+        let dist = Normal::new(Tensor1D::zeros().traced(), scale);
+        let r = dist.log_prob(&tensor([2.0]));
+        let g = r.sum().backward();
+        assert_close(g.ref_gradient(&a), &[3.0]);
+    }
+
+    #[test]
+    fn test_normal_entropy() {
+        let loc = tensor([0.0, 1.0]);
+        let scale = tensor([1.0, 2.0]);
+        let r = Normal::new(loc.trace(), scale.trace()).entropy();
+        assert_close(r.data(), &[1.4189385, 2.1120858]);
+        let g = r.sum().backward();
+        assert_close(g.ref_gradient(&scale), &[1.0, 0.5]);
+    }
+
+    #[test]
+    fn test_normal_rsample() {
+        let loc = tensor([0.0, 1.0, 2.0]);
+        let scale = tensor([1.0, 2.0, 0.5]);
+        let dist = Normal::new(loc.trace(), scale.trace());
+        let x = dist.rsample(&mut StdRng::seed_from_u64(0));
+        let eps: Tensor1D<3> = Tensor1D::randn(&mut StdRng::seed_from_u64(0));
+        let expected = add(loc.clone(), mul(scale.clone(), eps.clone()));
+        assert_close(x.data(), expected.data());
+        let g = x.sum().backward();
+        assert_eq!(g.ref_gradient(&loc), &[1.0; 3]);
+        assert_close(g.ref_gradient(&scale), eps.data());
+
+        let x = Normal::new(loc, scale).sample(&mut StdRng::seed_from_u64(0));
+        assert_close(x.data(), expected.data());
+    }
+
+    #[test]
+    fn test_categorical_log_prob_and_entropy() {
+        let logits = tensor([1.0, 2.0, 3.0]);
+        let r = Categorical::new(logits.trace()).log_prob(&2);
+        assert_close(&[*r.data()], &[-0.40760596]);
+        let g = r.backward();
+        assert_close(
+            g.ref_gradient(&logits),
+            &[-0.09003057, -0.24472847, 0.33475904],
+        );
+
+        let r = Categorical::new(logits.trace()).entropy();
+        assert_close(&[*r.data()], &[0.8323956]);
+        let g = r.backward();
+        assert_close(
+            g.ref_gradient(&logits),
+            &[0.1418171, 0.14077036, -0.28258745],
+        );
+    }
+
+    #[test]
+    fn test_categorical_batched() {
+        let logits = tensor([[1.0, 2.0, 3.0], [0.0, 0.0, 0.0]]);
+        let r = Categorical::new(logits.trace()).log_prob(&[2, 0]);
+        assert_close(r.data(), &[-0.40760596, -1.0986123]);
+        let r = Categorical::new(logits).entropy();
+        assert_close(r.data(), &[0.8323956, 1.0986123]);
+    }
+
+    #[test]
+    fn test_categorical_sample() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let dist = Categorical::new(tensor([[-100.0, 0.0, 100.0], [0.0, 0.0, 0.0]]));
+        let mut counts = [0; 3];
+        for _ in 0..3000 {
+            let [a, b] = dist.sample(&mut rng);
+            assert_eq!(a, 2);
+            counts[b] += 1;
+        }
+        assert!(counts.iter().all(|c| (900..1100).contains(c)), "{counts:?}");
+    }
+
+    #[test]
+    fn test_bernoulli() {
+        let logits = tensor([0.0, 2.0]);
+        let r = Bernoulli::new(logits.trace()).log_prob(&tensor([1.0, 0.0]));
+        assert_close(r.data(), &[-LN_2, -2.126928]);
+        let g = r.sum().backward();
+        assert_close(g.ref_gradient(&logits), &[0.5, -0.8807971]);
+
+        let r = Bernoulli::new(logits.trace()).entropy();
+        assert_close(r.data(), &[LN_2, 0.36533386]);
+        let g = r.sum().backward();
+        assert_close(g.ref_gradient(&logits), &[0.0, -0.20998716]);
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let dist = Bernoulli::new(Tensor1D::<1000>::zeros());
+        let total: f32 = dist.sample(&mut rng).data().iter().sum();
+        assert!((450.0..550.0).contains(&total), "{total}");
+    }
+}
@@ -21,6 +21,11 @@
 //! no-std-compat = { version = "0.4.1", features = [ "alloc", "compat_hash" ] }
 //! ```
 //!
+//! Without "std", float math like `f32::exp()` is provided by [libm](https://crates.io/crates/libm)
+//! through [num_traits::Float], which `dfdx::prelude` brings into scope. Everything except the "numpy"
+//! feature is available, so tensors, modules, and their forward passes can run on targets that only
+//! have `alloc`. Random initialization needs an rng to be passed in, e.g. `rand::rngs::StdRng::seed_from_u64()`.
+//!
 //! # "intel-mkl"
 //!
 //! Enables using the `Intel MKL` libraries (assuming you installed it already) for matrix multiplication.
@@ -56,6 +61,33 @@
 //! dfdx = { version = "...", features = ["numpy"] }
 //! ```
 //!
+//! # "torch"
+//!
+//! Enables loading PyTorch checkpoints saved with `torch.save()`, with the `torch` module.
+//!
+//! Example:
+//! ```toml
+//! dfdx = { version = "...", features = ["torch"] }
+//! ```
+//!
+//! # "tensorboard"
+//!
+//! Enables writing TensorBoard event files, with the `tensorboard` module.
+//!
+//! Example:
+//! ```toml
+//! dfdx = { version = "...", features = ["tensorboard"] }
+//! ```
+//!
+//! # "gguf"
+//!
+//! Enables exporting models to GGUF files, with the `gguf` module.
+//!
+//! Example:
+//! ```toml
+//! dfdx = { version = "...", features = ["gguf"] }
+//! ```
+//!
 //! # "nightly"
 //!
 //! Enables using all features that currently require the nightly rust compiler.
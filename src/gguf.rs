@@ -0,0 +1,409 @@
+//! Exporting models to [GGUF](https://github.com/ggerganov/ggml/blob/master/docs/gguf.md) files,
+//! the format used by llama.cpp and other ggml based runtimes.
+//!
+//! [save_gguf()] writes every parameter of a model, optionally quantized to `f16` or `Q8_0`:
+//!
+//! ```rust
+//! # use dfdx::prelude::*;
+//! # use dfdx::gguf::*;
+//! # let dir = tempfile::tempdir().unwrap();
+//! # let path = dir.path().join("model.gguf");
+//! let model: (Linear<64, 32>, ReLU, Linear<32, 2>) = Default::default();
+//! let mut options = GgufOptions {
+//!     quantization: Quantization::Q8_0,
+//!     ..Default::default()
+//! };
+//! options.metadata.insert("general.name".into(), "example".into());
+//! save_gguf(path, &model, &options).unwrap();
+//! ```
+//!
+//! Parameters are named the same as [VisitParams] names them, and `general.architecture` is `dfdx`
+//! unless it is set in [GgufOptions::metadata]. Runtimes expect the tensor names & metadata
+//! of the architectures they implement, e.g. `blk.0.attn_q.weight` and `llama.block_count`,
+//! so use [save_gguf_with()] to rename parameters, and add the metadata the runtime needs.
+//!
+//! Requires the "gguf" feature.
+
+use crate::arrays::HasShape;
+use crate::nn::{ParamVisitor, VisitParams};
+use crate::prelude::*;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::{string::String, vec::Vec};
+
+const MAGIC: &[u8; 4] = b"GGUF";
+const VERSION: u32 = 3;
+const ALIGNMENT: usize = 32;
+
+/// The number of values in each block of [Quantization::Q8_0].
+const Q8_0_BLOCK: usize = 32;
+
+const TYPE_UINT32: u32 = 4;
+const TYPE_STRING: u32 = 8;
+
+/// How parameters with 2 or more dimensions are stored. Parameters with 1 dimension, like biases,
+/// are always stored as `f32`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Quantization {
+    /// No quantization.
+    #[default]
+    F32,
+
+    /// Half precision floats.
+    F16,
+
+    /// Blocks of 32 `i8` values that share an `f16` scale. Parameters whose last dimension isn't
+    /// a multiple of 32 are stored as `f32`.
+    Q8_0,
+}
+
+impl Quantization {
+    /// The ggml type of a parameter with `shape`.
+    fn ggml_type(&self, shape: &[usize]) -> GgmlType {
+        match self {
+            _ if shape.len() < 2 => GgmlType::F32,
+            Quantization::F32 => GgmlType::F32,
+            Quantization::F16 => GgmlType::F16,
+            Quantization::Q8_0 if shape[shape.len() - 1].is_multiple_of(Q8_0_BLOCK) => {
+                GgmlType::Q8_0
+            }
+            Quantization::Q8_0 => GgmlType::F32,
+        }
+    }
+
+    /// The value of `general.file_type`.
+    fn file_type(&self) -> u32 {
+        match self {
+            Quantization::F32 => 0,
+            Quantization::F16 => 1,
+            Quantization::Q8_0 => 7,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GgmlType {
+    F32 = 0,
+    F16 = 1,
+    Q8_0 = 8,
+}
+
+/// Options for [save_gguf()].
+#[derive(Debug, Clone, Default)]
+pub struct GgufOptions {
+    /// How to store parameters. Defaults to [Quantization::F32].
+    pub quantization: Quantization,
+
+    /// String metadata to add to the file, like `general.name`.
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// Saves every parameter of `model` to a GGUF file at `path`. See [save_gguf_with()] to rename
+/// the parameters.
+pub fn save_gguf<M, P>(path: P, model: &M, options: &GgufOptions) -> io::Result<()>
+where
+    M: VisitParams,
+    P: AsRef<Path>,
+{
+    save_gguf_with(path, model, options, |name| name.into())
+}
+
+/// Saves every parameter `name` of `model` as a tensor named `name_map(name)` to a GGUF file at `path`.
+///
+/// Example:
+/// ```ignore
+/// # use dfdx::prelude::*;
+/// # use dfdx::gguf::*;
+/// let model: (Linear<64, 32>, ReLU, Linear<32, 2>) = Default::default();
+/// save_gguf_with("model.gguf", &model, &Default::default(), |name| format!("fc.{name}"))?;
+/// ```
+pub fn save_gguf_with<M, P, F>(
+    path: P,
+    model: &M,
+    options: &GgufOptions,
+    name_map: F,
+) -> io::Result<()>
+where
+    M: VisitParams,
+    P: AsRef<Path>,
+    F: FnMut(&str) -> String,
+{
+    let mut f = BufWriter::new(File::create(path)?);
+    write_gguf(&mut f, model, options, name_map)?;
+    f.flush()
+}
+
+/// Writes every parameter `name` of `model` as a tensor named `name_map(name)` in the GGUF format to `w`.
+pub fn write_gguf<M, W, F>(
+    w: &mut W,
+    model: &M,
+    options: &GgufOptions,
+    name_map: F,
+) -> io::Result<()>
+where
+    M: VisitParams,
+    W: Write,
+    F: FnMut(&str) -> String,
+{
+    let mut collector = Collect {
+        name_map,
+        params: Vec::new(),
+    };
+    model.visit_params(&mut collector);
+
+    let mut metadata: BTreeMap<&str, Metadata> = BTreeMap::new();
+    metadata.insert("general.architecture", Metadata::String("dfdx"));
+    metadata.insert("general.alignment", Metadata::U32(ALIGNMENT as u32));
+    let file_type = options.quantization.file_type();
+    metadata.insert("general.file_type", Metadata::U32(file_type));
+    if options.quantization == Quantization::Q8_0 {
+        metadata.insert("general.quantization_version", Metadata::U32(2));
+    }
+    for (key, value) in options.metadata.iter() {
+        metadata.insert(key, Metadata::String(value));
+    }
+
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&VERSION.to_le_bytes());
+    out.extend_from_slice(&(collector.params.len() as u64).to_le_bytes());
+    out.extend_from_slice(&(metadata.len() as u64).to_le_bytes());
+    for (key, value) in metadata.iter() {
+        write_str(&mut out, key);
+        match value {
+            Metadata::U32(v) => {
+                out.extend_from_slice(&TYPE_UINT32.to_le_bytes());
+                out.extend_from_slice(&v.to_le_bytes());
+            }
+            Metadata::String(v) => {
+                out.extend_from_slice(&TYPE_STRING.to_le_bytes());
+                write_str(&mut out, v);
+            }
+        }
+    }
+
+    let mut data = Vec::new();
+    for (name, shape, values) in collector.params.iter() {
+        let ggml_type = options.quantization.ggml_type(shape);
+        write_str(&mut out, name);
+        out.extend_from_slice(&(shape.len() as u32).to_le_bytes());
+        // ggml lists dimensions from the fastest changing to the slowest
+        for &dim in shape.iter().rev() {
+            out.extend_from_slice(&(dim as u64).to_le_bytes());
+        }
+        out.extend_from_slice(&(ggml_type as u32).to_le_bytes());
+        out.extend_from_slice(&(data.len() as u64).to_le_bytes());
+
+        match ggml_type {
+            GgmlType::F32 => data.extend(values.iter().flat_map(|v| v.to_le_bytes())),
+            GgmlType::F16 => data.extend(values.iter().flat_map(|v| f32_to_f16(*v).to_le_bytes())),
+            GgmlType::Q8_0 => {
+                for block in values.chunks(Q8_0_BLOCK) {
+                    write_q8_0_block(&mut data, block);
+                }
+            }
+        }
+        pad(&mut data);
+    }
+    pad(&mut out);
+
+    w.write_all(&out)?;
+    w.write_all(&data)
+}
+
+enum Metadata<'a> {
+    U32(u32),
+    String(&'a str),
+}
+
+/// Collects the name, shape, and values of every parameter.
+struct Collect<F> {
+    name_map: F,
+    params: Vec<(String, Vec<usize>, Vec<f32>)>,
+}
+
+impl<F: FnMut(&str) -> String> ParamVisitor for Collect<F> {
+    fn visit<T: Tensor<Dtype = f32>>(&mut self, name: &str, param: &T) {
+        let name = (self.name_map)(name);
+        self.params.push((name, T::Array::shape(), param.to_vec()));
+    }
+
+    fn visit_frozen<T: Tensor<Dtype = f32>>(&mut self, name: &str, param: &T) {
+        self.visit(name, param);
+    }
+}
+
+fn write_str(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(&(s.len() as u64).to_le_bytes());
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn pad(out: &mut Vec<u8>) {
+    let len = out.len().div_ceil(ALIGNMENT) * ALIGNMENT;
+    out.resize(len, 0);
+}
+
+/// Writes the `f16` scale `max(|x|) / 127`, and then each value divided by the scale as an `i8`.
+fn write_q8_0_block(out: &mut Vec<u8>, block: &[f32]) {
+    let amax = block.iter().fold(0.0f32, |m, v| m.max(v.abs()));
+    let scale = amax / 127.0;
+    let inv_scale = if scale == 0.0 { 0.0 } else { 1.0 / scale };
+    out.extend_from_slice(&f32_to_f16(scale).to_le_bytes());
+    out.extend(
+        block
+            .iter()
+            .map(|v| num_traits::Float::round(v * inv_scale) as i8 as u8),
+    );
+}
+
+/// Converts to the bits of the nearest `f16`, rounding ties to even.
+fn f32_to_f16(x: f32) -> u16 {
+    let bits = x.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32;
+    let man = bits & 0x7f_ffff;
+    if exp == 0xff {
+        let nan = if man != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+
+    let exp = exp - 127 + 15;
+    if exp >= 0x1f {
+        return sign | 0x7c00;
+    }
+    let (half, shift) = if exp <= 0 {
+        // subnormal
+        if exp < -10 {
+            return sign;
+        }
+        let shift = (14 - exp) as u32;
+        ((man | 0x80_0000) >> shift, shift)
+    } else {
+        (((exp as u32) << 10) | (man >> 13), 13)
+    };
+    let rem = (man | 0x80_0000) & ((1 << shift) - 1);
+    let halfway = 1 << (shift - 1);
+    let round_up = rem > halfway || (rem == halfway && half & 1 == 1);
+    // rounding up can carry into the exponent, which is still correct
+    sign | (half + round_up as u32) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u32_at(b: &[u8], i: usize) -> u32 {
+        u32::from_le_bytes(b[i..i + 4].try_into().unwrap())
+    }
+
+    fn u64_at(b: &[u8], i: usize) -> u64 {
+        u64::from_le_bytes(b[i..i + 8].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_f32_to_f16() {
+        assert_eq!(f32_to_f16(0.0), 0x0000);
+        assert_eq!(f32_to_f16(-0.0), 0x8000);
+        assert_eq!(f32_to_f16(1.0), 0x3c00);
+        assert_eq!(f32_to_f16(-2.0), 0xc000);
+        assert_eq!(f32_to_f16(0.1), 0x2e66);
+        assert_eq!(f32_to_f16(65504.0), 0x7bff);
+        assert_eq!(f32_to_f16(1e6), 0x7c00);
+        assert_eq!(f32_to_f16(f32::NEG_INFINITY), 0xfc00);
+        assert_eq!(f32_to_f16(f32::NAN) & 0x7e00, 0x7e00);
+        assert_eq!(f32_to_f16(5.960_464_5e-8), 0x0001);
+        assert_eq!(f32_to_f16(6.097_555e-5), 0x03ff);
+        assert_eq!(f32_to_f16(1e-9), 0x0000);
+        // halfway between 1.0 and the next f16, rounds to even
+        assert_eq!(f32_to_f16(1.0 + 1.0 / 2048.0), 0x3c00);
+        assert_eq!(f32_to_f16(1.0 + 3.0 / 2048.0), 0x3c02);
+    }
+
+    #[test]
+    fn test_q8_0_block() {
+        let mut block = [0.0f32; 32];
+        block[0] = 1.27;
+        block[1] = -0.5;
+        block[2] = 0.004;
+        let mut out = Vec::new();
+        write_q8_0_block(&mut out, &block);
+        assert_eq!(out.len(), 34);
+        assert_eq!(u16::from_le_bytes([out[0], out[1]]), f32_to_f16(0.01));
+        assert_eq!(&out[2..5], &[127, (-50i8) as u8, 0]);
+        assert!(out[5..].iter().all(|&q| q == 0));
+
+        let mut out = Vec::new();
+        write_q8_0_block(&mut out, &[0.0; 32]);
+        assert!(out.iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_write_gguf() {
+        let mut model: (Linear<2, 3>, ReLU) = Default::default();
+        model.0.weight = tensor([[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]);
+        model.0.bias = tensor([-1.0, 0.0, 1.0]);
+        let mut options: GgufOptions = Default::default();
+        options
+            .metadata
+            .insert("general.name".into(), "test".into());
+
+        let mut b = Vec::new();
+        write_gguf(&mut b, &model, &options, |name| std::format!("fc.{name}")).unwrap();
+        assert_eq!(&b[..4], b"GGUF");
+        assert_eq!(u32_at(&b, 4), 3);
+        assert_eq!(u64_at(&b, 8), 2);
+        assert_eq!(u64_at(&b, 16), 4);
+
+        // the first key is general.alignment
+        assert_eq!(u64_at(&b, 24), 17);
+        assert_eq!(&b[32..49], b"general.alignment");
+        assert_eq!(u32_at(&b, 49), TYPE_UINT32);
+        assert_eq!(u32_at(&b, 53), 32);
+
+        let weight = b.windows(11).position(|w| w == b"fc.0.weight").unwrap();
+        let i = weight + 11;
+        assert_eq!(u32_at(&b, i), 2);
+        assert_eq!(u64_at(&b, i + 4), 2);
+        assert_eq!(u64_at(&b, i + 12), 3);
+        assert_eq!(u32_at(&b, i + 20), GgmlType::F32 as u32);
+        assert_eq!(u64_at(&b, i + 24), 0);
+
+        let bias = b.windows(9).position(|w| w == b"fc.0.bias").unwrap();
+        let i = bias + 9;
+        assert_eq!(u32_at(&b, i), 1);
+        assert_eq!(u64_at(&b, i + 4), 3);
+        assert_eq!(u64_at(&b, i + 16), 32);
+
+        let data = i + 24 + (ALIGNMENT - (i + 24) % ALIGNMENT) % ALIGNMENT;
+        assert_eq!(b.len(), data + 64);
+        let floats: Vec<f32> = b[data..]
+            .chunks(4)
+            .map(|c| f32::from_le_bytes(c.try_into().unwrap()))
+            .collect();
+        assert_eq!(&floats[..6], &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(&floats[8..11], &[-1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_quantized_types() {
+        let model: (Linear<32, 2>, Linear<2, 3>) = Default::default();
+        let options = GgufOptions {
+            quantization: Quantization::Q8_0,
+            ..Default::default()
+        };
+        let mut b = Vec::new();
+        write_gguf(&mut b, &model, &options, |name| name.into()).unwrap();
+        let type_of = |name: &[u8], n_dims: usize| {
+            let i = b.windows(name.len()).position(|w| w == name).unwrap() + name.len();
+            u32_at(&b, i + 4 + 8 * n_dims)
+        };
+        assert_eq!(type_of(b"0.weight", 2), GgmlType::Q8_0 as u32);
+        assert_eq!(type_of(b"0.bias", 1), GgmlType::F32 as u32);
+        assert_eq!(type_of(b"1.weight", 2), GgmlType::F32 as u32);
+
+        assert_eq!(Quantization::F16.ggml_type(&[3, 2]), GgmlType::F16);
+        assert_eq!(Quantization::F16.ggml_type(&[3]), GgmlType::F32);
+    }
+}
@@ -1,16 +1,19 @@
 //! Implementations of [GradientTape] and generic Nd array containers via [Gradients].
 
-use std::collections::HashMap;
+use std::any::{Any, TypeId};
+use std::collections::{HashMap, HashSet};
 use std::{boxed::Box, vec::Vec};
 
-use crate::arrays::{HasArrayData, HasArrayType};
-use crate::devices::{AllocateZeros, HasDevice};
-use crate::unique_id::{HasUniqueId, UniqueId};
+use crate::arrays::{CountElements, HasArrayData, HasArrayType};
+use crate::devices::{AllocateZeros, FillElements, HasDevice};
+use crate::nn::{ParamVisitor, VisitParams};
+use crate::tensor::Tensor;
+use crate::unique_id::{unique_id, HasUniqueId, UniqueId};
 
 /// Records gradient computations to execute later.
 ///
 /// The only two things you can do with this are:
-/// 1. Adding an operation (an operation is a FnOnce that acts on &mut [Gradients])
+/// 1. Adding an operation (an operation is a FnMut that acts on &mut [Gradients])
 /// 2. Executing all the operations to produce [Gradients]
 ///
 /// The reason for this design, which forces users to specify gradient computations, as opposed to having
@@ -37,10 +40,32 @@ use crate::unique_id::{HasUniqueId, UniqueId};
 /// 2. We can combine computing the derivative and multiplying by the `gradient(result)` by just setting `t` to `-gradient(result)`
 ///
 /// This would not be possible if these chain rule operations were inside of GradientTape!
+///
+/// Operations must be [Send], so tensors with an [OwnedTape] can be moved to other threads,
+/// e.g. to run the forward & backward pass of separate batches in parallel.
+///
+/// Each operation is stored with a [UniqueId] from when it was added, and [GradientTape::append()]
+/// keeps them sorted by it. An operation is always added after the operations that produced its inputs,
+/// so running them in reverse is correct no matter which order tapes are merged in.
+///
+/// Operations can also record the ids of the tensors they add gradients into and read gradients from,
+/// which lets [crate::tensor_ops::backward_wrt()] skip the ones that can't affect the gradients it returns.
 #[derive(Default)]
-#[allow(clippy::type_complexity)]
 pub struct GradientTape {
-    operations: Vec<Box<dyn FnOnce(&mut Gradients)>>,
+    operations: Vec<BackwardOp>,
+}
+
+/// An operation on a [GradientTape], along with the ids of the tensors that it adds gradients into
+/// and reads gradients from, if they are known.
+struct BackwardOp {
+    id: UniqueId,
+    ids: Option<OpIds>,
+    operation: Box<dyn FnMut(&mut Gradients) + Send>,
+}
+
+struct OpIds {
+    inputs: Vec<UniqueId>,
+    outputs: Vec<UniqueId>,
 }
 
 impl std::fmt::Debug for GradientTape {
@@ -56,27 +81,119 @@ impl GradientTape {
     /// but the operation should likely call [Gradients::ref_gradient] and [Gradients::mut_gradient].
     ///
     /// # Arguments
-    /// * `operation` - A FnOnce that acts on [Gradients].
+    /// * `operation` - A FnMut that acts on [Gradients]. It runs once per execution of the tape,
+    ///   which can be more than once with [GradientTape::execute_retained()].
     ///
     /// See src/tensor_ops for implementation examples.
-    pub(crate) fn add_backward_op<F: 'static + FnOnce(&mut Gradients)>(&mut self, operation: F) {
-        self.operations.push(Box::new(operation));
+    #[track_caller]
+    pub(crate) fn add_backward_op<F: 'static + Send + FnMut(&mut Gradients)>(
+        &mut self,
+        operation: F,
+    ) {
+        self.push(None, operation);
+    }
+
+    /// Same as [GradientTape::add_backward_op()], but also records that `operation` only adds
+    /// gradients into `inputs`, and only reads the gradients of `outputs`.
+    #[track_caller]
+    pub(crate) fn add_backward_op_with_ids<F: 'static + Send + FnMut(&mut Gradients)>(
+        &mut self,
+        inputs: &[UniqueId],
+        outputs: &[UniqueId],
+        operation: F,
+    ) {
+        let ids = OpIds {
+            inputs: inputs.to_vec(),
+            outputs: outputs.to_vec(),
+        };
+        self.push(Some(ids), operation);
+    }
+
+    #[track_caller]
+    fn push<F: 'static + Send + FnMut(&mut Gradients)>(
+        &mut self,
+        ids: Option<OpIds>,
+        mut operation: F,
+    ) {
+        let operation: Box<dyn FnMut(&mut Gradients) + Send> = {
+            #[cfg(feature = "std")]
+            if crate::profiler::is_profiling() {
+                let location = std::panic::Location::caller();
+                crate::profiler::record_op(location);
+                Box::new(move |grads: &mut Gradients| {
+                    let start = std::time::Instant::now();
+                    operation(grads);
+                    crate::profiler::record_backward(location, start.elapsed());
+                })
+            } else {
+                Box::new(operation)
+            }
+            #[cfg(not(feature = "std"))]
+            Box::new(operation)
+        };
+        self.operations.push(BackwardOp {
+            id: unique_id(),
+            ids,
+            operation,
+        });
     }
 
     /// Compute the [Gradients]! This just runs all the operations on a new [Gradients] struct.
     ///
     /// Note that this method takes ownership of self, so it can't be called twice!
-    pub fn execute(mut self) -> Gradients {
+    pub fn execute(self) -> Gradients {
         let mut gradients: Gradients = Default::default();
-        for operation in self.operations.drain(..).rev() {
-            (operation)(&mut gradients);
-        }
+        self.execute_into(&mut gradients);
         gradients
     }
 
-    /// Moves all the operations from `other` into self. Leaves `other` empty.
+    /// Runs all the operations on `gradients`, which re-uses any allocations
+    /// left in it by [Gradients::clear()].
+    pub fn execute_into(mut self, gradients: &mut Gradients) {
+        for mut op in self.operations.drain(..).rev() {
+            (op.operation)(gradients);
+        }
+    }
+
+    /// Same as [GradientTape::execute_into()], but skips the operations that can't change the
+    /// gradients of `wrt`, because none of their inputs depend on any of `wrt`.
+    ///
+    /// If any operation was added without ids, all operations are run.
+    pub(crate) fn execute_wrt(mut self, gradients: &mut Gradients, wrt: &HashSet<UniqueId>) {
+        // operations are sorted by when they were added, so this sees the producer of a tensor before its users.
+        let mut depends_on_wrt = wrt.clone();
+        let mut needed = Vec::with_capacity(self.operations.len());
+        for op in self.operations.iter() {
+            let ids = match &op.ids {
+                Some(ids) => ids,
+                None => return self.execute_into(gradients),
+            };
+            let is_needed = ids.inputs.iter().any(|id| depends_on_wrt.contains(id));
+            if is_needed {
+                depends_on_wrt.extend(ids.outputs.iter().copied());
+            }
+            needed.push(is_needed);
+        }
+        for (mut op, is_needed) in self.operations.drain(..).zip(needed).rev() {
+            if is_needed {
+                (op.operation)(gradients);
+            }
+        }
+    }
+
+    /// Same as [GradientTape::execute_into()], but keeps the operations, so the tape can be
+    /// executed again, e.g. from a different head of the same forward pass. See [crate::tensor_ops::backward_heads()].
+    pub fn execute_retained(&mut self, gradients: &mut Gradients) {
+        for op in self.operations.iter_mut().rev() {
+            (op.operation)(gradients);
+        }
+    }
+
+    /// Moves all the operations from `other` into self, in the order they were added. Leaves `other` empty.
     pub fn append(&mut self, other: &mut Self) {
         self.operations.append(&mut other.operations);
+        // both are already sorted, which the stable sort merges in linear time
+        self.operations.sort_by_key(|op| op.id);
     }
 }
 
@@ -93,19 +210,43 @@ pub struct NoneTape;
 pub trait Tape: Merge<Self> + Merge<NoneTape> + Default {
     /// Whether this object currently owns the [GradientTape]. This is known at compile time.
     const OWNS_TAPE: bool;
-    fn add_backward_op<F: 'static + FnOnce(&mut Gradients)>(&mut self, operation: F);
+    fn add_backward_op<F: 'static + Send + FnMut(&mut Gradients)>(&mut self, operation: F);
+
+    /// Same as [Tape::add_backward_op()], but also records that `operation` only adds gradients into
+    /// the tensors with ids `inputs`, and only reads the gradients of `outputs`. This lets
+    /// [crate::tensor_ops::backward_wrt()] skip it when it can't change the requested gradients.
+    fn add_backward_op_with_ids<F: 'static + Send + FnMut(&mut Gradients)>(
+        &mut self,
+        inputs: &[UniqueId],
+        outputs: &[UniqueId],
+        operation: F,
+    ) {
+        let _ = (inputs, outputs);
+        self.add_backward_op(operation)
+    }
 }
 
 impl Tape for OwnedTape {
     const OWNS_TAPE: bool = true;
-    fn add_backward_op<F: 'static + FnOnce(&mut Gradients)>(&mut self, operation: F) {
+    #[track_caller]
+    fn add_backward_op<F: 'static + Send + FnMut(&mut Gradients)>(&mut self, operation: F) {
         self.0.add_backward_op(operation)
     }
+
+    #[track_caller]
+    fn add_backward_op_with_ids<F: 'static + Send + FnMut(&mut Gradients)>(
+        &mut self,
+        inputs: &[UniqueId],
+        outputs: &[UniqueId],
+        operation: F,
+    ) {
+        self.0.add_backward_op_with_ids(inputs, outputs, operation)
+    }
 }
 
 impl Tape for NoneTape {
     const OWNS_TAPE: bool = false;
-    fn add_backward_op<F: 'static + FnOnce(&mut Gradients)>(&mut self, _operation: F) {}
+    fn add_backward_op<F: 'static + Send + FnMut(&mut Gradients)>(&mut self, _operation: F) {}
 }
 
 pub trait Merge<T: ?Sized> {
@@ -146,12 +287,54 @@ impl Merge<OwnedTape> for OwnedTape {
 /// Under the hood, it actually is a HashMap, and stores values as Box<dyn Any>. The
 /// important part of key's implementing [HasArrayType] is that the associated type
 /// of that trait is used to downcast the box to the expected value.
+///
+/// This is [Send] and [Sync], so gradients computed on different threads can be combined.
+///
+/// [Gradients::clear()] keeps the allocated arrays around to be re-used by [Gradients::mut_gradient()],
+/// which [crate::tensor_ops::backward_into()] uses to avoid allocating all gradients every training step.
 #[derive(Debug, Default)]
 pub struct Gradients {
-    gradient_by_id: HashMap<UniqueId, Box<dyn std::any::Any>>,
+    gradient_by_id: HashMap<UniqueId, Box<dyn Any + Send + Sync>>,
+    unused: HashMap<TypeId, Vec<Box<dyn Any + Send + Sync>>>,
 }
 
 impl Gradients {
+    /// Removes all gradients, but keeps their allocations, so they can be re-used by
+    /// [Gradients::mut_gradient()] for any tensor with the same array type.
+    ///
+    /// Example usage:
+    /// ```
+    /// # use dfdx::{prelude::*, gradients::*};
+    /// let t = Tensor1D::new([1.0, 2.0, 3.0]);
+    /// let mut gradients: Gradients = Default::default();
+    /// *gradients.mut_gradient(&t) = [-4.0, 5.0, -6.0];
+    /// gradients.clear();
+    /// assert!(gradients.try_ref_gradient(&t).is_none());
+    /// assert_eq!(gradients.mut_gradient(&t), &[0.0; 3]);
+    /// ```
+    pub fn clear(&mut self) {
+        for (_, g) in self.gradient_by_id.drain() {
+            self.unused.entry((*g).type_id()).or_default().push(g);
+        }
+    }
+
+    /// Removes the gradients of all tensors except `ids`. See [crate::tensor_ops::backward_wrt()].
+    pub(crate) fn retain_ids(&mut self, ids: &HashSet<UniqueId>) {
+        self.gradient_by_id.retain(|id, _| ids.contains(id));
+    }
+
+    /// Moves the gradients of all of `model`'s parameters into a new [Gradients],
+    /// leaving the rest in `self`. This is useful to pass only the parameter gradients
+    /// to an optimizer, and keep the rest of the allocations in `self` to re-use.
+    pub fn split_params<M: VisitParams>(&mut self, model: &M) -> Gradients {
+        let mut params: Gradients = Default::default();
+        model.visit_params(&mut SplitParams {
+            src: self,
+            dst: &mut params,
+        });
+        params
+    }
+
     /// Borrows a pair of a gradients `(&mut L, &R)`.
     /// `l` is the gradient to update, and `r` is the gradient to backprop.
     ///
@@ -182,6 +365,29 @@ impl Gradients {
         (l_ref, r_ref)
     }
 
+    /// Same as [Gradients::muts_and_ref()], but with two mutable gradients.
+    #[cfg(feature = "nightly")]
+    pub(crate) fn muts2_and_ref<L1, L2, R>(
+        &mut self,
+        l1: &L1,
+        l2: &L2,
+        r: &R,
+    ) -> (&mut L1::Array, &mut L2::Array, &R::Array)
+    where
+        L1: HasUniqueId + HasArrayType + HasDevice,
+        L2: HasUniqueId + HasArrayType + HasDevice,
+        R: HasUniqueId + HasArrayType,
+    {
+        assert_ne!(l1.id(), l2.id());
+        let l1_ptr = self.mut_gradient(l1) as *mut L1::Array;
+        let l2_ptr = self.mut_gradient(l2) as *mut L2::Array;
+        let r_ptr = self.ref_gradient(r) as *const R::Array;
+        let l1_ref = unsafe { &mut *l1_ptr };
+        let l2_ref = unsafe { &mut *l2_ptr };
+        let r_ref = unsafe { &*r_ptr };
+        (l1_ref, l2_ref, r_ref)
+    }
+
     pub fn muts_and_ref<L1, L2, L3, R>(
         &mut self,
         l1: &L1,
@@ -227,8 +433,8 @@ impl Gradients {
 
     /// Returns a mutable reference to the data associated with `t`.
     ///
-    /// If no data is associated with `t`, then [AllocateZeros::zeros] is called
-    /// to allocate the data.
+    /// If no data is associated with `t`, then an array left by [Gradients::clear()] is zeroed and used,
+    /// or [AllocateZeros::zeros] is called to allocate the data.
     ///
     /// Example usage:
     /// ```
@@ -244,14 +450,65 @@ impl Gradients {
         &mut self,
         t: &T,
     ) -> &mut T::Array {
+        let unused = &mut self.unused;
         self.gradient_by_id
             .entry(*t.id())
-            .or_insert_with(|| T::Device::zeros::<T::Array>())
+            .or_insert_with(|| {
+                match unused
+                    .get_mut(&TypeId::of::<T::Array>())
+                    .and_then(|u| u.pop())
+                {
+                    Some(mut g) => {
+                        fill_zeros::<T::Array, T::Device>(g.downcast_mut().unwrap());
+                        g
+                    }
+                    None => T::Device::zeros::<T::Array>(),
+                }
+            })
             .as_mut()
             .downcast_mut()
             .unwrap()
     }
 
+    /// Returns a mutable reference to the data associated with `t`, or `None` if there
+    /// is no data associated with `t`. Unlike [Gradients::mut_gradient()], this never allocates.
+    ///
+    /// Example usage:
+    /// ```
+    /// # use dfdx::{prelude::*, gradients::*};
+    /// let t = Tensor1D::new([1.0, 2.0, 3.0]);
+    /// let mut gradients: Gradients = Default::default();
+    /// assert!(gradients.try_mut_gradient(&t).is_none());
+    /// gradients.mut_gradient(&t);
+    /// assert_eq!(gradients.try_mut_gradient(&t), Some(&mut [0.0, 0.0, 0.0]));
+    /// ```
+    pub fn try_mut_gradient<T: HasUniqueId + HasArrayType>(
+        &mut self,
+        t: &T,
+    ) -> Option<&mut T::Array> {
+        self.gradient_by_id
+            .get_mut(t.id())
+            .map(|g| g.as_mut().downcast_mut().unwrap())
+    }
+
+    /// Returns a reference to the data associated with `t`, or `None` if there
+    /// is no data associated with `t`.
+    ///
+    /// Example usage:
+    /// ```
+    /// # use dfdx::{prelude::*, gradients::*};
+    /// let t = Tensor1D::new([1.0, 2.0, 3.0]);
+    /// let mut gradients: Gradients = Default::default();
+    /// assert!(gradients.try_ref_gradient(&t).is_none());
+    /// gradients.mut_gradient(&t);
+    /// assert_eq!(gradients.try_ref_gradient(&t), Some(&[0.0, 0.0, 0.0]));
+    /// ```
+    pub fn try_ref_gradient<T: HasUniqueId + HasArrayType>(&self, t: &T) -> Option<&T::Array> {
+        self.gradient_by_id
+            .get(t.id())
+            .map(|g| g.as_ref().downcast_ref().unwrap())
+    }
+
     /// Returns a reference to the data associated with `t`.
     ///
     /// # Panics
@@ -277,6 +534,24 @@ impl Gradients {
     }
 }
 
+fn fill_zeros<A: CountElements, D: FillElements<A>>(a: &mut A) {
+    D::fill(a, &mut |v| *v = Default::default());
+}
+
+/// Moves the gradients of visited parameters from `src` to `dst`.
+struct SplitParams<'a> {
+    src: &'a mut Gradients,
+    dst: &'a mut Gradients,
+}
+
+impl<'a> ParamVisitor for SplitParams<'a> {
+    fn visit<T: Tensor<Dtype = f32>>(&mut self, _: &str, param: &T) {
+        if let Some(g) = self.src.gradient_by_id.remove(param.id()) {
+            self.dst.gradient_by_id.insert(*param.id(), g);
+        }
+    }
+}
+
 /// Represents something that can return a gradient for a given key.
 ///
 /// This is very similar to what [Gradients] does, however the intention
@@ -371,4 +646,13 @@ mod tests {
         let g = tape.execute();
         assert_eq!(g.ref_gradient(&t1), &[1.0; 5]);
     }
+
+    #[test]
+    fn test_backward_on_other_thread() {
+        use crate::prelude::{backward, tensor, Tensor1D};
+        let x: Tensor1D<3> = tensor([1.0, 2.0, 3.0]);
+        let y = x.trace().square().sum();
+        let g = std::thread::spawn(move || backward(y)).join().unwrap();
+        assert_eq!(g.ref_gradient(&x), &[2.0, 4.0, 6.0]);
+    }
 }
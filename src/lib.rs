@@ -86,6 +86,7 @@
 //!     lr: 1e-2,
 //!     momentum: Some(Momentum::Classic(0.9)),
 //!     weight_decay: None,
+//!     weight_decay_1d: true,
 //! });
 //!
 //! // pass the gradients & the model into the optimizer's update method
@@ -99,30 +100,50 @@
 extern crate alloc;
 extern crate no_std_compat as std;
 
+#[cfg(feature = "std")]
+pub mod anomaly;
 pub mod arrays;
 pub mod data;
 pub mod devices;
+pub mod distributions;
 pub mod feature_flags;
+#[cfg(feature = "gguf")]
+pub mod gguf;
 pub mod gradients;
 pub mod losses;
+pub mod metrics;
 pub mod nn;
 #[cfg(feature = "numpy")]
 pub mod numpy;
 pub mod optim;
+#[cfg(feature = "std")]
+pub mod parallel;
+#[cfg(feature = "std")]
+pub mod profiler;
+pub mod rl;
+pub mod rng;
 pub mod tensor;
 pub mod tensor_ops;
+#[cfg(feature = "tensorboard")]
+pub mod tensorboard;
+#[cfg(feature = "torch")]
+pub mod torch;
 pub mod unique_id;
 
 /// Contains all public exports.
 pub mod prelude {
     pub use crate::arrays::{AllAxes, Axes2, Axes3, Axes4, Axis, HasArrayData};
-    pub use crate::devices::HasDevice;
+    pub use crate::devices::{HasDevice, ReflectPadding, ReplicatePadding, ZeroPadding};
     pub use crate::gradients::{NoneTape, OwnedTape};
     pub use crate::losses::*;
     pub use crate::nn::*;
     pub use crate::optim::*;
+    pub use crate::rng::{default_rng, manual_seed, rand, randn};
     pub use crate::tensor::*;
     pub use crate::tensor_ops::*;
+
+    #[cfg(not(feature = "std"))]
+    pub use num_traits::Float as _;
 }
 
 /// Sets a CPU `sse` flag to flush denormal floating point numbers to zero. The opposite of this is [keep_denormals()].
@@ -2,6 +2,8 @@
 
 use crate::arrays::{AllAxes, HasArrayType, HasLastAxis};
 use crate::tensor_ops::*;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
 /// [Mean Squared Error](https://en.wikipedia.org/wiki/Mean_squared_error).
 /// This computes `(pred - targ).square().mean()`.
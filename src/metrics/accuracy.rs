@@ -0,0 +1,202 @@
+use crate::prelude::*;
+use crate::rl::argmax;
+
+/// Arrays of class scores, where the last axis is the class. `[f32; C]` is one sample
+/// with a `usize` label, and `[[f32; C]; B]` is a batch of `B` samples with a `[usize; B]` label.
+///
+/// **Not intended to be used outside of the crate.**
+pub trait ClassScores {
+    /// The type of the labels, one class index for each sample.
+    type Labels;
+
+    /// Calls `f` with the scores & label of each sample.
+    fn for_each_sample<F: FnMut(&[f32], usize)>(&self, labels: &Self::Labels, f: F);
+}
+
+impl<const C: usize> ClassScores for [f32; C] {
+    type Labels = usize;
+    fn for_each_sample<F: FnMut(&[f32], usize)>(&self, labels: &Self::Labels, mut f: F) {
+        f(self, *labels)
+    }
+}
+
+impl<const B: usize, const C: usize> ClassScores for [[f32; C]; B] {
+    type Labels = [usize; B];
+    fn for_each_sample<F: FnMut(&[f32], usize)>(&self, labels: &Self::Labels, mut f: F) {
+        for (row, &label) in self.iter().zip(labels.iter()) {
+            f(row, label)
+        }
+    }
+}
+
+/// The fraction of samples whose highest score is their label. Ties go to the first class.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::metrics::accuracy;
+/// let logits = tensor([[0.1, 2.0, -1.0], [3.0, 0.0, 0.5]]);
+/// assert_eq!(accuracy(&logits, &[1, 2]), 0.5);
+/// ```
+pub fn accuracy<T>(logits: &T, labels: &<T::Array as ClassScores>::Labels) -> f32
+where
+    T: HasArrayData,
+    T::Array: ClassScores,
+{
+    let mut acc = Accuracy::new();
+    acc.update(logits, labels);
+    acc.value()
+}
+
+/// The fraction of samples whose label is one of their `k` highest scores. Ties go to the
+/// first class, so `top_k_accuracy(logits, labels, 1)` is the same as [accuracy()].
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::metrics::top_k_accuracy;
+/// let logits = tensor([[0.1, 2.0, -1.0], [3.0, 0.0, 0.5]]);
+/// assert_eq!(top_k_accuracy(&logits, &[0, 2], 2), 1.0);
+/// ```
+pub fn top_k_accuracy<T>(logits: &T, labels: &<T::Array as ClassScores>::Labels, k: usize) -> f32
+where
+    T: HasArrayData,
+    T::Array: ClassScores,
+{
+    let mut acc = Accuracy::top_k(k);
+    acc.update(logits, labels);
+    acc.value()
+}
+
+/// Accumulates the (top-k) accuracy over many batches, e.g. of an evaluation loop.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::metrics::Accuracy;
+/// let mut acc = Accuracy::new();
+/// acc.update(&tensor([[1.0, 0.0], [0.0, 1.0]]), &[0, 0]);
+/// acc.update(&tensor([0.0, 1.0]), &1);
+/// assert_eq!(acc.value(), 2.0 / 3.0);
+/// assert_eq!(acc.count(), 3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Accuracy {
+    k: usize,
+    correct: usize,
+    total: usize,
+}
+
+impl Default for Accuracy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Accuracy {
+    /// Accumulates the top-1 accuracy, see [accuracy()].
+    pub fn new() -> Self {
+        Self::top_k(1)
+    }
+
+    /// Accumulates the top-`k` accuracy, see [top_k_accuracy()].
+    pub fn top_k(k: usize) -> Self {
+        Self {
+            k,
+            correct: 0,
+            total: 0,
+        }
+    }
+
+    /// Adds one sample or a batch of samples.
+    pub fn update<T>(&mut self, logits: &T, labels: &<T::Array as ClassScores>::Labels)
+    where
+        T: HasArrayData,
+        T::Array: ClassScores,
+    {
+        logits.data().for_each_sample(labels, |row, label| {
+            let hit = if self.k == 1 {
+                argmax(row) == label
+            } else {
+                in_top_k(row, label, self.k)
+            };
+            self.correct += hit as usize;
+            self.total += 1;
+        });
+    }
+
+    /// The accuracy of all the samples added since the last [Accuracy::reset()], or `0.0`
+    /// if there are none.
+    pub fn value(&self) -> f32 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.correct as f32 / self.total as f32
+        }
+    }
+
+    /// The number of samples added since the last [Accuracy::reset()].
+    pub fn count(&self) -> usize {
+        self.total
+    }
+
+    /// Forgets all the samples, e.g. at the start of an epoch.
+    pub fn reset(&mut self) {
+        self.correct = 0;
+        self.total = 0;
+    }
+}
+
+/// Whether `label` is in the `k` highest of `row`, where ties are ranked by index.
+fn in_top_k(row: &[f32], label: usize, k: usize) -> bool {
+    let x = row[label];
+    let rank = row
+        .iter()
+        .enumerate()
+        .filter(|&(i, &y)| y > x || (y == x && i < label))
+        .count();
+    rank < k
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accuracy() {
+        let logits = tensor([[1.0, 2.0, 3.0], [3.0, 2.0, 1.0], [0.0, 0.0, 0.0]]);
+        assert_eq!(accuracy(&logits, &[2, 0, 0]), 1.0);
+        assert_eq!(accuracy(&logits, &[2, 1, 1]), 1.0 / 3.0);
+        assert_eq!(accuracy(&tensor([0.5, -0.5]), &1), 0.0);
+    }
+
+    #[test]
+    fn test_top_k_accuracy() {
+        let logits = tensor([[1.0, 2.0, 3.0, 4.0], [4.0, 1.0, 1.0, 0.0]]);
+        assert_eq!(top_k_accuracy(&logits, &[1, 2], 1), 0.0);
+        assert_eq!(top_k_accuracy(&logits, &[1, 2], 2), 0.0);
+        assert_eq!(top_k_accuracy(&logits, &[1, 2], 3), 1.0);
+        assert_eq!(top_k_accuracy(&logits, &[0, 1], 2), 0.5);
+        assert_eq!(top_k_accuracy(&logits, &[0, 1], 4), 1.0);
+        for labels in [[0, 0], [3, 0], [2, 1]] {
+            assert_eq!(
+                top_k_accuracy(&logits, &labels, 1),
+                accuracy(&logits, &labels)
+            );
+        }
+    }
+
+    #[test]
+    fn test_accuracy_accumulator() {
+        let mut acc = Accuracy::top_k(2);
+        assert_eq!(acc.value(), 0.0);
+        acc.update(&tensor([[3.0, 2.0, 1.0], [1.0, 2.0, 3.0]]), &[1, 0]);
+        assert_eq!(acc.value(), 0.5);
+        acc.update(&tensor([0.0, 1.0, 2.0]), &1);
+        acc.update(&tensor([[0.0, 1.0, 2.0]]), &[2]);
+        assert_eq!(acc.count(), 4);
+        assert_eq!(acc.value(), 0.75);
+        acc.reset();
+        assert_eq!(acc, Accuracy::top_k(2));
+    }
+}
@@ -0,0 +1,178 @@
+use super::confusion::{f1, ratio};
+use crate::prelude::*;
+
+/// Arrays of binary classifier scores, with one score for each sample. `f32` is one sample
+/// with a `bool` label, and `[f32; B]` is a batch of `B` samples with a `[bool; B]` label.
+///
+/// **Not intended to be used outside of the crate.**
+pub trait BinaryScores {
+    /// The type of the labels, `true` for each positive sample.
+    type Labels;
+
+    /// Calls `f` with the score & label of each sample.
+    fn for_each_sample<F: FnMut(f32, bool)>(&self, labels: &Self::Labels, f: F);
+}
+
+impl BinaryScores for f32 {
+    type Labels = bool;
+    fn for_each_sample<F: FnMut(f32, bool)>(&self, labels: &Self::Labels, mut f: F) {
+        f(*self, *labels)
+    }
+}
+
+impl<const B: usize> BinaryScores for [f32; B] {
+    type Labels = [bool; B];
+    fn for_each_sample<F: FnMut(f32, bool)>(&self, labels: &Self::Labels, mut f: F) {
+        for (&score, &label) in self.iter().zip(labels.iter()) {
+            f(score, label)
+        }
+    }
+}
+
+/// The precision, recall, and F1 score (the harmonic mean of precision & recall) of a classifier.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrecisionRecallF1 {
+    pub precision: f32,
+    pub recall: f32,
+    pub f1: f32,
+}
+
+/// Accumulates the precision, recall, and F1 score of a binary classifier over many batches.
+/// A sample is predicted as positive if its score is at least `threshold`, so use `0.5` for
+/// probabilities and `0.0` for logits.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::metrics::BinaryMetrics;
+/// let mut m = BinaryMetrics::new(0.5);
+/// m.update(&tensor([0.9, 0.6, 0.2, 0.1]), &[true, false, true, false]);
+/// assert_eq!(m.precision(), 0.5);
+/// assert_eq!(m.recall(), 0.5);
+/// assert_eq!(m.f1(), 0.5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BinaryMetrics {
+    threshold: f32,
+    true_positives: usize,
+    false_positives: usize,
+    false_negatives: usize,
+    true_negatives: usize,
+}
+
+impl BinaryMetrics {
+    /// Predicts samples with a score of at least `threshold` as positive.
+    pub fn new(threshold: f32) -> Self {
+        Self {
+            threshold,
+            true_positives: 0,
+            false_positives: 0,
+            false_negatives: 0,
+            true_negatives: 0,
+        }
+    }
+
+    /// Adds one sample or a batch of samples.
+    pub fn update<T>(&mut self, scores: &T, labels: &<T::Array as BinaryScores>::Labels)
+    where
+        T: HasArrayData,
+        T::Array: BinaryScores,
+    {
+        scores
+            .data()
+            .for_each_sample(labels, |score, label| self.add(score, label));
+    }
+
+    /// Adds one sample with `score` that is positive if `label` is `true`.
+    pub fn add(&mut self, score: f32, label: bool) {
+        match (score >= self.threshold, label) {
+            (true, true) => self.true_positives += 1,
+            (true, false) => self.false_positives += 1,
+            (false, true) => self.false_negatives += 1,
+            (false, false) => self.true_negatives += 1,
+        }
+    }
+
+    /// The number of samples added since the last [BinaryMetrics::reset()].
+    pub fn count(&self) -> usize {
+        self.true_positives + self.false_positives + self.false_negatives + self.true_negatives
+    }
+
+    /// The fraction of samples predicted as positive that are positive, or `0.0` if none
+    /// were predicted as positive.
+    pub fn precision(&self) -> f32 {
+        ratio(
+            self.true_positives,
+            self.true_positives + self.false_positives,
+        )
+    }
+
+    /// The fraction of positive samples that were predicted as positive, or `0.0` if
+    /// there are none.
+    pub fn recall(&self) -> f32 {
+        ratio(
+            self.true_positives,
+            self.true_positives + self.false_negatives,
+        )
+    }
+
+    /// The harmonic mean of [BinaryMetrics::precision()] and [BinaryMetrics::recall()],
+    /// or `0.0` if both are `0.0`.
+    pub fn f1(&self) -> f32 {
+        f1(self.precision(), self.recall())
+    }
+
+    /// All of [BinaryMetrics::precision()], [BinaryMetrics::recall()] and [BinaryMetrics::f1()].
+    pub fn precision_recall_f1(&self) -> PrecisionRecallF1 {
+        PrecisionRecallF1 {
+            precision: self.precision(),
+            recall: self.recall(),
+            f1: self.f1(),
+        }
+    }
+
+    /// Forgets all the samples, e.g. at the start of an epoch.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.threshold);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+
+    #[test]
+    fn test_binary_metrics() {
+        let mut m = BinaryMetrics::new(0.0);
+        m.update(
+            &tensor([2.0, -1.0, 0.0, 1.0, -3.0]),
+            &[true, true, false, true, false],
+        );
+        m.update(&tensor(-0.5), &true);
+        assert_eq!(m.count(), 6);
+        let prf = m.precision_recall_f1();
+        assert_close(
+            &[prf.precision, prf.recall, prf.f1],
+            &[2.0 / 3.0, 0.5, 4.0 / 7.0],
+        );
+        assert_eq!(
+            (m.precision(), m.recall(), m.f1()),
+            (prf.precision, prf.recall, prf.f1)
+        );
+        m.reset();
+        assert_eq!(m, BinaryMetrics::new(0.0));
+    }
+
+    #[test]
+    fn test_binary_metrics_threshold() {
+        let scores = tensor([0.1, 0.4, 0.6, 0.9]);
+        let labels = [false, true, true, true];
+        let mut m = BinaryMetrics::new(0.5);
+        m.update(&scores, &labels);
+        assert_eq!((m.precision(), m.recall()), (1.0, 2.0 / 3.0));
+        let mut m = BinaryMetrics::new(0.95);
+        m.update(&scores, &labels);
+        assert_eq!((m.precision(), m.recall(), m.f1()), (0.0, 0.0, 0.0));
+    }
+}
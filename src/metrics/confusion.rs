@@ -0,0 +1,188 @@
+use super::{ClassScores, PrecisionRecallF1};
+use crate::prelude::*;
+use crate::rl::argmax;
+
+/// Accumulates a confusion matrix over many batches of a classifier with `C` classes,
+/// where `matrix()[label][prediction]` is the number of samples of class `label` that
+/// were predicted as class `prediction`. The prediction of a sample is the class with
+/// the highest score, like [super::accuracy()].
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::metrics::ConfusionMatrix;
+/// let mut cm: ConfusionMatrix<2> = Default::default();
+/// cm.update(&tensor([[0.9, 0.1], [0.2, 0.8], [0.6, 0.4]]), &[0, 1, 1]);
+/// assert_eq!(cm.matrix(), &[[1, 0], [1, 1]]);
+/// assert_eq!(cm.precision(0), 0.5);
+/// assert_eq!(cm.recall(1), 0.5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfusionMatrix<const C: usize> {
+    matrix: [[usize; C]; C],
+}
+
+impl<const C: usize> Default for ConfusionMatrix<C> {
+    fn default() -> Self {
+        Self {
+            matrix: [[0; C]; C],
+        }
+    }
+}
+
+impl<const C: usize> ConfusionMatrix<C> {
+    /// Adds the predictions of one sample or a batch of samples.
+    pub fn update<T>(&mut self, logits: &T, labels: &<T::Array as ClassScores>::Labels)
+    where
+        T: HasArrayData,
+        T::Array: ClassScores,
+    {
+        logits
+            .data()
+            .for_each_sample(labels, |row, label| self.add(argmax(row), label));
+    }
+
+    /// Adds one sample of class `label` that was predicted as class `prediction`.
+    pub fn add(&mut self, prediction: usize, label: usize) {
+        self.matrix[label][prediction] += 1;
+    }
+
+    /// The counts, where `matrix()[label][prediction]` is the number of samples of class `label`
+    /// that were predicted as class `prediction`.
+    pub fn matrix(&self) -> &[[usize; C]; C] {
+        &self.matrix
+    }
+
+    /// The number of samples added since the last [ConfusionMatrix::reset()].
+    pub fn count(&self) -> usize {
+        self.matrix.iter().flatten().sum()
+    }
+
+    /// The fraction of samples that were predicted as `class` and are of `class`, or `0.0` if
+    /// none were predicted as `class`.
+    pub fn precision(&self, class: usize) -> f32 {
+        let predicted: usize = self.matrix.iter().map(|row| row[class]).sum();
+        ratio(self.matrix[class][class], predicted)
+    }
+
+    /// The fraction of samples of `class` that were predicted as `class`, or `0.0` if
+    /// there are none of `class`.
+    pub fn recall(&self, class: usize) -> f32 {
+        let actual: usize = self.matrix[class].iter().sum();
+        ratio(self.matrix[class][class], actual)
+    }
+
+    /// The [ConfusionMatrix::precision()] of every class.
+    pub fn precisions(&self) -> [f32; C] {
+        core::array::from_fn(|c| self.precision(c))
+    }
+
+    /// The [ConfusionMatrix::recall()] of every class.
+    pub fn recalls(&self) -> [f32; C] {
+        core::array::from_fn(|c| self.recall(c))
+    }
+
+    /// The harmonic mean of [ConfusionMatrix::precision()] and [ConfusionMatrix::recall()]
+    /// of `class`, or `0.0` if both are `0.0`.
+    pub fn f1(&self, class: usize) -> f32 {
+        f1(self.precision(class), self.recall(class))
+    }
+
+    /// The [ConfusionMatrix::f1()] of every class.
+    pub fn f1s(&self) -> [f32; C] {
+        core::array::from_fn(|c| self.f1(c))
+    }
+
+    /// The unweighted mean of the precision, recall & F1 of each class, so every class
+    /// counts the same regardless of how many samples it has.
+    pub fn macro_average(&self) -> PrecisionRecallF1 {
+        let mean = |x: [f32; C]| x.iter().sum::<f32>() / C as f32;
+        PrecisionRecallF1 {
+            precision: mean(self.precisions()),
+            recall: mean(self.recalls()),
+            f1: mean(self.f1s()),
+        }
+    }
+
+    /// The precision, recall & F1 of the true/false positives/negatives summed over all
+    /// classes, so every sample counts the same. Since each sample has exactly one label
+    /// and one prediction, these are all equal to the accuracy.
+    pub fn micro_average(&self) -> PrecisionRecallF1 {
+        let correct = (0..C).map(|c| self.matrix[c][c]).sum();
+        let accuracy = ratio(correct, self.count());
+        PrecisionRecallF1 {
+            precision: accuracy,
+            recall: accuracy,
+            f1: f1(accuracy, accuracy),
+        }
+    }
+
+    /// Forgets all the samples, e.g. at the start of an epoch.
+    pub fn reset(&mut self) {
+        self.matrix = [[0; C]; C];
+    }
+}
+
+pub(super) fn ratio(numerator: usize, denominator: usize) -> f32 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f32 / denominator as f32
+    }
+}
+
+/// The harmonic mean of `precision` and `recall`, or `0.0` if both are `0.0`.
+pub(super) fn f1(precision: f32, recall: f32) -> f32 {
+    if precision + recall == 0.0 {
+        0.0
+    } else {
+        2.0 * precision * recall / (precision + recall)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+
+    #[test]
+    fn test_confusion_matrix() {
+        let mut cm: ConfusionMatrix<3> = Default::default();
+        cm.update(
+            &tensor([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]),
+            &[0, 0, 2],
+        );
+        cm.update(&tensor([0.0, 2.0, 1.0]), &1);
+        cm.add(0, 2);
+        assert_eq!(cm.matrix(), &[[1, 1, 0], [0, 1, 0], [1, 0, 1]]);
+        assert_eq!(cm.count(), 5);
+        assert_eq!(cm.precisions(), [0.5, 0.5, 1.0]);
+        assert_eq!(cm.recalls(), [0.5, 1.0, 0.5]);
+        cm.reset();
+        assert_eq!(cm, Default::default());
+    }
+
+    #[test]
+    fn test_confusion_matrix_averages() {
+        let mut cm: ConfusionMatrix<3> = Default::default();
+        for (prediction, label) in [(0, 0), (0, 0), (1, 0), (1, 1), (0, 2), (2, 2)] {
+            cm.add(prediction, label);
+        }
+        assert_close(&cm.f1s(), &[2.0 / 3.0, 2.0 / 3.0, 2.0 / 3.0]);
+        let m = cm.macro_average();
+        assert_close(
+            &[m.precision, m.recall, m.f1],
+            &[13.0 / 18.0, 13.0 / 18.0, 2.0 / 3.0],
+        );
+        let m = cm.micro_average();
+        assert_close(&[m.precision, m.recall, m.f1], &[2.0 / 3.0; 3]);
+    }
+
+    #[test]
+    fn test_confusion_matrix_empty_class() {
+        let mut cm: ConfusionMatrix<3> = Default::default();
+        cm.update(&tensor([[2.0, 1.0, 0.0], [1.0, 2.0, 0.0]]), &[0, 0]);
+        assert_eq!(cm.precisions(), [1.0, 0.0, 0.0]);
+        assert_eq!(cm.recalls(), [0.5, 0.0, 0.0]);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::arrays::{AllAxes, HasArrayType, HasLastAxis};
+use crate::prelude::*;
+
+/// The mean of all the values added since the last [RunningAverage::reset()], e.g. of the loss
+/// values returned by [crate::losses] over an epoch.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::metrics::RunningAverage;
+/// let mut avg = RunningAverage::default();
+/// avg.update(&mse_loss(tensor([1.0, 2.0]), tensor([1.0, 0.0])));
+/// avg.add(4.0);
+/// assert_eq!(avg.value(), 3.0);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RunningAverage {
+    sum: f64,
+    count: usize,
+}
+
+impl RunningAverage {
+    /// Adds the value of a loss, or any other [Tensor0D].
+    pub fn update<T: HasArrayData<Array = f32>>(&mut self, loss: &T) {
+        self.add(*loss.data());
+    }
+
+    /// Adds `value`.
+    pub fn add(&mut self, value: f32) {
+        self.sum += value as f64;
+        self.count += 1;
+    }
+
+    /// The mean of the added values, or `0.0` if there are none.
+    pub fn value(&self) -> f32 {
+        if self.count == 0 {
+            0.0
+        } else {
+            (self.sum / self.count as f64) as f32
+        }
+    }
+
+    /// The [perplexity()] of the mean, when the added values are
+    /// [cross_entropy_with_logits_loss()]es.
+    pub fn perplexity(&self) -> f32 {
+        self.value().exp()
+    }
+
+    /// The number of values added since the last [RunningAverage::reset()].
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Forgets all the values, e.g. at the start of an epoch.
+    pub fn reset(&mut self) {
+        *self = Default::default();
+    }
+}
+
+/// An exponential moving average of the added values, which follows recent values
+/// like the loss of the last few batches, and is less noisy than the values themselves.
+/// `decay` is how much of the average is kept for each added value, so larger is smoother.
+///
+/// The average is bias corrected like in [Adam], so the first values aren't pulled towards `0.0`.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::metrics::ExponentialAverage;
+/// let mut avg = ExponentialAverage::new(0.9);
+/// avg.update(&tensor(2.0));
+/// assert_eq!(avg.value(), 2.0);
+/// avg.add(4.0);
+/// assert!(avg.value() > 3.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExponentialAverage {
+    decay: f32,
+    average: f32,
+    correction: f32,
+}
+
+impl ExponentialAverage {
+    /// Keeps `decay` of the average for each added value. Should be in `[0.0, 1.0)`.
+    pub fn new(decay: f32) -> Self {
+        Self {
+            decay,
+            average: 0.0,
+            correction: 1.0,
+        }
+    }
+
+    /// Adds the value of a loss, or any other [Tensor0D].
+    pub fn update<T: HasArrayData<Array = f32>>(&mut self, loss: &T) {
+        self.add(*loss.data());
+    }
+
+    /// Adds `value`.
+    pub fn add(&mut self, value: f32) {
+        self.average = self.decay * self.average + (1.0 - self.decay) * value;
+        self.correction *= self.decay;
+    }
+
+    /// The bias corrected average, or `0.0` if nothing was added.
+    pub fn value(&self) -> f32 {
+        if self.correction == 1.0 {
+            0.0
+        } else {
+            self.average / (1.0 - self.correction)
+        }
+    }
+
+    /// The [perplexity()] of the average, when the added values are
+    /// [cross_entropy_with_logits_loss()]es.
+    pub fn perplexity(&self) -> f32 {
+        self.value().exp()
+    }
+
+    /// Forgets all the values.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.decay);
+    }
+}
+
+/// [Perplexity](https://en.wikipedia.org/wiki/Perplexity) of a language model, which is the
+/// exponential of [cross_entropy_with_logits_loss()]. Lower is better, and a model that
+/// is uniform over `N` tokens has a perplexity of `N`.
+///
+/// To compute it over many batches, add the losses to a [RunningAverage] and
+/// use [RunningAverage::perplexity()].
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::metrics::perplexity;
+/// let logits: Tensor2D<2, 4> = TensorCreator::zeros();
+/// let targets = tensor([[0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 0.0, 1.0]]);
+/// assert!((perplexity(logits, targets) - 4.0).abs() < 1e-5);
+/// ```
+pub fn perplexity<T>(logits: T, target_probs: T::NoTape) -> f32
+where
+    T: Reduce<AllAxes> + Reduce<<<T as HasArrayType>::Array as HasLastAxis>::LastAxis>,
+    <T as Reduce<AllAxes>>::Reduced: HasArrayType<Array = f32>,
+{
+    cross_entropy_with_logits_loss(logits, target_probs)
+        .data()
+        .exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+
+    #[test]
+    fn test_running_average() {
+        let mut avg = RunningAverage::default();
+        assert_eq!(avg.value(), 0.0);
+        for x in [1.0, 2.0, 3.0, 6.0] {
+            avg.add(x);
+        }
+        assert_eq!(avg.value(), 3.0);
+        assert_eq!(avg.count(), 4);
+        let loss = mse_loss(tensor([1.0, 2.0]).traced(), tensor([0.0, 0.0]));
+        avg.update(&loss);
+        assert_eq!(avg.value(), 2.9);
+        avg.reset();
+        assert_eq!(avg, Default::default());
+    }
+
+    #[test]
+    fn test_exponential_average() {
+        let mut avg = ExponentialAverage::new(0.5);
+        assert_eq!(avg.value(), 0.0);
+        avg.add(4.0);
+        assert_eq!(avg.value(), 4.0);
+        avg.add(1.0);
+        // (0.25 * 4 + 0.5 * 1) / 0.75
+        assert_eq!(avg.value(), 2.0);
+        avg.update(&tensor(2.0));
+        assert_close(&[avg.value()], &[(0.125 * 4.0 + 0.25 + 1.0) / 0.875]);
+        avg.reset();
+        assert_eq!(avg, ExponentialAverage::new(0.5));
+    }
+
+    #[test]
+    fn test_perplexity() {
+        let logits = tensor([[1.0, 2.0, 3.0], [0.5, -1.0, 0.0]]);
+        let targets = tensor([[0.0, 0.0, 1.0], [1.0, 0.0, 0.0]]);
+        let loss = cross_entropy_with_logits_loss(logits.clone(), targets.clone());
+        assert_close(&[perplexity(logits, targets)], &[loss.data().exp()]);
+
+        let mut avg = RunningAverage::default();
+        avg.add(2.0f32.ln());
+        avg.add(8.0f32.ln());
+        assert_close(&[avg.perplexity()], &[4.0]);
+    }
+}
@@ -0,0 +1,28 @@
+//! Metrics for evaluating classifiers, such as [accuracy()] and [top_k_accuracy()], and
+//! accumulators like [Accuracy], [ConfusionMatrix], [BinaryMetrics] and [RankingMetrics] that
+//! collect them over an evaluation loop. [RunningAverage] and [ExponentialAverage] summarize
+//! loss values for progress reporting.
+//!
+//! These take the scores of a model (e.g. logits or probabilities) as a tensor, where the
+//! last axis is the class, and the label of each sample as a class index. A single sample
+//! has a `usize` label, and a batch of `B` samples has `[usize; B]` labels:
+//!
+//! ```rust
+//! # use dfdx::prelude::*;
+//! # use dfdx::metrics::*;
+//! let logits = tensor([[0.1, 2.0, -1.0], [3.0, 0.0, 0.5]]);
+//! assert_eq!(accuracy(&logits, &[1, 2]), 0.5);
+//! assert_eq!(top_k_accuracy(&logits, &[1, 2], 2), 1.0);
+//! ```
+
+mod accuracy;
+mod binary;
+mod confusion;
+mod meters;
+mod ranking;
+
+pub use accuracy::*;
+pub use binary::*;
+pub use confusion::*;
+pub use meters::*;
+pub use ranking::*;
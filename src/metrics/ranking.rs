@@ -0,0 +1,147 @@
+use super::BinaryScores;
+use crate::prelude::*;
+use std::vec::Vec;
+
+/// Accumulates the scores of a binary classifier over many batches to compute metrics that
+/// depend on how the scores rank the samples rather than on a threshold: [RankingMetrics::auroc()]
+/// and [RankingMetrics::average_precision()]. Unlike accuracy, these are meaningful on imbalanced
+/// data.
+///
+/// This keeps every score, and computing the metrics sorts them.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::metrics::RankingMetrics;
+/// let mut m = RankingMetrics::default();
+/// m.update(&tensor([0.1, 0.4, 0.35, 0.8]), &[false, false, true, true]);
+/// assert_eq!(m.auroc(), 0.75);
+/// assert!((m.average_precision() - 0.8333).abs() < 1e-4);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RankingMetrics {
+    samples: Vec<(f32, bool)>,
+}
+
+impl RankingMetrics {
+    /// Adds one sample or a batch of samples.
+    pub fn update<T>(&mut self, scores: &T, labels: &<T::Array as BinaryScores>::Labels)
+    where
+        T: HasArrayData,
+        T::Array: BinaryScores,
+    {
+        scores
+            .data()
+            .for_each_sample(labels, |score, label| self.add(score, label));
+    }
+
+    /// Adds one sample with `score` that is positive if `label` is `true`.
+    pub fn add(&mut self, score: f32, label: bool) {
+        self.samples.push((score, label));
+    }
+
+    /// The number of samples added since the last [RankingMetrics::reset()].
+    pub fn count(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// The area under the [ROC curve](https://en.wikipedia.org/wiki/Receiver_operating_characteristic),
+    /// which is the probability that a random positive sample has a higher score than a random
+    /// negative sample, where ties count as half. `0.5` is a random classifier and `1.0` a perfect one.
+    ///
+    /// `NaN` if there are no positive or no negative samples.
+    ///
+    /// **Pytorch equivalent**: `torchmetrics.functional.auroc(scores, labels, task="binary")`
+    pub fn auroc(&self) -> f32 {
+        let mut sorted = self.samples.clone();
+        sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+        // sum the ranks of the positive samples, where tied samples share the mean of their ranks
+        let mut positive_ranks = 0.0f64;
+        let mut num_positive = 0usize;
+        let mut start = 0;
+        for group in sorted.chunk_by(|a, b| a.0 == b.0) {
+            let mean_rank = start as f64 + (group.len() + 1) as f64 / 2.0;
+            let positives = group.iter().filter(|s| s.1).count();
+            positive_ranks += positives as f64 * mean_rank;
+            num_positive += positives;
+            start += group.len();
+        }
+        let num_negative = sorted.len() - num_positive;
+        if num_positive == 0 || num_negative == 0 {
+            return f32::NAN;
+        }
+        let p = num_positive as f64;
+        let u = positive_ranks - p * (p + 1.0) / 2.0;
+        (u / (p * num_negative as f64)) as f32
+    }
+
+    /// The area under the precision-recall curve, as the precision at each distinct score
+    /// weighted by the increase in recall from the previous score. The score of a random
+    /// classifier is the fraction of positive samples, and `1.0` a perfect one.
+    ///
+    /// `NaN` if there are no positive samples.
+    ///
+    /// **Pytorch equivalent**: `torchmetrics.functional.average_precision(scores, labels, task="binary")`
+    pub fn average_precision(&self) -> f32 {
+        let num_positive = self.samples.iter().filter(|s| s.1).count();
+        if num_positive == 0 {
+            return f32::NAN;
+        }
+        let mut sorted = self.samples.clone();
+        sorted.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let (mut true_positives, mut predicted) = (0usize, 0usize);
+        let mut ap = 0.0f64;
+        for group in sorted.chunk_by(|a, b| a.0 == b.0) {
+            let positives = group.iter().filter(|s| s.1).count();
+            true_positives += positives;
+            predicted += group.len();
+            let precision = true_positives as f64 / predicted as f64;
+            ap += precision * positives as f64 / num_positive as f64;
+        }
+        ap as f32
+    }
+
+    /// Forgets all the samples, e.g. at the start of an epoch.
+    pub fn reset(&mut self) {
+        self.samples.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+
+    #[test]
+    fn test_auroc() {
+        let mut m = RankingMetrics::default();
+        m.update(&tensor([0.2, 0.9, 0.4]), &[false, true, false]);
+        assert_eq!(m.auroc(), 1.0);
+        m.add(0.1, true);
+        assert_eq!(m.auroc(), 0.5);
+        // ties between a positive and a negative count as half
+        m.add(0.4, true);
+        assert_close(&[m.auroc()], &[(2.0 + 1.5) / 6.0]);
+        assert_eq!(m.count(), 5);
+        m.reset();
+        m.add(0.5, true);
+        assert!(m.auroc().is_nan());
+    }
+
+    #[test]
+    fn test_average_precision() {
+        let mut m = RankingMetrics::default();
+        m.update(
+            &tensor([0.9, 0.8, 0.7, 0.6, 0.5]),
+            &[true, false, true, false, false],
+        );
+        assert_close(&[m.average_precision()], &[0.5 * 1.0 + 0.5 * (2.0 / 3.0)]);
+        // tied scores are one threshold
+        m.reset();
+        m.update(&tensor([0.5, 0.5, 0.5, 0.1]), &[true, false, false, true]);
+        assert_close(&[m.average_precision()], &[0.5 * (1.0 / 3.0) + 0.5 * 0.5]);
+        m.reset();
+        m.add(0.5, false);
+        assert!(m.average_precision().is_nan());
+    }
+}
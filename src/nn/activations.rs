@@ -83,6 +83,40 @@ where
     }
 }
 
+/// Unit struct that impls [Module] as calling [log_softmax()] on the last axis of `input`.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct LogSoftmax;
+
+impl CanUpdateWithGradients for LogSoftmax {
+    /// Does nothing.
+    fn update<G: GradientProvider>(&mut self, _: &mut G, _: &mut UnusedTensors) {}
+}
+
+impl ResetParams for LogSoftmax {
+    /// Does nothing.
+    fn reset_params<R: Rng>(&mut self, _: &mut R) {}
+}
+
+impl<T> Module<T> for LogSoftmax
+where
+    T: Reduce<<<T as HasArrayType>::Array as HasLastAxis>::LastAxis>,
+{
+    type Output = T;
+    fn forward(&self, input: T) -> Self::Output {
+        log_softmax(input)
+    }
+}
+
+impl<T> ModuleMut<T> for LogSoftmax
+where
+    Self: Module<T>,
+{
+    type Output = <Self as Module<T>>::Output;
+    fn forward_mut(&mut self, input: T) -> Self::Output {
+        self.forward(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -180,4 +214,24 @@ mod tests {
         let r2 = t.softmax::<crate::arrays::Axis<1>>();
         assert_eq!(r1.data(), r2.data());
     }
+
+    #[test]
+    fn test_log_softmax() {
+        let t = tensor([-2.0, -1.0, 0.0, 1.0, 2.0]);
+        let r1 = LogSoftmax.forward_mut(t.clone());
+        let r2 = t.log_softmax();
+        assert_eq!(r1.data(), r2.data());
+
+        let t = Tensor3D::new([[[-2.0, -1.0, 0.0], [1.0, 2.0, 3.0]]]);
+        let r1 = LogSoftmax.forward(t.clone());
+        let r2 = t.log_softmax::<crate::arrays::Axis<2>>();
+        assert_eq!(r1.data(), r2.data());
+    }
+
+    #[test]
+    fn test_softmax_head() {
+        let model: (Linear<3, 4>, Softmax) = Default::default();
+        let y = model.forward(Tensor2D::<2, 3>::zeros());
+        assert_eq!(y.data(), &[[0.25; 4]; 2]);
+    }
 }
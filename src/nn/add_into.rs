@@ -6,6 +6,9 @@ use crate::prelude::*;
 ///
 /// This provides a utility for networks where multiple inputs are needed
 ///
+/// If a single tensor is passed in instead of a tuple, every element of `T` is run on
+/// that same input, and the outputs are added together.
+///
 /// # Generics
 /// - `T` the module to add the outputs together of
 ///
@@ -15,6 +18,10 @@ use crate::prelude::*;
 /// type Model = AddInto<(Linear<2, 5>, Linear<3, 5>)>;
 /// let model: Model = Default::default();
 /// let _: Tensor1D<5> = model.forward((Tensor1D::<2>::zeros(), Tensor1D::<3>::zeros()));
+///
+/// type Parallel = AddInto<(Linear<3, 5>, Linear<3, 5>)>;
+/// let model: Parallel = Default::default();
+/// let _: Tensor1D<5> = model.forward(Tensor1D::<3>::zeros());
 /// ```
 #[derive(Debug, Default, Clone)]
 pub struct AddInto<T>(pub T);
@@ -95,6 +102,58 @@ macro_rules! tuple_impls {
         }    }
 }
 
+macro_rules! same_input_impls {
+    ($head:ident [$($tails:ident),+]) => {
+impl<
+    Input: Tensor<Dtype = f32>,
+    Output: Tensor<Dtype = f32, Tape = Input::Tape>,
+    $head: Module<Input, Output = Output>,
+    $($tails: Module<Input, Output = Output>,)+
+> Module<Input> for AddInto<($head, $($tails,)+)> {
+    type Output = Output;
+
+    #[allow(non_snake_case)]
+    fn forward(&self, x: Input) -> Self::Output {
+        let (x, tape) = x.split_tape();
+        let ($head, $($tails),+) = &self.0;
+        let y = $head.forward(x.clone().put_tape(tape));
+        $(
+            let (y, tape) = y.split_tape();
+            let y = add($tails.forward(x.clone().put_tape(tape)), y);
+        )+
+        y
+    }
+}
+
+impl<
+    Input: Tensor<Dtype = f32>,
+    Output: Tensor<Dtype = f32, Tape = Input::Tape>,
+    $head: ModuleMut<Input, Output = Output>,
+    $($tails: ModuleMut<Input, Output = Output>,)+
+> ModuleMut<Input> for AddInto<($head, $($tails,)+)> {
+    type Output = Output;
+
+    #[allow(non_snake_case)]
+    fn forward_mut(&mut self, x: Input) -> Self::Output {
+        let (x, tape) = x.split_tape();
+        let ($head, $($tails),+) = &mut self.0;
+        let y = $head.forward_mut(x.clone().put_tape(tape));
+        $(
+            let (y, tape) = y.split_tape();
+            let y = add($tails.forward_mut(x.clone().put_tape(tape)), y);
+        )+
+        y
+    }
+}
+    };
+}
+
+same_input_impls!(A[B]);
+same_input_impls!(A [B, C]);
+same_input_impls!(A [B, C, D]);
+same_input_impls!(A [B, C, D, E]);
+same_input_impls!(A [B, C, D, E, F]);
+
 tuple_impls!(A Ai [B Bi]);
 tuple_impls!(A Ai [B Bi, C Ci]);
 tuple_impls!(A Ai [B Bi, C Ci, D Di]);
@@ -236,6 +295,27 @@ mod tests {
         assert!(unused.is_empty());
     }
 
+    #[test]
+    fn test_add_into_same_input() {
+        type Model = AddInto<(Linear<2, 2>, Linear<2, 2>, Linear<2, 2>)>;
+        let mut m: Model = Default::default();
+        m.0 .0.weight = tensor([[1.0, 0.0], [0.0, 1.0]]);
+        m.0 .1.weight = tensor([[2.0, 0.0], [0.0, -1.0]]);
+        m.0 .2.bias = tensor([0.5, -0.5]);
+
+        let x = tensor([1.0, 2.0]);
+        let y = m.forward_mut(x.trace());
+        assert_eq!(y.data(), &[3.5, -0.5]);
+
+        let g = backward(y.sum());
+        assert_eq!(g.ref_gradient(&x), &[3.0, 0.0]);
+        assert_eq!(g.ref_gradient(&m.0 .0.weight), &[[1.0, 2.0]; 2]);
+        assert_eq!(g.ref_gradient(&m.0 .2.weight), &[[1.0, 2.0]; 2]);
+        assert_eq!(g.ref_gradient(&m.0 .2.bias), &[1.0; 2]);
+
+        let _: Tensor2D<4, 2> = m.forward(Tensor2D::<4, 2>::zeros());
+    }
+
     #[test]
     fn longer_network() {
         // check if it works in a longer neural net
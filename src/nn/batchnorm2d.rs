@@ -1,4 +1,4 @@
-use super::{Module, ModuleMut, ResetParams};
+use super::{Module, ModuleMode, ModuleMut, ResetParams};
 use crate::arrays::{HasArrayData, HasAxes};
 use crate::devices::{Cpu, FillElements};
 use crate::{gradients::*, tensor::*, tensor_ops::*};
@@ -20,6 +20,8 @@ use crate::{gradients::*, tensor::*, tensor_ops::*};
 ///
 /// *NOTE: ModuleMut/NoneTape, and Module/OwnedTape will fail to compile.*
 ///
+/// Use [ModuleMode::eval()] to make [ModuleMut] use the running statistics without updating them.
+///
 /// Examples:
 /// ```rust
 /// # use dfdx::prelude::*;
@@ -51,24 +53,43 @@ pub struct BatchNorm2D<const C: usize> {
     ///
     /// `running_stat * (1.0 - momentum) + stat * momentum`.
     pub momentum: f32,
+    training: bool,
 }
 
 impl<const C: usize> BatchNorm2D<C> {
-    /// generic forward for inference
+    /// Whether [ModuleMut] uses batch statistics (`true`), or running statistics (`false`).
+    /// See [ModuleMode].
+    pub fn is_training(&self) -> bool {
+        self.training
+    }
+}
+
+impl<const C: usize> ModuleMode for BatchNorm2D<C> {
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+impl<const C: usize> BatchNorm2D<C> {
+    /// generic forward for inference. If `x` has a tape, gradients are still tracked for
+    /// [Self::scale] and [Self::bias].
     fn infer_fwd<T, Axes>(&self, x: T) -> T
     where
-        T: Tensor<Dtype = f32, Tape = NoneTape>,
-        Tensor1D<C>: BroadcastTo<T, Axes>,
+        T: Tensor<Dtype = f32>,
+        Tensor1D<C>: BroadcastTo<T::NoTape, Axes>,
+        Tensor1D<C, T::Tape>: BroadcastTo<T, Axes>,
     {
         // statistics for normalizing
-        let std = (self.running_var.clone() + self.epsilon).sqrt();
-        let mean = self.running_mean.clone();
+        let std: T::NoTape = (self.running_var.clone() + self.epsilon).sqrt().broadcast();
+        let mean: T::NoTape = self.running_mean.clone().broadcast();
 
         // normalize & affine
-        let x = sub(x, mean.broadcast());
-        let x = div(x, std.broadcast());
-        let x = mul(x, self.scale.clone().broadcast());
-        add(x, self.bias.clone().broadcast())
+        let x = sub(x, mean);
+        let (x, tape) = div(x, std).split_tape();
+        let scale: T = self.scale.clone().put_tape(tape).broadcast();
+        let (x, tape) = mul(scale, x).split_tape();
+        let bias: T = self.bias.clone().put_tape(tape).broadcast();
+        add(bias, x)
     }
 
     fn train_fwd<T, Axes>(&mut self, x: T) -> T
@@ -138,9 +159,14 @@ impl<const C: usize, const H: usize, const W: usize> ModuleMut<Tensor3D<C, H, W,
 {
     type Output = Tensor3D<C, H, W, OwnedTape>;
 
-    /// Training 3d forward - updates [Self::running_mean] and [Self::running_var]
+    /// Training 3d forward - updates [Self::running_mean] and [Self::running_var].
+    /// In evaluation mode, this normalizes with the running statistics instead.
     fn forward_mut(&mut self, x: Tensor3D<C, H, W, OwnedTape>) -> Self::Output {
-        self.train_fwd(x)
+        if self.training {
+            self.train_fwd(x)
+        } else {
+            self.infer_fwd(x)
+        }
     }
 }
 
@@ -149,9 +175,14 @@ impl<const B: usize, const C: usize, const H: usize, const W: usize>
 {
     type Output = Tensor4D<B, C, H, W, OwnedTape>;
 
-    /// Training 4d forward - updates [Self::running_mean] and [Self::running_var]
+    /// Training 4d forward - updates [Self::running_mean] and [Self::running_var].
+    /// In evaluation mode, this normalizes with the running statistics instead.
     fn forward_mut(&mut self, x: Tensor4D<B, C, H, W, OwnedTape>) -> Self::Output {
-        self.train_fwd(x)
+        if self.training {
+            self.train_fwd(x)
+        } else {
+            self.infer_fwd(x)
+        }
     }
 }
 
@@ -164,6 +195,7 @@ impl<const C: usize> Default for BatchNorm2D<C> {
             running_var: TensorCreator::ones(),
             epsilon: 1e-5,
             momentum: 0.1,
+            training: true,
         }
     }
 }
@@ -292,4 +324,28 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn test_batchnorm2d_eval_forward_mut() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut bn: BatchNorm2D<2> = Default::default();
+        let _ = bn.forward_mut(Tensor4D::<4, 2, 3, 3>::randn(&mut rng).traced());
+        let m = bn.running_mean.clone();
+        let v = bn.running_var.clone();
+
+        bn.eval();
+        assert!(!bn.is_training());
+        let x: Tensor4D<2, 2, 3, 3> = TensorCreator::randn(&mut rng);
+        let y = bn.forward_mut(x.trace());
+        assert_eq!(bn.running_mean.data(), m.data());
+        assert_eq!(bn.running_var.data(), v.data());
+        assert_close(y.data(), bn.forward(x.clone()).data());
+
+        let g = backward(y.sum());
+        assert_close(g.ref_gradient(&bn.bias), &[18.0; 2]);
+        let std = (v.clone() + bn.epsilon).sqrt();
+        let x_sum: Tensor1D<2> = x.sum::<_, crate::arrays::Axes3<0, 2, 3>>();
+        let expected = (x_sum - m * 18.0) / std;
+        assert_close(g.ref_gradient(&bn.scale), expected.data());
+    }
 }
@@ -0,0 +1,183 @@
+use super::visit_params::{ParamVisitor, VisitParams};
+use crate::devices::{Device, ForEachElement};
+use crate::gradients::{CanUpdateWithGradients, GradientProvider, Gradients, Tape, UnusedTensors};
+use crate::prelude::*;
+
+/// Gradient checkpointing (rematerialization) of `M`: the activations inside `M` are not stored
+/// on the tape, and `M` is run again during backprop to compute its gradients.
+/// This trades an extra forward of `M` for the memory of all of its intermediate values.
+///
+/// Only the input to `M` is kept until backprop, along with a clone of `M`. Since tensors
+/// share their data when cloned, this doesn't copy the parameters.
+///
+/// [ModuleMut::forward_mut()] checkpoints [OwnedTape] inputs, and [Module::forward()] on [NoneTape]
+/// inputs just calls `M`. `M` is always run with [Module::forward()], so things like [Dropout]
+/// are not applied inside of a checkpoint.
+///
+/// **Pytorch equivalent**: `torch.utils.checkpoint.checkpoint(module, x)`
+///
+/// # Generics
+/// - `M`: The module to checkpoint.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// type Block = Checkpoint<(Linear<5, 5>, ReLU, Linear<5, 5>)>;
+/// let mut model: (Block, Block) = Default::default();
+/// let y = model.forward_mut(Tensor1D::<5>::zeros().traced());
+/// let gradients = backward(y.mean());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Checkpoint<M>(pub M);
+
+impl<M: CanUpdateWithGradients> CanUpdateWithGradients for Checkpoint<M> {
+    /// Pass through to `M`'s [CanUpdateWithGradients].
+    fn update<G: GradientProvider>(&mut self, grads: &mut G, unused: &mut UnusedTensors) {
+        self.0.update(grads, unused);
+    }
+}
+
+impl<M: ResetParams> ResetParams for Checkpoint<M> {
+    /// Pass through to `M`'s [ResetParams].
+    fn reset_params<R: rand::Rng>(&mut self, rng: &mut R) {
+        self.0.reset_params(rng);
+    }
+}
+
+impl<T, M> Module<T> for Checkpoint<M>
+where
+    T: Tensor<Dtype = f32, Tape = NoneTape>,
+    M: Module<T>,
+{
+    type Output = M::Output;
+    fn forward(&self, x: T) -> Self::Output {
+        self.0.forward(x)
+    }
+}
+
+impl<T, M, O> ModuleMut<T> for Checkpoint<M>
+where
+    T: Tensor<Dtype = f32, Tape = OwnedTape>,
+    O: Tensor<Dtype = f32, Tape = OwnedTape>,
+    M: 'static
+        + Send
+        + Clone
+        + VisitParams
+        + Module<T, Output = O>
+        + Module<T::NoTape, Output = O::NoTape>,
+{
+    type Output = O;
+    /// Calls `M` without a tape, and adds a single backward op that calls `M` again with a tape.
+    fn forward_mut(&mut self, x: T) -> Self::Output {
+        let (x, mut tape) = x.split_tape();
+        let y = self.0.forward(x.clone());
+        let phantom_y = y.clone();
+        let module = self.0.clone();
+        tape.add_backward_op(move |grads| {
+            let (inner_y, mut inner_tape) = module.forward(traced::<T>(x.clone())).split_tape();
+            let y_grad = O::Device::map(grads.ref_gradient(&phantom_y), |g| *g);
+            inner_tape.add_backward_op(move |inner_grads| {
+                let inner_y_grad = inner_grads.mut_gradient(&inner_y);
+                O::Device::foreach_mr(inner_y_grad, y_grad.as_ref(), &mut |g, y| *g = *y);
+            });
+            let mut inner_grads = inner_tape.0.execute();
+            if let Some(inner_x_grad) = inner_grads.remove(&x) {
+                let x_grad = grads.mut_gradient(&x);
+                T::Device::foreach_mr(x_grad, inner_x_grad.as_ref(), &mut |g, i| *g += i);
+            }
+            module.visit_params(&mut AccumulateGrads {
+                src: &mut inner_grads,
+                dst: grads,
+            });
+        });
+        y.put_tape(tape)
+    }
+}
+
+/// Adds the gradients in `src` to `dst`.
+struct AccumulateGrads<'a> {
+    src: &'a mut Gradients,
+    dst: &'a mut Gradients,
+}
+
+impl<'a> ParamVisitor for AccumulateGrads<'a> {
+    fn visit<T: Tensor<Dtype = f32>>(&mut self, _: &str, param: &T) {
+        if let Some(src) = self.src.remove(param) {
+            let dst = self.dst.mut_gradient(param);
+            T::Device::foreach_mr(dst, src.as_ref(), &mut |d, s| *d += s);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+    use rand::{prelude::StdRng, SeedableRng};
+
+    #[test]
+    fn test_checkpoint_forward_matches() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut model: Checkpoint<(Linear<3, 4>, ReLU)> = Default::default();
+        model.reset_params(&mut rng);
+        let x: Tensor2D<2, 3> = TensorCreator::randn(&mut rng);
+        let y = model.forward(x.clone());
+        assert_eq!(y.data(), model.0.forward(x.clone()).data());
+        assert_eq!(y.data(), model.forward_mut(x.trace()).data());
+    }
+
+    #[test]
+    fn test_checkpoint_gradients_match() {
+        type Block = (Linear<3, 4>, Tanh, Linear<4, 3>);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut model: (Linear<3, 3>, Checkpoint<Block>, Linear<3, 2>) = Default::default();
+        model.reset_params(&mut rng);
+        let mut unchecked = (model.0.clone(), model.1 .0.clone(), model.2.clone());
+
+        let x: Tensor2D<4, 3> = TensorCreator::randn(&mut rng);
+        let g = backward(model.forward_mut(x.trace()).square().mean());
+        let g_0 = backward(unchecked.forward_mut(x.trace()).square().mean());
+
+        assert_close(
+            g.ref_gradient(&model.0.weight),
+            g_0.ref_gradient(&unchecked.0.weight),
+        );
+        assert_close(
+            g.ref_gradient(&model.0.bias),
+            g_0.ref_gradient(&unchecked.0.bias),
+        );
+        let (block, block_0) = (&model.1 .0, &unchecked.1);
+        assert_close(
+            g.ref_gradient(&block.0.weight),
+            g_0.ref_gradient(&block_0.0.weight),
+        );
+        assert_close(
+            g.ref_gradient(&block.0.bias),
+            g_0.ref_gradient(&block_0.0.bias),
+        );
+        assert_close(
+            g.ref_gradient(&block.2.weight),
+            g_0.ref_gradient(&block_0.2.weight),
+        );
+        assert_close(
+            g.ref_gradient(&block.2.bias),
+            g_0.ref_gradient(&block_0.2.bias),
+        );
+        assert_close(
+            g.ref_gradient(&model.2.weight),
+            g_0.ref_gradient(&unchecked.2.weight),
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_input_gradient() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut model: Checkpoint<(Linear<3, 3>, Sigmoid)> = Default::default();
+        model.reset_params(&mut rng);
+        let x: Tensor1D<3> = TensorCreator::randn(&mut rng);
+
+        let g = backward(model.forward_mut(x.trace()).sum());
+        let g_0 = backward(model.0.forward(x.trace()).sum());
+        assert_close(g.ref_gradient(&x), g_0.ref_gradient(&x));
+    }
+}
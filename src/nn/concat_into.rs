@@ -0,0 +1,103 @@
+use crate::arrays::Axis;
+use crate::gradients::{CanUpdateWithGradients, GradientProvider, UnusedTensors};
+use crate::prelude::*;
+
+/// **Requires Nightly** Runs both elements of `T` on the same input, and concatenates their outputs
+/// along axis `AXIS` using [concat()].
+///
+/// To concatenate more than two branches, nest [ConcatInto] inside itself.
+///
+/// # Generics
+/// - `T` a tuple of two modules that accept the same input type.
+/// - `AXIS` the axis to concatenate along.
+///
+/// # Examples
+/// ```ignore
+/// # use dfdx::prelude::*;
+/// type Model = ConcatInto<(Linear<5, 3>, Linear<5, 7>), 0>;
+/// let model: Model = Default::default();
+/// let _: Tensor1D<10> = model.forward(Tensor1D::<5>::zeros());
+///
+/// type Batched = ConcatInto<(Linear<5, 3>, ConcatInto<(Linear<5, 2>, Linear<5, 1>), 1>), 1>;
+/// let model: Batched = Default::default();
+/// let _: Tensor2D<4, 6> = model.forward(Tensor2D::<4, 5>::zeros());
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct ConcatInto<T, const AXIS: isize>(pub T);
+
+impl<T: CanUpdateWithGradients, const AXIS: isize> CanUpdateWithGradients for ConcatInto<T, AXIS> {
+    fn update<G: GradientProvider>(&mut self, grads: &mut G, unused: &mut UnusedTensors) {
+        self.0.update(grads, unused);
+    }
+}
+
+impl<T: ResetParams, const AXIS: isize> ResetParams for ConcatInto<T, AXIS> {
+    fn reset_params<R: rand::Rng>(&mut self, rng: &mut R) {
+        self.0.reset_params(rng);
+    }
+}
+
+impl<Input: Tensor, A: Module<Input>, B: Module<Input>, const AXIS: isize> Module<Input>
+    for ConcatInto<(A, B), AXIS>
+where
+    A::Output: Tensor<Tape = Input::Tape>,
+    B::Output: Tensor<Tape = Input::Tape>,
+    A::Output: Concat<<B::Output as Tensor>::NoTape, Axis<AXIS>>,
+{
+    type Output = <A::Output as Concat<<B::Output as Tensor>::NoTape, Axis<AXIS>>>::Output;
+
+    fn forward(&self, x: Input) -> Self::Output {
+        let (x, tape) = x.split_tape();
+        let (a, tape) = self.0 .0.forward(x.clone().put_tape(tape)).split_tape();
+        let (b, tape) = self.0 .1.forward(x.put_tape(tape)).split_tape();
+        a.put_tape(tape).concat(b)
+    }
+}
+
+impl<Input: Tensor, A: ModuleMut<Input>, B: ModuleMut<Input>, const AXIS: isize> ModuleMut<Input>
+    for ConcatInto<(A, B), AXIS>
+where
+    A::Output: Tensor<Tape = Input::Tape>,
+    B::Output: Tensor<Tape = Input::Tape>,
+    A::Output: Concat<<B::Output as Tensor>::NoTape, Axis<AXIS>>,
+{
+    type Output = <A::Output as Concat<<B::Output as Tensor>::NoTape, Axis<AXIS>>>::Output;
+
+    fn forward_mut(&mut self, x: Input) -> Self::Output {
+        let (x, tape) = x.split_tape();
+        let (a, tape) = self.0 .0.forward_mut(x.clone().put_tape(tape)).split_tape();
+        let (b, tape) = self.0 .1.forward_mut(x.put_tape(tape)).split_tape();
+        a.put_tape(tape).concat(b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::tests::SimpleGradients;
+
+    #[test]
+    fn test_concat_into_1d() {
+        let mut m: ConcatInto<(Linear<2, 1>, Linear<2, 2>), 0> = Default::default();
+        m.0 .0.weight = tensor([[1.0, 1.0]]);
+        m.0 .1.bias = tensor([-1.0, 1.0]);
+        let x = tensor([2.0, 3.0]);
+        let y: Tensor1D<3, OwnedTape> = m.forward(x.trace());
+        assert_eq!(y.data(), &[5.0, -1.0, 1.0]);
+        let g = backward(y.sum());
+        assert_eq!(g.ref_gradient(&x), &[1.0, 1.0]);
+        assert_eq!(g.ref_gradient(&m.0 .1.bias), &[1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_concat_into_nested() {
+        type Model = ConcatInto<(Linear<5, 3>, ConcatInto<(Linear<5, 2>, Linear<5, 1>), 1>), 1>;
+        let mut m: Model = Default::default();
+        let _: Tensor2D<4, 6, OwnedTape> = m.forward_mut(Tensor2D::<4, 5>::zeros().traced());
+        let y: Tensor2D<4, 6, OwnedTape> = m.forward(Tensor2D::<4, 5>::zeros().traced());
+        let mut g = SimpleGradients(backward(y.mean()));
+        let mut unused = Default::default();
+        m.update(&mut g, &mut unused);
+        assert!(unused.is_empty());
+    }
+}
@@ -1,5 +1,7 @@
+use crate::devices::{PaddingMode, ZeroPadding};
 use crate::gradients::*;
 use crate::prelude::*;
+use core::marker::PhantomData;
 use rand::Rng;
 use rand_distr::Uniform;
 
@@ -12,7 +14,14 @@ use rand_distr::Uniform;
 /// - `OUT_CHAN`: The number of channels in the output of the layer.
 /// - `KERNEL_SIZE`: The size of the kernel applied to both width and height of the images.
 /// - `STRIDE`: How far to move the kernel each step. Defaults to `1`
-/// - `PADDING`: How much zero padding to add around the images. Defaults to `0`.
+/// - `PADDING`: How much padding to add around the images. Defaults to `0`.
+/// - `M`: The [PaddingMode] used to fill in the padding. Defaults to [ZeroPadding].
+///   [crate::devices::ReflectPadding] and [crate::devices::ReplicatePadding] are also available.
+///
+/// See [Conv2DSame] for a convolution that keeps the height & width of images the same.
+///
+/// The padding mode is stored in `padding`, which is always `PhantomData`. [Conv2D::new()] creates a
+/// Conv2D from existing tensors without having to spell it out.
 ///
 /// Examples:
 /// ```ignore
@@ -23,6 +32,9 @@ use rand_distr::Uniform;
 /// let _: Tensor3D<33, 30, 62> = m.forward(Tensor3D::<16, 32, 64>::zeros());
 /// #[cfg(feature = "nightly")]
 /// let _: Tensor4D<2, 33, 13, 12> = m.forward(Tensor4D::<2, 16, 15, 14>::zeros());
+/// let m: Conv2D<16, 33, 3, 1, 1, ReflectPadding> = Default::default();
+/// #[cfg(feature = "nightly")]
+/// let _: Tensor3D<33, 32, 64> = m.forward(Tensor3D::<16, 32, 64>::zeros());
 /// ```
 #[derive(Default, Debug, Clone)]
 pub struct Conv2D<
@@ -31,13 +43,64 @@ pub struct Conv2D<
     const KERNEL_SIZE: usize,
     const STRIDE: usize = 1,
     const PADDING: usize = 0,
+    M: PaddingMode = ZeroPadding,
 > {
     pub weight: Tensor4D<OUT_CHAN, IN_CHAN, KERNEL_SIZE, KERNEL_SIZE>,
     pub bias: Tensor1D<OUT_CHAN>,
+    pub padding: PhantomData<M>,
+}
+
+#[cfg(feature = "nightly")]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, M>
+    Conv2D<I, O, K, S, P, M>
+where
+    M: PaddingMode,
+{
+    /// Creates a Conv2D with the given `weight` & `bias`.
+    pub fn new(weight: Tensor4D<O, I, K, K>, bias: Tensor1D<O>) -> Self {
+        Self {
+            weight,
+            bias,
+            padding: PhantomData,
+        }
+    }
+}
+
+/// **Requires Nightly** A [Conv2D] with stride `1` and padding `(KERNEL_SIZE - 1) / 2`, so
+/// that the output images have the same height & width as the input images. `KERNEL_SIZE` must be odd,
+/// which is checked at compile time.
+///
+/// **Pytorch Equivalent**: `torch.nn.Conv2d(..., padding="same")`
+///
+/// Examples:
+/// ```ignore
+/// # use dfdx::prelude::*;
+/// let m: Conv2DSame<16, 33, 5, ReflectPadding> = Default::default();
+/// let _: Tensor3D<33, 32, 64> = m.forward(Tensor3D::<16, 32, 64>::zeros());
+/// ```
+#[cfg(feature = "nightly")]
+pub type Conv2DSame<
+    const IN_CHAN: usize,
+    const OUT_CHAN: usize,
+    const KERNEL_SIZE: usize,
+    M = ZeroPadding,
+> = Conv2D<IN_CHAN, OUT_CHAN, KERNEL_SIZE, 1, { same_padding(KERNEL_SIZE) }, M>;
+
+/// **Requires Nightly** The padding of [Conv2DSame], which fails to compile if `kernel_size` is even.
+/// This is a `const fn` instead of an `Assert<..>: ConstTrue` bound, because type aliases can't have bounds.
+#[cfg(feature = "nightly")]
+pub const fn same_padding(kernel_size: usize) -> usize {
+    assert!(
+        !kernel_size.is_multiple_of(2),
+        "Conv2DSame requires an odd KERNEL_SIZE"
+    );
+    (kernel_size - 1) / 2
 }
 
-impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize>
-    CanUpdateWithGradients for Conv2D<I, O, K, S, P>
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, M>
+    CanUpdateWithGradients for Conv2D<I, O, K, S, P, M>
+where
+    M: PaddingMode,
 {
     fn update<G: GradientProvider>(&mut self, grads: &mut G, unused: &mut UnusedTensors) {
         self.weight.update(grads, unused);
@@ -45,8 +108,10 @@ impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: us
     }
 }
 
-impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize> ResetParams
-    for Conv2D<I, O, K, S, P>
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, M> ResetParams
+    for Conv2D<I, O, K, S, P, M>
+where
+    M: PaddingMode,
 {
     fn reset_params<R: Rng>(&mut self, rng: &mut R) {
         let k = (I * K * K) as f32;
@@ -67,14 +132,136 @@ impl<
         const P: usize,
         const H: usize,
         const W: usize,
-    > Module<Tensor3D<I, H, W, T>> for Conv2D<I, O, K, S, P>
+        M: PaddingMode,
+    > Module<Tensor3D<I, H, W, T>> for Conv2D<I, O, K, S, P, M>
+where
+    [[[(); (W + 2 * P - K) / S + 1]; (H + 2 * P - K) / S + 1]; O]:,
+{
+    type Output = Tensor3D<O, { (H + 2 * P - K) / S + 1 }, { (W + 2 * P - K) / S + 1 }, T>;
+
+    fn forward(&self, x: Tensor3D<I, H, W, T>) -> Self::Output {
+        x.padded_conv2d::<O, K, S, P, M>(&self.weight, &self.bias)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        T: Tape,
+        const B: usize,
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const H: usize,
+        const W: usize,
+        M: PaddingMode,
+    > Module<Tensor4D<B, I, H, W, T>> for Conv2D<I, O, K, S, P, M>
+where
+    [[[[(); (W + 2 * P - K) / S + 1]; (H + 2 * P - K) / S + 1]; O]; B]:,
+{
+    type Output = Tensor4D<B, O, { (H + 2 * P - K) / S + 1 }, { (W + 2 * P - K) / S + 1 }, T>;
+
+    fn forward(&self, x: Tensor4D<B, I, H, W, T>) -> Self::Output {
+        x.padded_conv2d::<O, K, S, P, M>(&self.weight, &self.bias)
+    }
+}
+
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, M, T>
+    ModuleMut<T> for Conv2D<I, O, K, S, P, M>
+where
+    M: PaddingMode,
+    Self: Module<T>,
+{
+    type Output = <Self as Module<T>>::Output;
+    fn forward_mut(&mut self, input: T) -> Self::Output {
+        self.forward(input)
+    }
+}
+
+/// **Requires Nightly** A [Conv2D] without a bias, which is useful when the output is normalized afterwards,
+/// e.g. by [BatchNorm2D]. The generics are the same as [Conv2D], and [UnbiasedConv2D::new()] creates one
+/// from an existing weight.
+///
+/// **Pytorch Equivalent**: `torch.nn.Conv2d(..., bias=False)`
+///
+/// Examples:
+/// ```ignore
+/// # use dfdx::prelude::*;
+/// type Block = (UnbiasedConv2D<3, 8, 3, 1, 1>, BatchNorm2D<8>, ReLU);
+/// ```
+#[cfg(feature = "nightly")]
+#[derive(Default, Debug, Clone)]
+pub struct UnbiasedConv2D<
+    const IN_CHAN: usize,
+    const OUT_CHAN: usize,
+    const KERNEL_SIZE: usize,
+    const STRIDE: usize = 1,
+    const PADDING: usize = 0,
+    M: PaddingMode = ZeroPadding,
+> {
+    pub weight: Tensor4D<OUT_CHAN, IN_CHAN, KERNEL_SIZE, KERNEL_SIZE>,
+    pub padding: PhantomData<M>,
+}
+
+#[cfg(feature = "nightly")]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, M>
+    UnbiasedConv2D<I, O, K, S, P, M>
+where
+    M: PaddingMode,
+{
+    /// Creates an UnbiasedConv2D with the given `weight`.
+    pub fn new(weight: Tensor4D<O, I, K, K>) -> Self {
+        Self {
+            weight,
+            padding: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, M>
+    CanUpdateWithGradients for UnbiasedConv2D<I, O, K, S, P, M>
+where
+    M: PaddingMode,
+{
+    fn update<G: GradientProvider>(&mut self, grads: &mut G, unused: &mut UnusedTensors) {
+        self.weight.update(grads, unused);
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, M> ResetParams
+    for UnbiasedConv2D<I, O, K, S, P, M>
+where
+    M: PaddingMode,
+{
+    fn reset_params<R: Rng>(&mut self, rng: &mut R) {
+        let k = (I * K * K) as f32;
+        let bound = 1.0 / k.sqrt();
+        self.weight.randomize(rng, &Uniform::new(-bound, bound));
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        T: Tape,
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const H: usize,
+        const W: usize,
+        M: PaddingMode,
+    > Module<Tensor3D<I, H, W, T>> for UnbiasedConv2D<I, O, K, S, P, M>
 where
     [[[(); (W + 2 * P - K) / S + 1]; (H + 2 * P - K) / S + 1]; O]:,
 {
     type Output = Tensor3D<O, { (H + 2 * P - K) / S + 1 }, { (W + 2 * P - K) / S + 1 }, T>;
 
     fn forward(&self, x: Tensor3D<I, H, W, T>) -> Self::Output {
-        x.conv2d::<O, K, S, P>(&self.weight, &self.bias)
+        x.padded_conv2d_unbiased::<O, K, S, P, M>(&self.weight)
     }
 }
 
@@ -89,20 +276,23 @@ impl<
         const P: usize,
         const H: usize,
         const W: usize,
-    > Module<Tensor4D<B, I, H, W, T>> for Conv2D<I, O, K, S, P>
+        M: PaddingMode,
+    > Module<Tensor4D<B, I, H, W, T>> for UnbiasedConv2D<I, O, K, S, P, M>
 where
     [[[[(); (W + 2 * P - K) / S + 1]; (H + 2 * P - K) / S + 1]; O]; B]:,
 {
     type Output = Tensor4D<B, O, { (H + 2 * P - K) / S + 1 }, { (W + 2 * P - K) / S + 1 }, T>;
 
     fn forward(&self, x: Tensor4D<B, I, H, W, T>) -> Self::Output {
-        x.conv2d::<O, K, S, P>(&self.weight, &self.bias)
+        x.padded_conv2d_unbiased::<O, K, S, P, M>(&self.weight)
     }
 }
 
-impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, T> ModuleMut<T>
-    for Conv2D<I, O, K, S, P>
+#[cfg(feature = "nightly")]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, M, T>
+    ModuleMut<T> for UnbiasedConv2D<I, O, K, S, P, M>
 where
+    M: PaddingMode,
     Self: Module<T>,
 {
     type Output = <Self as Module<T>>::Output;
@@ -115,6 +305,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::nn::tests::SimpleGradients;
     use rand::thread_rng;
 
     #[test]
@@ -145,6 +336,50 @@ mod tests {
         let _: Tensor4D<5, 2, 6, 6> = Conv2D::<3, 2, 3, 2, 2>::default().forward(Img::zeros());
     }
 
+    #[test]
+    fn test_forward_same_sizes() {
+        type Img = Tensor4D<5, 3, 10, 10>;
+        let _: Tensor4D<5, 2, 10, 10> = Conv2DSame::<3, 2, 3>::default().forward(Img::zeros());
+        let _: Tensor4D<5, 2, 10, 10> = Conv2DSame::<3, 2, 5>::default().forward(Img::zeros());
+        let _: Tensor4D<5, 2, 10, 10> =
+            Conv2DSame::<3, 2, 7, ReplicatePadding>::default().forward(Img::zeros());
+    }
+
+    #[test]
+    fn test_padding_modes() {
+        let m: Conv2D<1, 1, 3, 1, 1, ReplicatePadding> =
+            Conv2D::new(TensorCreator::ones(), TensorCreator::zeros());
+        let x = tensor([[[1.0, 2.0], [3.0, 4.0]]]);
+        let y = m.forward(x.trace());
+        assert_eq!(y.data(), &[[[18.0, 21.0], [24.0, 27.0]]]);
+        let g = backward(y.sum());
+        assert_eq!(g.ref_gradient(&x), &[[[9.0; 2]; 2]]);
+
+        let m: Conv2D<1, 1, 3, 1, 1, ReflectPadding> = Conv2D {
+            weight: TensorCreator::ones(),
+            bias: TensorCreator::zeros(),
+            padding: PhantomData,
+        };
+        let y = m.forward(x);
+        assert_eq!(y.data(), &[[[27.0, 24.0], [21.0, 18.0]]]);
+    }
+
+    #[test]
+    fn test_unbiased_conv() {
+        let mut rng = thread_rng();
+        let mut m: UnbiasedConv2D<2, 4, 3, 1, 1> = Default::default();
+        m.reset_params(&mut rng);
+        let conv: Conv2D<2, 4, 3, 1, 1> = Conv2D::new(m.weight.clone(), TensorCreator::zeros());
+        let x: Tensor4D<2, 2, 5, 5> = TensorCreator::randn(&mut rng);
+        let y: Tensor4D<2, 4, 5, 5, _> = m.forward(x.trace());
+        assert_eq!(y.data(), conv.forward(x.clone()).data());
+
+        let mut g = SimpleGradients(backward(y.mean()));
+        let mut unused = Default::default();
+        m.update(&mut g, &mut unused);
+        assert!(unused.is_empty());
+    }
+
     #[test]
     fn test_2_conv_sizes() {
         type A = Conv2D<1, 2, 3>;
@@ -0,0 +1,185 @@
+use crate::arrays::CountElements;
+use crate::devices::{Cpu, FillElements};
+use crate::gradients::*;
+use crate::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// A residual connection around `F` that randomly skips `F` for entire samples: `x + mask * F(x)`.
+/// Also known as stochastic depth, as described in [Deep Networks with Stochastic Depth](https://arxiv.org/abs/1603.09382).
+///
+/// The first axis of the input is treated as the batch axis, so the input must be at least 2d.
+///
+/// Like [Dropout], [Module] and [ModuleMut] are only implemented for specific tapes:
+/// 1. [ModuleMut] requires an [OwnedTape], and skips `F` for each sample with probability [Self::p].
+/// 2. [Module] requires a [NoneTape], and scales the output of `F` by the survival probability `1 - p`.
+///
+/// Generics:
+/// - `F`: The residual branch to randomly skip.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let mut model: DropPath<Linear<5, 5>> = DropPath::p(0.2);
+/// let x: Tensor2D<3, 5> = TensorCreator::ones();
+/// let _: Tensor2D<3, 5, OwnedTape> = model.forward_mut(x.trace());
+/// let _: Tensor2D<3, 5> = model.forward(x);
+/// ```
+#[derive(Clone, Debug)]
+pub struct DropPath<F> {
+    pub f: F,
+    pub p: f32,
+    rng: StdRng,
+    training: bool,
+}
+
+impl<F> DropPath<F> {
+    /// Constructs [DropPath] around `f` with `p` and `rng`.
+    pub fn new(f: F, p: f32, rng_seed: u64) -> Self {
+        Self {
+            f,
+            p,
+            rng: StdRng::seed_from_u64(rng_seed),
+            training: true,
+        }
+    }
+
+    /// Whether [ModuleMut] randomly skips `F`. See [ModuleMode].
+    pub fn is_training(&self) -> bool {
+        self.training
+    }
+}
+
+impl<F: Default> DropPath<F> {
+    /// Constructs [DropPath] around a default `F` with `p`, using [default_rng()].
+    pub fn p(p: f32) -> Self {
+        Self {
+            f: Default::default(),
+            p,
+            rng: default_rng(),
+            training: true,
+        }
+    }
+}
+
+impl<F: Default> Default for DropPath<F> {
+    /// Sets `self.p` to `0.1`, and seeds [StdRng] with 0.
+    fn default() -> Self {
+        Self::new(Default::default(), 0.1, 0)
+    }
+}
+
+impl<F: CanUpdateWithGradients> CanUpdateWithGradients for DropPath<F> {
+    /// Pass through to `F`'s [CanUpdateWithGradients].
+    fn update<G: GradientProvider>(&mut self, grads: &mut G, unused: &mut UnusedTensors) {
+        self.f.update(grads, unused);
+    }
+}
+
+impl<F: ModuleMode> ModuleMode for DropPath<F> {
+    /// Sets the mode of `self` and `F`.
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+        self.f.set_training(training);
+    }
+}
+
+impl<F: ResetParams> ResetParams for DropPath<F> {
+    /// Pass through to `F`'s [ResetParams].
+    fn reset_params<R: Rng>(&mut self, rng: &mut R) {
+        self.f.reset_params(rng);
+    }
+}
+
+impl<T, F> Module<T> for DropPath<F>
+where
+    T: Tensor<Dtype = f32, Tape = NoneTape>,
+    F: Module<T, Output = T>,
+{
+    type Output = T;
+    /// Calls `x + (1 - p) * F(x)`.
+    fn forward(&self, x: T) -> Self::Output {
+        add(
+            mul_scalar(self.f.forward(x.with_empty_tape()), 1.0 - self.p),
+            x,
+        )
+    }
+}
+
+impl<T, F, A, const B: usize, const M: usize> ModuleMut<T> for DropPath<F>
+where
+    T: Tensor<Dtype = f32, Tape = OwnedTape, Array = [[A; M]; B]>,
+    F: ModuleMut<T, Output = T>,
+    A: CountElements<Dtype = f32>,
+    Cpu: FillElements<[A; M]>,
+{
+    type Output = T;
+    /// Calls `x + mask * F(x)`, where `mask` is `0` for each sample with probability `p`, and `1` otherwise.
+    /// In evaluation mode, this calls `x + (1 - p) * F(x)` instead.
+    fn forward_mut(&mut self, x: T) -> Self::Output {
+        if !self.training {
+            let y = self.f.forward_mut(x.with_empty_tape());
+            return add(mul_scalar(y, 1.0 - self.p), x);
+        }
+        let mut mask: T::NoTape = TensorCreator::zeros();
+        for mask_b in mask.mut_data().iter_mut() {
+            let keep = if self.rng.gen::<f32>() < self.p {
+                0.0
+            } else {
+                1.0
+            };
+            Cpu::fill(mask_b, &mut |v| *v = keep);
+        }
+        add(mul(self.f.forward_mut(x.with_empty_tape()), mask), x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+
+    #[test]
+    fn test_drop_path_eval_scales_branch() {
+        let model: DropPath<Linear<2, 2>> = DropPath::new(
+            Linear {
+                weight: tensor([[1.0, 0.0], [0.0, 2.0]]),
+                bias: tensor([0.0, 1.0]),
+            },
+            0.25,
+            0,
+        );
+        let y = model.forward(tensor([[1.0, 2.0], [-1.0, 0.5]]));
+        assert_close(y.data(), &[[1.75, 5.75], [-1.75, 2.0]]);
+    }
+
+    #[test]
+    fn test_drop_path_drops_whole_samples() {
+        let mut model: DropPath<Linear<3, 3>> = DropPath::new(Default::default(), 0.5, 0);
+        model.f.bias = tensor([1.0; 3]);
+        let x: Tensor2D<16, 3> = TensorCreator::zeros();
+        let y = model.forward_mut(x.trace());
+        let mut num_dropped = 0;
+        for y_b in y.data().iter() {
+            assert!(y_b == &[0.0; 3] || y_b == &[1.0; 3]);
+            if y_b == &[0.0; 3] {
+                num_dropped += 1;
+            }
+        }
+        assert!(num_dropped > 0 && num_dropped < 16);
+
+        let g = backward(y.sum());
+        assert_eq!(
+            g.ref_gradient(&model.f.bias),
+            &[(16 - num_dropped) as f32; 3]
+        );
+        assert_eq!(g.ref_gradient(&x), &[[1.0; 3]; 16]);
+    }
+
+    #[test]
+    fn test_drop_path_zero_p() {
+        let mut model: DropPath<ReLU> = DropPath::p(0.0);
+        let x = tensor([[[-1.0, 2.0]], [[3.0, -4.0]]]);
+        let y = model.forward_mut(x.trace());
+        assert_eq!(y.data(), &[[[-1.0, 4.0]], [[6.0, -4.0]]]);
+    }
+}
@@ -1,8 +1,8 @@
 use super::*;
 use crate::gradients::*;
+use crate::rng::default_rng;
 use crate::tensor::*;
 use crate::tensor_ops::dropout;
-use crate::unique_id::unique_id;
 use rand::prelude::*;
 
 /// Does nothing as a [Module], and calls [dropout()] as [ModuleMut] with probability `1.0 / N`.
@@ -45,15 +45,28 @@ use rand::prelude::*;
 #[derive(Clone, Debug)]
 pub struct DropoutOneIn<const N: usize> {
     rng: StdRng,
+    training: bool,
+}
+
+impl<const N: usize> DropoutOneIn<N> {
+    /// Whether [ModuleMut] applies dropout. See [ModuleMode].
+    pub fn is_training(&self) -> bool {
+        self.training
+    }
+}
+
+impl<const N: usize> ModuleMode for DropoutOneIn<N> {
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
 }
 
 impl<const N: usize> Default for DropoutOneIn<N> {
-    /// Seeds [StdRng] with a new seed every time this is called. The seed is initialized
-    /// with a deterministic value.
+    /// Uses [default_rng()], so the rng is different every time this is called.
     fn default() -> Self {
-        let seed = unique_id().as_u64();
         Self {
-            rng: StdRng::seed_from_u64(seed),
+            rng: default_rng(),
+            training: true,
         }
     }
 }
@@ -78,9 +91,13 @@ impl<const N: usize, T: Tensor<Dtype = f32, Tape = NoneTape>> Module<T> for Drop
 
 impl<const N: usize, T: Tensor<Dtype = f32, Tape = OwnedTape>> ModuleMut<T> for DropoutOneIn<N> {
     type Output = T;
-    /// Calls [dropout()] with `p=1/N` using `self.rng`.
+    /// Calls [dropout()] with `p=1/N` using `self.rng`. Does nothing in evaluation mode.
     fn forward_mut(&mut self, input: T) -> Self::Output {
-        dropout(input, 1.0 / N as f32, &mut self.rng)
+        if self.training {
+            dropout(input, 1.0 / N as f32, &mut self.rng)
+        } else {
+            input
+        }
     }
 }
 
@@ -125,6 +142,7 @@ impl<const N: usize, T: Tensor<Dtype = f32, Tape = OwnedTape>> ModuleMut<T> for
 pub struct Dropout {
     pub p: f32,
     rng: StdRng,
+    training: bool,
 }
 
 impl Dropout {
@@ -133,19 +151,33 @@ impl Dropout {
         Self {
             p,
             rng: StdRng::seed_from_u64(rng_seed),
+            training: true,
         }
     }
 
-    /// Constructs [Dropout] with `p` and a different seed every call.
+    /// Constructs [Dropout] with `p`, using [default_rng()].
     pub fn p(p: f32) -> Self {
-        let seed = unique_id().as_u64();
         Self {
             p,
-            rng: StdRng::seed_from_u64(seed),
+            rng: default_rng(),
+            training: true,
         }
     }
 }
 
+impl Dropout {
+    /// Whether [ModuleMut] applies dropout. See [ModuleMode].
+    pub fn is_training(&self) -> bool {
+        self.training
+    }
+}
+
+impl ModuleMode for Dropout {
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
 impl Default for Dropout {
     /// Sets `self.p` to `0.5`, and seeds [StdRng] with 0.
     fn default() -> Self {
@@ -173,9 +205,13 @@ impl<T: Tensor<Dtype = f32, Tape = NoneTape>> Module<T> for Dropout {
 
 impl<T: Tensor<Dtype = f32, Tape = OwnedTape>> ModuleMut<T> for Dropout {
     type Output = T;
-    /// Calls [dropout()]
+    /// Calls [dropout()]. Does nothing in evaluation mode.
     fn forward_mut(&mut self, input: T) -> Self::Output {
-        dropout(input, self.p, &mut self.rng)
+        if self.training {
+            dropout(input, self.p, &mut self.rng)
+        } else {
+            input
+        }
     }
 }
 
@@ -211,4 +247,17 @@ mod tests {
         let r = dropout.forward_mut(t.trace());
         assert!(t.data() != r.data());
     }
+
+    #[test]
+    fn test_dropout_eval_mode() {
+        let mut dropout = Dropout::p(0.5);
+        dropout.eval();
+        let t: Tensor1D<100> = Tensor1D::ones();
+        let r = dropout.forward_mut(t.trace());
+        assert_eq!(t.data(), r.data());
+
+        dropout.train();
+        let r = dropout.forward_mut(t.trace());
+        assert!(t.data() != r.data());
+    }
 }
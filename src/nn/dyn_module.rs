@@ -0,0 +1,216 @@
+use super::{Module, ModuleMode, ModuleMut, ResetParams};
+use rand::RngCore;
+use std::boxed::Box;
+
+/// An object safe version of [ResetParams] and [ModuleMode], which is a supertrait of
+/// [DynModule] and [DynModuleMut].
+///
+/// This is implemented for everything that implements both of those traits.
+pub trait DynParams {
+    /// Calls [ResetParams::reset_params()].
+    fn reset_params_dyn(&mut self, rng: &mut dyn RngCore);
+
+    /// Calls [ModuleMode::set_training()].
+    fn set_training_dyn(&mut self, training: bool);
+}
+
+impl<M: ResetParams + ModuleMode> DynParams for M {
+    fn reset_params_dyn(&mut self, mut rng: &mut dyn RngCore) {
+        self.reset_params(&mut rng)
+    }
+
+    fn set_training_dyn(&mut self, training: bool) {
+        self.set_training(training)
+    }
+}
+
+/// An object safe version of [Module] with a fixed `Input` and `Output`, so that different
+/// types of modules can be stored as a [BoxedModule], e.g. in a [std::vec::Vec] or a map
+/// of models to select from at runtime.
+///
+/// This is implemented for everything that implements [Module], [ResetParams] and [ModuleMode].
+/// [BoxedModule] implements those traits again, so it can be used like any other module
+/// (including in tuples). See [DynModuleMut] for [ModuleMut].
+///
+/// [crate::gradients::CanUpdateWithGradients], [super::VisitParams] and [super::SaveToNpz]
+/// are generic over their arguments, so they can't be called on a [BoxedModule]. This means a
+/// [BoxedModule] can be traced through, but can't be updated by an optimizer, visited or saved.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let mut models: Vec<BoxedModule<Tensor1D<4>, Tensor1D<2>>> = vec![
+///     Linear::<4, 2>::default().into_boxed(),
+///     (Linear::<4, 8>::default(), ReLU, Linear::<8, 2>::default()).into_boxed(),
+/// ];
+/// for model in models.iter_mut() {
+///     model.reset_params(&mut default_rng());
+/// }
+/// let y: Tensor1D<2> = models[1].forward(TensorCreator::zeros());
+/// ```
+pub trait DynModule<Input, Output>: DynParams {
+    /// Calls [Module::forward()].
+    fn forward_dyn(&self, input: Input) -> Output;
+
+    /// Boxes `self` as a [BoxedModule].
+    fn into_boxed(self) -> BoxedModule<Input, Output>
+    where
+        Self: 'static + Sized,
+    {
+        Box::new(self)
+    }
+}
+
+/// An object safe version of [ModuleMut] with a fixed `Input` and `Output`. See [DynModule].
+///
+/// This is implemented for everything that implements [ModuleMut], [ResetParams] and
+/// [ModuleMode], and [BoxedModuleMut] implements those traits again.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let mut model: BoxedModuleMut<Tensor1D<4, OwnedTape>, Tensor1D<4, OwnedTape>> =
+///     Dropout::p(0.5).into_boxed_mut();
+/// let y = model.forward_mut(Tensor1D::zeros().traced());
+/// ```
+pub trait DynModuleMut<Input, Output>: DynParams {
+    /// Calls [ModuleMut::forward_mut()].
+    fn forward_mut_dyn(&mut self, input: Input) -> Output;
+
+    /// Boxes `self` as a [BoxedModuleMut].
+    fn into_boxed_mut(self) -> BoxedModuleMut<Input, Output>
+    where
+        Self: 'static + Sized,
+    {
+        Box::new(self)
+    }
+}
+
+/// A boxed [DynModule] that maps `Input` to `Output`.
+pub type BoxedModule<Input, Output> = Box<dyn DynModule<Input, Output>>;
+
+/// A boxed [DynModuleMut] that maps `Input` to `Output`.
+pub type BoxedModuleMut<Input, Output> = Box<dyn DynModuleMut<Input, Output>>;
+
+impl<Input, Output, M> DynModule<Input, Output> for M
+where
+    M: Module<Input, Output = Output> + ResetParams + ModuleMode,
+{
+    fn forward_dyn(&self, input: Input) -> Output {
+        self.forward(input)
+    }
+}
+
+impl<Input, Output, M> DynModuleMut<Input, Output> for M
+where
+    M: ModuleMut<Input, Output = Output> + ResetParams + ModuleMode,
+{
+    fn forward_mut_dyn(&mut self, input: Input) -> Output {
+        self.forward_mut(input)
+    }
+}
+
+impl<Input, Output> Module<Input> for BoxedModule<Input, Output> {
+    type Output = Output;
+    fn forward(&self, input: Input) -> Self::Output {
+        self.as_ref().forward_dyn(input)
+    }
+}
+
+impl<Input, Output> ModuleMut<Input> for BoxedModule<Input, Output> {
+    type Output = Output;
+    fn forward_mut(&mut self, input: Input) -> Self::Output {
+        self.forward(input)
+    }
+}
+
+impl<Input, Output> ModuleMut<Input> for BoxedModuleMut<Input, Output> {
+    type Output = Output;
+    fn forward_mut(&mut self, input: Input) -> Self::Output {
+        self.as_mut().forward_mut_dyn(input)
+    }
+}
+
+macro_rules! boxed_params_impl {
+    ($Boxed:ident) => {
+        impl<Input, Output> ResetParams for $Boxed<Input, Output> {
+            fn reset_params<R: rand::Rng>(&mut self, rng: &mut R) {
+                self.as_mut().reset_params_dyn(rng)
+            }
+        }
+
+        impl<Input, Output> ModuleMode for $Boxed<Input, Output> {
+            fn set_training(&mut self, training: bool) {
+                self.as_mut().set_training_dyn(training)
+            }
+        }
+    };
+}
+
+boxed_params_impl!(BoxedModule);
+boxed_params_impl!(BoxedModuleMut);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::tests::assert_close;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::{vec, vec::Vec};
+
+    #[test]
+    fn test_boxed_module_matches_module() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut linear: Linear<3, 2> = Default::default();
+        linear.reset_params(&mut rng);
+        let x = tensor([1.0, -2.0, 0.5]);
+
+        let boxed: BoxedModule<Tensor1D<3>, Tensor1D<2>> = linear.clone().into_boxed();
+        assert_eq!(
+            boxed.forward(x.clone()).data(),
+            linear.forward(x.clone()).data()
+        );
+
+        let boxed: BoxedModule<Tensor1D<3, OwnedTape>, _> = linear.clone().into_boxed();
+        let y: Tensor1D<2, OwnedTape> = (boxed, Tanh).forward(x.trace());
+        let g = y.sum().backward();
+        let expected = linear.forward(x.trace()).tanh().sum().backward();
+        assert_close(g.ref_gradient(&x), expected.ref_gradient(&x));
+        assert_close(
+            g.ref_gradient(&linear.weight),
+            expected.ref_gradient(&linear.weight),
+        );
+    }
+
+    #[test]
+    fn test_boxed_module_registry() {
+        let mut models: Vec<(&str, BoxedModule<Tensor1D<2>, Tensor1D<2>>)> = vec![
+            ("relu", ReLU.into_boxed()),
+            ("linear", Linear::<2, 2>::default().into_boxed()),
+            (
+                "mlp",
+                (Linear::<2, 4>::default(), Linear::<4, 2>::default()).into_boxed(),
+            ),
+        ];
+        for (_, model) in models.iter_mut() {
+            model.reset_params(&mut StdRng::seed_from_u64(0));
+        }
+        let x = tensor([1.0, -1.0]);
+        let (_, relu) = models.iter().find(|(name, _)| *name == "relu").unwrap();
+        assert_eq!(relu.forward(x.clone()).data(), &[1.0, 0.0]);
+        for (_, model) in models.iter_mut().skip(1) {
+            assert_ne!(model.forward_mut(x.clone()).data(), &[0.0, 0.0]);
+        }
+    }
+
+    #[test]
+    fn test_boxed_module_mut_mode() {
+        let mut dropout: BoxedModuleMut<Tensor1D<2, OwnedTape>, _> =
+            Dropout::p(1.0).into_boxed_mut();
+        let x = tensor([1.0, -1.0]);
+        assert_eq!(dropout.forward_mut(x.trace()).data(), &[0.0, 0.0]);
+        dropout.eval();
+        let y: Tensor1D<2, OwnedTape> = dropout.forward_mut(x.trace());
+        assert_eq!(y.data(), &[1.0, -1.0]);
+    }
+}
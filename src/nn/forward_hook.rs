@@ -0,0 +1,156 @@
+use crate::gradients::{CanUpdateWithGradients, GradientProvider, UnusedTensors};
+use crate::prelude::*;
+
+/// Something that observes the input & output of a module every time it is run. See [WithForwardHook].
+///
+/// This is implemented for all closures `Fn(&I, &O)`, so a closure can be used directly as a hook.
+/// Use interior mutability (e.g. [std::cell::RefCell]) to record values from inside the hook.
+pub trait ForwardHook<I, O> {
+    /// Called after the module has been run on `input` and produced `output`.
+    fn on_forward(&self, input: &I, output: &O);
+}
+
+impl<I, O, F: Fn(&I, &O)> ForwardHook<I, O> for F {
+    fn on_forward(&self, input: &I, output: &O) {
+        self(input, output)
+    }
+}
+
+/// Runs `M`, and then calls [ForwardHook::on_forward()] of `H` with the input & output of `M`.
+/// This can be used to extract features from intermediate layers, or to log activation statistics,
+/// without splitting the model into separate stages.
+///
+/// The hook receives both tensors without a tape, so the same hook works for both [NoneTape]
+/// and [OwnedTape], and cannot affect the gradients. The output of [WithForwardHook] is
+/// exactly the output of `M`.
+///
+/// Generics:
+/// - `M`: The module to observe.
+/// - `H`: The [ForwardHook] to call.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// use std::{cell::RefCell, rc::Rc};
+/// let activations = Rc::new(RefCell::new(Vec::new()));
+/// let recorder = activations.clone();
+/// let model = (
+///     WithForwardHook::new(Linear::<5, 3>::default(), move |_: &Tensor1D<5>, y: &Tensor1D<3>| {
+///         recorder.borrow_mut().push(y.clone());
+///     }),
+///     ReLU,
+///     Linear::<3, 2>::default(),
+/// );
+/// let _: Tensor1D<2> = model.forward(Tensor1D::zeros());
+/// let _: Tensor1D<2> = model.forward(Tensor1D::ones());
+/// assert_eq!(activations.borrow().len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WithForwardHook<M, H> {
+    pub module: M,
+    pub hook: H,
+}
+
+impl<M, H> WithForwardHook<M, H> {
+    /// Attaches `hook` to `module`.
+    pub fn new(module: M, hook: H) -> Self {
+        Self { module, hook }
+    }
+}
+
+impl<M: CanUpdateWithGradients, H> CanUpdateWithGradients for WithForwardHook<M, H> {
+    /// Pass through to `M`'s [CanUpdateWithGradients].
+    fn update<G: GradientProvider>(&mut self, grads: &mut G, unused: &mut UnusedTensors) {
+        self.module.update(grads, unused);
+    }
+}
+
+impl<M: ResetParams, H> ResetParams for WithForwardHook<M, H> {
+    /// Pass through to `M`'s [ResetParams].
+    fn reset_params<R: rand::Rng>(&mut self, rng: &mut R) {
+        self.module.reset_params(rng);
+    }
+}
+
+impl<T: Tensor, M: Module<T>, H> Module<T> for WithForwardHook<M, H>
+where
+    M::Output: Tensor,
+    H: ForwardHook<T::NoTape, <M::Output as Tensor>::NoTape>,
+{
+    type Output = M::Output;
+
+    /// Calls `M`'s [Module::forward()], and then passes the input & output to `H`.
+    fn forward(&self, x: T) -> Self::Output {
+        let (x, tape) = x.split_tape();
+        let (y, tape) = self.module.forward(x.clone().put_tape(tape)).split_tape();
+        self.hook.on_forward(&x, &y);
+        y.put_tape(tape)
+    }
+}
+
+impl<T: Tensor, M: ModuleMut<T>, H> ModuleMut<T> for WithForwardHook<M, H>
+where
+    M::Output: Tensor,
+    H: ForwardHook<T::NoTape, <M::Output as Tensor>::NoTape>,
+{
+    type Output = M::Output;
+
+    /// Calls `M`'s [ModuleMut::forward_mut()], and then passes the input & output to `H`.
+    fn forward_mut(&mut self, x: T) -> Self::Output {
+        let (x, tape) = x.split_tape();
+        let (y, tape) = self
+            .module
+            .forward_mut(x.clone().put_tape(tape))
+            .split_tape();
+        self.hook.on_forward(&x, &y);
+        y.put_tape(tape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::HasAxes;
+    use std::{cell::RefCell, vec::Vec};
+
+    #[derive(Default)]
+    struct MeanRecorder(RefCell<Vec<f32>>);
+
+    impl<I, O: Reduce<AllAxes, Reduced = Tensor0D> + Clone> ForwardHook<I, O> for MeanRecorder
+    where
+        O::Array: HasAxes<AllAxes>,
+    {
+        fn on_forward(&self, _: &I, output: &O) {
+            self.0.borrow_mut().push(*mean(output.clone()).data());
+        }
+    }
+
+    #[test]
+    fn test_forward_hook_sees_input_and_output() {
+        let inputs = RefCell::new(Vec::new());
+        let model = WithForwardHook::new(ReLU, |x: &Tensor1D<3>, y: &Tensor1D<3>| {
+            inputs.borrow_mut().push((*x.data(), *y.data()));
+        });
+        let _ = model.forward(tensor([-1.0, 0.0, 1.0]));
+        assert_eq!(
+            inputs.borrow().as_slice(),
+            &[([-1.0, 0.0, 1.0], [0.0, 0.0, 1.0])]
+        );
+    }
+
+    #[test]
+    fn test_forward_hook_keeps_gradients() {
+        let mut model = WithForwardHook::new(Linear::<2, 2>::default(), MeanRecorder::default());
+        model.module.weight = tensor([[1.0, 2.0], [3.0, 4.0]]);
+
+        let x = tensor([1.0, -1.0]);
+        let y = model.forward_mut(x.trace());
+        assert_eq!(y.data(), &[-1.0, -1.0]);
+        let g = backward(y.sum());
+        assert_eq!(g.ref_gradient(&x), &[4.0, 6.0]);
+        assert_eq!(g.ref_gradient(&model.module.weight), &[[1.0, -1.0]; 2]);
+
+        let _: Tensor2D<2, 2> = model.forward(tensor([[1.0, 1.0], [0.0, 1.0]]));
+        assert_eq!(model.hook.0.borrow().as_slice(), &[-1.0, 4.0]);
+    }
+}
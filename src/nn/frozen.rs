@@ -0,0 +1,177 @@
+use super::visit_params::ParamVisitor;
+use crate::gradients::{CanUpdateWithGradients, GradientProvider, UnusedTensors};
+use crate::prelude::*;
+
+/// Freezes the parameters of `M`, so optimizers don't update them. This is useful for fine-tuning
+/// only some of a model, like a head on top of a pretrained backbone.
+///
+/// Gradients still flow through `M` to the modules before it. If nothing before `M` needs gradients,
+/// call `M` with a [NoneTape] input instead, so nothing inside of `M` is recorded on the tape:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let model: (Frozen<Linear<5, 3>>, ReLU, Linear<3, 2>) = Default::default();
+/// let x: Tensor1D<5> = TensorCreator::zeros();
+/// let features = model.0.forward(x);
+/// let y = (model.1, model.2).forward(features.traced());
+/// ```
+///
+/// [VisitParams] passes the parameters of `M` to [ParamVisitor::visit_frozen()] and
+/// [ParamVisitorMut::visit_frozen_mut()], so they are still included in [VisitParams::num_params()],
+/// [summary()], and when loading weights with a [ParamVisitorMut]. Saving & loading `Frozen<M>` is the same as `M`,
+/// so pretrained weights can be loaded directly.
+///
+/// # Generics
+/// - `M`: The module to freeze.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// type Model = (Frozen<Linear<5, 3>>, ReLU, Linear<3, 2>);
+/// let mut model: Model = Default::default();
+/// let mut opt: Sgd<Model> = Default::default();
+/// let y = model.forward(Tensor1D::<5>::zeros().traced());
+/// opt.update(&mut model, backward(y.mean())).expect("");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Frozen<M>(pub M);
+
+impl<M> CanUpdateWithGradients for Frozen<M> {
+    /// Does nothing, so `M`'s parameters are not updated, or reported as unused.
+    fn update<G: GradientProvider>(&mut self, _: &mut G, _: &mut UnusedTensors) {}
+}
+
+impl<M: ResetParams> ResetParams for Frozen<M> {
+    /// Pass through to `M`'s [ResetParams].
+    fn reset_params<R: rand::Rng>(&mut self, rng: &mut R) {
+        self.0.reset_params(rng);
+    }
+}
+
+impl<T, M: Module<T>> Module<T> for Frozen<M> {
+    type Output = M::Output;
+    fn forward(&self, x: T) -> Self::Output {
+        self.0.forward(x)
+    }
+}
+
+impl<T, M: ModuleMut<T>> ModuleMut<T> for Frozen<M> {
+    type Output = M::Output;
+    fn forward_mut(&mut self, x: T) -> Self::Output {
+        self.0.forward_mut(x)
+    }
+}
+
+impl<M: VisitParams> VisitParams for Frozen<M> {
+    /// Passes `M`'s parameters to [ParamVisitor::visit_frozen()], at the same prefix.
+    fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+        self.0.visit_named(p, &mut VisitFrozen(v));
+    }
+
+    /// Passes `M`'s parameters to [ParamVisitorMut::visit_frozen_mut()], at the same prefix.
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+        self.0.visit_named_mut(p, &mut VisitFrozen(v));
+    }
+}
+
+/// Passes every parameter to [ParamVisitor::visit_frozen()] or [ParamVisitorMut::visit_frozen_mut()]
+/// of the inner visitor.
+struct VisitFrozen<'a, V>(&'a mut V);
+
+impl<'a, V: ParamVisitor> ParamVisitor for VisitFrozen<'a, V> {
+    fn visit<T: Tensor<Dtype = f32>>(&mut self, name: &str, param: &T) {
+        self.0.visit_frozen(name, param);
+    }
+
+    fn visit_frozen<T: Tensor<Dtype = f32>>(&mut self, name: &str, param: &T) {
+        self.0.visit_frozen(name, param);
+    }
+}
+
+impl<'a, V: ParamVisitorMut> ParamVisitorMut for VisitFrozen<'a, V> {
+    fn visit_mut<T: Tensor<Dtype = f32>>(&mut self, name: &str, param: &mut T) {
+        self.0.visit_frozen_mut(name, param);
+    }
+
+    fn visit_frozen_mut<T: Tensor<Dtype = f32>>(&mut self, name: &str, param: &mut T) {
+        self.0.visit_frozen_mut(name, param);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::devices::FillElements;
+    use rand::{prelude::StdRng, SeedableRng};
+
+    #[test]
+    fn test_frozen_params_not_updated() {
+        type Model = (Frozen<Linear<3, 4>>, ReLU, Linear<4, 2>);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut model: Model = Default::default();
+        model.reset_params(&mut rng);
+        let model_0 = model.clone();
+
+        let mut opt: Sgd<Model> = Default::default();
+        let x: Tensor2D<4, 3> = TensorCreator::randn(&mut rng);
+        let y = model.forward(x.trace());
+        opt.update(&mut model, backward(y.square().mean()))
+            .expect("");
+
+        assert_eq!(model.0 .0.weight.data(), model_0.0 .0.weight.data());
+        assert_eq!(model.0 .0.bias.data(), model_0.0 .0.bias.data());
+        assert_ne!(model.2.weight.data(), model_0.2.weight.data());
+    }
+
+    #[test]
+    fn test_frozen_gradients_flow_through() {
+        type Model = (Linear<3, 3>, Frozen<Linear<3, 3>>);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut model: Model = Default::default();
+        model.reset_params(&mut rng);
+        let model_0 = model.clone();
+
+        let mut opt: Sgd<Model> = Default::default();
+        let x: Tensor1D<3> = TensorCreator::randn(&mut rng);
+        let y = model.forward(x.trace());
+        opt.update(&mut model, backward(y.square().mean()))
+            .expect("");
+
+        assert_ne!(model.0.weight.data(), model_0.0.weight.data());
+        assert_eq!(model.1 .0.weight.data(), model_0.1 .0.weight.data());
+    }
+
+    #[test]
+    fn test_frozen_visit_params() {
+        let mut model: (Frozen<Linear<3, 4>>, Linear<4, 2>) = Default::default();
+        assert_eq!(model.num_params(), 16 + 10);
+        assert_eq!(model.num_trainable_params(), 10);
+
+        let s = summary(&model);
+        assert_eq!(s.params.len(), 4);
+        assert_eq!(s.params[0].name, "0.weight");
+        assert!(!s.params[0].trainable);
+        assert!(s.params[2].trainable);
+        assert_eq!(s.num_trainable_params(), 10);
+
+        struct Fill(f32);
+        impl ParamVisitorMut for Fill {
+            fn visit_mut<T: Tensor<Dtype = f32>>(&mut self, _: &str, p: &mut T) {
+                T::Device::fill(p.mut_data(), &mut |v| *v = self.0);
+            }
+        }
+        model.visit_params_mut(&mut Fill(1.0));
+        assert_eq!(model.0 .0.weight.data(), &[[1.0; 3]; 4]);
+        assert_eq!(model.1.bias.data(), &[1.0; 2]);
+
+        struct FillTrainable(f32);
+        impl ParamVisitorMut for FillTrainable {
+            fn visit_mut<T: Tensor<Dtype = f32>>(&mut self, _: &str, p: &mut T) {
+                T::Device::fill(p.mut_data(), &mut |v| *v = self.0);
+            }
+            fn visit_frozen_mut<T: Tensor<Dtype = f32>>(&mut self, _: &str, _: &mut T) {}
+        }
+        model.visit_params_mut(&mut FillTrainable(2.0));
+        assert_eq!(model.0 .0.bias.data(), &[1.0; 4]);
+        assert_eq!(model.1.weight.data(), &[[2.0; 4]; 2]);
+    }
+}
@@ -122,6 +122,7 @@ mod tests {
             lr: 1.0,
             momentum: None,
             weight_decay: None,
+            weight_decay_1d: true,
         });
         sgd.update(&mut model, gradients).expect("");
 
@@ -0,0 +1,135 @@
+use crate::devices::{Cpu, FillElements};
+use crate::gradients::{CanUpdateWithGradients, GradientProvider, Tape, UnusedTensors};
+use crate::prelude::*;
+
+/// Scales the last axis of the input by a learnable per-channel vector [Self::gamma],
+/// as described in [Going deeper with Image Transformers](https://arxiv.org/abs/2103.17239).
+///
+/// This is intended to be applied at the end of residual branches. [Self::gamma] is initialized
+/// to [Self::init_value], which defaults to `1e-5`, so that each branch starts out close to the identity.
+///
+/// # Generics
+/// - `M` The size of the last axis of the input.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// type Block = Residual<(Linear<5, 5>, ReLU, LayerScale<5>)>;
+/// let model: Block = Default::default();
+/// let _: Tensor2D<3, 5> = model.forward(Tensor2D::zeros());
+/// ```
+#[derive(Debug, Clone)]
+pub struct LayerScale<const M: usize> {
+    pub gamma: Tensor1D<M>,
+    pub init_value: f32,
+}
+
+impl<const M: usize> LayerScale<M> {
+    /// Constructs [LayerScale] with [Self::gamma] filled with `init_value`.
+    pub fn new(init_value: f32) -> Self {
+        let mut gamma: Tensor1D<M> = TensorCreator::zeros();
+        Cpu::fill(gamma.mut_data(), &mut |v| *v = init_value);
+        Self { gamma, init_value }
+    }
+}
+
+impl<const M: usize> Default for LayerScale<M> {
+    /// Fills [Self::gamma] with `1e-5`.
+    fn default() -> Self {
+        Self::new(1e-5)
+    }
+}
+
+impl<const M: usize> ResetParams for LayerScale<M> {
+    /// Fills [Self::gamma] with [Self::init_value].
+    fn reset_params<R: rand::Rng>(&mut self, _: &mut R) {
+        let init_value = self.init_value;
+        Cpu::fill(self.gamma.mut_data(), &mut |v| *v = init_value);
+    }
+}
+
+impl<const M: usize> CanUpdateWithGradients for LayerScale<M> {
+    /// Updates [Self::gamma].
+    fn update<G: GradientProvider>(&mut self, grads: &mut G, unused: &mut UnusedTensors) {
+        self.gamma.update(grads, unused);
+    }
+}
+
+impl<H: Tape, const M: usize> Module<Tensor1D<M, H>> for LayerScale<M> {
+    type Output = Tensor1D<M, H>;
+
+    /// Calls [mul()] with [Self::gamma]
+    fn forward(&self, x: Tensor1D<M, H>) -> Self::Output {
+        mul(x, self.gamma.clone())
+    }
+}
+
+impl<H: Tape, const B: usize, const M: usize> Module<Tensor2D<B, M, H>> for LayerScale<M> {
+    type Output = Tensor2D<B, M, H>;
+
+    /// Calls [mul()] with [Self::gamma] broadcasted over the first axis.
+    fn forward(&self, x: Tensor2D<B, M, H>) -> Self::Output {
+        x * self.gamma.clone()
+    }
+}
+
+impl<H: Tape, const B: usize, const S: usize, const M: usize> Module<Tensor3D<B, S, M, H>>
+    for LayerScale<M>
+{
+    type Output = Tensor3D<B, S, M, H>;
+
+    /// Calls [mul()] with [Self::gamma] broadcasted over the first two axes.
+    fn forward(&self, x: Tensor3D<B, S, M, H>) -> Self::Output {
+        x * self.gamma.clone()
+    }
+}
+
+impl<T, const M: usize> ModuleMut<T> for LayerScale<M>
+where
+    Self: Module<T>,
+{
+    type Output = <Self as Module<T>>::Output;
+    fn forward_mut(&mut self, input: T) -> Self::Output {
+        self.forward(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+    use rand::{prelude::StdRng, SeedableRng};
+    use rand_distr::Standard;
+
+    #[test]
+    fn test_layer_scale_reset() {
+        let mut m: LayerScale<3> = LayerScale::new(0.1);
+        assert_eq!(m.gamma.data(), &[0.1; 3]);
+
+        let mut rng = StdRng::seed_from_u64(0);
+        m.gamma.randomize(&mut rng, &Standard);
+        assert_ne!(m.gamma.data(), &[0.1; 3]);
+
+        m.reset_params(&mut rng);
+        assert_eq!(m.gamma.data(), &[0.1; 3]);
+        assert_eq!(LayerScale::<3>::default().gamma.data(), &[1e-5; 3]);
+    }
+
+    #[test]
+    fn test_layer_scale_forward() {
+        let mut m = LayerScale {
+            gamma: tensor([1.0, 2.0, -0.5]),
+            init_value: 1e-5,
+        };
+
+        let x = tensor([[1.0, 2.0, 3.0], [-1.0, 0.5, 2.0]]);
+        let r = m.forward_mut(x.trace());
+        assert_close(r.data(), &[[1.0, 4.0, -1.5], [-1.0, 1.0, -1.0]]);
+        let g = backward(r.mean());
+        assert_close(g.ref_gradient(&m.gamma), &[0.0, 2.5 / 6.0, 5.0 / 6.0]);
+        assert_close(g.ref_gradient(&x), &[[1.0 / 6.0, 2.0 / 6.0, -0.5 / 6.0]; 2]);
+
+        let r = m.forward(Tensor3D::<2, 4, 3>::ones());
+        assert_eq!(r.data(), &[[[1.0, 2.0, -0.5]; 4]; 2]);
+    }
+}
@@ -67,11 +67,9 @@ impl<const B: usize, const I: usize, const O: usize, H: Tape> Module<Tensor2D<B,
 {
     type Output = Tensor2D<B, O, H>;
 
-    /// Batched 2d forward using [matmul()] and [add()]
+    /// Batched 2d forward using [matmul()] and a broadcasted add of the bias
     fn forward(&self, x: Tensor2D<B, I, H>) -> Self::Output {
-        let x = matmul_transpose(x, self.weight.clone());
-        let bias: Self::Output = self.bias.with_diff_tape().broadcast();
-        add(x, bias)
+        matmul_transpose(x, self.weight.clone()) + self.bias.clone()
     }
 }
 
@@ -80,11 +78,9 @@ impl<const B: usize, const S: usize, const I: usize, const O: usize, H: Tape>
 {
     type Output = Tensor3D<B, S, O, H>;
 
-    /// Batched 3d forward using [matmul()] and [add()]
+    /// Batched 3d forward using [matmul()] and a broadcasted add of the bias
     fn forward(&self, x: Tensor3D<B, S, I, H>) -> Self::Output {
-        let x = matmul_transpose(x, self.weight.clone());
-        let bias: Self::Output = self.bias.with_diff_tape().broadcast();
-        add(bias, x)
+        matmul_transpose(x, self.weight.clone()) + self.bias.clone()
     }
 }
 
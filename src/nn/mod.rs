@@ -20,8 +20,13 @@
 //! two functions:
 //!
 //! - [BatchNorm2D]
+//! - [DropPath]
 //! - [DropoutOneIn]
 //! - [Dropout]
+//! - [SpectralNorm]
+//!
+//! The behavior of [ModuleMut::forward_mut()] for these can also be switched at runtime with
+//! [ModuleMode::train()] and [ModuleMode::eval()], so the same model can be used for both.
 //!
 //! # Initializing
 //!
@@ -61,6 +66,33 @@
 //! );
 //! ```
 //!
+//! # Boxed modules
+//!
+//! [Module] is generic over its input, so models of different types can't be stored together. For a
+//! fixed input & output, [DynModule::into_boxed()] turns any module into a [BoxedModule], which can be
+//! stored in a `Vec` or map and selected at runtime, and still implements [Module]. [DynModuleMut]
+//! and [BoxedModuleMut] do the same for [ModuleMut]. Boxed modules can't be updated by optimizers.
+//!
+//! # Visiting parameters
+//!
+//! Call [VisitParams::visit_params()] with a [ParamVisitor] to look at every parameter of a model,
+//! along with its hierarchical name (e.g. `"2.0.weight"`). [VisitParams::visit_params_mut()] with a
+//! [ParamVisitorMut] can modify them. All modules provided here implement [VisitParams], including tuples.
+//!
+//! [summary()] and [summary_with_input()] use this to print a table of the parameters of a model,
+//! and [ModuleShapes] to add the output shape of each layer.
+//!
+//! # Freezing parameters
+//!
+//! Wrap any module in [Frozen] to keep optimizers from updating its parameters, e.g. `(Frozen<Backbone>, Head)`.
+//!
+//! # Quantization
+//!
+//! For inference, a trained [Linear] can be converted into a [QuantizedLinear] with [QuantizedLinear::quantize()],
+//! which stores its weights as `i8` and multiplies with integers. [QScheme] selects per-tensor or per-channel,
+//! symmetric or affine (with a zero point) weight quantization. Inputs are quantized dynamically, or statically
+//! with a [MinMaxObserver] calibrated on representative data. On nightly, `QuantizedConv2D` does the same for `Conv2D`.
+//!
 //! # Saving and Loading
 //!
 //! Call [SaveToNpz::save()] and [LoadFromNpz::load()] traits. All modules provided here implement it,
@@ -75,46 +107,88 @@
 //! state_dict = {k: torch.from_numpy(v) for k, v in np.load("dfdx-model.npz").items()}
 //! mlp.load_state_dict(state_dict)
 //! ```
+//!
+//! To load only part of a file, like a pretrained backbone, use [LoadFromNpz::load_partial()] or
+//! [LoadFromNpz::load_partial_with()], which report the parameters that weren't loaded instead of failing.
 
 mod activations;
 mod add_into;
 mod batchnorm2d;
+mod checkpoint;
+#[cfg(feature = "nightly")]
+mod concat_into;
 mod conv;
+mod drop_path;
 mod dropout;
+mod dyn_module;
 mod flatten;
+mod forward_hook;
+mod frozen;
 mod generalized_residual;
 mod impl_module_for_tuples;
 mod layer_norm;
+mod layer_scale;
 mod linear;
+mod mode;
 mod module;
+#[cfg(feature = "nightly")]
+mod pixel_shuffle;
 mod pool2d;
 mod pool_global;
+mod quantization;
+#[cfg(feature = "nightly")]
+mod quantized_conv;
+mod quantized_linear;
 mod repeated;
 mod residual;
+mod spectral_norm;
 mod split_into;
+mod summary;
 mod transformer;
+mod unbiased_linear;
+mod visit_params;
+mod visit_params_impls;
 
 pub use activations::*;
 pub use add_into::*;
 pub use batchnorm2d::*;
+pub use checkpoint::*;
+pub use drop_path::*;
 pub use dropout::*;
+pub use dyn_module::*;
+pub use forward_hook::*;
+pub use frozen::*;
 pub use generalized_residual::*;
 pub use impl_module_for_tuples::*;
 pub use layer_norm::*;
+pub use layer_scale::*;
 pub use linear::*;
+pub use mode::*;
 pub use module::*;
 pub use pool_global::*;
+pub use quantization::*;
+pub use quantized_linear::*;
 pub use repeated::*;
 pub use residual::*;
+pub use spectral_norm::*;
 pub use split_into::*;
+pub use summary::*;
+pub use unbiased_linear::*;
+pub use visit_params::*;
 
+#[cfg(feature = "nightly")]
+pub use concat_into::*;
 #[cfg(feature = "nightly")]
 pub use conv::*;
 #[cfg(feature = "nightly")]
 pub use flatten::*;
 #[cfg(feature = "nightly")]
+pub use pixel_shuffle::*;
+#[cfg(feature = "nightly")]
 pub use pool2d::*;
 #[cfg(feature = "nightly")]
+pub use quantized_conv::*;
+#[cfg(feature = "nightly")]
 pub use transformer::*;
 
 #[cfg(feature = "numpy")]
@@ -0,0 +1,219 @@
+use crate::devices::PaddingMode;
+use crate::prelude::*;
+
+// nightly includes
+#[cfg(not(feature = "nightly"))]
+use super::conv::Conv2D;
+#[cfg(not(feature = "nightly"))]
+use super::flatten::*;
+#[cfg(not(feature = "nightly"))]
+use super::pool2d::*;
+#[cfg(not(feature = "nightly"))]
+use super::transformer::*;
+
+/// Switches a module between training & evaluation mode at runtime, without changing its type.
+///
+/// Modules are in training mode by default. The mode only affects [ModuleMut::forward_mut()],
+/// since [Module::forward()] can't update any state and always evaluates:
+/// 1. Training mode: [ModuleMut::forward_mut()] applies [Dropout], [DropoutOneIn] & [DropPath],
+///    and [BatchNorm2D] normalizes with batch statistics and updates its running statistics.
+/// 2. Evaluation mode: [ModuleMut::forward_mut()] gives the same results as [Module::forward()],
+///    while still tracking gradients.
+///
+/// All [Module]s in nn implement ModuleMode, including tuples, and pass the mode to their sub modules.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let mut model: (Linear<5, 5>, Dropout) = Default::default();
+/// let x: Tensor1D<5> = TensorCreator::ones();
+///
+/// model.train();
+/// let _: Tensor1D<5, OwnedTape> = model.forward_mut(x.trace());
+///
+/// model.eval();
+/// let a: Tensor1D<5, OwnedTape> = model.forward_mut(x.trace());
+/// let b: Tensor1D<5> = model.forward(x.clone());
+/// assert_eq!(a.data(), b.data());
+/// ```
+pub trait ModuleMode {
+    /// Sets whether `self` and all of its sub modules are in training mode.
+    ///
+    /// There is no default, so that a module can't silently ignore the mode of its sub modules.
+    /// Modules without a mode implement this as an empty function.
+    fn set_training(&mut self, training: bool);
+
+    /// Puts `self` and all of its sub modules into training mode.
+    fn train(&mut self) {
+        self.set_training(true);
+    }
+
+    /// Puts `self` and all of its sub modules into evaluation mode.
+    fn eval(&mut self) {
+        self.set_training(false);
+    }
+}
+
+impl<F: ModuleMode, R: ModuleMode> ModuleMode for GeneralizedResidual<F, R> {
+    fn set_training(&mut self, training: bool) {
+        self.f.set_training(training);
+        self.r.set_training(training);
+    }
+}
+
+impl<M: ModuleMode, H> ModuleMode for WithForwardHook<M, H> {
+    fn set_training(&mut self, training: bool) {
+        self.module.set_training(training);
+    }
+}
+
+macro_rules! tuple_mode_impl {
+    ([$($name:ident),+], [$($idx:tt),+]) => {
+impl<$($name: ModuleMode),+> ModuleMode for ($($name,)+) {
+    fn set_training(&mut self, training: bool) {
+        $(self.$idx.set_training(training);)+
+    }
+}
+    };
+}
+
+tuple_mode_impl!([A, B], [0, 1]);
+tuple_mode_impl!([A, B, C], [0, 1, 2]);
+tuple_mode_impl!([A, B, C, D], [0, 1, 2, 3]);
+tuple_mode_impl!([A, B, C, D, E], [0, 1, 2, 3, 4]);
+tuple_mode_impl!([A, B, C, D, E, F], [0, 1, 2, 3, 4, 5]);
+
+impl<T: ModuleMode, const N: usize> ModuleMode for Repeated<T, N> {
+    fn set_training(&mut self, training: bool) {
+        for m in self.modules.iter_mut() {
+            m.set_training(training);
+        }
+    }
+}
+
+macro_rules! newtype_mode_impl {
+    ($TyName:ident) => {
+        impl<T: ModuleMode> ModuleMode for $TyName<T> {
+            fn set_training(&mut self, training: bool) {
+                self.0.set_training(training);
+            }
+        }
+    };
+}
+
+newtype_mode_impl!(Residual);
+newtype_mode_impl!(Checkpoint);
+newtype_mode_impl!(Frozen);
+newtype_mode_impl!(SplitInto);
+newtype_mode_impl!(AddInto);
+
+#[cfg(feature = "nightly")]
+impl<T: ModuleMode, const AXIS: isize> ModuleMode for ConcatInto<T, AXIS> {
+    fn set_training(&mut self, training: bool) {
+        self.0.set_training(training);
+    }
+}
+
+macro_rules! empty_mode_impl {
+    ([$($generics:tt)*] $TyName:ty) => {
+        impl<$($generics)*> ModuleMode for $TyName {
+            fn set_training(&mut self, _: bool) {}
+        }
+    };
+    ($TyName:ty) => {
+        empty_mode_impl!([] $TyName);
+    };
+}
+
+empty_mode_impl!(ReLU);
+empty_mode_impl!(Sin);
+empty_mode_impl!(Cos);
+empty_mode_impl!(Ln);
+empty_mode_impl!(Exp);
+empty_mode_impl!(Sigmoid);
+empty_mode_impl!(Tanh);
+empty_mode_impl!(Square);
+empty_mode_impl!(Sqrt);
+empty_mode_impl!(Abs);
+empty_mode_impl!(Softmax);
+empty_mode_impl!(LogSoftmax);
+empty_mode_impl!(AvgPoolGlobal);
+empty_mode_impl!(MaxPoolGlobal);
+empty_mode_impl!(MinPoolGlobal);
+empty_mode_impl!(Flatten2D);
+
+empty_mode_impl!([const M: usize] LayerNorm1D<M>);
+empty_mode_impl!([const M: usize] LayerScale<M>);
+empty_mode_impl!([const I: usize, const O: usize] Linear<I, O>);
+empty_mode_impl!([const I: usize, const O: usize] UnbiasedLinear<I, O>);
+
+empty_mode_impl!(
+    [const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, M: PaddingMode]
+    Conv2D<I, O, K, S, P, M>
+);
+#[cfg(feature = "nightly")]
+empty_mode_impl!(
+    [const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, M: PaddingMode]
+    UnbiasedConv2D<I, O, K, S, P, M>
+);
+
+#[cfg(feature = "nightly")]
+empty_mode_impl!([const R: usize] PixelShuffle<R>);
+
+empty_mode_impl!([const K: usize, const S: usize, const P: usize] AvgPool2D<K, S, P>);
+empty_mode_impl!([const K: usize, const S: usize, const P: usize] MaxPool2D<K, S, P>);
+empty_mode_impl!([const K: usize, const S: usize, const P: usize] MinPool2D<K, S, P>);
+
+empty_mode_impl!(
+    [const M: usize, const H: usize, const K: usize, const V: usize]
+    MultiHeadAttention<M, H, K, V>
+);
+empty_mode_impl!([const M: usize, const H: usize, const F: usize] TransformerEncoderBlock<M, H, F>);
+empty_mode_impl!([const M: usize, const H: usize, const F: usize] TransformerDecoderBlock<M, H, F>);
+empty_mode_impl!(
+    [const M: usize, const H: usize, const F: usize, const L: usize]
+    TransformerDecoder<M, H, F, L>
+);
+empty_mode_impl!(
+    [const M: usize, const H: usize, const E: usize, const D: usize, const F: usize]
+    Transformer<M, H, E, D, F>
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mode_reaches_nested_modules() {
+        type Model = (
+            Linear<4, 4>,
+            Residual<(Dropout, ReLU)>,
+            Repeated<DropPath<Linear<4, 4>>, 2>,
+        );
+        let mut model: Model = Default::default();
+        assert!(model.1 .0 .0.is_training());
+        assert!(model.2.modules[1].is_training());
+
+        model.eval();
+        assert!(!model.1 .0 .0.is_training());
+        assert!(!model.2.modules[0].is_training());
+        assert!(!model.2.modules[1].is_training());
+
+        model.train();
+        assert!(model.1 .0 .0.is_training());
+        assert!(model.2.modules[1].is_training());
+    }
+
+    #[test]
+    fn test_eval_forward_mut_matches_forward() {
+        let mut model: (Linear<4, 4>, Dropout, DropPath<Linear<4, 4>>) = Default::default();
+        model.reset_params(&mut rand::thread_rng());
+        let x: Tensor2D<3, 4> = TensorCreator::ones();
+        let y_train = model.forward_mut(x.trace());
+
+        model.eval();
+        let y_eval = model.forward_mut(x.trace());
+        assert_ne!(y_train.data(), y_eval.data());
+        assert_eq!(y_eval.data(), model.forward(x.clone()).data());
+    }
+}
@@ -1,9 +1,13 @@
 use crate::numpy::{self, NpyError, NumpyDtype, NumpyShape, ReadNumbers, WriteNumbers};
+use std::collections::BTreeSet;
 use std::error::Error;
 use std::{
-    io::{BufReader, BufWriter, Read, Seek, Write},
+    boxed::Box,
+    format,
+    io::{BufReader, BufWriter, Cursor, Read, Seek, Write},
     path::Path,
     string::String,
+    vec::Vec,
 };
 use zip::{
     result::{ZipError, ZipResult},
@@ -16,6 +20,9 @@ use zip::{
 pub trait SaveToNpz {
     /// Save this object into the `.npz` file determined located at `path`.
     ///
+    /// The version of dfdx and the type of `self` are stored in the comment of the archive, so
+    /// [LoadFromNpz::load()] can say which architecture a file came from if it fails to load.
+    ///
     /// Example:
     /// ```ignore
     /// # use dfdx::prelude::*;
@@ -26,6 +33,7 @@ pub trait SaveToNpz {
         let f = std::fs::File::create(path)?;
         let f = BufWriter::new(f);
         let mut zip = ZipWriter::new(f);
+        write_architecture::<Self, _>(&mut zip);
         self.write("", &mut zip)?;
         zip.finish()?;
         Ok(())
@@ -70,8 +78,54 @@ pub trait LoadFromNpz {
         let f = std::fs::File::open(path)?;
         let f = BufReader::new(f);
         let mut zip = ZipArchive::new(f)?;
-        self.read("", &mut zip)?;
-        Ok(())
+        let result = self.read("", &mut zip);
+        check_architecture::<Self, _>(&zip, result)
+    }
+
+    /// Loads every entry of the `.npz` at `path` that has the same name & shape as a parameter of `self`,
+    /// and leaves the rest of `self` unchanged. Unlike [LoadFromNpz::load()], missing and unexpected
+    /// entries are not errors, and are listed in the returned [LoadReport] instead.
+    ///
+    /// Example:
+    /// ```ignore
+    /// # use dfdx::prelude::*;
+    /// // the file has a (Linear<5, 10>, ReLU, Linear<10, 2>)
+    /// let mut model: (Linear<5, 10>, ReLU, Linear<10, 4>) = Default::default();
+    /// let report = model.load_partial("tst.npz")?;
+    /// assert_eq!(report.mismatched, ["2.weight", "2.bias"]);
+    /// ```
+    fn load_partial<P: AsRef<Path>>(&mut self, path: P) -> Result<LoadReport, NpzError>
+    where
+        Self: SaveToNpz,
+    {
+        self.load_partial_with(path, |name| name.into())
+    }
+
+    /// Same as [LoadFromNpz::load_partial()], but loads the entry named `name_map(name)` into the
+    /// parameter `name` of `self`. Names don't include the `.npy` extension.
+    ///
+    /// Example:
+    /// ```ignore
+    /// # use dfdx::prelude::*;
+    /// // the file has a (Linear<5, 10>, ReLU, Linear<10, 2>), and the first two are the backbone
+    /// let mut model: (Frozen<(Linear<5, 10>, ReLU)>, Linear<10, 4>) = Default::default();
+    /// let report = model.load_partial_with("tst.npz", |name| match name.strip_prefix("0.") {
+    ///     Some(name) => name.into(),
+    ///     None => format!("head.{name}"),
+    /// })?;
+    /// assert_eq!(report.missing, ["1.weight", "1.bias"]);
+    /// assert_eq!(report.unexpected, ["2.bias", "2.weight"]);
+    /// ```
+    fn load_partial_with<P, F>(&mut self, path: P, name_map: F) -> Result<LoadReport, NpzError>
+    where
+        Self: SaveToNpz,
+        P: AsRef<Path>,
+        F: FnMut(&str) -> String,
+    {
+        let f = std::fs::File::open(path)?;
+        let f = BufReader::new(f);
+        let mut zip = ZipArchive::new(f)?;
+        read_partial(self, &mut zip, name_map)
     }
 
     /// Reads this object from a [ZipArchive]. `r` with a base filename of `filename_prefix`.
@@ -94,6 +148,95 @@ pub trait LoadFromNpz {
     }
 }
 
+/// The names of the parameters loaded by [LoadFromNpz::load_partial()], and of the entries
+/// that were not. Names don't include the `.npy` extension.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadReport {
+    /// Parameters that were loaded from the file.
+    pub loaded: Vec<String>,
+
+    /// Parameters that have no entry in the file, and were left unchanged.
+    pub missing: Vec<String>,
+
+    /// Parameters whose entry in the file has a different shape, and were left unchanged.
+    pub mismatched: Vec<String>,
+
+    /// Entries in the file that were not loaded into any parameter.
+    pub unexpected: Vec<String>,
+}
+
+/// Builds an archive with the entries of `r` that match `m`, and the current values of `m` for
+/// everything else, and then reads `m` from it.
+fn read_partial<M, R, F>(
+    m: &mut M,
+    r: &mut ZipArchive<R>,
+    mut name_map: F,
+) -> Result<LoadReport, NpzError>
+where
+    M: LoadFromNpz + SaveToNpz + ?Sized,
+    R: Read + Seek,
+    F: FnMut(&str) -> String,
+{
+    let mut own = ZipWriter::new(Cursor::new(Vec::new()));
+    m.write("", &mut own)?;
+    let mut own = ZipArchive::new(Cursor::new(own.finish()?.into_inner()))?;
+
+    let mut report: LoadReport = Default::default();
+    let mut used = BTreeSet::new();
+    let mut merged = ZipWriter::new(Cursor::new(Vec::new()));
+    for i in 0..own.len() {
+        let mut f = own.by_index(i)?;
+        let name = String::from(f.name());
+        let mut data = Vec::new();
+        f.read_to_end(&mut data)?;
+        if let Some(key) = name.strip_suffix(".npy") {
+            let file_name = format!("{}.npy", name_map(key));
+            let file_data = match r.by_name(&file_name) {
+                Ok(mut f) => {
+                    let mut file_data = Vec::new();
+                    f.read_to_end(&mut file_data)?;
+                    Some(file_data)
+                }
+                Err(ZipError::FileNotFound) => None,
+                Err(e) => return Err(e.into()),
+            };
+            match file_data {
+                None => report.missing.push(key.into()),
+                Some(file_data) => {
+                    let shape = numpy::read_shape(&mut data.as_slice())?;
+                    let file_shape =
+                        numpy::read_shape(&mut file_data.as_slice()).map_err(|error| {
+                            NpzError::NpyFile {
+                                filename: file_name.clone(),
+                                error,
+                            }
+                        })?;
+                    used.insert(file_name);
+                    if shape == file_shape {
+                        report.loaded.push(key.into());
+                        data = file_data;
+                    } else {
+                        report.mismatched.push(key.into());
+                    }
+                }
+            }
+        }
+        merged.start_file(name, Default::default())?;
+        merged.write_all(&data)?;
+    }
+    report.unexpected = r
+        .file_names()
+        .filter(|name| !used.contains(*name))
+        .filter_map(|name| name.strip_suffix(".npy"))
+        .map(|name| name.into())
+        .collect();
+    report.unexpected.sort();
+
+    let mut merged = ZipArchive::new(Cursor::new(merged.finish()?.into_inner()))?;
+    m.read("", &mut merged)?;
+    Ok(report)
+}
+
 /// Error that can happen while loading data from a `.npz` zip archive.
 #[derive(Debug)]
 pub enum NpzError {
@@ -102,6 +245,22 @@ pub enum NpzError {
 
     /// Something went wrong with loading data from a `.npy` file
     Npy(NpyError),
+
+    /// The file was written by a newer version of the format.
+    UnsupportedVersion(u32),
+
+    /// A file that should be in the archive is missing.
+    MissingFile(String),
+
+    /// Something went wrong with loading the `.npy` file named `filename`.
+    NpyFile { filename: String, error: NpyError },
+
+    /// Loading failed, and the archive was saved from a different architecture.
+    Architecture {
+        expected: String,
+        found: String,
+        error: Box<NpzError>,
+    },
 }
 
 impl std::fmt::Display for NpzError {
@@ -109,6 +268,18 @@ impl std::fmt::Display for NpzError {
         match self {
             NpzError::Zip(err) => write!(fmt, "{}", err),
             NpzError::Npy(err) => write!(fmt, "{}", err),
+            NpzError::UnsupportedVersion(v) => write!(fmt, "unsupported version {}", v),
+            NpzError::MissingFile(filename) => write!(fmt, "{} is missing", filename),
+            NpzError::NpyFile { filename, error } => write!(fmt, "{}: {}", filename, error),
+            NpzError::Architecture {
+                expected,
+                found,
+                error,
+            } => write!(
+                fmt,
+                "{} (expected a {}, the file has a {})",
+                error, expected, found
+            ),
         }
     }
 }
@@ -118,6 +289,9 @@ impl Error for NpzError {
         match self {
             NpzError::Zip(err) => Some(err),
             NpzError::Npy(err) => Some(err),
+            NpzError::NpyFile { error, .. } => Some(error),
+            NpzError::Architecture { error, .. } => Some(error.as_ref()),
+            NpzError::UnsupportedVersion(_) | NpzError::MissingFile(_) => None,
         }
     }
 }
@@ -171,7 +345,68 @@ pub fn npz_fread<R: Read + Seek, T: NumpyDtype + NumpyShape + ReadNumbers>(
     filename: String,
     data: &mut T,
 ) -> Result<(), NpzError> {
-    let mut f = r.by_name(&filename)?;
-    numpy::read(&mut f, data)?;
-    Ok(())
+    let mut f = match r.by_name(&filename) {
+        Ok(f) => f,
+        Err(ZipError::FileNotFound) => return Err(NpzError::MissingFile(filename)),
+        Err(e) => return Err(e.into()),
+    };
+    numpy::read(&mut f, data).map_err(|error| NpzError::NpyFile { filename, error })
+}
+
+const ARCHITECTURE_KEY: &str = "architecture: ";
+
+/// Stores the version of dfdx and the architecture of `M` in the comment of `w`.
+pub(crate) fn write_architecture<M: ?Sized, W: Write + Seek>(w: &mut ZipWriter<W>) {
+    w.set_comment(format!(
+        "dfdx-version: {}\n{}{}",
+        env!("CARGO_PKG_VERSION"),
+        ARCHITECTURE_KEY,
+        architecture::<M>()
+    ));
+}
+
+/// If `result` is an error and `r` was saved from a different architecture than `M`,
+/// wraps the error in [NpzError::Architecture].
+pub(crate) fn check_architecture<M: ?Sized, R: Read + Seek>(
+    r: &ZipArchive<R>,
+    result: Result<(), NpzError>,
+) -> Result<(), NpzError> {
+    let error = match result {
+        Ok(()) => return Ok(()),
+        Err(error) => error,
+    };
+    let comment = String::from_utf8_lossy(r.comment());
+    let found = comment
+        .lines()
+        .find_map(|line| line.strip_prefix(ARCHITECTURE_KEY));
+    let expected = architecture::<M>();
+    match found {
+        Some(found) if found != expected => Err(NpzError::Architecture {
+            expected,
+            found: found.into(),
+            error: Box::new(error),
+        }),
+        _ => Err(error),
+    }
+}
+
+/// The name of `M` without module paths, e.g. `(Linear<5, 10>, ReLU)`.
+fn architecture<M: ?Sized>() -> String {
+    let mut name = String::new();
+    let mut segment = String::new();
+    let mut chars = std::any::type_name::<M>().chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_alphanumeric() || c == '_' {
+            segment.push(c);
+        } else if c == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            segment.clear();
+        } else {
+            name.push_str(&segment);
+            segment.clear();
+            name.push(c);
+        }
+    }
+    name.push_str(&segment);
+    name
 }
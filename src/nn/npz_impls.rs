@@ -1,4 +1,5 @@
 use super::npz::{npz_fread, npz_fwrite, LoadFromNpz, SaveToNpz};
+use crate::devices::PaddingMode;
 use crate::prelude::*;
 use std::format;
 use std::io::{Read, Seek, Write};
@@ -36,8 +37,10 @@ impl<const C: usize> LoadFromNpz for BatchNorm2D<C> {
     }
 }
 
-impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize> SaveToNpz
-    for Conv2D<I, O, K, S, P>
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, M> SaveToNpz
+    for Conv2D<I, O, K, S, P, M>
+where
+    M: PaddingMode,
 {
     fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
         npz_fwrite(w, format!("{p}weight.npy"), self.weight.data())?;
@@ -46,8 +49,10 @@ impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: us
     }
 }
 
-impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize> LoadFromNpz
-    for Conv2D<I, O, K, S, P>
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, M> LoadFromNpz
+    for Conv2D<I, O, K, S, P, M>
+where
+    M: PaddingMode,
 {
     fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
         npz_fread(r, format!("{p}weight.npy"), self.weight.mut_data())?;
@@ -56,6 +61,40 @@ impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: us
     }
 }
 
+#[cfg(feature = "nightly")]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, M> SaveToNpz
+    for UnbiasedConv2D<I, O, K, S, P, M>
+where
+    M: PaddingMode,
+{
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        npz_fwrite(w, format!("{p}weight.npy"), self.weight.data())
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, M> LoadFromNpz
+    for UnbiasedConv2D<I, O, K, S, P, M>
+where
+    M: PaddingMode,
+{
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        npz_fread(r, format!("{p}weight.npy"), self.weight.mut_data())
+    }
+}
+
+impl<F: SaveToNpz> SaveToNpz for DropPath<F> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.f.write(&format!("{p}.f"), w)
+    }
+}
+
+impl<F: LoadFromNpz> LoadFromNpz for DropPath<F> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.f.read(&format!("{p}.f"), r)
+    }
+}
+
 impl<F: SaveToNpz, R: SaveToNpz> SaveToNpz for GeneralizedResidual<F, R> {
     fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
         self.f.write(&format!("{p}.f"), w)?;
@@ -70,6 +109,20 @@ impl<F: LoadFromNpz, R: LoadFromNpz> LoadFromNpz for GeneralizedResidual<F, R> {
     }
 }
 
+impl<const M: usize> SaveToNpz for LayerScale<M> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        npz_fwrite(w, format!("{p}gamma.npy"), self.gamma.data())?;
+        Ok(())
+    }
+}
+
+impl<const M: usize> LoadFromNpz for LayerScale<M> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        npz_fread(r, format!("{p}gamma.npy"), self.gamma.mut_data())?;
+        Ok(())
+    }
+}
+
 impl<const M: usize> SaveToNpz for LayerNorm1D<M> {
     fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
         npz_fwrite(w, format!("{p}gamma.npy"), self.gamma.data())?;
@@ -102,6 +155,44 @@ impl<const I: usize, const O: usize> LoadFromNpz for Linear<I, O> {
     }
 }
 
+impl<const I: usize, const O: usize> SaveToNpz for UnbiasedLinear<I, O> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        npz_fwrite(w, format!("{p}weight.npy"), self.weight.data())
+    }
+}
+
+impl<const I: usize, const O: usize> LoadFromNpz for UnbiasedLinear<I, O> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        npz_fread(r, format!("{p}weight.npy"), self.weight.mut_data())
+    }
+}
+
+impl<M: SaveToNpz, H> SaveToNpz for WithForwardHook<M, H> {
+    /// Saves `module` at the same prefix, so it can be loaded directly into `M`.
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.module.write(p, w)
+    }
+}
+
+impl<M: LoadFromNpz, H> LoadFromNpz for WithForwardHook<M, H> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.module.read(p, r)
+    }
+}
+
+impl<M: SpectralNormalize + SaveToNpz> SaveToNpz for SpectralNorm<M> {
+    /// Saves `module` at the same prefix, so it can be loaded directly into `M`.
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.module.write(p, w)
+    }
+}
+
+impl<M: SpectralNormalize + LoadFromNpz> LoadFromNpz for SpectralNorm<M> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.module.read(p, r)
+    }
+}
+
 macro_rules! tuple_npz_impl {
     ([$($name:ident),+], [$($idx:tt),+]) => {
 impl<$($name: SaveToNpz),+> SaveToNpz for ($($name,)+) {
@@ -156,6 +247,32 @@ impl<F: LoadFromNpz> LoadFromNpz for Residual<F> {
     }
 }
 
+impl<M: SaveToNpz> SaveToNpz for Frozen<M> {
+    /// Saves `M` at the same prefix, so it can be loaded directly into `M`.
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.0.write(p, w)
+    }
+}
+
+impl<M: LoadFromNpz> LoadFromNpz for Frozen<M> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.0.read(p, r)
+    }
+}
+
+impl<M: SaveToNpz> SaveToNpz for Checkpoint<M> {
+    /// Saves `M` at the same prefix, so it can be loaded directly into `M`.
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.0.write(p, w)
+    }
+}
+
+impl<M: LoadFromNpz> LoadFromNpz for Checkpoint<M> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.0.read(p, r)
+    }
+}
+
 impl<T: SaveToNpz> SaveToNpz for SplitInto<T> {
     fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
         self.0.write(&format!("{p}.0"), w)
@@ -180,6 +297,20 @@ impl<T: LoadFromNpz> LoadFromNpz for AddInto<T> {
     }
 }
 
+#[cfg(feature = "nightly")]
+impl<T: SaveToNpz, const AXIS: isize> SaveToNpz for ConcatInto<T, AXIS> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.0.write(&format!("{p}.0"), w)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T: LoadFromNpz, const AXIS: isize> LoadFromNpz for ConcatInto<T, AXIS> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.0.read(&format!("{p}.0"), r)
+    }
+}
+
 impl<const M: usize, const H: usize, const F: usize, const L: usize> SaveToNpz
     for TransformerDecoder<M, H, F, L>
 {
@@ -314,6 +445,7 @@ empty_npz_impl!(Square);
 empty_npz_impl!(Sqrt);
 empty_npz_impl!(Abs);
 empty_npz_impl!(Softmax);
+empty_npz_impl!(LogSoftmax);
 empty_npz_impl!(Dropout);
 empty_npz_impl!(AvgPoolGlobal);
 empty_npz_impl!(MaxPoolGlobal);
@@ -323,6 +455,11 @@ empty_npz_impl!(Flatten2D);
 impl<const N: usize> SaveToNpz for DropoutOneIn<N> {}
 impl<const N: usize> LoadFromNpz for DropoutOneIn<N> {}
 
+#[cfg(feature = "nightly")]
+impl<const R: usize> SaveToNpz for PixelShuffle<R> {}
+#[cfg(feature = "nightly")]
+impl<const R: usize> LoadFromNpz for PixelShuffle<R> {}
+
 impl<const K: usize, const S: usize, const P: usize> SaveToNpz for AvgPool2D<K, S, P> {}
 impl<const K: usize, const S: usize, const P: usize> LoadFromNpz for AvgPool2D<K, S, P> {}
 impl<const K: usize, const S: usize, const P: usize> SaveToNpz for MaxPool2D<K, S, P> {}
@@ -392,6 +529,8 @@ mod tests {
     fn test_save_load_conv() {
         type T = Conv2D<2, 4, 3>;
         test_save_load::<Tensor3D<2, 8, 8>, T>();
+        type U = UnbiasedConv2D<2, 4, 3>;
+        test_save_load::<Tensor3D<2, 8, 8>, U>();
     }
 
     #[test]
@@ -408,6 +547,13 @@ mod tests {
         test_save_load::<Tensor1D<5>, (T, T)>();
     }
 
+    #[test]
+    fn test_save_load_unbiased_linear() {
+        type T = UnbiasedLinear<5, 5>;
+        test_save_load::<Tensor1D<5>, T>();
+        test_save_load::<Tensor1D<5>, (T, T)>();
+    }
+
     #[test]
     fn test_save_load_tuple() {
         type Model = (
@@ -442,6 +588,28 @@ mod tests {
         assert_eq!(loaded.forward(x).data(), y.data());
     }
 
+    #[test]
+    fn test_save_load_layer_scale() {
+        type M = LayerScale<3>;
+
+        let mut rng = thread_rng();
+        let x: Tensor2D<2, 3> = TensorCreator::randn(&mut rng);
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+
+        let mut saved: M = Default::default();
+        let mut loaded: M = Default::default();
+
+        saved.gamma.randomize(&mut rng, &Standard);
+        let y = saved.forward(x.clone());
+
+        assert_ne!(loaded.forward(x.clone()).data(), y.data());
+
+        saved.save(file.path()).expect("");
+        loaded.load(file.path()).expect("");
+
+        assert_eq!(loaded.forward(x).data(), y.data());
+    }
+
     #[test]
     fn test_save_load_repeated() {
         type T = Repeated<Linear<3, 3>, 4>;
@@ -449,6 +617,13 @@ mod tests {
         test_save_load::<Tensor1D<3>, (T, T)>();
     }
 
+    #[test]
+    fn test_save_load_drop_path() {
+        type T = DropPath<Linear<5, 5>>;
+        test_save_load::<Tensor2D<3, 5>, T>();
+        test_save_load::<Tensor2D<3, 5>, (T, T)>();
+    }
+
     #[test]
     fn test_save_load_residual() {
         type T = Residual<Linear<5, 5>>;
@@ -501,4 +676,65 @@ mod tests {
 
         assert_eq!(y1.data(), y2.data());
     }
+
+    #[test]
+    fn test_load_wrong_architecture() {
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+        let saved: (Linear<3, 4>, ReLU, Linear<4, 2>) = Default::default();
+        saved.save(file.path()).expect("");
+
+        let mut loaded: (Linear<3, 4>, ReLU, Linear<5, 2>) = Default::default();
+        let err = loaded.load(file.path()).unwrap_err();
+        assert_eq!(
+            std::format!("{}", err),
+            "2.weight.npy: expected shape (2, 5), found (2, 4) \
+            (expected a (Linear<3, 4>, ReLU, Linear<5, 2>), \
+            the file has a (Linear<3, 4>, ReLU, Linear<4, 2>))"
+        );
+
+        let mut loaded: (Linear<3, 4>, ReLU, Linear<4, 2>, Linear<2, 2>) = Default::default();
+        let err = loaded.load(file.path()).unwrap_err();
+        assert!(std::format!("{}", err).starts_with("3.weight.npy is missing"));
+    }
+
+    #[test]
+    fn test_load_partial() {
+        let mut rng = thread_rng();
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+        let mut saved: (Linear<3, 4>, ReLU, Linear<4, 2>) = Default::default();
+        saved.reset_params(&mut rng);
+        saved.save(file.path()).expect("");
+
+        let mut loaded: (Linear<3, 4>, ReLU, Linear<4, 5>) = Default::default();
+        let loaded_2 = loaded.2.clone();
+        let report = loaded.load_partial(file.path()).expect("");
+        assert_eq!(report.loaded, ["0.weight", "0.bias"]);
+        assert_eq!(report.mismatched, ["2.weight", "2.bias"]);
+        assert!(report.missing.is_empty());
+        assert!(report.unexpected.is_empty());
+        assert_eq!(loaded.0.weight.data(), saved.0.weight.data());
+        assert_eq!(loaded.2.weight.data(), loaded_2.weight.data());
+    }
+
+    #[test]
+    fn test_load_partial_with_name_map() {
+        let mut rng = thread_rng();
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+        let mut saved: (Linear<3, 4>, ReLU, Linear<4, 2>) = Default::default();
+        saved.reset_params(&mut rng);
+        saved.save(file.path()).expect("");
+
+        let mut loaded: (Frozen<(Linear<3, 4>, ReLU)>, Linear<4, 7>) = Default::default();
+        let report = loaded
+            .load_partial_with(file.path(), |name| match name.strip_prefix("0.") {
+                Some(name) => name.into(),
+                None => std::format!("head.{name}"),
+            })
+            .expect("");
+        assert_eq!(report.loaded, ["0.0.weight", "0.0.bias"]);
+        assert_eq!(report.missing, ["1.weight", "1.bias"]);
+        assert_eq!(report.unexpected, ["2.bias", "2.weight"]);
+        assert_eq!(loaded.0 .0 .0.weight.data(), saved.0.weight.data());
+        assert_eq!(loaded.0 .0 .0.bias.data(), saved.0.bias.data());
+    }
 }
@@ -0,0 +1,85 @@
+use crate::gradients::*;
+use crate::prelude::*;
+use crate::{Assert, ConstTrue};
+
+/// **Requires Nightly** Rearranges images of shape `(C * R * R, H, W)` into `(C, H * R, W * R)`,
+/// which is used for efficient sub-pixel convolution upsampling in super-resolution models.
+///
+/// **Pytorch Equivalent**: `torch.nn.PixelShuffle`
+///
+/// Generics:
+/// - `R`: The upscale factor. The number of input channels must be divisible by `R * R`,
+///   which is checked at compile time.
+///
+/// Examples:
+/// ```ignore
+/// # use dfdx::prelude::*;
+/// let m: PixelShuffle<2> = Default::default();
+/// let _: Tensor3D<3, 8, 8> = m.forward(Tensor3D::<12, 4, 4>::zeros());
+/// let _: Tensor4D<5, 3, 8, 8> = m.forward(Tensor4D::<5, 12, 4, 4>::zeros());
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PixelShuffle<const R: usize>;
+
+impl<const R: usize> ResetParams for PixelShuffle<R> {
+    fn reset_params<RNG: rand::Rng>(&mut self, _: &mut RNG) {}
+}
+
+impl<const R: usize> CanUpdateWithGradients for PixelShuffle<R> {
+    fn update<G: GradientProvider>(&mut self, _: &mut G, _: &mut UnusedTensors) {}
+}
+
+impl<const R: usize, const C: usize, const H: usize, const W: usize, T: Tape>
+    Module<Tensor3D<C, H, W, T>> for PixelShuffle<R>
+where
+    [[[(); W * R]; H * R]; C / (R * R)]:,
+    Assert<{ C.is_multiple_of(R * R) }>: ConstTrue,
+{
+    type Output = Tensor3D<{ C / (R * R) }, { H * R }, { W * R }, T>;
+    fn forward(&self, x: Tensor3D<C, H, W, T>) -> Self::Output {
+        x.pixel_shuffle::<R>()
+    }
+}
+
+impl<const R: usize, const B: usize, const C: usize, const H: usize, const W: usize, T: Tape>
+    Module<Tensor4D<B, C, H, W, T>> for PixelShuffle<R>
+where
+    [[[[(); W * R]; H * R]; C / (R * R)]; B]:,
+    Assert<{ C.is_multiple_of(R * R) }>: ConstTrue,
+{
+    type Output = Tensor4D<B, { C / (R * R) }, { H * R }, { W * R }, T>;
+    fn forward(&self, x: Tensor4D<B, C, H, W, T>) -> Self::Output {
+        x.pixel_shuffle::<R>()
+    }
+}
+
+impl<const R: usize, T> ModuleMut<T> for PixelShuffle<R>
+where
+    Self: Module<T>,
+{
+    type Output = <Self as Module<T>>::Output;
+    fn forward_mut(&mut self, input: T) -> Self::Output {
+        self.forward(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pixel_shuffle_shapes() {
+        let m: PixelShuffle<2> = Default::default();
+        let _: Tensor3D<3, 8, 8> = m.forward(Tensor3D::<12, 4, 4>::zeros());
+        let _: Tensor4D<5, 3, 8, 8, OwnedTape> =
+            m.forward(Tensor4D::<5, 12, 4, 4>::zeros().traced());
+    }
+
+    #[test]
+    fn test_pixel_shuffle_matches_op() {
+        let x: Tensor3D<4, 1, 2> = tensor([[[1.0, 2.0]], [[3.0, 4.0]], [[5.0, 6.0]], [[7.0, 8.0]]]);
+        let r1 = PixelShuffle::<2>.forward(x.clone());
+        let r2 = x.pixel_shuffle::<2>();
+        assert_eq!(r1.data(), r2.data());
+    }
+}
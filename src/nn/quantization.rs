@@ -0,0 +1,268 @@
+use super::ForwardHook;
+use crate::arrays::CountElements;
+use crate::prelude::*;
+use core::cell::Cell;
+
+/// How the weights of a layer are quantized to `i8`, e.g. by [QuantizedLinear::quantize_with()].
+///
+/// Symmetric schemes map `0.0` to `0` and only use `[-127, 127]`. Affine schemes also store a zero point,
+/// so that all of `[-128, 127]` is used for values that aren't centered around `0.0`.
+///
+/// **Pytorch equivalent**: `torch.per_tensor_symmetric`, `torch.per_tensor_affine`,
+/// `torch.per_channel_symmetric` and `torch.per_channel_affine`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum QScheme {
+    /// One scale for the whole weight.
+    PerTensorSymmetric,
+    /// One scale & zero point for the whole weight.
+    PerTensorAffine,
+    /// One scale per output channel.
+    #[default]
+    PerChannelSymmetric,
+    /// One scale & zero point per output channel.
+    PerChannelAffine,
+}
+
+impl QScheme {
+    /// The [QParams] of each output channel, given the `(min, max)` of the weights of each output channel.
+    pub(super) fn channel_qparams<const O: usize>(&self, ranges: [(f32, f32); O]) -> [QParams; O] {
+        let ranges = match self {
+            Self::PerTensorSymmetric | Self::PerTensorAffine => {
+                let (min, max) = ranges
+                    .iter()
+                    .fold((0.0f32, 0.0f32), |(lo, hi), (min, max)| {
+                        (lo.min(*min), hi.max(*max))
+                    });
+                [(min, max); O]
+            }
+            Self::PerChannelSymmetric | Self::PerChannelAffine => ranges,
+        };
+        let symmetric = matches!(self, Self::PerTensorSymmetric | Self::PerChannelSymmetric);
+        ranges.map(|(min, max)| QParams::from_range(min, max, symmetric))
+    }
+}
+
+/// The scale & zero point that map `f32` values to `i8` values `q`, so that `x ≈ scale * (q - zero_point)`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct QParams {
+    pub scale: f32,
+    pub zero_point: i8,
+    /// Whether `q` is limited to `[-127, 127]`, which is only valid when `zero_point` is `0`.
+    pub symmetric: bool,
+}
+
+impl QParams {
+    /// The params that cover `[min, max]`, after extending it to include `0.0` so that
+    /// `0.0` is represented exactly. If `symmetric` is true, `zero_point` is `0`.
+    pub fn from_range(min: f32, max: f32, symmetric: bool) -> Self {
+        let (min, max) = (min.min(0.0), max.max(0.0));
+        if symmetric {
+            return Self {
+                scale: max.max(-min) / 127.0,
+                zero_point: 0,
+                symmetric: true,
+            };
+        }
+        let scale = (max - min) / 255.0;
+        if scale == 0.0 {
+            return Self::default();
+        }
+        Self {
+            scale,
+            zero_point: (-128.0 - min / scale).round().clamp(-128.0, 127.0) as i8,
+            symmetric: false,
+        }
+    }
+
+    /// Rounds `x` to the nearest `i8`, clamping values outside of the range of `self`.
+    pub fn quantize(&self, x: f32) -> i8 {
+        if self.scale == 0.0 {
+            return self.zero_point;
+        }
+        let min = if self.symmetric && self.zero_point == 0 {
+            -127.0
+        } else {
+            -128.0
+        };
+        ((x / self.scale).round() + self.zero_point as f32).clamp(min, 127.0) as i8
+    }
+
+    /// The `f32` value that `q` represents.
+    pub fn dequantize(&self, q: i8) -> f32 {
+        (q as i32 - self.zero_point as i32) as f32 * self.scale
+    }
+}
+
+/// The smallest & largest of `values`, where both are extended to include `0.0`.
+pub(super) fn range<'a, V: IntoIterator<Item = &'a f32>>(values: V) -> (f32, f32) {
+    values
+        .into_iter()
+        .fold((0.0f32, 0.0f32), |(lo, hi), x| (lo.min(*x), hi.max(*x)))
+}
+
+/// A [ForwardHook] that records the smallest & largest input values of a module, to calibrate
+/// the static quantization of its inputs.
+///
+/// Wrap each layer to quantize in a [WithForwardHook], run representative data through the model
+/// with [Module::forward()], and then convert each layer with [QuantizedLinear::calibrated()].
+///
+/// **Pytorch equivalent**: `torch.ao.quantization.MinMaxObserver`
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let mut rng = rand::thread_rng();
+/// let mut linear: Linear<5, 3> = Default::default();
+/// linear.reset_params(&mut rng);
+/// let model = (WithForwardHook::new(linear, MinMaxObserver::default()), ReLU);
+/// for _ in 0..10 {
+///     let _: Tensor2D<4, 3> = model.forward(Tensor2D::randn(&mut rng));
+/// }
+/// let quantized = (
+///     QuantizedLinear::calibrated(&model.0, QScheme::PerChannelAffine),
+///     ReLU,
+/// );
+/// let y: Tensor2D<4, 3> = quantized.forward(Tensor2D::randn(&mut rng));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct MinMaxObserver {
+    range: Cell<Option<(f32, f32)>>,
+}
+
+impl MinMaxObserver {
+    /// The smallest & largest input values so far, or `None` if nothing has been observed.
+    pub fn range(&self) -> Option<(f32, f32)> {
+        self.range.get()
+    }
+
+    /// Affine [QParams] that cover every input value so far, or `None` if nothing has been observed.
+    pub fn qparams(&self) -> Option<QParams> {
+        self.range()
+            .map(|(min, max)| QParams::from_range(min, max, false))
+    }
+}
+
+impl<I: Tensor<Dtype = f32>, O> ForwardHook<I, O> for MinMaxObserver {
+    fn on_forward(&self, input: &I, _: &O) {
+        let n = <I::Array as CountElements>::NUM_ELEMENTS;
+        if n == 0 {
+            return;
+        }
+        // the elements of nested arrays are contiguous, so they can be viewed as a flat slice
+        let data = unsafe { std::slice::from_raw_parts(input.data().ref_first_elem(), n) };
+        let init = self.range().unwrap_or((f32::INFINITY, f32::NEG_INFINITY));
+        let range = data
+            .iter()
+            .fold(init, |(min, max), x| (min.min(*x), max.max(*x)));
+        self.range.set(Some(range));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qparams_symmetric() {
+        let qp = QParams::from_range(-25.0, 254.0, true);
+        assert_eq!(
+            qp,
+            QParams {
+                scale: 2.0,
+                zero_point: 0,
+                symmetric: true,
+            }
+        );
+        assert_eq!(qp.quantize(254.0), 127);
+        assert_eq!(qp.quantize(-25.0), -13);
+        assert_eq!(qp.quantize(1000.0), 127);
+        assert_eq!(qp.quantize(-1000.0), -127);
+        assert_eq!(qp.dequantize(-13), -26.0);
+    }
+
+    #[test]
+    fn test_qparams_affine() {
+        let qp = QParams::from_range(0.0, 255.0, false);
+        assert_eq!(
+            qp,
+            QParams {
+                scale: 1.0,
+                zero_point: -128,
+                symmetric: false,
+            }
+        );
+        assert_eq!(qp.quantize(0.0), -128);
+        assert_eq!(qp.quantize(255.0), 127);
+        assert_eq!(qp.quantize(-1.0), -128);
+
+        let qp = QParams::from_range(-100.0, 155.0, false);
+        assert_eq!(
+            qp,
+            QParams {
+                scale: 1.0,
+                zero_point: -28,
+                symmetric: false,
+            }
+        );
+        assert_eq!(qp.dequantize(qp.quantize(0.0)), 0.0);
+        assert_eq!(qp.dequantize(qp.quantize(-100.0)), -100.0);
+
+        assert_eq!(QParams::from_range(0.0, 0.0, false), QParams::default());
+        assert_eq!(QParams::default().quantize(1.0), 0);
+    }
+
+    #[test]
+    fn test_channel_qparams() {
+        let ranges = [(-127.0, 50.0), (0.0, 254.0)];
+        assert_eq!(
+            QScheme::PerChannelSymmetric.channel_qparams(ranges),
+            [
+                QParams {
+                    scale: 1.0,
+                    zero_point: 0,
+                    symmetric: true,
+                },
+                QParams {
+                    scale: 2.0,
+                    zero_point: 0,
+                    symmetric: true,
+                },
+            ]
+        );
+        assert_eq!(
+            QScheme::PerTensorSymmetric.channel_qparams(ranges),
+            [QParams {
+                scale: 2.0,
+                zero_point: 0,
+                symmetric: true,
+            }; 2]
+        );
+        assert_eq!(
+            QScheme::PerChannelAffine.channel_qparams(ranges),
+            [
+                QParams::from_range(-127.0, 50.0, false),
+                QParams::from_range(0.0, 254.0, false),
+            ]
+        );
+        assert_eq!(
+            QScheme::PerTensorAffine.channel_qparams(ranges),
+            [QParams::from_range(-127.0, 254.0, false); 2]
+        );
+    }
+
+    #[test]
+    fn test_min_max_observer() {
+        let observer = MinMaxObserver::default();
+        assert_eq!(observer.range(), None);
+        assert_eq!(observer.qparams(), None);
+
+        let model = WithForwardHook::new(ReLU, observer);
+        let _ = model.forward(tensor([1.0, -2.0, 3.0]));
+        let _ = model.forward(tensor([[0.5, 4.0], [-1.0, 0.0]]));
+        assert_eq!(model.hook.range(), Some((-2.0, 4.0)));
+        assert_eq!(
+            model.hook.qparams(),
+            Some(QParams::from_range(-2.0, 4.0, false))
+        );
+    }
+}
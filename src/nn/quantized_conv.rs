@@ -0,0 +1,326 @@
+use super::quantization::range;
+use crate::devices::{PaddingMode, ZeroPadding};
+use crate::prelude::*;
+use core::marker::PhantomData;
+use std::vec::Vec;
+
+/// **Requires Nightly** A [Conv2D] with `i8` weights, for inference on CPU constrained targets.
+/// The convolution itself is computed with integers.
+///
+/// This works the same way as [QuantizedLinear]: the weights of each output channel are quantized with a [QScheme],
+/// and images are quantized either dynamically (with symmetric [QParams] computed from each image), or statically
+/// with fixed [QParams], e.g. from [QuantizedConv2D::calibrated()]. The bias stays in `f32`.
+///
+/// This only supports [NoneTape] inputs. The generics are the same as [Conv2D].
+///
+/// **Pytorch equivalent**: `torch.ao.nn.quantized.Conv2d`
+///
+/// Examples:
+/// ```ignore
+/// # use dfdx::prelude::*;
+/// let mut rng = rand::thread_rng();
+/// let mut conv: Conv2D<3, 8, 3, 1, 1> = Default::default();
+/// conv.reset_params(&mut rng);
+/// let q = QuantizedConv2D::quantize(&conv);
+/// let _: Tensor4D<2, 8, 16, 16> = q.forward(Tensor4D::<2, 3, 16, 16>::zeros());
+/// ```
+#[derive(Debug, Clone)]
+pub struct QuantizedConv2D<
+    const IN_CHAN: usize,
+    const OUT_CHAN: usize,
+    const KERNEL_SIZE: usize,
+    const STRIDE: usize = 1,
+    const PADDING: usize = 0,
+    M: PaddingMode = ZeroPadding,
+> {
+    weight: Vec<[[[i8; KERNEL_SIZE]; KERNEL_SIZE]; IN_CHAN]>,
+    weight_qparams: [QParams; OUT_CHAN],
+    input_qparams: Option<QParams>,
+
+    /// Bias vector, shape (OUT_CHAN, )
+    pub bias: Tensor1D<OUT_CHAN>,
+    padding: PhantomData<M>,
+}
+
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, M> Default
+    for QuantizedConv2D<I, O, K, S, P, M>
+where
+    M: PaddingMode,
+{
+    fn default() -> Self {
+        Self {
+            weight: alloc::vec![[[[0; K]; K]; I]; O],
+            weight_qparams: [Default::default(); O],
+            input_qparams: None,
+            bias: Default::default(),
+            padding: PhantomData,
+        }
+    }
+}
+
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, M>
+    QuantizedConv2D<I, O, K, S, P, M>
+where
+    M: PaddingMode,
+{
+    /// Quantizes the weight of `conv` to `i8` with [QScheme::PerChannelSymmetric], and
+    /// quantizes images dynamically.
+    pub fn quantize(conv: &Conv2D<I, O, K, S, P, M>) -> Self {
+        Self::quantize_with(conv, Default::default())
+    }
+
+    /// Quantizes the weight of `conv` to `i8` with `scheme`, and quantizes images dynamically.
+    pub fn quantize_with(conv: &Conv2D<I, O, K, S, P, M>, scheme: QScheme) -> Self {
+        let w = conv.weight.data();
+        let mut ranges = [(0.0, 0.0); O];
+        for (r, w) in ranges.iter_mut().zip(w.iter()) {
+            *r = range(w.iter().flatten().flatten());
+        }
+        let weight_qparams = scheme.channel_qparams(ranges);
+        let weight = w
+            .iter()
+            .zip(weight_qparams.iter())
+            .map(|(w, qp)| w.map(|w| w.map(|w| w.map(|w| qp.quantize(w)))))
+            .collect();
+        Self {
+            weight,
+            weight_qparams,
+            input_qparams: None,
+            bias: conv.bias.clone(),
+            padding: PhantomData,
+        }
+    }
+
+    /// Quantizes the weight of `conv` to `i8` with `scheme`, and quantizes all images with `input`.
+    pub fn quantize_static(
+        conv: &Conv2D<I, O, K, S, P, M>,
+        scheme: QScheme,
+        input: QParams,
+    ) -> Self {
+        Self {
+            input_qparams: Some(input),
+            ..Self::quantize_with(conv, scheme)
+        }
+    }
+
+    /// Quantizes the [Conv2D] in `observed` with `scheme`, and quantizes images with the [QParams] of
+    /// the images that its [MinMaxObserver] has seen. If it hasn't seen any images, they are quantized dynamically.
+    pub fn calibrated(
+        observed: &WithForwardHook<Conv2D<I, O, K, S, P, M>, MinMaxObserver>,
+        scheme: QScheme,
+    ) -> Self {
+        Self {
+            input_qparams: observed.hook.qparams(),
+            ..Self::quantize_with(&observed.module, scheme)
+        }
+    }
+
+    /// Converts back to a [Conv2D], with the weights rounded to what is stored in `self`.
+    pub fn dequantize(&self) -> Conv2D<I, O, K, S, P, M> {
+        let mut weight: Tensor4D<O, I, K, K> = TensorCreator::zeros();
+        let channels = self.weight.iter().zip(self.weight_qparams.iter());
+        for (w, (q, qp)) in weight.mut_data().iter_mut().zip(channels) {
+            *w = q.map(|q| q.map(|q| q.map(|q| qp.dequantize(q))));
+        }
+        Conv2D::new(weight, self.bias.clone())
+    }
+
+    /// The `i8` weight of output channel `o`, and its [QParams].
+    pub fn weight_channel(&self, o: usize) -> (&[[[i8; K]; K]; I], QParams) {
+        (&self.weight[o], self.weight_qparams[o])
+    }
+
+    /// The [QParams] used for all images, or `None` if images are quantized dynamically.
+    pub fn input_qparams(&self) -> Option<QParams> {
+        self.input_qparams
+    }
+
+    fn forward_image<const H: usize, const W: usize, const OH: usize, const OW: usize>(
+        &self,
+        img: &[[[f32; W]; H]; I],
+        out: &mut [[[f32; OW]; OH]; O],
+    ) {
+        let x_qp = self.input_qparams.unwrap_or_else(|| {
+            let (min, max) = range(img.iter().flatten().flatten());
+            QParams::from_range(min, max, true)
+        });
+        let img_q: Vec<[[i32; W]; H]> = img
+            .iter()
+            .map(|c| c.map(|r| r.map(|x| x_qp.quantize(x) as i32 - x_qp.zero_point as i32)))
+            .collect();
+
+        let channels = self.weight.iter().zip(self.weight_qparams.iter());
+        for ((out, (w_q, w_qp)), b) in out.iter_mut().zip(channels).zip(self.bias.data().iter()) {
+            for (oh, out) in out.iter_mut().enumerate() {
+                for (ow, out) in out.iter_mut().enumerate() {
+                    // each product is up to 255 * 255, so an i32 overflows once I * K * K is above ~33k
+                    let mut acc: i64 = 0;
+                    for (w_q, img_q) in w_q.iter().zip(img_q.iter()) {
+                        for (k1, w_q) in w_q.iter().enumerate() {
+                            for (k2, w_q) in w_q.iter().enumerate() {
+                                let y = M::unpadded_index(oh * S + k1, P, H);
+                                let x = M::unpadded_index(ow * S + k2, P, W);
+                                if let (Some(y), Some(x)) = (y, x) {
+                                    let w_q = *w_q as i64 - w_qp.zero_point as i64;
+                                    acc += w_q * img_q[y][x] as i64;
+                                }
+                            }
+                        }
+                    }
+                    *out = acc as f32 * x_qp.scale * w_qp.scale + b;
+                }
+            }
+        }
+    }
+}
+
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const H: usize,
+        const W: usize,
+        M: PaddingMode,
+    > Module<Tensor3D<I, H, W>> for QuantizedConv2D<I, O, K, S, P, M>
+where
+    [[[(); (W + 2 * P - K) / S + 1]; (H + 2 * P - K) / S + 1]; O]:,
+{
+    type Output = Tensor3D<O, { (H + 2 * P - K) / S + 1 }, { (W + 2 * P - K) / S + 1 }>;
+
+    fn forward(&self, x: Tensor3D<I, H, W>) -> Self::Output {
+        let mut y: Self::Output = TensorCreator::zeros();
+        self.forward_image(x.data(), y.mut_data());
+        y
+    }
+}
+
+impl<
+        const B: usize,
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const H: usize,
+        const W: usize,
+        M: PaddingMode,
+    > Module<Tensor4D<B, I, H, W>> for QuantizedConv2D<I, O, K, S, P, M>
+where
+    [[[[(); (W + 2 * P - K) / S + 1]; (H + 2 * P - K) / S + 1]; O]; B]:,
+{
+    type Output = Tensor4D<B, O, { (H + 2 * P - K) / S + 1 }, { (W + 2 * P - K) / S + 1 }>;
+
+    fn forward(&self, x: Tensor4D<B, I, H, W>) -> Self::Output {
+        let mut y: Self::Output = TensorCreator::zeros();
+        for (x, y) in x.data().iter().zip(y.mut_data().iter_mut()) {
+            self.forward_image(x, y);
+        }
+        y
+    }
+}
+
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, M, T>
+    ModuleMut<T> for QuantizedConv2D<I, O, K, S, P, M>
+where
+    M: PaddingMode,
+    Self: Module<T>,
+{
+    type Output = <Self as Module<T>>::Output;
+    fn forward_mut(&mut self, input: T) -> Self::Output {
+        self.forward(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::devices::ReplicatePadding;
+    use crate::tests::assert_close;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn assert_all_close<const N: usize>(a: &[f32; N], b: &[f32; N], tolerance: f32) {
+        for (a, b) in a.iter().zip(b.iter()) {
+            assert!((a - b).abs() < tolerance, "{a} vs {b}");
+        }
+    }
+
+    #[test]
+    fn test_quantized_conv_close_to_conv() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut conv: Conv2D<2, 3, 3, 1, 1, ReplicatePadding> = Default::default();
+        conv.reset_params(&mut rng);
+        let x: Tensor4D<2, 2, 5, 5> = TensorCreator::randn(&mut rng);
+        let y_0 = conv.forward(x.clone());
+
+        for scheme in [QScheme::PerChannelSymmetric, QScheme::PerTensorAffine] {
+            let q = QuantizedConv2D::quantize_with(&conv, scheme);
+            let y = q.forward(x.clone());
+            for (y, y_0) in y.data().iter().zip(y_0.data().iter()) {
+                for (y, y_0) in y.iter().zip(y_0.iter()) {
+                    for (y, y_0) in y.iter().zip(y_0.iter()) {
+                        assert_all_close(y, y_0, 2e-2);
+                    }
+                }
+            }
+
+            let x_0: Tensor3D<2, 5, 5> = TensorCreator::new(x.data()[1]);
+            assert_eq!(q.forward(x_0).data(), &y.data()[1]);
+        }
+    }
+
+    #[test]
+    fn test_quantized_conv_dequantize() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut conv: Conv2D<2, 4, 2, 2> = Default::default();
+        conv.reset_params(&mut rng);
+        let q = QuantizedConv2D::quantize_with(&conv, QScheme::PerChannelAffine);
+        let (_, qp) = q.weight_channel(3);
+        assert_ne!(qp.zero_point, 0);
+
+        let conv_q = q.dequantize();
+        assert_eq!(conv_q.bias.data(), conv.bias.data());
+        let channels = conv.weight.data().iter().zip(conv_q.weight.data().iter());
+        for (o, (w, w_q)) in channels.enumerate() {
+            let max_err = 0.5 * q.weight_channel(o).1.scale;
+            for (w, w_q) in w.iter().flatten().zip(w_q.iter().flatten()) {
+                assert_all_close(w, w_q, max_err + 1e-7);
+            }
+        }
+    }
+
+    #[test]
+    fn test_quantized_conv_calibrated() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut conv: Conv2D<1, 2, 3> = Default::default();
+        conv.reset_params(&mut rng);
+        let observed = WithForwardHook::new(conv.clone(), MinMaxObserver::default());
+        for _ in 0..4 {
+            let _ = observed.forward(Tensor3D::<1, 6, 6>::randn(&mut rng));
+        }
+
+        let q = QuantizedConv2D::calibrated(&observed, QScheme::PerChannelAffine);
+        assert_eq!(q.input_qparams(), observed.hook.qparams());
+        let x: Tensor3D<1, 6, 6> = TensorCreator::randn(&mut rng);
+        let y = q.forward(x.clone());
+        for (y, y_0) in y.data().iter().zip(conv.forward(x).data().iter()) {
+            for (y, y_0) in y.iter().zip(y_0.iter()) {
+                assert_all_close(y, y_0, 5e-2);
+            }
+        }
+
+        // every element is exactly representable with `input`
+        let input = QParams {
+            scale: 0.25,
+            zero_point: -3,
+            symmetric: false,
+        };
+        let q = QuantizedConv2D::quantize_static(&conv, QScheme::PerChannelAffine, input);
+        let x = tensor([[[0.25, -1.0, 0.5], [2.0, 0.0, -0.75], [1.5, 1.0, -2.0]]]);
+        assert_close(
+            q.forward(x.clone()).data(),
+            q.dequantize().forward(x).data(),
+        );
+    }
+}
@@ -0,0 +1,351 @@
+use super::quantization::range;
+use crate::prelude::*;
+use std::vec::Vec;
+
+/// A [Linear] with `i8` weights, for inference on CPU constrained targets. This uses 4x less memory
+/// for the weights than [Linear], and computes the matrix multiplication with integers.
+///
+/// Weights are quantized with a [QScheme], which is symmetric per output channel by default: row `o`
+/// of the weight matrix is stored as `i8` values `q[o]` along with its [QParams], so that
+/// `weight[o][i] ≈ scale[o] * (q[o][i] - zero_point[o])`. The bias stays in `f32`.
+///
+/// Inputs are quantized when calling [Module::forward()] in one of two ways:
+/// 1. Dynamically, with symmetric [QParams] computed from each input row. This is what
+///    [QuantizedLinear::quantize()] and [QuantizedLinear::quantize_with()] do, and needs no calibration data.
+/// 2. Statically, with fixed [QParams] for all inputs. Use [QuantizedLinear::calibrated()] to get them from
+///    a [MinMaxObserver] that has seen representative inputs, or pass them to [QuantizedLinear::quantize_static()].
+///
+/// This only supports [NoneTape] inputs, so it can't be trained. Train a [Linear] and convert it
+/// with [QuantizedLinear::quantize()] instead.
+///
+/// **Pytorch equivalent**: `torch.ao.nn.quantized.dynamic.Linear` & `torch.ao.nn.quantized.Linear`
+///
+/// # Generics
+/// - `I` The "input" size of vectors & matrices.
+/// - `O` The "output" size of vectors & matrices.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let mut rng = rand::thread_rng();
+/// let mut model: (Linear<5, 3>, ReLU, Linear<3, 2>) = Default::default();
+/// model.reset_params(&mut rng);
+/// let quantized = (
+///     QuantizedLinear::quantize(&model.0),
+///     model.1,
+///     QuantizedLinear::quantize_with(&model.2, QScheme::PerTensorAffine),
+/// );
+/// let y: Tensor2D<4, 2> = quantized.forward(Tensor2D::<4, 5>::zeros());
+/// ```
+///
+/// See [MinMaxObserver] for an example of calibrating static quantization.
+#[derive(Debug, Clone)]
+pub struct QuantizedLinear<const I: usize, const O: usize> {
+    weight: Vec<[i8; I]>,
+    weight_qparams: [QParams; O],
+    input_qparams: Option<QParams>,
+
+    /// Bias vector, shape (O, )
+    pub bias: Tensor1D<O>,
+}
+
+impl<const I: usize, const O: usize> Default for QuantizedLinear<I, O> {
+    fn default() -> Self {
+        Self {
+            weight: alloc::vec![[0; I]; O],
+            weight_qparams: [Default::default(); O],
+            input_qparams: None,
+            bias: Default::default(),
+        }
+    }
+}
+
+impl<const I: usize, const O: usize> QuantizedLinear<I, O> {
+    /// Quantizes the weight of `linear` to `i8` with [QScheme::PerChannelSymmetric], and
+    /// quantizes inputs dynamically.
+    pub fn quantize(linear: &Linear<I, O>) -> Self {
+        Self::quantize_with(linear, Default::default())
+    }
+
+    /// Quantizes the weight of `linear` to `i8` with `scheme`, and quantizes inputs dynamically.
+    pub fn quantize_with(linear: &Linear<I, O>, scheme: QScheme) -> Self {
+        let w = linear.weight.data();
+        let mut ranges = [(0.0, 0.0); O];
+        for (r, w) in ranges.iter_mut().zip(w.iter()) {
+            *r = range(w);
+        }
+        let weight_qparams = scheme.channel_qparams(ranges);
+        let weight = w
+            .iter()
+            .zip(weight_qparams.iter())
+            .map(|(w, qp)| w.map(|w| qp.quantize(w)))
+            .collect();
+        Self {
+            weight,
+            weight_qparams,
+            input_qparams: None,
+            bias: linear.bias.clone(),
+        }
+    }
+
+    /// Quantizes the weight of `linear` to `i8` with `scheme`, and quantizes all inputs with `input`.
+    pub fn quantize_static(linear: &Linear<I, O>, scheme: QScheme, input: QParams) -> Self {
+        Self {
+            input_qparams: Some(input),
+            ..Self::quantize_with(linear, scheme)
+        }
+    }
+
+    /// Quantizes the [Linear] in `observed` with `scheme`, and quantizes inputs with the [QParams] of
+    /// the inputs that its [MinMaxObserver] has seen. If it hasn't seen any inputs, they are quantized dynamically.
+    pub fn calibrated(
+        observed: &WithForwardHook<Linear<I, O>, MinMaxObserver>,
+        scheme: QScheme,
+    ) -> Self {
+        Self {
+            input_qparams: observed.hook.qparams(),
+            ..Self::quantize_with(&observed.module, scheme)
+        }
+    }
+
+    /// Converts back to a [Linear], with the weights rounded to what is stored in `self`.
+    pub fn dequantize(&self) -> Linear<I, O> {
+        let mut linear: Linear<I, O> = Linear {
+            weight: TensorCreator::zeros(),
+            bias: self.bias.clone(),
+        };
+        let rows = self.weight.iter().zip(self.weight_qparams.iter());
+        for (w, (q, qp)) in linear.weight.mut_data().iter_mut().zip(rows) {
+            for (w, q) in w.iter_mut().zip(q.iter()) {
+                *w = qp.dequantize(*q);
+            }
+        }
+        linear
+    }
+
+    /// The `i8` weight of output channel `o`, and its [QParams].
+    pub fn weight_row(&self, o: usize) -> (&[i8; I], QParams) {
+        (&self.weight[o], self.weight_qparams[o])
+    }
+
+    /// The [QParams] used for all inputs, or `None` if inputs are quantized dynamically.
+    pub fn input_qparams(&self) -> Option<QParams> {
+        self.input_qparams
+    }
+
+    fn forward_row(&self, x: &[f32; I], y: &mut [f32; O]) {
+        let x_qp = self.input_qparams.unwrap_or_else(|| {
+            let (min, max) = range(x);
+            QParams::from_range(min, max, true)
+        });
+        let x_q = x.map(|x| x_qp.quantize(x) as i64 - x_qp.zero_point as i64);
+        let rows = self.weight.iter().zip(self.weight_qparams.iter());
+        for ((y, (w_q, w_qp)), b) in y.iter_mut().zip(rows).zip(self.bias.data().iter()) {
+            // each product is up to 255 * 255, so an i32 overflows once I is above ~33k
+            let acc: i64 = w_q
+                .iter()
+                .zip(x_q.iter())
+                .map(|(w, x)| (*w as i64 - w_qp.zero_point as i64) * x)
+                .sum();
+            *y = acc as f32 * x_qp.scale * w_qp.scale + b;
+        }
+    }
+}
+
+impl<const I: usize, const O: usize> Module<Tensor1D<I>> for QuantizedLinear<I, O> {
+    type Output = Tensor1D<O>;
+    fn forward(&self, x: Tensor1D<I>) -> Self::Output {
+        let mut y: Self::Output = TensorCreator::zeros();
+        self.forward_row(x.data(), y.mut_data());
+        y
+    }
+}
+
+impl<const B: usize, const I: usize, const O: usize> Module<Tensor2D<B, I>>
+    for QuantizedLinear<I, O>
+{
+    type Output = Tensor2D<B, O>;
+    fn forward(&self, x: Tensor2D<B, I>) -> Self::Output {
+        let mut y: Self::Output = TensorCreator::zeros();
+        for (x, y) in x.data().iter().zip(y.mut_data().iter_mut()) {
+            self.forward_row(x, y);
+        }
+        y
+    }
+}
+
+impl<const B: usize, const S: usize, const I: usize, const O: usize> Module<Tensor3D<B, S, I>>
+    for QuantizedLinear<I, O>
+{
+    type Output = Tensor3D<B, S, O>;
+    fn forward(&self, x: Tensor3D<B, S, I>) -> Self::Output {
+        let mut y: Self::Output = TensorCreator::zeros();
+        for (x, y) in x.data().iter().zip(y.mut_data().iter_mut()) {
+            for (x, y) in x.iter().zip(y.iter_mut()) {
+                self.forward_row(x, y);
+            }
+        }
+        y
+    }
+}
+
+impl<T, const I: usize, const O: usize> ModuleMut<T> for QuantizedLinear<I, O>
+where
+    Self: Module<T>,
+{
+    type Output = <Self as Module<T>>::Output;
+    fn forward_mut(&mut self, input: T) -> Self::Output {
+        self.forward(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+
+    const W: [[f32; 5]; 2] = [
+        [-0.3458893, -0.30371523, -0.3712057, 0.14303583, -0.0268966],
+        [0.11733949, 0.14059687, -0.10670426, -0.09373143, 0.18974298],
+    ];
+    const B: [f32; 2] = [0.3765365, -0.290717];
+
+    #[test]
+    fn test_quantize_weights() {
+        let linear: Linear<5, 2> = Linear {
+            weight: tensor(W),
+            bias: tensor(B),
+        };
+        let q = QuantizedLinear::quantize(&linear);
+        let (row, qp) = q.weight_row(0);
+        assert_eq!(row, &[-118, -104, -127, 49, -9]);
+        assert_eq!(qp.scale, 0.3712057 / 127.0);
+        assert_eq!(qp.zero_point, 0);
+        assert_eq!(q.weight_row(1).0, &[79, 94, -71, -63, 127]);
+        assert_eq!(q.bias.data(), &B);
+
+        let linear_q = q.dequantize();
+        for (w, w_q) in W.iter().zip(linear_q.weight.data().iter()) {
+            for (w, w_q) in w.iter().zip(w_q.iter()) {
+                assert!((w - w_q).abs() <= 0.5 * 0.3712057 / 127.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_quantized_forward_close_to_linear() {
+        let linear: Linear<5, 2> = Linear {
+            weight: tensor(W),
+            bias: tensor(B),
+        };
+        let q = QuantizedLinear::quantize(&linear);
+        let x = tensor([
+            [-0.8808001, 2.4185333, 2.2478335, 0.0565211, 2.031299],
+            [-1.2111, 0.75, 0.0, -2.5, 1.0],
+        ]);
+        let y = q.forward(x.clone());
+        let y_0 = linear.forward(x.clone());
+        for (y, y_0) in y.data().iter().zip(y_0.data().iter()) {
+            for (y, y_0) in y.iter().zip(y_0.iter()) {
+                assert!((y - y_0).abs() < 1e-2, "{y} vs {y_0}");
+            }
+        }
+
+        let x_0: Tensor1D<5> = x.clone().select(&0);
+        assert_eq!(q.forward(x_0).data(), &y.data()[0]);
+
+        let x_3d: Tensor3D<1, 2, 5> = x.broadcast();
+        assert_eq!(q.forward(x_3d).data(), &[*y.data()]);
+    }
+
+    #[test]
+    fn test_quantize_schemes() {
+        let linear: Linear<5, 2> = Linear {
+            weight: tensor(W),
+            bias: tensor(B),
+        };
+        let per_channel = QuantizedLinear::quantize(&linear);
+        let per_tensor = QuantizedLinear::quantize_with(&linear, QScheme::PerTensorSymmetric);
+        assert_eq!(per_tensor.weight_row(0), per_channel.weight_row(0));
+        assert_eq!(per_tensor.weight_row(1).1, per_tensor.weight_row(0).1);
+        assert_eq!(per_tensor.weight_row(1).0, &[40, 48, -37, -32, 65]);
+
+        for scheme in [QScheme::PerTensorAffine, QScheme::PerChannelAffine] {
+            let q = QuantizedLinear::quantize_with(&linear, scheme);
+            let (_, qp) = q.weight_row(1);
+            assert_ne!(qp.zero_point, 0);
+            let max_err = 0.5 * q.weight_row(0).1.scale.max(qp.scale);
+            for (w, w_q) in W.iter().zip(q.dequantize().weight.data().iter()) {
+                for (w, w_q) in w.iter().zip(w_q.iter()) {
+                    assert!((w - w_q).abs() <= max_err, "{w} vs {w_q}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_quantized_calibrated_forward() {
+        let linear: Linear<5, 2> = Linear {
+            weight: tensor(W),
+            bias: tensor(B),
+        };
+        let x = tensor([
+            [-0.8808001, 2.4185333, 2.2478335, 0.0565211, 2.031299],
+            [-1.2111, 0.75, 0.0, -2.5, 1.0],
+        ]);
+        let observed = WithForwardHook::new(linear.clone(), MinMaxObserver::default());
+        assert_eq!(
+            QuantizedLinear::calibrated(&observed, Default::default()).input_qparams(),
+            None
+        );
+        let _ = observed.forward(x.clone());
+
+        let y_0 = linear.forward(x.clone());
+        for scheme in [QScheme::PerChannelSymmetric, QScheme::PerTensorAffine] {
+            let q = QuantizedLinear::calibrated(&observed, scheme);
+            assert_eq!(q.input_qparams(), observed.hook.qparams());
+            let y = q.forward(x.clone());
+            for (y, y_0) in y.data().iter().zip(y_0.data().iter()) {
+                for (y, y_0) in y.iter().zip(y_0.iter()) {
+                    assert!((y - y_0).abs() < 2e-2, "{y} vs {y_0}");
+                }
+            }
+        }
+
+        let input = QParams {
+            scale: 0.5,
+            zero_point: 10,
+            symmetric: false,
+        };
+        let q = QuantizedLinear::quantize_static(&linear, QScheme::PerChannelAffine, input);
+        assert_eq!(q.input_qparams(), Some(input));
+        // every element is exactly representable with `input`
+        let x = tensor([1.0, -0.5, 0.0, 2.0, 0.5]);
+        assert_close(
+            q.forward(x.clone()).data(),
+            q.dequantize().forward(x).data(),
+        );
+    }
+
+    #[test]
+    fn test_quantized_zeros() {
+        let q: QuantizedLinear<3, 2> = QuantizedLinear::quantize(&Default::default());
+        let y = q.forward(tensor([1.0, 2.0, 3.0]));
+        assert_eq!(y.data(), &[0.0; 2]);
+        let q: QuantizedLinear<3, 2> = Default::default();
+        assert_close(q.forward(Tensor1D::zeros()).data(), &[0.0; 2]);
+    }
+
+    #[test]
+    fn test_quantized_forward_large_input() {
+        // 34000 * 255 * 255 doesn't fit in an i32
+        let linear: Linear<34000, 1> = Linear {
+            weight: TensorCreator::ones(),
+            bias: TensorCreator::zeros(),
+        };
+        let input = QParams::from_range(0.0, 1.0, false);
+        let q = QuantizedLinear::quantize_static(&linear, QScheme::PerChannelAffine, input);
+        let y = q.forward(Tensor1D::ones());
+        assert!((y.data()[0] - 34000.0).abs() < 1.0, "{:?}", y.data());
+    }
+}
@@ -0,0 +1,252 @@
+use crate::gradients::*;
+use crate::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand_distr::StandardNormal;
+
+/// A module with a weight matrix that can be wrapped in [SpectralNorm].
+pub trait SpectralNormalize: Clone {
+    /// Estimate of the first left singular vector of the weight matrix.
+    type U: Clone + std::fmt::Debug;
+
+    /// Estimate of the first right singular vector of the weight matrix.
+    type V: Clone + std::fmt::Debug;
+
+    /// Samples random unit vectors to start power iteration from.
+    fn random_vectors<R: Rng>(rng: &mut R) -> (Self::U, Self::V);
+
+    /// Runs a single step of power iteration on the weight matrix, updating `u` and `v`.
+    fn power_iteration(&self, u: &mut Self::U, v: &mut Self::V, epsilon: f32);
+
+    /// Returns a copy of `self` whose weight is divided by its spectral norm `u^T * W * v`.
+    /// The division is recorded onto `tape`, so gradients flow back into the original weight.
+    fn spectral_normalized<H: Tape>(&self, u: &Self::U, v: &Self::V, tape: H) -> (Self, H);
+}
+
+/// Wraps a module and divides its weight by the weight's spectral norm (largest singular value)
+/// each forward, as described in [Spectral Normalization for Generative Adversarial Networks](https://arxiv.org/abs/1802.05957).
+///
+/// The spectral norm is estimated using power iteration with the vectors [Self::u] and [Self::v].
+///
+/// 1. [ModuleMut::forward_mut()] runs [Self::n_power_iterations] steps of power iteration
+///    before normalizing the weight.
+/// 2. [Module::forward()] normalizes the weight using the current [Self::u] and [Self::v].
+///
+/// **Pytorch Equivalent**: `torch.nn.utils.spectral_norm`
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let mut model: SpectralNorm<Linear<5, 2>> = Default::default();
+/// model.reset_params(&mut rand::thread_rng());
+/// let _: Tensor1D<2, OwnedTape> = model.forward_mut(Tensor1D::zeros().traced());
+/// let _: Tensor1D<2> = model.forward(Tensor1D::zeros());
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpectralNorm<M: SpectralNormalize> {
+    pub module: M,
+    pub u: M::U,
+    pub v: M::V,
+    pub n_power_iterations: usize,
+    pub epsilon: f32,
+    training: bool,
+}
+
+impl<M: SpectralNormalize> SpectralNorm<M> {
+    /// Whether [ModuleMut] runs power iteration. See [ModuleMode].
+    pub fn is_training(&self) -> bool {
+        self.training
+    }
+}
+
+impl<M: SpectralNormalize + ModuleMode> ModuleMode for SpectralNorm<M> {
+    /// Sets the mode of `self` and `module`.
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+        self.module.set_training(training);
+    }
+}
+
+impl<M: SpectralNormalize + Default> Default for SpectralNorm<M> {
+    /// Uses a default `module`, fixed random unit vectors, 1 power iteration,
+    /// and sets [Self::epsilon] to `1e-12`.
+    fn default() -> Self {
+        let (u, v) = M::random_vectors(&mut StdRng::seed_from_u64(0));
+        Self {
+            module: Default::default(),
+            u,
+            v,
+            n_power_iterations: 1,
+            epsilon: 1e-12,
+            training: true,
+        }
+    }
+}
+
+impl<M: SpectralNormalize + ResetParams> ResetParams for SpectralNorm<M> {
+    /// Resets `module` and resamples [Self::u] and [Self::v].
+    fn reset_params<R: Rng>(&mut self, rng: &mut R) {
+        self.module.reset_params(rng);
+        let (u, v) = M::random_vectors(rng);
+        self.u = u;
+        self.v = v;
+    }
+}
+
+impl<M: SpectralNormalize + CanUpdateWithGradients> CanUpdateWithGradients for SpectralNorm<M> {
+    /// Updates `module`. [Self::u] and [Self::v] are not parameters.
+    fn update<G: GradientProvider>(&mut self, grads: &mut G, unused: &mut UnusedTensors) {
+        self.module.update(grads, unused);
+    }
+}
+
+impl<M, T> Module<T> for SpectralNorm<M>
+where
+    M: SpectralNormalize + Module<T>,
+    T: Tensor<Dtype = f32>,
+{
+    type Output = M::Output;
+
+    /// Normalizes the weight with the current [Self::u] and [Self::v], and then calls `module`.
+    fn forward(&self, x: T) -> Self::Output {
+        let (x, tape) = x.split_tape();
+        let (module, tape) = self.module.spectral_normalized(&self.u, &self.v, tape);
+        module.forward(x.put_tape(tape))
+    }
+}
+
+impl<M, T> ModuleMut<T> for SpectralNorm<M>
+where
+    M: SpectralNormalize + Module<T>,
+    T: Tensor<Dtype = f32>,
+{
+    type Output = M::Output;
+
+    /// Runs [Self::n_power_iterations] steps of power iteration, and then calls [Module::forward()].
+    /// Power iteration is skipped in evaluation mode.
+    fn forward_mut(&mut self, x: T) -> Self::Output {
+        if !self.training {
+            return self.forward(x);
+        }
+        for _ in 0..self.n_power_iterations {
+            self.module
+                .power_iteration(&mut self.u, &mut self.v, self.epsilon);
+        }
+        self.forward(x)
+    }
+}
+
+fn l2_normalized<T>(t: T, epsilon: f32) -> T
+where
+    T: Reduce<AllAxes, Reduced = Tensor0D> + Clone,
+{
+    let norm = sum(square(t.clone())).data().sqrt();
+    div_scalar(t, norm.max(epsilon))
+}
+
+impl<const I: usize, const O: usize> SpectralNormalize for Linear<I, O> {
+    type U = Tensor1D<O>;
+    type V = Tensor1D<I>;
+
+    fn random_vectors<R: Rng>(rng: &mut R) -> (Self::U, Self::V) {
+        let mut u: Self::U = TensorCreator::zeros();
+        let mut v: Self::V = TensorCreator::zeros();
+        u.randomize(rng, &StandardNormal);
+        v.randomize(rng, &StandardNormal);
+        (l2_normalized(u, 1e-12), l2_normalized(v, 1e-12))
+    }
+
+    fn power_iteration(&self, u: &mut Self::U, v: &mut Self::V, epsilon: f32) {
+        *v = l2_normalized(vecmat_mul(u.clone(), self.weight.clone()), epsilon);
+        *u = l2_normalized(
+            vecmat_mul_transpose(v.clone(), self.weight.clone()),
+            epsilon,
+        );
+    }
+
+    fn spectral_normalized<H: Tape>(&self, u: &Self::U, v: &Self::V, tape: H) -> (Self, H) {
+        let mut uv: Tensor2D<O, I> = TensorCreator::zeros();
+        for (uv_i, u_i) in uv.mut_data().iter_mut().zip(u.data().iter()) {
+            for (uv_ij, v_j) in uv_i.iter_mut().zip(v.data().iter()) {
+                *uv_ij = u_i * v_j;
+            }
+        }
+
+        let weight = self.weight.clone().put_tape(tape);
+        let sigma = sum::<_, AllAxes>(mul(weight.with_empty_tape(), uv));
+        let sigma: Tensor2D<O, I, H> = BroadcastTo::<_, AllAxes>::broadcast(sigma);
+        let (weight, tape) = div(weight, sigma).split_tape();
+        let mut module = self.clone();
+        module.weight = weight;
+        (module, tape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::tests::SimpleGradients;
+    use crate::tests::assert_close;
+    use crate::unique_id::HasUniqueId;
+
+    const W: [[f32; 3]; 2] = [[3.0, 0.0, 0.0], [0.0, -2.0, 0.0]];
+
+    #[test]
+    fn test_power_iteration_finds_largest_singular_value() {
+        let mut model: SpectralNorm<Linear<3, 2>> = Default::default();
+        model.module.weight = tensor(W);
+        model.n_power_iterations = 20;
+        let _ = model.forward_mut(Tensor1D::<3>::zeros());
+        assert_close(&model.u.data().map(f32::abs), &[1.0, 0.0]);
+        assert_close(&model.v.data().map(f32::abs), &[1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_spectral_norm_forward() {
+        let mut model: SpectralNorm<Linear<3, 2>> = Default::default();
+        model.module.weight = tensor(W);
+        model.module.bias = tensor([0.5, -0.5]);
+        model.u = tensor([1.0, 0.0]);
+        model.v = tensor([1.0, 0.0, 0.0]);
+
+        let y = model.forward(tensor([1.0, 2.0, 3.0]));
+        assert_close(y.data(), &[1.5, -1.8333334]);
+
+        let y = model.forward(tensor([[1.0, 2.0, 3.0], [-1.0, 0.0, 1.0]]));
+        assert_close(y.data(), &[[1.5, -1.8333334], [-0.5, -0.5]]);
+    }
+
+    #[test]
+    fn test_spectral_norm_gradients() {
+        let mut model: SpectralNorm<Linear<3, 2>> = Default::default();
+        model.module.weight = tensor(W);
+        model.u = tensor([1.0, 0.0]);
+        model.v = tensor([1.0, 0.0, 0.0]);
+        model.n_power_iterations = 0;
+
+        let y = model.forward_mut(tensor([1.0, 2.0, 3.0]).traced());
+        let g = backward(y.sum());
+
+        // d/dW sum(W x / sigma) = (1 x^T) / sigma - sum(W x) / sigma^2 * u v^T
+        assert_close(
+            g.ref_gradient(&model.module.weight),
+            &[
+                [1.0 / 3.0 + 1.0 / 9.0, 2.0 / 3.0, 1.0],
+                [1.0 / 3.0, 2.0 / 3.0, 1.0],
+            ],
+        );
+        assert_eq!(g.ref_gradient(&model.module.bias), &[1.0; 2]);
+
+        let mut g = SimpleGradients(g);
+        let mut unused = Default::default();
+        model.update(&mut g, &mut unused);
+        assert!(unused.is_empty());
+    }
+
+    #[test]
+    fn test_spectral_norm_keeps_ids() {
+        let mut model: SpectralNorm<Linear<3, 2>> = Default::default();
+        let id = *model.module.weight.id();
+        let _ = model.forward_mut(Tensor2D::<4, 3>::zeros().traced());
+        assert_eq!(model.module.weight.id(), &id);
+    }
+}
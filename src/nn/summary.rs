@@ -0,0 +1,317 @@
+use super::visit_params::{ParamVisitor, VisitParams};
+use crate::arrays::{CountElements, HasArrayType, HasShape};
+use crate::prelude::*;
+use alloc::format;
+use std::{fmt, string::String, vec::Vec};
+
+/// The name, shape, and number of elements of a single parameter. See [ModelSummary].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamSummary {
+    pub name: String,
+    pub shape: Vec<usize>,
+    pub num_elements: usize,
+    /// Whether the parameter is updated by optimizers, which is `false` inside of [Frozen].
+    pub trainable: bool,
+}
+
+/// The name and output shape of a single layer. See [ModelSummary].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerSummary {
+    pub name: String,
+    pub output_shape: Vec<usize>,
+}
+
+/// A table of all the parameters of a model, and optionally the shapes of its input, its output,
+/// and the output of each of its layers. Created with [summary()] or [summary_with_input()], and
+/// printed with [std::fmt::Display].
+///
+/// Parameters are grouped into layers by everything before the last `.` in their name, so layers
+/// without parameters (like [ReLU]) are only listed with the output shapes.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let model: (Linear<5, 3>, ReLU, Linear<3, 2>) = Default::default();
+/// let s = summary_with_input::<Tensor1D<5>, _>(&model);
+/// assert_eq!(s.num_params(), 26);
+/// assert_eq!(s.output_shape, Some(vec![2]));
+/// assert_eq!(s.layers[1].output_shape, vec![3]);
+/// println!("{s}");
+/// ```
+/// Prints:
+/// ```text
+/// Layer  Parameter  Shape   Count
+/// 0      weight     [3, 5]  15
+///        bias       [3]     3
+/// 2      weight     [2, 3]  6
+///        bias       [2]     2
+/// Total params: 26
+/// Trainable params: 26
+/// Input shape: [5]
+/// Layer  Output shape
+/// 0      [3]
+/// 1      [3]
+/// 2      [2]
+/// Output shape: [2]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModelSummary {
+    pub params: Vec<ParamSummary>,
+    pub input_shape: Option<Vec<usize>>,
+    /// The output shape of each layer, which is empty unless created with [summary_with_input()].
+    pub layers: Vec<LayerSummary>,
+    pub output_shape: Option<Vec<usize>>,
+}
+
+impl ModelSummary {
+    /// The total number of elements in all parameters.
+    pub fn num_params(&self) -> usize {
+        self.params.iter().map(|p| p.num_elements).sum()
+    }
+
+    /// The total number of elements in all parameters that are updated by optimizers.
+    pub fn num_trainable_params(&self) -> usize {
+        self.params
+            .iter()
+            .filter(|p| p.trainable)
+            .map(|p| p.num_elements)
+            .sum()
+    }
+}
+
+impl ParamVisitor for ModelSummary {
+    fn visit<T: Tensor<Dtype = f32>>(&mut self, name: &str, _: &T) {
+        self.params.push(ParamSummary {
+            name: name.into(),
+            shape: T::Array::shape(),
+            num_elements: T::Array::NUM_ELEMENTS,
+            trainable: true,
+        });
+    }
+
+    fn visit_frozen<T: Tensor<Dtype = f32>>(&mut self, name: &str, param: &T) {
+        self.visit(name, param);
+        self.params.last_mut().unwrap().trainable = false;
+    }
+}
+
+/// Returns a [ModelSummary] of all the parameters in `model`.
+pub fn summary<M: VisitParams>(model: &M) -> ModelSummary {
+    let mut s: ModelSummary = Default::default();
+    model.visit_params(&mut s);
+    s
+}
+
+/// Returns a [ModelSummary] of all the parameters in `model`, along with the input & output shapes
+/// of the whole model, and the output shape of each of its layers from calling
+/// [ModuleShapes::forward_shapes()] on a zeroed `I`.
+pub fn summary_with_input<I, M>(model: &M) -> ModelSummary
+where
+    I: Tensor<Dtype = f32, Tape = NoneTape> + TensorCreator,
+    M: VisitParams + ModuleShapes<I>,
+    M::Output: HasArrayType,
+{
+    let mut s = summary(model);
+    s.input_shape = Some(I::Array::shape());
+    let _: M::Output = model.forward_shapes(I::zeros(), &mut s.layers);
+    s.output_shape = Some(<M::Output as HasArrayType>::Array::shape());
+    s
+}
+
+/// A [Module] made of layers, that can record the output shape of each of them.
+/// Implemented for tuples and [Repeated]. Each element of a tuple is one layer, even if it
+/// is made of other layers itself.
+pub trait ModuleShapes<Input>: Module<Input> {
+    /// Same as [Module::forward()], but also pushes the name & output shape of each layer to `layers`.
+    fn forward_shapes(&self, input: Input, layers: &mut Vec<LayerSummary>) -> Self::Output;
+}
+
+fn layer<T: HasArrayType>(name: String, output: T, layers: &mut Vec<LayerSummary>) -> T {
+    layers.push(LayerSummary {
+        name,
+        output_shape: T::Array::shape(),
+    });
+    output
+}
+
+macro_rules! tuple_impls {
+    ([$($name:ident),+] [$($idx:tt),+], $last:ident, [$($rev_tail:ident),+]) => {
+        impl<
+            Input,
+            $last:
+            $(Module::<$rev_tail ::Output>, $rev_tail: )+
+            Module<Input>
+        > ModuleShapes<Input> for ($($name,)+)
+        where
+            $($name::Output: HasArrayType,)+
+        {
+            fn forward_shapes(&self, x: Input, layers: &mut Vec<LayerSummary>) -> Self::Output {
+                $(let x = layer(format!("{}", $idx), self.$idx.forward(x), layers);)+
+                x
+            }
+        }
+    };
+}
+
+tuple_impls!([A, B] [0, 1], B, [A]);
+tuple_impls!([A, B, C] [0, 1, 2], C, [B, A]);
+tuple_impls!([A, B, C, D] [0, 1, 2, 3], D, [C, B, A]);
+tuple_impls!([A, B, C, D, E] [0, 1, 2, 3, 4], E, [D, C, B, A]);
+tuple_impls!([A, B, C, D, E, F] [0, 1, 2, 3, 4, 5], F, [E, D, C, B, A]);
+
+impl<Input: HasArrayType, T: Module<Input, Output = Input>, const N: usize> ModuleShapes<Input>
+    for Repeated<T, N>
+{
+    fn forward_shapes(&self, mut x: Input, layers: &mut Vec<LayerSummary>) -> Self::Output {
+        for i in 0..N {
+            x = layer(format!("{i}"), self.modules[i].forward(x), layers);
+        }
+        x
+    }
+}
+
+impl fmt::Display for ModelSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows: Vec<[String; 4]> = self
+            .params
+            .iter()
+            .scan(None, |prev_layer, p| {
+                let (layer, param) = match p.name.rsplit_once('.') {
+                    Some((layer, param)) => (layer, param),
+                    None => ("", p.name.as_str()),
+                };
+                let layer = if prev_layer.as_deref() == Some(layer) {
+                    String::new()
+                } else {
+                    *prev_layer = Some(String::from(layer));
+                    layer.into()
+                };
+                Some([
+                    layer,
+                    param.into(),
+                    format!("{:?}", p.shape),
+                    format!("{}", p.num_elements),
+                ])
+            })
+            .collect();
+
+        let header = ["Layer", "Parameter", "Shape", "Count"];
+        let mut widths = header.map(str::len);
+        for row in rows.iter() {
+            for (w, cell) in widths.iter_mut().zip(row.iter()) {
+                *w = (*w).max(cell.len());
+            }
+        }
+
+        let write_row = |f: &mut fmt::Formatter<'_>, row: [&str; 4]| {
+            let line = format!(
+                "{:w0$}  {:w1$}  {:w2$}  {}",
+                row[0],
+                row[1],
+                row[2],
+                row[3],
+                w0 = widths[0],
+                w1 = widths[1],
+                w2 = widths[2],
+            );
+            writeln!(f, "{}", line.trim_end())
+        };
+
+        write_row(f, header)?;
+        for row in rows.iter() {
+            write_row(f, [&row[0], &row[1], &row[2], &row[3]])?;
+        }
+        writeln!(f, "Total params: {}", self.num_params())?;
+        write!(f, "Trainable params: {}", self.num_trainable_params())?;
+        if let Some(shape) = &self.input_shape {
+            write!(f, "\nInput shape: {shape:?}")?;
+        }
+        if !self.layers.is_empty() {
+            let w = self.layers.iter().fold(5, |w, l| w.max(l.name.len()));
+            write!(f, "\n{:w$}  Output shape", "Layer")?;
+            for l in self.layers.iter() {
+                write!(f, "\n{:w$}  {:?}", l.name, l.output_shape)?;
+            }
+        }
+        if let Some(shape) = &self.output_shape {
+            write!(f, "\nOutput shape: {shape:?}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn test_summary_params() {
+        let model: (Linear<5, 3>, ReLU, Residual<LayerNorm1D<3>>) = Default::default();
+        let s = summary(&model);
+        assert_eq!(s.num_params(), 24);
+        assert_eq!(s.input_shape, None);
+        assert_eq!(s.output_shape, None);
+        assert_eq!(
+            s.params[0],
+            ParamSummary {
+                name: "0.weight".into(),
+                shape: std::vec![3, 5],
+                num_elements: 15,
+                trainable: true,
+            }
+        );
+        assert_eq!(s.params[3].name, "2.0.beta");
+        assert!(s.layers.is_empty());
+    }
+
+    #[test]
+    fn test_summary_layer_shapes() {
+        let model: (
+            Linear<5, 3>,
+            Repeated<(Linear<3, 3>, ReLU), 2>,
+            Linear<3, 2>,
+        ) = Default::default();
+        let s = summary_with_input::<Tensor2D<4, 5>, _>(&model);
+        let layers: Vec<(&str, Vec<usize>)> = s
+            .layers
+            .iter()
+            .map(|l| (l.name.as_str(), l.output_shape.clone()))
+            .collect();
+        assert_eq!(
+            layers,
+            [
+                ("0", std::vec![4, 3]),
+                ("1", std::vec![4, 3]),
+                ("2", std::vec![4, 2])
+            ]
+        );
+
+        let mut layers = Vec::new();
+        let _: Tensor1D<3> = model.1.forward_shapes(Tensor1D::zeros(), &mut layers);
+        assert_eq!(layers.len(), 2);
+        assert_eq!(layers[1].output_shape, std::vec![3]);
+    }
+
+    #[test]
+    fn test_summary_display() {
+        let model: (Linear<5, 3>, ReLU, Linear<3, 2>) = Default::default();
+        let s = summary_with_input::<Tensor2D<4, 5>, _>(&model);
+        assert_eq!(
+            s.to_string(),
+            "Layer  Parameter  Shape   Count
+0      weight     [3, 5]  15
+       bias       [3]     3
+2      weight     [2, 3]  6
+       bias       [2]     2
+Total params: 26
+Trainable params: 26
+Input shape: [4, 5]
+Layer  Output shape
+0      [4, 3]
+1      [4, 3]
+2      [4, 2]
+Output shape: [4, 2]"
+        );
+    }
+}
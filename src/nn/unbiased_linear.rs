@@ -0,0 +1,146 @@
+use crate::gradients::{CanUpdateWithGradients, GradientProvider, Tape, UnusedTensors};
+use crate::prelude::*;
+use rand::Rng;
+use rand_distr::Uniform;
+
+/// A linear transformation of the form `weight * x`, where `weight` is a matrix, and `x` is a vector or matrix.
+/// This is [Linear] without a bias, which is useful when the output is normalized afterwards.
+///
+/// # Generics
+/// - `I` The "input" size of vectors & matrices.
+/// - `O` The "output" size of vectors & matrices.
+///
+/// # Examples
+/// `UnbiasedLinear<5, 2>` can act on vectors with 5 elements, and results in vectors with 2 elements.
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let model: UnbiasedLinear<5, 2> = Default::default();
+/// assert_eq!(model.weight.data(), &[[0.0; 5]; 2]);
+/// let x: Tensor1D<5> = Default::default();
+/// let y: Tensor1D<2> = model.forward(x);
+/// assert_eq!(y.data(), &[0.0; 2]);
+/// ```
+#[derive(Default, Debug, Clone)]
+pub struct UnbiasedLinear<const I: usize, const O: usize> {
+    /// Transposed weight matrix, shape (O, I)
+    pub weight: Tensor2D<O, I>,
+}
+
+impl<const I: usize, const O: usize> CanUpdateWithGradients for UnbiasedLinear<I, O> {
+    fn update<G: GradientProvider>(&mut self, grads: &mut G, unused: &mut UnusedTensors) {
+        self.weight.update(grads, unused);
+    }
+}
+
+impl<const I: usize, const O: usize> ResetParams for UnbiasedLinear<I, O> {
+    /// Initializes [Self::weight] from a [Uniform] distribution
+    /// between [-1 / sqrt(I), 1 / sqrt(I)].
+    ///
+    /// This uses [Randomize::randomize()] to set the values of the tensor.
+    fn reset_params<R: Rng>(&mut self, rng: &mut R) {
+        let bound: f32 = 1.0 / (I as f32).sqrt();
+        let dist = Uniform::new(-bound, bound);
+        self.weight.randomize(rng, &dist);
+    }
+}
+
+impl<const I: usize, const O: usize, H: Tape> Module<Tensor1D<I, H>> for UnbiasedLinear<I, O> {
+    type Output = Tensor1D<O, H>;
+
+    /// 1d forward using [vecmat_mul_transpose()].
+    fn forward(&self, x: Tensor1D<I, H>) -> Self::Output {
+        vecmat_mul_transpose(x, self.weight.clone())
+    }
+}
+
+impl<const B: usize, const I: usize, const O: usize, H: Tape> Module<Tensor2D<B, I, H>>
+    for UnbiasedLinear<I, O>
+{
+    type Output = Tensor2D<B, O, H>;
+
+    /// Batched 2d forward using [matmul_transpose()]
+    fn forward(&self, x: Tensor2D<B, I, H>) -> Self::Output {
+        matmul_transpose(x, self.weight.clone())
+    }
+}
+
+impl<const B: usize, const S: usize, const I: usize, const O: usize, H: Tape>
+    Module<Tensor3D<B, S, I, H>> for UnbiasedLinear<I, O>
+{
+    type Output = Tensor3D<B, S, O, H>;
+
+    /// Batched 3d forward using [matmul_transpose()]
+    fn forward(&self, x: Tensor3D<B, S, I, H>) -> Self::Output {
+        matmul_transpose(x, self.weight.clone())
+    }
+}
+
+impl<T, const I: usize, const O: usize> ModuleMut<T> for UnbiasedLinear<I, O>
+where
+    Self: Module<T>,
+{
+    type Output = <Self as Module<T>>::Output;
+    fn forward_mut(&mut self, input: T) -> Self::Output {
+        self.forward(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unique_id::HasUniqueId;
+    use crate::{nn::tests::SimpleGradients, tests::assert_close};
+    use rand::{prelude::StdRng, SeedableRng};
+
+    #[test]
+    fn test_unbiased_linear_matches_linear() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut model: UnbiasedLinear<5, 2> = Default::default();
+        model.reset_params(&mut rng);
+        let linear: Linear<5, 2> = Linear {
+            weight: model.weight.clone(),
+            bias: TensorCreator::zeros(),
+        };
+
+        let x: Tensor1D<5> = TensorCreator::randn(&mut rng);
+        let y = model.forward(x.trace());
+        assert_close(y.data(), linear.forward(x.clone()).data());
+        let g = backward(y.square().mean());
+        let weight_grad = *g.ref_gradient(&model.weight);
+        let g = backward(linear.forward(x.trace()).square().mean());
+        assert_close(&weight_grad, g.ref_gradient(&linear.weight));
+
+        let x: Tensor3D<3, 4, 5> = TensorCreator::randn(&mut rng);
+        let y = model.forward(x.clone());
+        assert_close(y.data(), linear.forward(x).data());
+    }
+
+    #[test]
+    fn test_unbiased_linear_forward_2d() {
+        let model: UnbiasedLinear<3, 2> = UnbiasedLinear {
+            weight: tensor([[1.0, 0.0, -1.0], [0.5, 2.0, 0.0]]),
+        };
+        let x = tensor([[1.0, 2.0, 3.0], [-1.0, 0.0, 1.0]]);
+        let y = model.forward(x.trace());
+        assert_eq!(y.data(), &[[-2.0, 4.5], [-2.0, -0.5]]);
+        let g = backward(y.sum());
+        assert_eq!(g.ref_gradient(&model.weight), &[[0.0, 2.0, 4.0]; 2]);
+        assert_eq!(g.ref_gradient(&x), &[[1.5, 2.0, -1.0]; 2]);
+    }
+
+    #[test]
+    fn test_unbiased_linear_missing_gradients() {
+        let mut model: UnbiasedLinear<5, 3> = Default::default();
+        let mut g: SimpleGradients = Default::default();
+
+        let mut unused = Default::default();
+        model.update(&mut g, &mut unused);
+        assert_eq!(&unused.ids, &[*model.weight.id()]);
+
+        g.0.mut_gradient(&model.weight);
+
+        let mut unused = Default::default();
+        model.update(&mut g, &mut unused);
+        assert!(unused.is_empty());
+    }
+}
@@ -0,0 +1,202 @@
+use crate::arrays::CountElements;
+use crate::devices::ForEachElement;
+use crate::prelude::*;
+use std::{any::Any, boxed::Box, vec::Vec};
+
+/// Something that can look at every parameter of a module. See [VisitParams].
+///
+/// Use [crate::arrays::HasShape] on `T::Array` to get the shape of the parameter,
+/// and [HasArrayData::data()] to get its values.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::arrays::HasShape;
+/// struct Shapes(Vec<(String, Vec<usize>)>);
+///
+/// impl ParamVisitor for Shapes {
+///     fn visit<T: Tensor<Dtype = f32>>(&mut self, name: &str, _: &T) {
+///         self.0.push((name.into(), T::Array::shape()));
+///     }
+/// }
+///
+/// let model: (Linear<5, 3>, ReLU, Linear<3, 2>) = Default::default();
+/// let mut shapes = Shapes(Vec::new());
+/// model.visit_params(&mut shapes);
+/// assert_eq!(shapes.0, [
+///     ("0.weight".into(), vec![3, 5]),
+///     ("0.bias".into(), vec![3]),
+///     ("2.weight".into(), vec![2, 3]),
+///     ("2.bias".into(), vec![2]),
+/// ]);
+/// ```
+pub trait ParamVisitor {
+    /// Called once for every parameter, with the full hierarchical `name` of the parameter.
+    fn visit<T: Tensor<Dtype = f32>>(&mut self, name: &str, param: &T);
+
+    /// Called once for every parameter that is not updated by optimizers, like the parameters
+    /// inside of [Frozen]. Does nothing by default.
+    fn visit_frozen<T: Tensor<Dtype = f32>>(&mut self, _name: &str, _param: &T) {}
+}
+
+/// Something that can look at & modify every parameter of a module. See [VisitParams].
+pub trait ParamVisitorMut {
+    /// Called once for every parameter, with the full hierarchical `name` of the parameter.
+    fn visit_mut<T: Tensor<Dtype = f32>>(&mut self, name: &str, param: &mut T);
+
+    /// Called once for every parameter that is not updated by optimizers, like the parameters
+    /// inside of [Frozen]. Calls [ParamVisitorMut::visit_mut()] by default, so that e.g. loading
+    /// a model also loads its frozen parameters.
+    fn visit_frozen_mut<T: Tensor<Dtype = f32>>(&mut self, name: &str, param: &mut T) {
+        self.visit_mut(name, param);
+    }
+}
+
+/// Something that can pass all of its parameters to a [ParamVisitor] or [ParamVisitorMut],
+/// along with the hierarchical name of each parameter.
+///
+/// All [super::Module]s in nn implement VisitParams, including tuples. Names are made of the field
+/// names & indices that lead to the parameter, separated by `.`, e.g. `"1.0.weight"` for
+/// the weight of a [Linear] inside a [Residual] at index `1` of a tuple.
+///
+/// Only the parameters that are updated by optimizers are visited. For example the running statistics
+/// of [BatchNorm2D] are not visited. Parameters inside of [Frozen] are passed to [ParamVisitor::visit_frozen()]
+/// and [ParamVisitorMut::visit_frozen_mut()] instead.
+pub trait VisitParams {
+    /// Passes every parameter of `self` to `visitor`.
+    fn visit_params<V: ParamVisitor>(&self, visitor: &mut V) {
+        self.visit_named("", visitor);
+    }
+
+    /// Passes every parameter of `self` to `visitor`, allowing the visitor to modify them.
+    fn visit_params_mut<V: ParamVisitorMut>(&mut self, visitor: &mut V) {
+        self.visit_named_mut("", visitor);
+    }
+
+    /// Returns the total number of elements in all parameters of `self`.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// let model: (Linear<5, 3>, ReLU, Linear<3, 2>) = Default::default();
+    /// assert_eq!(model.num_params(), (5 * 3 + 3) + (3 * 2 + 2));
+    /// ```
+    fn num_params(&self) -> usize {
+        let mut counter: ParamCounter = Default::default();
+        self.visit_params(&mut counter);
+        counter.all
+    }
+
+    /// Returns the total number of elements in the parameters of `self` that are updated by optimizers,
+    /// which excludes the parameters inside of [Frozen].
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// let model: (Frozen<Linear<5, 3>>, ReLU, Linear<3, 2>) = Default::default();
+    /// assert_eq!(model.num_params(), (5 * 3 + 3) + (3 * 2 + 2));
+    /// assert_eq!(model.num_trainable_params(), 3 * 2 + 2);
+    /// ```
+    fn num_trainable_params(&self) -> usize {
+        let mut counter: ParamCounter = Default::default();
+        self.visit_params(&mut counter);
+        counter.trainable
+    }
+
+    /// Passes every parameter of `self` to `visitor` with names starting with `prefix`.
+    ///
+    /// Example:
+    /// ```ignore
+    /// # use dfdx::prelude::*;
+    /// let model: Linear<5, 10> = Default::default();
+    /// model.visit_named("0.", &mut visitor);
+    /// ```
+    /// Will visit `0.weight` and `0.bias`.
+    ///
+    /// There is no default, so that a module can't silently skip its parameters. Modules without
+    /// parameters implement this as an empty function.
+    fn visit_named<V: ParamVisitor>(&self, prefix: &str, visitor: &mut V);
+
+    /// Same as [VisitParams::visit_named()], but allows the visitor to modify the parameters.
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, prefix: &str, visitor: &mut V);
+}
+
+#[derive(Default)]
+struct ParamCounter {
+    all: usize,
+    trainable: usize,
+}
+
+impl ParamVisitor for ParamCounter {
+    fn visit<T: Tensor<Dtype = f32>>(&mut self, _: &str, _: &T) {
+        self.all += T::Array::NUM_ELEMENTS;
+        self.trainable += T::Array::NUM_ELEMENTS;
+    }
+
+    fn visit_frozen<T: Tensor<Dtype = f32>>(&mut self, _: &str, _: &T) {
+        self.all += T::Array::NUM_ELEMENTS;
+    }
+}
+
+/// Calls `f` on each element of each parameter of `dst`, along with the corresponding element of `src`.
+pub(crate) fn zip_params_mut<M, F>(dst: &mut M, src: &M, f: F)
+where
+    M: VisitParams,
+    F: FnMut(&mut f32, &f32),
+{
+    let mut collector = ParamCollector(Vec::new());
+    src.visit_params(&mut collector);
+    dst.visit_params_mut(&mut ParamZipper {
+        src: collector.0.into_iter(),
+        f,
+    });
+}
+
+struct ParamCollector(Vec<Box<dyn Any>>);
+
+impl ParamVisitor for ParamCollector {
+    fn visit<T: Tensor<Dtype = f32>>(&mut self, _: &str, param: &T) {
+        self.0.push(Box::new(param.data().clone()));
+    }
+
+    fn visit_frozen<T: Tensor<Dtype = f32>>(&mut self, name: &str, param: &T) {
+        self.visit(name, param);
+    }
+}
+
+struct ParamZipper<I, F> {
+    src: I,
+    f: F,
+}
+
+impl<I: Iterator<Item = Box<dyn Any>>, F: FnMut(&mut f32, &f32)> ParamVisitorMut
+    for ParamZipper<I, F>
+{
+    fn visit_mut<T: Tensor<Dtype = f32>>(&mut self, _: &str, param: &mut T) {
+        let src = self.src.next().unwrap();
+        let src: &T::Array = src.downcast_ref().unwrap();
+        T::Device::foreach_mr(param.mut_data(), src, &mut self.f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_num_params() {
+        assert_eq!(Linear::<5, 3>::default().num_params(), 18);
+        assert_eq!(UnbiasedLinear::<5, 3>::default().num_params(), 15);
+        assert_eq!(BatchNorm2D::<4>::default().num_params(), 8);
+        assert_eq!(ReLU.num_params(), 0);
+
+        type Model = (
+            Linear<5, 3>,
+            Residual<(LayerNorm1D<3>, Linear<3, 3>)>,
+            Repeated<Linear<3, 3>, 2>,
+        );
+        let model: Model = Default::default();
+        assert_eq!(model.num_params(), 18 + 6 + 12 + 2 * 12);
+        assert_eq!(model.num_trainable_params(), model.num_params());
+    }
+}
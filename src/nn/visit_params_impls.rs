@@ -0,0 +1,425 @@
+use super::visit_params::{ParamVisitor, ParamVisitorMut, VisitParams};
+use crate::devices::PaddingMode;
+use crate::prelude::*;
+use alloc::format;
+
+// nightly includes
+#[cfg(not(feature = "nightly"))]
+use super::conv::Conv2D;
+#[cfg(not(feature = "nightly"))]
+use super::flatten::*;
+#[cfg(not(feature = "nightly"))]
+use super::pool2d::*;
+#[cfg(not(feature = "nightly"))]
+use super::transformer::*;
+
+impl<const C: usize> VisitParams for BatchNorm2D<C> {
+    fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+        v.visit(&format!("{p}scale"), &self.scale);
+        v.visit(&format!("{p}bias"), &self.bias);
+    }
+
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+        v.visit_mut(&format!("{p}scale"), &mut self.scale);
+        v.visit_mut(&format!("{p}bias"), &mut self.bias);
+    }
+}
+
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, M> VisitParams
+    for Conv2D<I, O, K, S, P, M>
+where
+    M: PaddingMode,
+{
+    fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+        v.visit(&format!("{p}weight"), &self.weight);
+        v.visit(&format!("{p}bias"), &self.bias);
+    }
+
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+        v.visit_mut(&format!("{p}weight"), &mut self.weight);
+        v.visit_mut(&format!("{p}bias"), &mut self.bias);
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, M> VisitParams
+    for UnbiasedConv2D<I, O, K, S, P, M>
+where
+    M: PaddingMode,
+{
+    fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+        v.visit(&format!("{p}weight"), &self.weight);
+    }
+
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+        v.visit_mut(&format!("{p}weight"), &mut self.weight);
+    }
+}
+
+impl<const M: usize> VisitParams for LayerNorm1D<M> {
+    fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+        v.visit(&format!("{p}gamma"), &self.gamma);
+        v.visit(&format!("{p}beta"), &self.beta);
+    }
+
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+        v.visit_mut(&format!("{p}gamma"), &mut self.gamma);
+        v.visit_mut(&format!("{p}beta"), &mut self.beta);
+    }
+}
+
+impl<const M: usize> VisitParams for LayerScale<M> {
+    fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+        v.visit(&format!("{p}gamma"), &self.gamma);
+    }
+
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+        v.visit_mut(&format!("{p}gamma"), &mut self.gamma);
+    }
+}
+
+impl<const I: usize, const O: usize> VisitParams for Linear<I, O> {
+    fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+        v.visit(&format!("{p}weight"), &self.weight);
+        v.visit(&format!("{p}bias"), &self.bias);
+    }
+
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+        v.visit_mut(&format!("{p}weight"), &mut self.weight);
+        v.visit_mut(&format!("{p}bias"), &mut self.bias);
+    }
+}
+
+impl<const I: usize, const O: usize> VisitParams for UnbiasedLinear<I, O> {
+    fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+        v.visit(&format!("{p}weight"), &self.weight);
+    }
+
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+        v.visit_mut(&format!("{p}weight"), &mut self.weight);
+    }
+}
+
+impl<F: VisitParams> VisitParams for DropPath<F> {
+    fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+        self.f.visit_named(&format!("{p}f."), v);
+    }
+
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+        self.f.visit_named_mut(&format!("{p}f."), v);
+    }
+}
+
+impl<F: VisitParams, R: VisitParams> VisitParams for GeneralizedResidual<F, R> {
+    fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+        self.f.visit_named(&format!("{p}f."), v);
+        self.r.visit_named(&format!("{p}r."), v);
+    }
+
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+        self.f.visit_named_mut(&format!("{p}f."), v);
+        self.r.visit_named_mut(&format!("{p}r."), v);
+    }
+}
+
+impl<M: VisitParams> VisitParams for Checkpoint<M> {
+    /// Visits `M` at the same prefix, so the names are the same as `M`'s.
+    fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+        self.0.visit_named(p, v);
+    }
+
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+        self.0.visit_named_mut(p, v);
+    }
+}
+
+impl<M: VisitParams, H> VisitParams for WithForwardHook<M, H> {
+    /// Visits `module` at the same prefix, so the names are the same as `M`'s.
+    fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+        self.module.visit_named(p, v);
+    }
+
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+        self.module.visit_named_mut(p, v);
+    }
+}
+
+impl<M: SpectralNormalize + VisitParams> VisitParams for SpectralNorm<M> {
+    /// Visits `module` at the same prefix, so the names are the same as `M`'s.
+    fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+        self.module.visit_named(p, v);
+    }
+
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+        self.module.visit_named_mut(p, v);
+    }
+}
+
+macro_rules! tuple_visit_impl {
+    ([$($name:ident),+], [$($idx:tt),+]) => {
+impl<$($name: VisitParams),+> VisitParams for ($($name,)+) {
+    fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+        $(self.$idx.visit_named(&format!("{p}{}.", $idx), v);)+
+    }
+
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+        $(self.$idx.visit_named_mut(&format!("{p}{}.", $idx), v);)+
+    }
+}
+    };
+}
+
+tuple_visit_impl!([A, B], [0, 1]);
+tuple_visit_impl!([A, B, C], [0, 1, 2]);
+tuple_visit_impl!([A, B, C, D], [0, 1, 2, 3]);
+tuple_visit_impl!([A, B, C, D, E], [0, 1, 2, 3, 4]);
+tuple_visit_impl!([A, B, C, D, E, F], [0, 1, 2, 3, 4, 5]);
+
+impl<T: VisitParams, const N: usize> VisitParams for Repeated<T, N> {
+    fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+        for (i, m) in self.modules.iter().enumerate() {
+            m.visit_named(&format!("{p}{i}."), v);
+        }
+    }
+
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+        for (i, m) in self.modules.iter_mut().enumerate() {
+            m.visit_named_mut(&format!("{p}{i}."), v);
+        }
+    }
+}
+
+macro_rules! newtype_visit_impl {
+    ($TyName:ident) => {
+        impl<T: VisitParams> VisitParams for $TyName<T> {
+            fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+                self.0.visit_named(&format!("{p}0."), v);
+            }
+
+            fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+                self.0.visit_named_mut(&format!("{p}0."), v);
+            }
+        }
+    };
+}
+
+newtype_visit_impl!(Residual);
+newtype_visit_impl!(SplitInto);
+newtype_visit_impl!(AddInto);
+
+#[cfg(feature = "nightly")]
+impl<T: VisitParams, const AXIS: isize> VisitParams for ConcatInto<T, AXIS> {
+    fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+        self.0.visit_named(&format!("{p}0."), v);
+    }
+
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+        self.0.visit_named_mut(&format!("{p}0."), v);
+    }
+}
+
+impl<const M: usize, const H: usize, const K: usize, const V: usize> VisitParams
+    for MultiHeadAttention<M, H, K, V>
+{
+    fn visit_named<Vis: ParamVisitor>(&self, p: &str, v: &mut Vis) {
+        self.w_q.visit_named(&format!("{p}w_q."), v);
+        self.w_k.visit_named(&format!("{p}w_k."), v);
+        self.w_v.visit_named(&format!("{p}w_v."), v);
+        self.w_o.visit_named(&format!("{p}w_o."), v);
+    }
+
+    fn visit_named_mut<Vis: ParamVisitorMut>(&mut self, p: &str, v: &mut Vis) {
+        self.w_q.visit_named_mut(&format!("{p}w_q."), v);
+        self.w_k.visit_named_mut(&format!("{p}w_k."), v);
+        self.w_v.visit_named_mut(&format!("{p}w_v."), v);
+        self.w_o.visit_named_mut(&format!("{p}w_o."), v);
+    }
+}
+
+impl<const M: usize, const H: usize, const F: usize> VisitParams
+    for TransformerEncoderBlock<M, H, F>
+{
+    fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+        self.self_attn.visit_named(&format!("{p}self_attn."), v);
+        self.norm1.visit_named(&format!("{p}norm1."), v);
+        self.norm2.visit_named(&format!("{p}norm2."), v);
+        self.ff.0 .0.visit_named(&format!("{p}linear1."), v);
+        self.ff.0 .2.visit_named(&format!("{p}linear2."), v);
+    }
+
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+        self.self_attn.visit_named_mut(&format!("{p}self_attn."), v);
+        self.norm1.visit_named_mut(&format!("{p}norm1."), v);
+        self.norm2.visit_named_mut(&format!("{p}norm2."), v);
+        self.ff.0 .0.visit_named_mut(&format!("{p}linear1."), v);
+        self.ff.0 .2.visit_named_mut(&format!("{p}linear2."), v);
+    }
+}
+
+impl<const M: usize, const H: usize, const F: usize> VisitParams
+    for TransformerDecoderBlock<M, H, F>
+{
+    fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+        self.self_attn.visit_named(&format!("{p}self_attn."), v);
+        self.norm1.visit_named(&format!("{p}norm1."), v);
+        self.mh_attn.visit_named(&format!("{p}mh_attn."), v);
+        self.norm2.visit_named(&format!("{p}norm2."), v);
+        self.ff.0 .0.visit_named(&format!("{p}linear1."), v);
+        self.ff.0 .2.visit_named(&format!("{p}linear2."), v);
+        self.norm3.visit_named(&format!("{p}norm3."), v);
+    }
+
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+        self.self_attn.visit_named_mut(&format!("{p}self_attn."), v);
+        self.norm1.visit_named_mut(&format!("{p}norm1."), v);
+        self.mh_attn.visit_named_mut(&format!("{p}mh_attn."), v);
+        self.norm2.visit_named_mut(&format!("{p}norm2."), v);
+        self.ff.0 .0.visit_named_mut(&format!("{p}linear1."), v);
+        self.ff.0 .2.visit_named_mut(&format!("{p}linear2."), v);
+        self.norm3.visit_named_mut(&format!("{p}norm3."), v);
+    }
+}
+
+impl<const M: usize, const H: usize, const F: usize, const L: usize> VisitParams
+    for TransformerDecoder<M, H, F, L>
+{
+    /// Visits the inner [Repeated] at the same prefix, so layers are named like [TransformerEncoder]'s.
+    fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+        self.0.visit_named(p, v);
+    }
+
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+        self.0.visit_named_mut(p, v);
+    }
+}
+
+impl<const M: usize, const H: usize, const E: usize, const D: usize, const F: usize> VisitParams
+    for Transformer<M, H, E, D, F>
+{
+    fn visit_named<V: ParamVisitor>(&self, p: &str, v: &mut V) {
+        self.encoder.visit_named(&format!("{p}encoder."), v);
+        self.decoder.visit_named(&format!("{p}decoder."), v);
+    }
+
+    fn visit_named_mut<V: ParamVisitorMut>(&mut self, p: &str, v: &mut V) {
+        self.encoder.visit_named_mut(&format!("{p}encoder."), v);
+        self.decoder.visit_named_mut(&format!("{p}decoder."), v);
+    }
+}
+
+macro_rules! empty_visit_impl {
+    ([$($generics:tt)*] $TyName:ty) => {
+        impl<$($generics)*> VisitParams for $TyName {
+            fn visit_named<V: ParamVisitor>(&self, _: &str, _: &mut V) {}
+            fn visit_named_mut<V: ParamVisitorMut>(&mut self, _: &str, _: &mut V) {}
+        }
+    };
+    ($TyName:ty) => {
+        empty_visit_impl!([] $TyName);
+    };
+}
+
+empty_visit_impl!(ReLU);
+empty_visit_impl!(Sin);
+empty_visit_impl!(Cos);
+empty_visit_impl!(Ln);
+empty_visit_impl!(Exp);
+empty_visit_impl!(Sigmoid);
+empty_visit_impl!(Tanh);
+empty_visit_impl!(Square);
+empty_visit_impl!(Sqrt);
+empty_visit_impl!(Abs);
+empty_visit_impl!(Softmax);
+empty_visit_impl!(LogSoftmax);
+empty_visit_impl!(Dropout);
+empty_visit_impl!(AvgPoolGlobal);
+empty_visit_impl!(MaxPoolGlobal);
+empty_visit_impl!(MinPoolGlobal);
+empty_visit_impl!(Flatten2D);
+empty_visit_impl!([const N: usize] DropoutOneIn<N>);
+
+#[cfg(feature = "nightly")]
+empty_visit_impl!([const R: usize] PixelShuffle<R>);
+
+empty_visit_impl!([const K: usize, const S: usize, const P: usize] AvgPool2D<K, S, P>);
+empty_visit_impl!([const K: usize, const S: usize, const P: usize] MaxPool2D<K, S, P>);
+empty_visit_impl!([const K: usize, const S: usize, const P: usize] MinPool2D<K, S, P>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::HasShape;
+    use crate::devices::FillElements;
+    use std::{string::String, vec::Vec};
+
+    #[derive(Default)]
+    struct Names(Vec<(String, Vec<usize>)>);
+
+    impl ParamVisitor for Names {
+        fn visit<T: Tensor<Dtype = f32>>(&mut self, name: &str, _: &T) {
+            self.0.push((name.into(), T::Array::shape()));
+        }
+    }
+
+    struct Fill(f32);
+
+    impl ParamVisitorMut for Fill {
+        fn visit_mut<T: Tensor<Dtype = f32>>(&mut self, _: &str, param: &mut T) {
+            T::Device::fill(param.mut_data(), &mut |v| *v = self.0);
+        }
+    }
+
+    fn names<M: VisitParams>(m: &M) -> Vec<String> {
+        let mut names: Names = Default::default();
+        m.visit_params(&mut names);
+        names.0.into_iter().map(|(n, _)| n).collect()
+    }
+
+    #[test]
+    fn test_visit_nested_names() {
+        type Model = (
+            Linear<5, 3>,
+            ReLU,
+            Residual<(LayerNorm1D<3>, Linear<3, 3>)>,
+            GeneralizedResidual<UnbiasedLinear<3, 3>, Linear<3, 3>>,
+            Repeated<DropPath<LayerScale<3>>, 2>,
+        );
+        let model: Model = Default::default();
+        let mut names: Names = Default::default();
+        model.visit_params(&mut names);
+        assert_eq!(
+            names.0,
+            [
+                ("0.weight".into(), std::vec![3, 5]),
+                ("0.bias".into(), std::vec![3]),
+                ("2.0.0.gamma".into(), std::vec![3]),
+                ("2.0.0.beta".into(), std::vec![3]),
+                ("2.0.1.weight".into(), std::vec![3, 3]),
+                ("2.0.1.bias".into(), std::vec![3]),
+                ("3.f.weight".into(), std::vec![3, 3]),
+                ("3.r.weight".into(), std::vec![3, 3]),
+                ("3.r.bias".into(), std::vec![3]),
+                ("4.0.f.gamma".into(), std::vec![3]),
+                ("4.1.f.gamma".into(), std::vec![3]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_visit_wrappers_keep_names() {
+        let model: SpectralNorm<Linear<3, 2>> = Default::default();
+        assert_eq!(names(&model), ["weight", "bias"]);
+        assert_eq!(names(&BatchNorm2D::<3>::default()), ["scale", "bias"]);
+        assert!(names(&(ReLU, Dropout::default(), Softmax)).is_empty());
+    }
+
+    #[test]
+    fn test_visit_params_mut() {
+        let mut model: (Linear<2, 3>, Residual<LayerNorm1D<3>>) = Default::default();
+        model.visit_params_mut(&mut Fill(2.0));
+        assert_eq!(model.0.weight.data(), &[[2.0; 2]; 3]);
+        assert_eq!(model.0.bias.data(), &[2.0; 3]);
+        assert_eq!(model.1 .0.gamma.data(), &[2.0; 3]);
+        assert_eq!(model.1 .0.beta.data(), &[2.0; 3]);
+    }
+}
@@ -77,6 +77,12 @@ pub enum NpyError {
         found_str: String,
     },
 
+    /// The shape in the header is not the shape of the array being loaded into.
+    ShapeMismatch {
+        expected: Vec<usize>,
+        found: Vec<usize>,
+    },
+
     /// Unexpected alignment for [Endian].
     InvalidAlignment,
 }
@@ -97,6 +103,12 @@ impl std::fmt::Display for NpyError {
                 "error while parsing: expected {} found {}",
                 expected_str, found_str
             ),
+            NpyError::ShapeMismatch { expected, found } => write!(
+                fmt,
+                "expected shape ({}), found ({})",
+                to_shape_str(expected.clone()),
+                to_shape_str(found.clone())
+            ),
             NpyError::InvalidAlignment => write!(fmt, "invalid alignment"),
         }
     }
@@ -124,37 +136,35 @@ impl From<std::string::FromUtf8Error> for NpyError {
     }
 }
 
+/// Reads just the shape from the header of a .npy file, leaving `r` at the start of the data.
+pub(crate) fn read_shape<R: Read>(r: &mut R) -> Result<Vec<usize>, NpyError> {
+    let header = read_header_bytes(r)?;
+    let key = b"'shape': (";
+    match header.windows(key.len()).position(|w| w == key) {
+        Some(i) => parse_shape(&header[i + key.len()..]),
+        None => Err(NpyError::ParsingMismatch {
+            expected: key.to_vec(),
+            found: header.clone(),
+            expected_str: String::from_utf8(key.to_vec())?,
+            found_str: String::from_utf8(header)?,
+        }),
+    }
+}
+
 fn read_header<T, R>(r: &mut R) -> Result<Endian, NpyError>
 where
     T: NumpyDtype + NumpyShape,
     R: Read,
 {
-    let mut magic = [0; 6];
-    r.read_exact(&mut magic)?;
-    if magic != MAGIC_NUMBER {
-        return Err(NpyError::InvalidMagicNumber(magic));
-    }
-
-    let mut version = [0; 2];
-    r.read_exact(&mut version)?;
-    if version != VERSION {
-        return Err(NpyError::InvalidVersion(version));
-    }
-
-    let mut header_len_bytes = [0; 2];
-    r.read_exact(&mut header_len_bytes)?;
-    let header_len = u16::from_le_bytes(header_len_bytes);
-
-    let mut header: Vec<u8> = vec![0; header_len as usize];
-    r.read_exact(&mut header)?;
+    let header = read_header_bytes(r)?;
 
     let mut i = 0;
     i = expect(&header, i, b"{'descr': '")?;
 
-    let endian = match header[i] {
-        b'>' => Endian::Big,
-        b'<' => Endian::Little,
-        b'=' => Endian::Native,
+    let endian = match header.get(i) {
+        Some(b'>') => Endian::Big,
+        Some(b'<') => Endian::Little,
+        Some(b'=') => Endian::Native,
         _ => return Err(NpyError::InvalidAlignment),
     };
     i += 1;
@@ -167,18 +177,70 @@ where
 
     // shape
     i = expect(&header, i, b"'shape': (")?;
-    let shape_str = to_shape_str(T::shape());
-    i = expect(&header, i, shape_str.as_bytes())?;
-    expect(&header, i, b"), }")?;
+    let found = parse_shape(&header[i..])?;
+    if found != T::shape() {
+        return Err(NpyError::ShapeMismatch {
+            expected: T::shape(),
+            found,
+        });
+    }
 
     Ok(endian)
 }
 
+/// Parses the dimensions of a shape tuple up to its closing `)`, e.g. `3, 5)` or `3,)`.
+fn parse_shape(buf: &[u8]) -> Result<Vec<usize>, NpyError> {
+    let end = buf.iter().position(|&c| c == b')').unwrap_or(buf.len());
+    let shape_str = String::from_utf8(buf[..end].to_vec())?;
+    let mut shape = Vec::new();
+    for dim in shape_str
+        .split(',')
+        .map(|d| d.trim())
+        .filter(|d| !d.is_empty())
+    {
+        match dim.parse() {
+            Ok(d) => shape.push(d),
+            Err(_) => {
+                return Err(NpyError::ParsingMismatch {
+                    expected: b"usize".to_vec(),
+                    found: dim.as_bytes().to_vec(),
+                    expected_str: "usize".into(),
+                    found_str: dim.into(),
+                })
+            }
+        }
+    }
+    expect(buf, end, b"), }")?;
+    Ok(shape)
+}
+
+fn read_header_bytes<R: Read>(r: &mut R) -> Result<Vec<u8>, NpyError> {
+    let mut magic = [0; 6];
+    r.read_exact(&mut magic)?;
+    if magic != MAGIC_NUMBER {
+        return Err(NpyError::InvalidMagicNumber(magic));
+    }
+
+    let mut version = [0; 2];
+    r.read_exact(&mut version)?;
+    if version != VERSION {
+        return Err(NpyError::InvalidVersion(version));
+    }
+
+    let mut header_len_bytes = [0; 2];
+    r.read_exact(&mut header_len_bytes)?;
+    let header_len = u16::from_le_bytes(header_len_bytes);
+
+    let mut header: Vec<u8> = vec![0; header_len as usize];
+    r.read_exact(&mut header)?;
+    Ok(header)
+}
+
 fn expect(buf: &[u8], i: usize, chars: &[u8]) -> Result<usize, NpyError> {
     for (offset, &c) in chars.iter().enumerate() {
-        if buf[i + offset] != c {
+        if buf.get(i + offset) != Some(&c) {
             let expected = chars.to_vec();
-            let found = buf[i..i + offset + 1].to_vec();
+            let found = buf[i.min(buf.len())..(i + offset + 1).min(buf.len())].to_vec();
             let expected_str = String::from_utf8(expected.clone())?;
             let found_str = String::from_utf8(found.clone())?;
             return Err(NpyError::ParsingMismatch {
@@ -301,4 +363,40 @@ mod tests {
         let mut value = [[0.0f32; 2]; 3];
         assert!(load(file.path(), &mut value).is_err());
     }
+
+    #[test]
+    fn test_load_shape_mismatch() {
+        let data = [[0.0f32; 3]; 2];
+
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+
+        save(file.path(), &data).expect("Saving failed");
+
+        let mut value = [[0.0f32; 2]; 3];
+        let err = load(file.path(), &mut value).unwrap_err();
+        assert!(matches!(err, NpyError::ShapeMismatch { .. }));
+        assert_eq!(
+            std::format!("{}", err),
+            "expected shape (3, 2), found (2, 3)"
+        );
+
+        let mut value = [0.0f32; 6];
+        let err = load(file.path(), &mut value).unwrap_err();
+        assert_eq!(std::format!("{}", err), "expected shape (6,), found (2, 3)");
+    }
+
+    #[test]
+    fn test_load_truncated_header() {
+        let mut bytes = std::vec::Vec::new();
+        bytes.extend_from_slice(MAGIC_NUMBER);
+        bytes.extend_from_slice(VERSION);
+        bytes.extend_from_slice(&4u16.to_le_bytes());
+        bytes.extend_from_slice(b"{'de");
+
+        let mut value = 0.0f32;
+        assert!(matches!(
+            read(&mut bytes.as_slice(), &mut value),
+            Err(NpyError::ParsingMismatch { .. })
+        ));
+    }
 }
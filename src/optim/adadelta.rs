@@ -0,0 +1,244 @@
+use super::weight_decay::weight_decay_for;
+use crate::arrays::HasArrayType;
+use crate::devices::ForEachElement;
+use crate::gradients::{CanUpdateWithGradients, GradientProvider, Gradients};
+use crate::prelude::*;
+use crate::unique_id::HasUniqueId;
+use std::{boxed::Box, marker::PhantomData};
+
+/// An implementation of the Adadelta optimizer from
+/// [ADADELTA: An Adaptive Learning Rate Method](https://arxiv.org/abs/1212.5701).
+/// Based on [pytorch's implementation](https://pytorch.org/docs/stable/generated/torch.optim.Adadelta.html)
+///
+/// Keeps running averages of both the squared gradients and the squared updates, and scales each
+/// gradient by the ratio of their square roots. [AdadeltaConfig::lr] is only a multiplier on that update.
+///
+/// # Example Usage
+///
+/// Constructing using default:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor0D;
+/// let mut opt: Adadelta<Model> = Default::default();
+/// ```
+///
+/// Constructing using new:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor0D;
+/// let mut opt: Adadelta<Model> = Adadelta::new(AdadeltaConfig {
+///     lr: 0.5,
+///     rho: 0.95,
+///     eps: 1e-8,
+///     weight_decay: Some(WeightDecay::L2(1e-2)),
+///     weight_decay_1d: true,
+/// });
+/// ```
+///
+/// See module level documentation at [crate::optim] for examples of how to actually use an optimizer.
+#[derive(Debug)]
+pub struct Adadelta<M> {
+    /// Hyperparameter configuration
+    pub cfg: AdadeltaConfig,
+
+    pub(super) square_avg: Gradients,
+    pub(super) delta_avg: Gradients,
+    gradients: Gradients,
+
+    marker: PhantomData<*const M>,
+}
+
+/// Configuration of hyperparameters for [Adadelta].
+#[derive(Debug, Clone, Copy)]
+pub struct AdadeltaConfig {
+    /// Learning rate. Defaults to `1.0`.
+    pub lr: f32,
+
+    /// Decay of the running averages of squared gradients & squared updates. Defaults to `0.9`.
+    pub rho: f32,
+
+    /// Epsilon for numerical stability, added inside the square roots. Defaults to `1e-6`.
+    pub eps: f32,
+
+    /// Optional weight decay. Defaults to `None`.
+    pub weight_decay: Option<WeightDecay>,
+
+    /// Whether weight decay is also applied to 1d parameters, like biases and the gains of
+    /// normalization layers. Defaults to `true`.
+    pub weight_decay_1d: bool,
+}
+
+impl Default for AdadeltaConfig {
+    fn default() -> Self {
+        Self {
+            lr: 1.0,
+            rho: 0.9,
+            eps: 1e-6,
+            weight_decay: None,
+            weight_decay_1d: true,
+        }
+    }
+}
+
+impl<M> Default for Adadelta<M> {
+    /// See [AdadeltaConfig]
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<M> Adadelta<M> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(cfg: AdadeltaConfig) -> Self {
+        Self {
+            cfg,
+            square_avg: Default::default(),
+            delta_avg: Default::default(),
+            gradients: Default::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M> GradientProvider for Adadelta<M> {
+    fn gradient<P>(&mut self, p: &P) -> Option<Box<P::Array>>
+    where
+        P: HasUniqueId + HasArrayType<Dtype = f32> + HasDevice + HasArrayData,
+    {
+        let mut g_t = self.gradients.remove(p)?;
+
+        let weight_decay = weight_decay_for::<P>(self.cfg.weight_decay, self.cfg.weight_decay_1d);
+        if let Some(WeightDecay::L2(wd)) = weight_decay {
+            P::Device::foreach_mr(g_t.as_mut(), p.data(), &mut |g, p_el| {
+                *g += wd * p_el;
+            });
+        }
+
+        let square_avg = self.square_avg.mut_gradient(p);
+        let delta_avg = self.delta_avg.mut_gradient(p);
+        let (rho, eps) = (self.cfg.rho, self.cfg.eps);
+        P::Device::foreach_mmm(g_t.as_mut(), square_avg, delta_avg, &mut |g, sa, da| {
+            *sa = *sa * rho + g.powi(2) * (1.0 - rho);
+            let delta = *g * (*da + eps).sqrt() / (*sa + eps).sqrt();
+            *da = *da * rho + delta.powi(2) * (1.0 - rho);
+            *g = delta * self.cfg.lr;
+        });
+
+        if let Some(WeightDecay::Decoupled(wd)) = weight_decay {
+            P::Device::foreach_mr(g_t.as_mut(), p.data(), &mut |g, p_el| {
+                *g += wd * self.cfg.lr * p_el;
+            });
+        }
+
+        Some(g_t)
+    }
+}
+
+impl<M: CanUpdateWithGradients> Optimizer<M> for Adadelta<M> {
+    fn update(&mut self, module: &mut M, gradients: Gradients) -> Result<(), UnusedParamsError> {
+        self.gradients = gradients;
+        let mut unused_tensors = Default::default();
+        module.update(self, &mut unused_tensors);
+        unused_tensors.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+
+    fn test_matches_expected(cfg: AdadeltaConfig, expected: [[f32; 5]; 5]) {
+        let rate = Tensor1D::new([0.1, 1.0, 2.0, 10.0, 100.0]);
+        let mut t: Tensor1D<5> = Tensor1D::ones();
+        let mut opt = Adadelta::new(cfg);
+        for e in expected.iter() {
+            let gradients = backward((t.trace() * rate.clone()).square().sum());
+            opt.update(&mut t, gradients).expect("");
+            assert_close(t.data(), e);
+        }
+    }
+
+    #[test]
+    fn test_adadelta_default() {
+        #[rustfmt::skip]
+        const EXPECTED: [[f32; 5]; 5] = [
+            [0.99687654, 0.99683774, 0.99683774, 0.99683774, 0.99683774],
+            [0.9936778, 0.99359816, 0.99359816, 0.99359816, 0.99359816],
+            [0.99043053, 0.99030906, 0.99030906, 0.99030906, 0.99030906],
+            [0.9871482, 0.986984, 0.986984, 0.986984, 0.986984],
+            [0.9838387, 0.9836313, 0.9836313, 0.9836313, 0.9836313],
+        ];
+        test_matches_expected(Default::default(), EXPECTED);
+    }
+
+    #[test]
+    fn test_adadelta_rho_eps() {
+        const CFG: AdadeltaConfig = AdadeltaConfig {
+            lr: 1e-1,
+            rho: 0.5,
+            eps: 1e-3,
+            weight_decay: None,
+            weight_decay_1d: true,
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f32; 5]; 5] = [
+            [0.99817425, 0.995529, 0.9955279, 0.99552786, 0.99552786],
+            [0.9962825, 0.99037427, 0.99037176, 0.99037164, 0.99037164],
+            [0.9943545, 0.98470604, 0.9847018, 0.9847016, 0.9847016],
+            [0.9924059, 0.9786035, 0.9785973, 0.97859687, 0.97859687],
+            [0.99044603, 0.97211355, 0.97210497, 0.9721044, 0.9721044],
+        ];
+        test_matches_expected(CFG, EXPECTED);
+    }
+
+    #[test]
+    fn test_adadelta_l2_weight_decay() {
+        const CFG: AdadeltaConfig = AdadeltaConfig {
+            lr: 1.0,
+            rho: 0.9,
+            eps: 1e-6,
+            weight_decay: Some(WeightDecay::L2(0.5)),
+            weight_decay_1d: true,
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f32; 5]; 5] = [
+            [0.9968378, 0.99683774, 0.99683774, 0.99683774, 0.99683774],
+            [0.9935983, 0.99359816, 0.99359816, 0.99359816, 0.99359816],
+            [0.99030924, 0.99030906, 0.99030906, 0.99030906, 0.99030906],
+            [0.98698425, 0.986984, 0.986984, 0.986984, 0.986984],
+            [0.9836316, 0.9836313, 0.9836313, 0.9836313, 0.9836313],
+        ];
+        test_matches_expected(CFG, EXPECTED);
+    }
+
+    #[test]
+    fn test_adadelta_decoupled_weight_decay() {
+        const CFG: AdadeltaConfig = AdadeltaConfig {
+            lr: 1.0,
+            rho: 0.9,
+            eps: 1e-6,
+            weight_decay: Some(WeightDecay::Decoupled(0.5)),
+            weight_decay_1d: true,
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f32; 5]; 5] = [
+            [0.49687654, 0.49683774, 0.49683774, 0.49683774, 0.49683774],
+            [0.24639814, 0.24634406, 0.24634406, 0.24634406, 0.24634406],
+            [0.12208284, 0.122034445, 0.122034445, 0.122034445, 0.122034445],
+            [0.06046403, 0.06042811, 0.060428105, 0.060428105, 0.060428105],
+            [0.029937923, 0.029913714, 0.02991371, 0.02991371, 0.02991371],
+        ];
+        test_matches_expected(CFG, EXPECTED);
+    }
+
+    #[test]
+    fn test_adadelta_unused_params() {
+        type Model = (Linear<5, 16>, Linear<16, 10>);
+        let mut model: Model = Default::default();
+        let mut opt: Adadelta<Model> = Default::default();
+        let y = model.1.forward(Tensor2D::<8, 16>::zeros().trace());
+        let g = backward(y.mean());
+        opt.update(&mut model, g).expect_err("");
+    }
+}
@@ -0,0 +1,276 @@
+use super::weight_decay::weight_decay_for;
+use crate::arrays::HasArrayType;
+use crate::devices::{FillElements, ForEachElement};
+use crate::gradients::{CanUpdateWithGradients, GradientProvider, Gradients};
+use crate::prelude::*;
+use crate::unique_id::HasUniqueId;
+use std::{boxed::Box, marker::PhantomData};
+
+/// An implementation of the Adagrad optimizer from
+/// [Adaptive Subgradient Methods for Online Learning and Stochastic Optimization](https://jmlr.org/papers/v12/duchi11a.html).
+/// Based on [pytorch's implementation](https://pytorch.org/docs/stable/generated/torch.optim.Adagrad.html)
+///
+/// Each parameter's learning rate is divided by the square root of the sum of all of its squared gradients,
+/// so rarely updated parameters take larger steps.
+///
+/// # Example Usage
+///
+/// Constructing using default:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor0D;
+/// let mut opt: Adagrad<Model> = Default::default();
+/// ```
+///
+/// Constructing using new:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor0D;
+/// let mut opt: Adagrad<Model> = Adagrad::new(AdagradConfig {
+///     lr: 1e-1,
+///     lr_decay: 1e-3,
+///     initial_accumulator_value: 0.1,
+///     eps: 1e-10,
+///     weight_decay: Some(WeightDecay::L2(1e-2)),
+///     weight_decay_1d: true,
+/// });
+/// ```
+///
+/// See module level documentation at [crate::optim] for examples of how to actually use an optimizer.
+#[derive(Debug)]
+pub struct Adagrad<M> {
+    /// Hyperparameter configuration
+    pub cfg: AdagradConfig,
+
+    pub(super) step: usize,
+    pub(super) sum_sq: Gradients,
+    gradients: Gradients,
+
+    marker: PhantomData<*const M>,
+}
+
+/// Configuration of hyperparameters for [Adagrad].
+#[derive(Debug, Clone, Copy)]
+pub struct AdagradConfig {
+    /// Learning rate. Defaults to `1e-2`.
+    pub lr: f32,
+
+    /// The learning rate at step `t` is `lr / (1 + t * lr_decay)`. Defaults to `0.0`.
+    pub lr_decay: f32,
+
+    /// The starting value of the sum of squared gradients. Defaults to `0.0`.
+    pub initial_accumulator_value: f32,
+
+    /// Epsilon for numerical stability. Defaults to `1e-10`.
+    pub eps: f32,
+
+    /// Optional weight decay. Defaults to `None`.
+    pub weight_decay: Option<WeightDecay>,
+
+    /// Whether weight decay is also applied to 1d parameters, like biases and the gains of
+    /// normalization layers. Defaults to `true`.
+    pub weight_decay_1d: bool,
+}
+
+impl Default for AdagradConfig {
+    fn default() -> Self {
+        Self {
+            lr: 1e-2,
+            lr_decay: 0.0,
+            initial_accumulator_value: 0.0,
+            eps: 1e-10,
+            weight_decay: None,
+            weight_decay_1d: true,
+        }
+    }
+}
+
+impl<M> Default for Adagrad<M> {
+    /// See [AdagradConfig]
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<M> Adagrad<M> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(cfg: AdagradConfig) -> Self {
+        Self {
+            cfg,
+            step: 0,
+            sum_sq: Default::default(),
+            gradients: Default::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M> GradientProvider for Adagrad<M> {
+    fn gradient<P>(&mut self, p: &P) -> Option<Box<P::Array>>
+    where
+        P: HasUniqueId + HasArrayType<Dtype = f32> + HasDevice + HasArrayData,
+    {
+        let mut g_t = self.gradients.remove(p)?;
+
+        let is_new = self.sum_sq.try_ref_gradient(p).is_none();
+        let sum_sq = self.sum_sq.mut_gradient(p);
+        if is_new {
+            P::Device::fill(sum_sq, &mut |s| *s = self.cfg.initial_accumulator_value);
+        }
+
+        let weight_decay = weight_decay_for::<P>(self.cfg.weight_decay, self.cfg.weight_decay_1d);
+        if let Some(WeightDecay::L2(wd)) = weight_decay {
+            P::Device::foreach_mr(g_t.as_mut(), p.data(), &mut |g, p_el| {
+                *g += wd * p_el;
+            });
+        }
+
+        let lr = self.cfg.lr / (1.0 + self.step as f32 * self.cfg.lr_decay);
+        P::Device::foreach_mm(g_t.as_mut(), sum_sq, &mut |g, s| {
+            *s += g.powi(2);
+            *g *= lr / (s.sqrt() + self.cfg.eps);
+        });
+
+        if let Some(WeightDecay::Decoupled(wd)) = weight_decay {
+            P::Device::foreach_mr(g_t.as_mut(), p.data(), &mut |g, p_el| {
+                *g += wd * lr * p_el;
+            });
+        }
+
+        Some(g_t)
+    }
+}
+
+impl<M: CanUpdateWithGradients> Optimizer<M> for Adagrad<M> {
+    fn update(&mut self, module: &mut M, gradients: Gradients) -> Result<(), UnusedParamsError> {
+        self.gradients = gradients;
+        let mut unused_tensors = Default::default();
+        module.update(self, &mut unused_tensors);
+        self.step += 1;
+        unused_tensors.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+
+    fn test_matches_expected(cfg: AdagradConfig, expected: [[f32; 5]; 5]) {
+        let rate = Tensor1D::new([0.1, 1.0, 2.0, 10.0, 100.0]);
+        let mut t: Tensor1D<5> = Tensor1D::ones();
+        let mut opt = Adagrad::new(cfg);
+        for e in expected.iter() {
+            let gradients = backward((t.trace() * rate.clone()).square().sum());
+            opt.update(&mut t, gradients).expect("");
+            assert_close(t.data(), e);
+        }
+    }
+
+    #[test]
+    fn test_adagrad_default() {
+        #[rustfmt::skip]
+        const EXPECTED: [[f32; 5]; 5] = [
+            [0.99, 0.99, 0.99, 0.99, 0.99],
+            [0.9829646, 0.9829646, 0.9829646, 0.9829646, 0.9829646],
+            [0.97723794, 0.97723794, 0.97723794, 0.97723794, 0.97723794],
+            [0.97229034, 0.97229034, 0.97229034, 0.97229034, 0.97229034],
+            [0.9678739, 0.9678739, 0.9678739, 0.9678739, 0.9678739],
+        ];
+        test_matches_expected(Default::default(), EXPECTED);
+    }
+
+    #[test]
+    fn test_adagrad_lr_decay() {
+        const CFG: AdagradConfig = AdagradConfig {
+            lr: 1e-1,
+            lr_decay: 0.5,
+            initial_accumulator_value: 0.1,
+            eps: 1e-10,
+            weight_decay: None,
+            weight_decay_1d: true,
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f32; 5]; 5] = [
+            [0.99368805, 0.90122706, 0.90007806, 0.9000001, 0.9],
+            [0.9895148, 0.8569005, 0.8554975, 0.8554025, 0.85540235],
+            [0.9864041, 0.83018225, 0.82867706, 0.8285752, 0.828575],
+            [0.98392814, 0.81179243, 0.8102343, 0.8101289, 0.8101288],
+            [0.9818739, 0.7981247, 0.79653513, 0.79642767, 0.7964275],
+        ];
+        test_matches_expected(CFG, EXPECTED);
+    }
+
+    #[test]
+    fn test_adagrad_l2_weight_decay() {
+        const CFG: AdagradConfig = AdagradConfig {
+            lr: 1e-2,
+            lr_decay: 0.0,
+            initial_accumulator_value: 1.0,
+            eps: 1e-10,
+            weight_decay: Some(WeightDecay::L2(0.5)),
+            weight_decay_1d: true,
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f32; 5]; 5] = [
+            [0.9953865, 0.9907152, 0.9900685, 0.9900001, 0.99],
+            [0.99121326, 0.98394525, 0.98305726, 0.9829647, 0.9829646],
+            [0.9873757, 0.9783655, 0.9773438, 0.9772381, 0.97723794],
+            [0.983805, 0.9735141, 0.9724048, 0.9722905, 0.97229034],
+            [0.980453, 0.9691667, 0.9679944, 0.9678741, 0.9678739],
+        ];
+        test_matches_expected(CFG, EXPECTED);
+    }
+
+    #[test]
+    fn test_adagrad_decoupled_weight_decay() {
+        const CFG: AdagradConfig = AdagradConfig {
+            lr: 1e-2,
+            lr_decay: 0.0,
+            initial_accumulator_value: 0.0,
+            eps: 1e-10,
+            weight_decay: Some(WeightDecay::Decoupled(0.5)),
+            weight_decay_1d: true,
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f32; 5]; 5] = [
+            [0.985, 0.985, 0.985, 0.985, 0.985],
+            [0.97305757, 0.97305757, 0.97305757, 0.97305757, 0.97305757],
+            [0.962495, 0.962495, 0.962495, 0.962495, 0.962495],
+            [0.95277303, 0.95277303, 0.95277303, 0.95277303, 0.95277303],
+            [0.94363815, 0.94363815, 0.94363815, 0.94363815, 0.94363815],
+        ];
+        test_matches_expected(CFG, EXPECTED);
+    }
+
+    #[test]
+    fn test_adagrad_initial_accumulator_late_param() {
+        let cfg = AdagradConfig {
+            lr: 1.0,
+            initial_accumulator_value: 3.0,
+            ..Default::default()
+        };
+        let mut model: (Tensor1D<2>, Tensor1D<2>) = (Tensor1D::ones(), Tensor1D::ones());
+        let mut opt = Adagrad::new(cfg);
+
+        let gradients = backward(model.0.trace().sum());
+        opt.update(&mut model, gradients).expect_err("");
+        assert_close(model.0.data(), &[0.5; 2]);
+
+        // model.1 first receives a gradient on the second step, so its accumulator
+        // should still start at initial_accumulator_value.
+        let gradients = backward(model.1.trace().sum());
+        opt.update(&mut model, gradients).expect_err("");
+        assert_close(model.1.data(), &[0.5; 2]);
+    }
+
+    #[test]
+    fn test_adagrad_unused_params() {
+        type Model = (Linear<5, 16>, Linear<16, 10>);
+        let mut model: Model = Default::default();
+        let mut opt: Adagrad<Model> = Default::default();
+        let y = model.1.forward(Tensor2D::<8, 16>::zeros().trace());
+        let g = backward(y.mean());
+        opt.update(&mut model, g).expect_err("");
+    }
+}
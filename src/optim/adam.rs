@@ -1,3 +1,4 @@
+use super::weight_decay::weight_decay_for;
 use crate::arrays::HasArrayType;
 use crate::devices::ForEachElement;
 use crate::gradients::{CanUpdateWithGradients, GradientProvider, Gradients};
@@ -26,6 +27,20 @@ use std::{boxed::Box, marker::PhantomData};
 ///     betas: [0.5, 0.25],
 ///     eps: 1e-6,
 ///     weight_decay: Some(WeightDecay::Decoupled(1e-2)),
+///     weight_decay_1d: true,
+/// });
+/// ```
+///
+/// # AdamW
+///
+/// [AdamW](https://arxiv.org/abs/1711.05101) is Adam with [WeightDecay::Decoupled], which
+/// decays the parameters directly instead of adding the decay to the gradients:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor0D;
+/// let mut opt: Adam<Model> = Adam::new(AdamConfig {
+///     weight_decay: Some(WeightDecay::Decoupled(1e-2)),
+///     ..Default::default()
 /// });
 /// ```
 ///
@@ -35,10 +50,10 @@ pub struct Adam<M> {
     /// Hyperparameter configuration
     pub cfg: AdamConfig,
 
-    t: i32,
+    pub(super) t: i32,
     gradients: Gradients,
-    moment1: Gradients,
-    moment2: Gradients,
+    pub(super) moment1: Gradients,
+    pub(super) moment2: Gradients,
 
     marker: PhantomData<*const M>,
 }
@@ -53,6 +68,7 @@ pub struct Adam<M> {
 ///     betas: [0.1, 0.2],
 ///     eps: 1e-6,
 ///     weight_decay: Some(WeightDecay::L2(1e-1)),
+///     weight_decay_1d: true,
 /// };
 /// ```
 #[derive(Debug, Clone, Copy)]
@@ -68,6 +84,10 @@ pub struct AdamConfig {
 
     /// Optional weight decay. Defaults to `None`.
     pub weight_decay: Option<WeightDecay>,
+
+    /// Whether weight decay is also applied to 1d parameters, like biases and the gains of
+    /// normalization layers. Defaults to `true`.
+    pub weight_decay_1d: bool,
 }
 
 impl Default for AdamConfig {
@@ -77,6 +97,7 @@ impl Default for AdamConfig {
             betas: [0.9, 0.999],
             eps: 1e-8,
             weight_decay: None,
+            weight_decay_1d: true,
         }
     }
 }
@@ -110,7 +131,8 @@ impl<M> GradientProvider for Adam<M> {
         let mut g_t = self.gradients.remove(p)?;
         let m_t = self.moment1.mut_gradient(p);
         let v_t = self.moment2.mut_gradient(p);
-        if let Some(WeightDecay::L2(wd)) = self.cfg.weight_decay {
+        let weight_decay = weight_decay_for::<P>(self.cfg.weight_decay, self.cfg.weight_decay_1d);
+        if let Some(WeightDecay::L2(wd)) = weight_decay {
             P::Device::foreach_mr(g_t.as_mut(), p.data(), &mut |g, p_el| {
                 *g += wd * p_el;
             });
@@ -122,7 +144,7 @@ impl<M> GradientProvider for Adam<M> {
             let v_hat = *v * (1.0 - self.cfg.betas[1].powi(self.t)).recip();
             *g = self.cfg.lr * m_hat / (v_hat.sqrt() + self.cfg.eps)
         });
-        if let Some(WeightDecay::Decoupled(wd)) = self.cfg.weight_decay {
+        if let Some(WeightDecay::Decoupled(wd)) = weight_decay {
             P::Device::foreach_mr(g_t.as_mut(), p.data(), &mut |g, p_el| {
                 *g += wd * self.cfg.lr * p_el;
             });
@@ -179,6 +201,7 @@ mod tests {
             betas: [0.5, 0.25],
             eps: 1e-8,
             weight_decay: None,
+            weight_decay_1d: true,
         });
         let mut t: Tensor1D<5> = Tensor1D::ones();
         let rate = Tensor1D::new([1e-4, 1e-3, 1e-2, 1e-1, 1e-0]);
@@ -217,6 +240,7 @@ mod tests {
             betas: [0.9, 0.999],
             eps: 1e-8,
             weight_decay: None,
+            weight_decay_1d: true,
         });
 
         let py = model.forward(x.trace());
@@ -301,4 +325,19 @@ mod tests {
             assert_eq!(t.data(), e);
         }
     }
+
+    #[test]
+    fn test_adam_decoupled_decay_ignores_moments() {
+        let mut opt: Adam<Tensor1D<3>> = Adam::new(AdamConfig {
+            lr: 1e-1,
+            weight_decay: Some(WeightDecay::Decoupled(1e-1)),
+            ..Default::default()
+        });
+        let mut t: Tensor1D<3> = tensor([-1.0, 0.5, 2.0]);
+        for _ in 0..2 {
+            let gradients = (t.trace() * 0.0).sum().backward();
+            opt.update(&mut t, gradients).expect("");
+        }
+        assert_close(t.data(), &[-0.98010004, 0.49005002, 1.9602001]);
+    }
 }
@@ -0,0 +1,255 @@
+use super::npz::{read_scalar, write_scalar};
+use super::{LoadOptimizerFromNpz, SaveOptimizerToNpz};
+use crate::nn::{check_architecture, write_architecture, LoadFromNpz, NpzError, SaveToNpz};
+use crate::rng::{rng_state, set_rng_state, RngState};
+use std::collections::BTreeMap;
+use std::{
+    format,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+    string::String,
+    vec::Vec,
+};
+use zip::{result::ZipResult, ZipArchive, ZipWriter};
+
+/// The version of the format written by [save_checkpoint()].
+const VERSION: u32 = 1;
+
+/// The state of a training run, other than the model & optimizer, that is saved by [save_checkpoint()].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrainingState {
+    /// The number of steps taken. Pass this to [super::lr_scheduler::LrScheduler::set_num_steps()]
+    /// after [resume()].
+    pub step: usize,
+
+    /// Anything else to save, like the epoch or a description of the run.
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// Saves everything needed to resume training into a single `.npz` file at `path`:
+/// `model`, the state of `opt`, `state`, and the state of [crate::rng::default_rng()].
+///
+/// The file is written next to `path` first, and then moved to `path`, so an interrupted save
+/// doesn't overwrite a previous checkpoint.
+///
+/// The model is saved with the prefix `model.`, so the weights can also be loaded with
+/// [crate::nn::LoadFromNpz::read()].
+///
+/// Example:
+/// ```ignore
+/// # use dfdx::prelude::*;
+/// let model: Linear<5, 2> = Default::default();
+/// let opt: Adam<Linear<5, 2>> = Default::default();
+/// let mut state = TrainingState { step: 100, ..Default::default() };
+/// state.metadata.insert("epoch".into(), "3".into());
+/// save_checkpoint("checkpoint.npz", &model, &opt, &state)?;
+/// ```
+pub fn save_checkpoint<M, O, P>(path: P, model: &M, opt: &O, state: &TrainingState) -> ZipResult<()>
+where
+    M: SaveToNpz,
+    O: SaveOptimizerToNpz<M>,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let mut tmp = std::ffi::OsString::from(path.as_os_str());
+    tmp.push(".tmp");
+
+    let f = std::fs::File::create(&tmp)?;
+    let mut zip = ZipWriter::new(BufWriter::new(f));
+    write_architecture::<M, _>(&mut zip);
+    write_scalar(&mut zip, "", "version", &(VERSION as f64))?;
+    model.write("model.", &mut zip)?;
+    opt.write(model, "optimizer.", &mut zip)?;
+    write_scalar(&mut zip, "", "step", &(state.step as f64))?;
+    let rng = rng_state();
+    write_scalar(&mut zip, "", "rng.seed_lo", &(rng.seed as u32 as f64))?;
+    write_scalar(&mut zip, "", "rng.seed_hi", &((rng.seed >> 32) as f64))?;
+    write_scalar(&mut zip, "", "rng.counter", &(rng.counter as f64))?;
+    for (key, value) in state.metadata.iter() {
+        zip.start_file(format!("metadata/{key}"), Default::default())?;
+        zip.write_all(value.as_bytes())?;
+    }
+    zip.finish()?.flush()?;
+
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Loads a checkpoint written by [save_checkpoint()] into `model` and `opt`, restores the state of
+/// [crate::rng::default_rng()], and returns the [TrainingState].
+///
+/// If anything fails to load, `model`, `opt` and the rng are left unchanged.
+///
+/// Example:
+/// ```ignore
+/// # use dfdx::prelude::*;
+/// # use dfdx::optim::lr_scheduler::*;
+/// let mut model: Linear<5, 2> = Default::default();
+/// let mut opt: Adam<Linear<5, 2>> = Default::default();
+/// let mut sched = LrScheduler::new(ExponentialLR { lr: 1e-3, gamma: 0.9 });
+/// let state = resume("checkpoint.npz", &mut model, &mut opt)?;
+/// sched.set_num_steps(state.step);
+/// ```
+pub fn resume<M, O, P>(path: P, model: &mut M, opt: &mut O) -> Result<TrainingState, NpzError>
+where
+    M: LoadFromNpz + Clone,
+    O: LoadOptimizerFromNpz<M>,
+    P: AsRef<Path>,
+{
+    let f = std::fs::File::open(path)?;
+    let mut zip = ZipArchive::new(BufReader::new(f))?;
+
+    let version = read_scalar::<_, f64>(&mut zip, "", "version")? as u32;
+    if version != VERSION {
+        return Err(NpzError::UnsupportedVersion(version));
+    }
+
+    // clones keep the ids of the parameters, so the optimizer state is keyed the same as `model`.
+    let mut loaded = model.clone();
+    let result = loaded.read("model.", &mut zip);
+    check_architecture::<M, _>(&zip, result)?;
+    let step = read_scalar::<_, f64>(&mut zip, "", "step")? as usize;
+    let seed_lo = read_scalar::<_, f64>(&mut zip, "", "rng.seed_lo")? as u64;
+    let seed_hi = read_scalar::<_, f64>(&mut zip, "", "rng.seed_hi")? as u64;
+    let rng = RngState {
+        seed: (seed_hi << 32) | seed_lo,
+        counter: read_scalar::<_, f64>(&mut zip, "", "rng.counter")? as usize,
+    };
+    let keys: Vec<String> = zip
+        .file_names()
+        .filter_map(|name| name.strip_prefix("metadata/"))
+        .map(String::from)
+        .collect();
+    let mut metadata = BTreeMap::new();
+    for key in keys {
+        let mut value = String::new();
+        zip.by_name(&format!("metadata/{key}"))?
+            .read_to_string(&mut value)?;
+        metadata.insert(key, value);
+    }
+
+    // `read` only modifies `opt` once all of its state has been read.
+    opt.read(&loaded, "optimizer.", &mut zip)?;
+    *model = loaded;
+    set_rng_state(rng);
+    Ok(TrainingState { step, metadata })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optim::npz::write_state;
+    use crate::prelude::*;
+    use rand::{prelude::StdRng, SeedableRng};
+    use tempfile::NamedTempFile;
+
+    type Model = (Linear<3, 4>, ReLU, Linear<4, 2>);
+
+    fn train_step(model: &mut Model, opt: &mut Adam<Model>) {
+        let x: Tensor2D<5, 3> = tensor([[1.0, -2.0, 0.5]; 5]);
+        let loss = mse_loss(model.forward(x.trace()), Tensor2D::ones());
+        opt.update(model, backward(loss)).expect("");
+    }
+
+    #[test]
+    fn test_save_and_resume() {
+        let _lock = crate::rng::TEST_SEED_LOCK.lock().unwrap();
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+
+        let mut model: Model = Default::default();
+        model.reset_params(&mut StdRng::seed_from_u64(0));
+        let mut opt: Adam<Model> = Default::default();
+        for _ in 0..3 {
+            train_step(&mut model, &mut opt);
+        }
+        let mut state = TrainingState {
+            step: 3,
+            ..Default::default()
+        };
+        state.metadata.insert("epoch".into(), "1".into());
+        state.metadata.insert("run".into(), "test run".into());
+        let rng = RngState {
+            seed: u64::MAX - 1,
+            counter: 5,
+        };
+        set_rng_state(rng);
+        save_checkpoint(file.path(), &model, &opt, &state).expect("");
+        set_rng_state(Default::default());
+
+        let mut loaded_model: Model = Default::default();
+        let mut loaded_opt: Adam<Model> = Default::default();
+        let loaded_state = resume(file.path(), &mut loaded_model, &mut loaded_opt).expect("");
+        assert_eq!(loaded_state, state);
+        // other tests may call default_rng() in between
+        assert_eq!(rng_state().seed, rng.seed);
+        assert!(rng_state().counter >= rng.counter);
+        assert_eq!(loaded_model.0.weight.data(), model.0.weight.data());
+        set_rng_state(Default::default());
+
+        train_step(&mut model, &mut opt);
+        train_step(&mut loaded_model, &mut loaded_opt);
+        assert_eq!(loaded_model.0.weight.data(), model.0.weight.data());
+        assert_eq!(loaded_model.2.bias.data(), model.2.bias.data());
+    }
+
+    #[test]
+    fn test_resume_failure_keeps_model() {
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+        let model: Linear<3, 2> = Default::default();
+        let opt: Adam<Linear<3, 2>> = Default::default();
+        save_checkpoint(file.path(), &model, &opt, &Default::default()).expect("");
+
+        let mut other: Linear<2, 2> = Default::default();
+        let other_0 = other.clone();
+        let mut other_opt: Adam<Linear<2, 2>> = Default::default();
+        assert!(resume(file.path(), &mut other, &mut other_opt).is_err());
+        assert_eq!(other.weight.data(), other_0.weight.data());
+    }
+
+    #[test]
+    fn test_resume_failure_keeps_optimizer() {
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+        let mut model: Linear<3, 2> = Default::default();
+        let mut opt: Adam<Linear<3, 2>> = Default::default();
+        let y = model.forward(Tensor1D::ones().trace());
+        opt.update(&mut model, backward(y.sum())).expect("");
+
+        // a checkpoint where the optimizer state is missing `moment2`
+        let f = std::fs::File::create(file.path()).expect("");
+        let mut zip = ZipWriter::new(f);
+        write_architecture::<Linear<3, 2>, _>(&mut zip);
+        write_scalar(&mut zip, "", "version", &(VERSION as f64)).expect("");
+        model.write("model.", &mut zip).expect("");
+        write_scalar(&mut zip, "optimizer.", "t", &(opt.t as f64)).expect("");
+        write_state(&opt.moment1, &model, "optimizer.moment1.", &mut zip).expect("");
+        for name in ["step", "rng.seed_lo", "rng.seed_hi", "rng.counter"] {
+            write_scalar(&mut zip, "", name, &0.0f64).expect("");
+        }
+        zip.finish().expect("");
+
+        let mut loaded_model: Linear<3, 2> = Default::default();
+        let mut loaded_opt: Adam<Linear<3, 2>> = Default::default();
+        assert!(resume(file.path(), &mut loaded_model, &mut loaded_opt).is_err());
+        assert_eq!(loaded_opt.t, 0);
+        assert!(loaded_opt
+            .moment1
+            .try_ref_gradient(&loaded_model.weight)
+            .is_none());
+    }
+
+    #[test]
+    fn test_resume_newer_version() {
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+        let f = std::fs::File::create(file.path()).expect("");
+        let mut zip = ZipWriter::new(f);
+        write_scalar(&mut zip, "", "version", &2.0f64).expect("");
+        zip.finish().expect("");
+
+        let mut model: Linear<3, 2> = Default::default();
+        let mut opt: Adam<Linear<3, 2>> = Default::default();
+        assert!(matches!(
+            resume(file.path(), &mut model, &mut opt),
+            Err(NpzError::UnsupportedVersion(2))
+        ));
+    }
+}
@@ -0,0 +1,156 @@
+use crate::devices::ForEachElement;
+use crate::gradients::Gradients;
+use crate::nn::{ParamVisitor, VisitParams};
+use crate::prelude::*;
+
+impl Gradients {
+    /// Scales the gradients of all of `model`'s parameters, so that the L2 norm of all of them together
+    /// is at most `max_norm`. Returns the norm from before clipping.
+    ///
+    /// Parameters of `model` without gradients are ignored.
+    ///
+    /// **Pytorch equivalent**: `torch.nn.utils.clip_grad_norm_(model.parameters(), max_norm)`
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx::{prelude::*, gradients::*};
+    /// # type Model = Linear<5, 2>;
+    /// let mut model: Model = Default::default();
+    /// let mut opt: Sgd<Model> = Default::default();
+    /// # let y = model.forward(Tensor1D::zeros().traced());
+    /// # let loss = mse_loss(y, Tensor1D::ones());
+    /// // -- snip loss computation --
+    ///
+    /// let mut gradients: Gradients = backward(loss);
+    /// let norm = gradients.clip_norm(&model, 1.0);
+    /// opt.update(&mut model, gradients);
+    /// ```
+    pub fn clip_norm<M: VisitParams>(&mut self, model: &M, max_norm: f32) -> f32 {
+        let norm = self.global_norm(model);
+        if norm > max_norm {
+            model.visit_params(&mut ScaleGrads {
+                grads: self,
+                scale: max_norm / (norm + 1e-6),
+            });
+        }
+        norm
+    }
+
+    /// The L2 norm of the gradients of all of `model`'s parameters together.
+    pub(crate) fn global_norm<M: VisitParams>(&mut self, model: &M) -> f32 {
+        let mut norm = GradNormSq {
+            grads: self,
+            norm_sq: 0.0,
+        };
+        model.visit_params(&mut norm);
+        norm.norm_sq.sqrt()
+    }
+
+    /// Clamps every element of the gradients of `model`'s parameters into `[-limit, limit]`.
+    ///
+    /// Parameters of `model` without gradients are ignored.
+    ///
+    /// **Pytorch equivalent**: `torch.nn.utils.clip_grad_value_(model.parameters(), limit)`
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx::{prelude::*, gradients::*};
+    /// # type Model = Linear<5, 2>;
+    /// let mut model: Model = Default::default();
+    /// let mut opt: Sgd<Model> = Default::default();
+    /// # let y = model.forward(Tensor1D::zeros().traced());
+    /// # let loss = mse_loss(y, Tensor1D::ones());
+    /// // -- snip loss computation --
+    ///
+    /// let mut gradients: Gradients = backward(loss);
+    /// gradients.clip_value(&model, 0.5);
+    /// opt.update(&mut model, gradients);
+    /// ```
+    pub fn clip_value<M: VisitParams>(&mut self, model: &M, limit: f32) {
+        model.visit_params(&mut ClampGrads { grads: self, limit });
+    }
+}
+
+struct GradNormSq<'a> {
+    grads: &'a mut Gradients,
+    norm_sq: f32,
+}
+
+impl<'a> ParamVisitor for GradNormSq<'a> {
+    fn visit<T: Tensor<Dtype = f32>>(&mut self, _: &str, param: &T) {
+        if let Some(g) = self.grads.try_mut_gradient(param) {
+            T::Device::foreach_m(g, &mut |g| self.norm_sq += g.powi(2));
+        }
+    }
+}
+
+struct ScaleGrads<'a> {
+    grads: &'a mut Gradients,
+    scale: f32,
+}
+
+impl<'a> ParamVisitor for ScaleGrads<'a> {
+    fn visit<T: Tensor<Dtype = f32>>(&mut self, _: &str, param: &T) {
+        if let Some(g) = self.grads.try_mut_gradient(param) {
+            T::Device::foreach_m(g, &mut |g| *g *= self.scale);
+        }
+    }
+}
+
+struct ClampGrads<'a> {
+    grads: &'a mut Gradients,
+    limit: f32,
+}
+
+impl<'a> ParamVisitor for ClampGrads<'a> {
+    fn visit<T: Tensor<Dtype = f32>>(&mut self, _: &str, param: &T) {
+        if let Some(g) = self.grads.try_mut_gradient(param) {
+            T::Device::foreach_m(g, &mut |g| *g = g.clamp(-self.limit, self.limit));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+
+    #[test]
+    fn test_clip_norm() {
+        let model: Linear<2, 1> = Default::default();
+        let mut grads: Gradients = Default::default();
+        *grads.mut_gradient(&model.weight) = [[3.0, 0.0]];
+        *grads.mut_gradient(&model.bias) = [-4.0];
+
+        assert_close(&[grads.clip_norm(&model, 10.0)], &[5.0]);
+        assert_eq!(grads.ref_gradient(&model.weight), &[[3.0, 0.0]]);
+        assert_eq!(grads.ref_gradient(&model.bias), &[-4.0]);
+
+        assert_close(&[grads.clip_norm(&model, 1.0)], &[5.0]);
+        assert_close(grads.ref_gradient(&model.weight), &[[0.6, 0.0]]);
+        assert_close(grads.ref_gradient(&model.bias), &[-0.8]);
+    }
+
+    #[test]
+    fn test_clip_norm_missing_grads() {
+        let model: Linear<2, 2> = Default::default();
+        let mut grads: Gradients = Default::default();
+        *grads.mut_gradient(&model.bias) = [6.0, 8.0];
+        assert_close(&[grads.clip_norm(&model, 5.0)], &[10.0]);
+        assert_close(grads.ref_gradient(&model.bias), &[3.0, 4.0]);
+        assert!(grads.try_mut_gradient(&model.weight).is_none());
+    }
+
+    #[test]
+    fn test_clip_value() {
+        let model: Linear<2, 2> = Default::default();
+        let mut grads: Gradients = Default::default();
+        *grads.mut_gradient(&model.weight) = [[-3.0, 0.5], [0.0, 1.5]];
+        grads.clip_value(&model, 1.0);
+        assert_eq!(
+            grads.ref_gradient(&model.weight),
+            &[[-1.0, 0.5], [0.0, 1.0]]
+        );
+        assert!(grads.try_mut_gradient(&model.bias).is_none());
+    }
+}
@@ -0,0 +1,178 @@
+/// Stops training once a validation metric hasn't improved for `patience` epochs,
+/// and keeps a copy of the model from the best epoch.
+///
+/// Call [EarlyStopping::step()] with the metric after every epoch, which returns `true`
+/// when training should stop. The best model is available with [EarlyStopping::best_model()]
+/// (for example to `.save()` it), or can be copied back with [EarlyStopping::restore_best()].
+///
+/// Copies of a model share the data of their parameters until either is updated, so keeping
+/// the best model only costs memory once the model is trained further.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Linear<5, 2>;
+/// let mut model: Model = Default::default();
+/// let mut early_stopping: EarlyStopping<Model> = EarlyStopping::new(EarlyStoppingConfig {
+///     patience: 3,
+///     ..Default::default()
+/// });
+/// for _ in 0..100 {
+///     // -- snip training --
+/// #   let val_loss = 1.0;
+///     if early_stopping.step(val_loss, &model) {
+///         break;
+///     }
+/// }
+/// early_stopping.restore_best(&mut model);
+/// ```
+#[derive(Debug, Clone)]
+pub struct EarlyStopping<M> {
+    /// Hyperparameter configuration
+    pub cfg: EarlyStoppingConfig,
+
+    best: Option<(f32, M)>,
+    num_bad_epochs: usize,
+}
+
+/// Configuration of hyperparameters for [EarlyStopping].
+#[derive(Debug, Clone, Copy)]
+pub struct EarlyStoppingConfig {
+    /// The number of epochs in a row without improvement before stopping. Defaults to `10`.
+    pub patience: usize,
+
+    /// The amount the metric has to improve by to count as an improvement. Defaults to `0.0`.
+    pub min_delta: f32,
+
+    /// Whether higher values of the metric are better, like for accuracy. Defaults to `false`.
+    pub maximize: bool,
+}
+
+impl Default for EarlyStoppingConfig {
+    fn default() -> Self {
+        Self {
+            patience: 10,
+            min_delta: 0.0,
+            maximize: false,
+        }
+    }
+}
+
+impl<M> Default for EarlyStopping<M> {
+    /// See [EarlyStoppingConfig]
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<M> EarlyStopping<M> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(cfg: EarlyStoppingConfig) -> Self {
+        Self {
+            cfg,
+            best: None,
+            num_bad_epochs: 0,
+        }
+    }
+
+    /// The best metric so far, or `None` before the first [EarlyStopping::step()].
+    pub fn best_metric(&self) -> Option<f32> {
+        self.best.as_ref().map(|(metric, _)| *metric)
+    }
+
+    /// The model from the epoch with the best metric, or `None` before the first [EarlyStopping::step()].
+    pub fn best_model(&self) -> Option<&M> {
+        self.best.as_ref().map(|(_, model)| model)
+    }
+
+    /// The number of epochs in a row without improvement.
+    pub fn num_bad_epochs(&self) -> usize {
+        self.num_bad_epochs
+    }
+
+    fn is_improvement(&self, metric: f32) -> bool {
+        match self.best_metric() {
+            None => true,
+            Some(best) if self.cfg.maximize => metric > best + self.cfg.min_delta,
+            Some(best) => metric < best - self.cfg.min_delta,
+        }
+    }
+}
+
+impl<M: Clone> EarlyStopping<M> {
+    /// Records the `metric` of `model` for an epoch. If it is an improvement, a copy of `model` is kept.
+    /// Returns `true` once there has been no improvement for [EarlyStoppingConfig::patience] epochs in a row.
+    pub fn step(&mut self, metric: f32, model: &M) -> bool {
+        if self.is_improvement(metric) {
+            self.best = Some((metric, model.clone()));
+            self.num_bad_epochs = 0;
+        } else {
+            self.num_bad_epochs += 1;
+        }
+        self.num_bad_epochs >= self.cfg.patience
+    }
+
+    /// Copies the best model into `model`. Returns `false` and leaves `model` unchanged
+    /// before the first [EarlyStopping::step()].
+    pub fn restore_best(&self, model: &mut M) -> bool {
+        match self.best_model() {
+            Some(best) => {
+                *model = best.clone();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_early_stopping_patience() {
+        let mut es: EarlyStopping<Tensor0D> = EarlyStopping::new(EarlyStoppingConfig {
+            patience: 2,
+            min_delta: 0.1,
+            maximize: false,
+        });
+        assert!(!es.step(1.0, &tensor(0.0)));
+        assert!(!es.step(0.5, &tensor(1.0)));
+        assert!(!es.step(0.45, &tensor(2.0)));
+        assert!(es.step(0.6, &tensor(3.0)));
+        assert_eq!(es.num_bad_epochs(), 2);
+        assert_eq!(es.best_metric(), Some(0.5));
+        assert_eq!(es.best_model().unwrap().data(), &1.0);
+    }
+
+    #[test]
+    fn test_early_stopping_maximize() {
+        let mut es: EarlyStopping<Tensor0D> = EarlyStopping::new(EarlyStoppingConfig {
+            patience: 2,
+            min_delta: 0.0,
+            maximize: true,
+        });
+        assert!(!es.step(0.5, &tensor(0.0)));
+        assert!(!es.step(0.4, &tensor(1.0)));
+        assert!(!es.step(0.7, &tensor(2.0)));
+        assert!(!es.step(0.7, &tensor(3.0)));
+        assert!(es.step(0.6, &tensor(4.0)));
+        assert_eq!(es.best_metric(), Some(0.7));
+    }
+
+    #[test]
+    fn test_early_stopping_restore_best() {
+        let mut model: Linear<2, 1> = Default::default();
+        let mut es: EarlyStopping<Linear<2, 1>> = Default::default();
+        assert!(!es.restore_best(&mut model));
+
+        es.step(1.0, &model);
+        let best = model.clone();
+        model.weight = tensor([[1.0, 2.0]]);
+        es.step(2.0, &model);
+
+        assert!(es.restore_best(&mut model));
+        assert_eq!(model.weight.data(), best.weight.data());
+    }
+}
@@ -0,0 +1,103 @@
+use crate::nn::{zip_params_mut, VisitParams};
+
+/// An exponential moving average of the parameters of a model, which often
+/// generalizes better than the model itself.
+///
+/// After each [super::Optimizer::update()], call [ModelEMA::update()] to move every parameter
+/// of [ModelEMA::model] towards the corresponding parameter of the trained model:
+/// `ema = decay * ema + (1 - decay) * param`.
+///
+/// Only parameters visited by [VisitParams] are averaged; for example the running statistics
+/// of [crate::nn::BatchNorm2D] are left as they were when the [ModelEMA] was created.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Linear<5, 2>;
+/// let mut model: Model = Default::default();
+/// let mut ema = ModelEMA::new(&model, 0.999);
+/// let mut opt: Sgd<Model> = Default::default();
+/// for _ in 0..10 {
+///     // -- snip loss computation & opt.update(&mut model, gradients) --
+///     ema.update(&model);
+/// }
+///
+/// // evaluate with the averaged parameters
+/// let y = ema.model.forward(Tensor1D::zeros());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ModelEMA<M> {
+    /// The averaged model.
+    pub model: M,
+
+    /// How much of the average to keep every update.
+    pub decay: f32,
+}
+
+impl<M: VisitParams + Clone> ModelEMA<M> {
+    /// Starts the average at a copy of `model`.
+    pub fn new(model: &M, decay: f32) -> Self {
+        Self {
+            model: model.clone(),
+            decay,
+        }
+    }
+
+    /// Moves the averaged parameters towards the parameters of `model`.
+    pub fn update(&mut self, model: &M) {
+        let decay = self.decay;
+        zip_params_mut(&mut self.model, model, |e, p| {
+            *e = *e * decay + *p * (1.0 - decay)
+        });
+    }
+
+    /// Swaps [ModelEMA::model] with `model`. Call this again to swap back, for example
+    /// after evaluating or saving the averaged model in place of `model`.
+    pub fn swap(&mut self, model: &mut M) {
+        std::mem::swap(&mut self.model, model);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::tests::assert_close;
+
+    #[test]
+    fn test_model_ema() {
+        let mut model: Linear<2, 1> = Linear {
+            weight: tensor([[1.0, -1.0]]),
+            bias: tensor([2.0]),
+        };
+        let mut ema = ModelEMA::new(&model, 0.5);
+
+        model.weight = tensor([[3.0, 1.0]]);
+        model.bias = tensor([0.0]);
+        ema.update(&model);
+        assert_close(ema.model.weight.data(), &[[2.0, 0.0]]);
+        assert_close(ema.model.bias.data(), &[1.0]);
+
+        ema.update(&model);
+        assert_close(ema.model.weight.data(), &[[2.5, 0.5]]);
+        assert_close(ema.model.bias.data(), &[0.5]);
+
+        ema.swap(&mut model);
+        assert_close(model.weight.data(), &[[2.5, 0.5]]);
+        assert_close(ema.model.weight.data(), &[[3.0, 1.0]]);
+    }
+
+    #[test]
+    fn test_model_ema_nested() {
+        type Model = (Linear<2, 2>, ReLU, Residual<LayerNorm1D<2>>);
+        let mut rng = rand::thread_rng();
+        let mut model: Model = Default::default();
+        model.reset_params(&mut rng);
+        let mut ema = ModelEMA::new(&model, 0.0);
+        model.reset_params(&mut rng);
+        model.2 .0.gamma = tensor([2.0, 3.0]);
+        ema.update(&model);
+        assert_eq!(ema.model.0.weight.data(), model.0.weight.data());
+        assert_eq!(ema.model.2 .0.gamma.data(), &[2.0, 3.0]);
+    }
+}
@@ -0,0 +1,230 @@
+use super::{Optimizer, UnusedParamsError};
+use crate::devices::ForEachElement;
+use crate::gradients::{CanUpdateWithGradients, Gradients};
+use crate::nn::{ParamVisitor, VisitParams};
+use crate::prelude::*;
+
+/// Dynamic loss scaling, so that small gradients don't underflow when training with reduced precision.
+///
+/// The loss is multiplied by the current scale before calling [backward()], and [GradScaler::step()]
+/// divides the gradients by the scale again before updating the model with an optimizer.
+/// - If any gradient is `inf` or `nan`, the update is skipped and the scale is multiplied by `backoff_factor`.
+/// - After `growth_interval` updates in a row with finite gradients, the scale is multiplied by `growth_factor`.
+///
+/// **Pytorch equivalent**: `torch.cuda.amp.GradScaler`
+///
+/// # Example Usage
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Linear<5, 2>;
+/// let mut model: Model = Default::default();
+/// let mut opt: Sgd<Model> = Default::default();
+/// let mut scaler: GradScaler = Default::default();
+/// # let y = model.forward(Tensor1D::zeros().traced());
+/// # let loss = mse_loss(y, Tensor1D::ones());
+/// // -- snip loss computation --
+///
+/// let gradients = backward(scaler.scale(loss));
+/// scaler.step(&mut opt, &mut model, gradients);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GradScaler {
+    /// Hyperparameter configuration
+    pub cfg: GradScalerConfig,
+
+    scale: f32,
+    num_finite: usize,
+}
+
+/// Configuration of hyperparameters for [GradScaler].
+#[derive(Debug, Clone, Copy)]
+pub struct GradScalerConfig {
+    /// The starting scale. Defaults to `65536.0`.
+    pub init_scale: f32,
+
+    /// What the scale is multiplied by when it grows. Defaults to `2.0`.
+    pub growth_factor: f32,
+
+    /// What the scale is multiplied by when non-finite gradients are found. Defaults to `0.5`.
+    pub backoff_factor: f32,
+
+    /// The number of updates in a row with finite gradients before the scale grows. Defaults to `2000`.
+    pub growth_interval: usize,
+}
+
+impl Default for GradScalerConfig {
+    fn default() -> Self {
+        Self {
+            init_scale: 65536.0,
+            growth_factor: 2.0,
+            backoff_factor: 0.5,
+            growth_interval: 2000,
+        }
+    }
+}
+
+impl Default for GradScaler {
+    /// See [GradScalerConfig]
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl GradScaler {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(cfg: GradScalerConfig) -> Self {
+        Self {
+            cfg,
+            scale: cfg.init_scale,
+            num_finite: 0,
+        }
+    }
+
+    /// The current scale.
+    pub fn loss_scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Multiplies `loss` by the current scale.
+    pub fn scale<T: Tensor<Dtype = f32>>(&self, loss: T) -> T {
+        mul_scalar(loss, self.scale)
+    }
+
+    /// Divides the gradients of all of `model`'s parameters by the current scale.
+    /// Returns `false` if any of them are `inf` or `nan`.
+    pub fn unscale<M: VisitParams>(&self, gradients: &mut Gradients, model: &M) -> bool {
+        let mut unscale = UnscaleGrads {
+            grads: gradients,
+            inv_scale: 1.0 / self.scale,
+            finite: true,
+        };
+        model.visit_params(&mut unscale);
+        unscale.finite
+    }
+
+    /// Unscales `gradients`, and updates `module` with `opt` if they are all finite. Then adjusts the scale.
+    ///
+    /// If the update is skipped, this returns `Ok(())`.
+    pub fn step<M, O>(
+        &mut self,
+        opt: &mut O,
+        module: &mut M,
+        mut gradients: Gradients,
+    ) -> Result<(), UnusedParamsError>
+    where
+        M: CanUpdateWithGradients + VisitParams,
+        O: Optimizer<M>,
+    {
+        let finite = self.unscale(&mut gradients, module);
+        let result = if finite {
+            opt.update(module, gradients)
+        } else {
+            Ok(())
+        };
+        self.update_scale(finite);
+        result
+    }
+
+    fn update_scale(&mut self, finite: bool) {
+        if !finite {
+            self.scale *= self.cfg.backoff_factor;
+            self.num_finite = 0;
+        } else {
+            self.num_finite += 1;
+            if self.num_finite >= self.cfg.growth_interval {
+                self.scale *= self.cfg.growth_factor;
+                self.num_finite = 0;
+            }
+        }
+    }
+}
+
+struct UnscaleGrads<'a> {
+    grads: &'a mut Gradients,
+    inv_scale: f32,
+    finite: bool,
+}
+
+impl<'a> ParamVisitor for UnscaleGrads<'a> {
+    fn visit<T: Tensor<Dtype = f32>>(&mut self, _: &str, param: &T) {
+        if let Some(g) = self.grads.try_mut_gradient(param) {
+            T::Device::foreach_m(g, &mut |g| {
+                *g *= self.inv_scale;
+                self.finite &= g.is_finite();
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+
+    const CFG: GradScalerConfig = GradScalerConfig {
+        init_scale: 1024.0,
+        growth_factor: 2.0,
+        backoff_factor: 0.5,
+        growth_interval: 2,
+    };
+
+    #[test]
+    fn test_grad_scaler_matches_unscaled() {
+        let mut model = Linear::<2, 1> {
+            weight: tensor([[0.5, -1.0]]),
+            bias: Tensor1D::zeros(),
+        };
+        let mut opt: Sgd<Linear<2, 1>> = Default::default();
+        let mut scaler = GradScaler::new(CFG);
+
+        let x: Tensor1D<2> = tensor([1.0, 2.0]);
+        let loss = model.forward(x.trace()).square().sum();
+        let gradients = backward(scaler.scale(loss));
+        assert_close(gradients.ref_gradient(&model.weight), &[[-3072.0, -6144.0]]);
+
+        scaler.step(&mut opt, &mut model, gradients).expect("");
+        assert_close(model.weight.data(), &[[0.53, -0.94]]);
+        assert_close(model.bias.data(), &[0.03]);
+        assert_eq!(scaler.loss_scale(), 1024.0);
+    }
+
+    #[test]
+    fn test_grad_scaler_skips_non_finite() {
+        let mut model: Linear<2, 1> = Default::default();
+        let model_0 = model.clone();
+        let mut opt: Sgd<Linear<2, 1>> = Default::default();
+        let mut scaler = GradScaler::new(CFG);
+
+        let mut gradients: Gradients = Default::default();
+        *gradients.mut_gradient(&model.weight) = [[1.0, f32::INFINITY]];
+        *gradients.mut_gradient(&model.bias) = [1.0];
+        scaler.step(&mut opt, &mut model, gradients).expect("");
+        assert_eq!(model.weight.data(), model_0.weight.data());
+        assert_eq!(model.bias.data(), model_0.bias.data());
+        assert_eq!(scaler.loss_scale(), 512.0);
+    }
+
+    #[test]
+    fn test_grad_scaler_growth() {
+        let mut model: Linear<2, 1> = Default::default();
+        let mut opt: Sgd<Linear<2, 1>> = Default::default();
+        let mut scaler = GradScaler::new(CFG);
+
+        let mut step = |scaler: &mut GradScaler, g: f32| {
+            let mut gradients: Gradients = Default::default();
+            *gradients.mut_gradient(&model.weight) = [[g, 0.0]];
+            *gradients.mut_gradient(&model.bias) = [0.0];
+            scaler.step(&mut opt, &mut model, gradients).expect("");
+        };
+
+        step(&mut scaler, 1.0);
+        assert_eq!(scaler.loss_scale(), 1024.0);
+        step(&mut scaler, f32::NAN);
+        assert_eq!(scaler.loss_scale(), 512.0);
+        step(&mut scaler, 1.0);
+        assert_eq!(scaler.loss_scale(), 512.0);
+        step(&mut scaler, 1.0);
+        assert_eq!(scaler.loss_scale(), 1024.0);
+    }
+}
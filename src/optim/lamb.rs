@@ -0,0 +1,273 @@
+use super::weight_decay::weight_decay_for;
+use crate::arrays::HasArrayType;
+use crate::devices::ForEachElement;
+use crate::gradients::{CanUpdateWithGradients, GradientProvider, Gradients};
+use crate::prelude::*;
+use crate::unique_id::HasUniqueId;
+use std::{boxed::Box, marker::PhantomData};
+
+/// An implementation of the LAMB optimizer from
+/// [Large Batch Optimization for Deep Learning: Training BERT in 76 minutes](https://arxiv.org/abs/1904.00962)
+///
+/// LAMB computes the same update as [Adam], and then scales the update of each parameter tensor
+/// by the trust ratio `||param|| / ||update||`, so every layer moves by a similar relative amount.
+/// If either norm is `0.0`, the trust ratio is `1.0`.
+///
+/// [WeightDecay::Decoupled] is added to the update before computing the trust ratio, as in the paper.
+///
+/// # Example Usage
+///
+/// Constructing using default:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor0D;
+/// let mut opt: Lamb<Model> = Default::default();
+/// ```
+///
+/// Changing using new
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor0D;
+/// let mut opt: Lamb<Model> = Lamb::new(LambConfig {
+///     lr: 1e-2,
+///     betas: [0.5, 0.25],
+///     eps: 1e-6,
+///     weight_decay: Some(WeightDecay::Decoupled(1e-2)),
+///     weight_decay_1d: true,
+/// });
+/// ```
+///
+/// See module level documentation at [crate::optim] for examples of how to actually use an optimizer.
+#[derive(Debug)]
+pub struct Lamb<M> {
+    /// Hyperparameter configuration
+    pub cfg: LambConfig,
+
+    pub(super) t: i32,
+    gradients: Gradients,
+    pub(super) moment1: Gradients,
+    pub(super) moment2: Gradients,
+
+    marker: PhantomData<*const M>,
+}
+
+/// Configuration of hyperparameters for [Lamb].
+///
+/// Changing all default parameters:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// LambConfig {
+///     lr: 1e-2,
+///     betas: [0.1, 0.2],
+///     eps: 1e-8,
+///     weight_decay: Some(WeightDecay::L2(1e-1)),
+///     weight_decay_1d: true,
+/// };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct LambConfig {
+    /// Learning rate. Defaults to `1e-3`.
+    pub lr: f32,
+
+    /// Betas from Adam paper. Defaults to `[0.9, 0.999]`.
+    pub betas: [f32; 2],
+
+    /// Epsilon for numerical stability. Defaults to `1e-6`.
+    pub eps: f32,
+
+    /// Optional weight decay. Defaults to `None`.
+    pub weight_decay: Option<WeightDecay>,
+
+    /// Whether weight decay is also applied to 1d parameters, like biases and the gains of
+    /// normalization layers. Defaults to `true`.
+    pub weight_decay_1d: bool,
+}
+
+impl Default for LambConfig {
+    fn default() -> Self {
+        Self {
+            lr: 1e-3,
+            betas: [0.9, 0.999],
+            eps: 1e-6,
+            weight_decay: None,
+            weight_decay_1d: true,
+        }
+    }
+}
+
+impl<M> Default for Lamb<M> {
+    /// See [LambConfig]
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<M> Lamb<M> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(cfg: LambConfig) -> Self {
+        Self {
+            cfg,
+            t: 0,
+            gradients: Default::default(),
+            moment1: Default::default(),
+            moment2: Default::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M> GradientProvider for Lamb<M> {
+    fn gradient<P>(&mut self, p: &P) -> Option<Box<P::Array>>
+    where
+        P: HasUniqueId + HasArrayType<Dtype = f32> + HasDevice + HasArrayData,
+    {
+        let mut g_t = self.gradients.remove(p)?;
+        let m_t = self.moment1.mut_gradient(p);
+        let v_t = self.moment2.mut_gradient(p);
+        let weight_decay = weight_decay_for::<P>(self.cfg.weight_decay, self.cfg.weight_decay_1d);
+        if let Some(WeightDecay::L2(wd)) = weight_decay {
+            P::Device::foreach_mr(g_t.as_mut(), p.data(), &mut |g, p_el| {
+                *g += wd * p_el;
+            });
+        }
+        let [b1, b2] = self.cfg.betas;
+        P::Device::foreach_mmm(g_t.as_mut(), m_t, v_t, &mut |g, m, v| {
+            *m = *m * b1 + *g * (1.0 - b1);
+            *v = *v * b2 + g.powi(2) * (1.0 - b2);
+            let m_hat = *m * (1.0 - b1.powi(self.t)).recip();
+            let v_hat = *v * (1.0 - b2.powi(self.t)).recip();
+            *g = m_hat / (v_hat.sqrt() + self.cfg.eps);
+        });
+        if let Some(WeightDecay::Decoupled(wd)) = weight_decay {
+            P::Device::foreach_mr(g_t.as_mut(), p.data(), &mut |g, p_el| {
+                *g += wd * p_el;
+            });
+        }
+
+        let mut p_norm_sq = 0.0;
+        let mut g_norm_sq = 0.0;
+        P::Device::foreach_mr(g_t.as_mut(), p.data(), &mut |g, p_el| {
+            p_norm_sq += p_el.powi(2);
+            g_norm_sq += g.powi(2);
+        });
+        let trust_ratio = if p_norm_sq > 0.0 && g_norm_sq > 0.0 {
+            (p_norm_sq / g_norm_sq).sqrt()
+        } else {
+            1.0
+        };
+        let scale = self.cfg.lr * trust_ratio;
+        P::Device::foreach_m(g_t.as_mut(), &mut |g| *g *= scale);
+        Some(g_t)
+    }
+}
+
+impl<M: CanUpdateWithGradients> Optimizer<M> for Lamb<M> {
+    fn update(&mut self, module: &mut M, gradients: Gradients) -> Result<(), UnusedParamsError> {
+        self.t = self.t.checked_add(1).unwrap();
+        self.gradients = gradients;
+        let mut unused_tensors = Default::default();
+        module.update(self, &mut unused_tensors);
+        unused_tensors.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+    use rand::{prelude::*, SeedableRng};
+
+    #[test]
+    fn test_lamb_params() {
+        let mut opt: Lamb<Tensor1D<5>> = Lamb::new(LambConfig {
+            lr: 1e-2,
+            ..Default::default()
+        });
+        let mut t: Tensor1D<5> = tensor([-0.5, -0.25, 0.1, 0.6, 1.0]);
+        let rate = tensor([1e-4, 1e-3, 1e-2, 1e-1, 1e-0]);
+        #[rustfmt::skip]
+        let expected = [
+            [-0.4999841, -0.24927528, 0.09362243, 0.59203136, 0.99202806],
+            [-0.49996826, -0.24855572, 0.08733656, 0.58411145, 0.9841037],
+            [-0.49995252, -0.2478409, 0.08114936, 0.57623726, 0.9762229],
+            [-0.49993685, -0.24713038, 0.07506833, 0.5684056, 0.96838176],
+            [-0.49992126, -0.2464237, 0.06910155, 0.5606132, 0.96057624],
+        ];
+
+        for e in expected.iter() {
+            let gradients = backward((t.trace() * rate.clone()).square().mean());
+            opt.update(&mut t, gradients).expect("");
+            assert_close(t.data(), e);
+        }
+    }
+
+    #[test]
+    fn test_lamb_decoupled_decay() {
+        let mut opt: Lamb<Tensor1D<5>> = Lamb::new(LambConfig {
+            lr: 1e-2,
+            weight_decay: Some(WeightDecay::Decoupled(1e-1)),
+            ..Default::default()
+        });
+        let mut t: Tensor1D<5> = tensor([-0.5, -0.25, 0.1, 0.6, 1.0]);
+        let rate = tensor([1e-4, 1e-3, 1e-2, 1e-1, 1e-0]);
+        #[rustfmt::skip]
+        let expected = [
+            [-0.49961093, -0.2491327, 0.09393901, 0.5920715, 0.991769],
+            [-0.49922433, -0.24827199, 0.087965325, 0.58419585, 0.98359185],
+            [-0.49883997, -0.24741746, 0.08208511, 0.5763707, 0.97546506],
+            [-0.49845767, -0.24656864, 0.07630493, 0.56859326, 0.967385],
+            [-0.49807718, -0.24572505, 0.070631795, 0.5608609, 0.95934814],
+        ];
+
+        for e in expected.iter() {
+            let gradients = backward((t.trace() * rate.clone()).square().mean());
+            opt.update(&mut t, gradients).expect("");
+            assert_close(t.data(), e);
+        }
+    }
+
+    #[test]
+    fn test_lamb_zero_params_trust_ratio() {
+        let mut opt: Lamb<Tensor1D<2>> = Lamb::new(LambConfig {
+            lr: 1e-1,
+            ..Default::default()
+        });
+        let mut t: Tensor1D<2> = Tensor1D::zeros();
+        let gradients = backward((t.trace() - tensor([1.0, -1.0])).square().mean());
+        opt.update(&mut t, gradients).expect("");
+        assert_close(t.data(), &[0.1, -0.1]);
+    }
+
+    #[test]
+    fn test_lamb_changes_all_params() {
+        type Model = (Linear<5, 16>, ReLU, Linear<16, 10>);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut model: Model = Default::default();
+        model.reset_params(&mut rng);
+        let model_0 = model.clone();
+
+        let x: Tensor2D<16, 5> = Tensor2D::rand(&mut rng);
+        let y: Tensor2D<16, 10> = Tensor2D::rand(&mut rng);
+        let mut opt: Lamb<Model> = Default::default();
+
+        let py = model.forward(x.trace());
+        let loss = (py - y).square().mean();
+        let gradients = backward(loss);
+        opt.update(&mut model, gradients).expect("");
+
+        assert!(model_0.0.weight.data() != model.0.weight.data());
+        assert!(model_0.0.bias.data() != model.0.bias.data());
+        assert!(model_0.2.weight.data() != model.2.weight.data());
+        assert!(model_0.2.bias.data() != model.2.bias.data());
+    }
+
+    #[test]
+    fn test_lamb_unused_params() {
+        type Model = (Linear<5, 16>, Linear<16, 10>);
+        let mut model: Model = Default::default();
+        let mut opt: Lamb<Model> = Default::default();
+        let y = model.1.forward(Tensor2D::<8, 16>::zeros().trace());
+        let g = backward(y.mean());
+        opt.update(&mut model, g).expect_err("");
+    }
+}
@@ -0,0 +1,221 @@
+use super::weight_decay::weight_decay_for;
+use crate::arrays::HasArrayType;
+use crate::devices::ForEachElement;
+use crate::gradients::{CanUpdateWithGradients, GradientProvider, Gradients};
+use crate::prelude::*;
+use crate::unique_id::HasUniqueId;
+use std::{boxed::Box, marker::PhantomData};
+
+/// An implementation of the Lion optimizer from
+/// [Symbolic Discovery of Optimization Algorithms](https://arxiv.org/abs/2302.06675).
+///
+/// Lion updates each parameter by the sign of an interpolation between the momentum and the gradient,
+/// so every element moves by exactly `lr`. It only keeps a single momentum buffer per parameter,
+/// compared to the two buffers of [Adam].
+///
+/// Since the updates are larger than Adam's, Lion usually needs a learning rate 3-10x smaller,
+/// and a weight decay 3-10x larger.
+///
+/// # Example Usage
+///
+/// Constructing using default:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor0D;
+/// let mut opt: Lion<Model> = Default::default();
+/// ```
+///
+/// Constructing using new:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor0D;
+/// let mut opt: Lion<Model> = Lion::new(LionConfig {
+///     lr: 3e-4,
+///     betas: [0.95, 0.98],
+///     weight_decay: Some(WeightDecay::Decoupled(1e-1)),
+///     weight_decay_1d: true,
+/// });
+/// ```
+///
+/// See module level documentation at [crate::optim] for examples of how to actually use an optimizer.
+#[derive(Debug)]
+pub struct Lion<M> {
+    /// Hyperparameter configuration
+    pub cfg: LionConfig,
+
+    pub(super) momentum: Gradients,
+    gradients: Gradients,
+
+    marker: PhantomData<*const M>,
+}
+
+/// Configuration of hyperparameters for [Lion].
+#[derive(Debug, Clone, Copy)]
+pub struct LionConfig {
+    /// Learning rate. Defaults to `1e-4`.
+    pub lr: f32,
+
+    /// The first beta interpolates between the momentum and the gradient for the update,
+    /// and the second beta is the decay of the momentum. Defaults to `[0.9, 0.99]`.
+    pub betas: [f32; 2],
+
+    /// Optional weight decay. Defaults to `None`.
+    pub weight_decay: Option<WeightDecay>,
+
+    /// Whether weight decay is also applied to 1d parameters, like biases and the gains of
+    /// normalization layers. Defaults to `true`.
+    pub weight_decay_1d: bool,
+}
+
+impl Default for LionConfig {
+    fn default() -> Self {
+        Self {
+            lr: 1e-4,
+            betas: [0.9, 0.99],
+            weight_decay: None,
+            weight_decay_1d: true,
+        }
+    }
+}
+
+impl<M> Default for Lion<M> {
+    /// See [LionConfig]
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<M> Lion<M> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(cfg: LionConfig) -> Self {
+        Self {
+            cfg,
+            momentum: Default::default(),
+            gradients: Default::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M> GradientProvider for Lion<M> {
+    fn gradient<P>(&mut self, p: &P) -> Option<Box<P::Array>>
+    where
+        P: HasUniqueId + HasArrayType<Dtype = f32> + HasDevice + HasArrayData,
+    {
+        let mut g_t = self.gradients.remove(p)?;
+
+        let weight_decay = weight_decay_for::<P>(self.cfg.weight_decay, self.cfg.weight_decay_1d);
+        if let Some(WeightDecay::L2(wd)) = weight_decay {
+            P::Device::foreach_mr(g_t.as_mut(), p.data(), &mut |g, p_el| {
+                *g += wd * p_el;
+            });
+        }
+
+        let m_t = self.momentum.mut_gradient(p);
+        let [b1, b2] = self.cfg.betas;
+        P::Device::foreach_mm(g_t.as_mut(), m_t, &mut |g, m| {
+            let c = *m * b1 + *g * (1.0 - b1);
+            *m = *m * b2 + *g * (1.0 - b2);
+            *g = if c == 0.0 {
+                0.0
+            } else {
+                c.signum() * self.cfg.lr
+            };
+        });
+
+        if let Some(WeightDecay::Decoupled(wd)) = weight_decay {
+            P::Device::foreach_mr(g_t.as_mut(), p.data(), &mut |g, p_el| {
+                *g += wd * self.cfg.lr * p_el;
+            });
+        }
+
+        Some(g_t)
+    }
+}
+
+impl<M: CanUpdateWithGradients> Optimizer<M> for Lion<M> {
+    fn update(&mut self, module: &mut M, gradients: Gradients) -> Result<(), UnusedParamsError> {
+        self.gradients = gradients;
+        let mut unused_tensors = Default::default();
+        module.update(self, &mut unused_tensors);
+        unused_tensors.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+
+    fn test_matches_expected(cfg: LionConfig, expected: [[f32; 5]; 5]) {
+        let mut t: Tensor1D<5> = tensor([-0.5, -0.25, 0.1, 0.6, 1.0]);
+        let target: Tensor1D<5> = tensor([0.5; 5]);
+        let mut opt = Lion::new(cfg);
+        for e in expected.iter() {
+            let gradients = backward(mse_loss(t.trace(), target.clone()));
+            opt.update(&mut t, gradients).expect("");
+            assert_close(t.data(), e);
+        }
+    }
+
+    #[test]
+    fn test_lion_default() {
+        #[rustfmt::skip]
+        const EXPECTED: [[f32; 5]; 5] = [
+            [-0.4999, -0.2499, 0.1001, 0.5999, 0.9999],
+            [-0.4998, -0.2498, 0.1002, 0.5998, 0.9998],
+            [-0.4997, -0.2497, 0.1003, 0.5997, 0.9997],
+            [-0.4996, -0.2496, 0.1004, 0.5996, 0.9996],
+            [-0.4995, -0.2495, 0.1005, 0.5995, 0.9995],
+        ];
+        test_matches_expected(Default::default(), EXPECTED);
+    }
+
+    #[test]
+    fn test_lion_l2_weight_decay() {
+        const CFG: LionConfig = LionConfig {
+            lr: 1e-1,
+            betas: [0.9, 0.99],
+            weight_decay: Some(WeightDecay::L2(1.0)),
+            weight_decay_1d: true,
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f32; 5]; 5] = [
+            [-0.4, -0.15, 0.2, 0.5, 0.9],
+            [-0.3, -0.05, 0.1, 0.4, 0.8],
+            [-0.2, 0.05, 0.2, 0.3, 0.7],
+            [-0.1, 0.15, 0.1, 0.2, 0.6],
+            [0.0, 0.25, 0.2, 0.1, 0.5],
+        ];
+        test_matches_expected(CFG, EXPECTED);
+    }
+
+    #[test]
+    fn test_lion_decoupled_weight_decay() {
+        const CFG: LionConfig = LionConfig {
+            lr: 1e-1,
+            betas: [0.5, 0.9],
+            weight_decay: Some(WeightDecay::Decoupled(1.0)),
+            weight_decay_1d: true,
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f32; 5]; 5] = [
+            [-0.35, -0.125, 0.19, 0.44, 0.8],
+            [-0.215, -0.0125, 0.271, 0.496, 0.62],
+            [-0.0935, 0.08875, 0.3439, 0.5464, 0.458],
+            [0.01585, 0.179875, 0.40951, 0.39176, 0.3122],
+            [0.114265, 0.2618875, 0.468559, 0.452584, 0.38098],
+        ];
+        test_matches_expected(CFG, EXPECTED);
+    }
+
+    #[test]
+    fn test_lion_unused_params() {
+        type Model = (Linear<5, 16>, Linear<16, 10>);
+        let mut model: Model = Default::default();
+        let mut opt: Lion<Model> = Default::default();
+        let y = model.1.forward(Tensor2D::<8, 16>::zeros().trace());
+        let g = backward(y.mean());
+        opt.update(&mut model, g).expect_err("");
+    }
+}
@@ -0,0 +1,146 @@
+use super::lr_scheduler::{HasLearningRate, HasMomentum};
+use super::{Optimizer, UnusedParamsError};
+use crate::gradients::{CanUpdateWithGradients, Gradients};
+use crate::nn::{zip_params_mut, VisitParams};
+
+/// Wraps another optimizer `O` with the Lookahead procedure from
+/// [Lookahead Optimizer: k steps forward, 1 step back](https://arxiv.org/abs/1907.08610).
+///
+/// A copy of the parameters, the slow weights, is saved before the first update. The inner optimizer updates
+/// the model as usual, and every `k` updates the slow weights move `alpha` of the way towards the model's parameters,
+/// and then the model's parameters are reset to the slow weights.
+///
+/// # Example Usage
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Linear<5, 2>;
+/// let mut opt: Lookahead<Model, Adam<Model>> = Lookahead::new(Default::default(), LookaheadConfig {
+///     k: 5,
+///     alpha: 0.5,
+/// });
+/// ```
+///
+/// See module level documentation at [crate::optim] for examples of how to actually use an optimizer.
+#[derive(Debug)]
+pub struct Lookahead<M, O> {
+    /// The inner optimizer
+    pub opt: O,
+
+    /// Hyperparameter configuration
+    pub cfg: LookaheadConfig,
+
+    pub(super) step: usize,
+    pub(super) slow: Option<M>,
+}
+
+/// Configuration of hyperparameters for [Lookahead].
+#[derive(Debug, Clone, Copy)]
+pub struct LookaheadConfig {
+    /// The number of updates between syncing with the slow weights. Defaults to `5`.
+    pub k: usize,
+
+    /// How far the slow weights move towards the model's parameters. Defaults to `0.5`.
+    pub alpha: f32,
+}
+
+impl Default for LookaheadConfig {
+    fn default() -> Self {
+        Self { k: 5, alpha: 0.5 }
+    }
+}
+
+impl<M, O: Default> Default for Lookahead<M, O> {
+    /// See [LookaheadConfig]
+    fn default() -> Self {
+        Self::new(Default::default(), Default::default())
+    }
+}
+
+impl<M, O> Lookahead<M, O> {
+    /// Wraps `opt` using hyperparameters from `cfg`.
+    pub fn new(opt: O, cfg: LookaheadConfig) -> Self {
+        Self {
+            opt,
+            cfg,
+            step: 0,
+            slow: None,
+        }
+    }
+}
+
+impl<M, O> Optimizer<M> for Lookahead<M, O>
+where
+    M: CanUpdateWithGradients + VisitParams + Clone,
+    O: Optimizer<M>,
+{
+    fn update(&mut self, module: &mut M, gradients: Gradients) -> Result<(), UnusedParamsError> {
+        let slow = self.slow.get_or_insert_with(|| module.clone());
+        let result = self.opt.update(module, gradients);
+        self.step += 1;
+        if self.step >= self.cfg.k {
+            self.step = 0;
+            let alpha = self.cfg.alpha;
+            zip_params_mut(slow, module, |s, f| *s += alpha * (f - *s));
+            zip_params_mut(module, slow, |f, s| *f = *s);
+        }
+        result
+    }
+}
+
+impl<M, O: HasLearningRate> HasLearningRate for Lookahead<M, O> {
+    fn learning_rate(&self) -> f32 {
+        self.opt.learning_rate()
+    }
+    fn set_learning_rate(&mut self, lr: f32) {
+        self.opt.set_learning_rate(lr);
+    }
+}
+
+impl<M, O: HasMomentum> HasMomentum for Lookahead<M, O> {
+    fn momentum(&self) -> Option<f32> {
+        self.opt.momentum()
+    }
+    fn set_momentum(&mut self, momentum: f32) {
+        self.opt.set_momentum(momentum);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::tests::assert_close;
+
+    #[test]
+    fn test_lookahead_sgd() {
+        type Model = Linear<1, 1>;
+        let mut model: Model = Linear {
+            weight: tensor([[0.0]]),
+            bias: tensor([0.0]),
+        };
+        let sgd = Sgd::new(SgdConfig {
+            lr: 1.0,
+            ..Default::default()
+        });
+        let mut opt: Lookahead<Model, Sgd<Model>> =
+            Lookahead::new(sgd, LookaheadConfig { k: 2, alpha: 0.5 });
+
+        // the gradient of the bias is always -1, so each sgd step adds 1 to the bias
+        let expected = [1.0, 1.0, 2.0, 2.0, 3.0];
+        for e in expected {
+            let y = model.forward(tensor([1.0]).traced());
+            let gradients = backward((-y).sum());
+            opt.update(&mut model, gradients).expect("");
+            assert_close(model.bias.data(), &[e]);
+        }
+    }
+
+    #[test]
+    fn test_lookahead_lr() {
+        let mut opt: Lookahead<Linear<1, 1>, Adam<Linear<1, 1>>> = Default::default();
+        opt.set_learning_rate(0.5);
+        assert_eq!(opt.opt.cfg.lr, 0.5);
+        assert_eq!(opt.learning_rate(), 0.5);
+    }
+}
@@ -0,0 +1,140 @@
+use super::LrSchedule;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// Anneals the learning rate from `max_lr` to `min_lr` along half of a cosine wave over `period` steps.
+/// See [SGDR: Stochastic Gradient Descent with Warm Restarts](https://arxiv.org/abs/1608.03983).
+///
+/// After `period` steps the learning rate stays at `min_lr`, unless `warm_restarts` is set.
+///
+/// [LrSchedule::lr()] panics if `period` is `0`, or if `warm_restarts` is `Some(0)`.
+///
+/// **Pytorch equivalent**: `CosineAnnealingLR` & `CosineAnnealingWarmRestarts`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::optim::lr_scheduler::*;
+/// let sched = CosineAnnealing {
+///     max_lr: 1.0,
+///     min_lr: 0.0,
+///     period: 10,
+///     warm_restarts: Some(2),
+/// };
+/// assert_eq!(sched.lr(0), 1.0);
+/// assert!(sched.lr(9) < 0.1);
+/// assert_eq!(sched.lr(10), 1.0); // restarted with a period of 20
+/// assert_eq!(sched.lr(30), 1.0); // restarted with a period of 40
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CosineAnnealing {
+    /// The learning rate at the start of every period.
+    pub max_lr: f32,
+
+    /// The learning rate at the end of every period.
+    pub min_lr: f32,
+
+    /// The number of steps in the first period. Must be greater than `0`.
+    pub period: usize,
+
+    /// If `Some(mult)`, restarts at `max_lr` at the end of every period,
+    /// and multiplies the length of the next period by `mult`, which must be at least `1`.
+    pub warm_restarts: Option<usize>,
+}
+
+impl CosineAnnealing {
+    /// Returns the step within the current period, and the length of the current period.
+    fn position(&self, step: usize) -> (usize, usize) {
+        assert!(
+            self.period > 0,
+            "CosineAnnealing period must be greater than 0"
+        );
+        assert!(
+            self.warm_restarts != Some(0),
+            "CosineAnnealing warm_restarts must be at least 1"
+        );
+        match self.warm_restarts {
+            None => (step.min(self.period), self.period),
+            Some(1) => (step % self.period, self.period),
+            Some(mult) => {
+                let mut step = step;
+                let mut period = self.period;
+                while step >= period {
+                    step -= period;
+                    period *= mult;
+                }
+                (step, period)
+            }
+        }
+    }
+}
+
+impl LrSchedule for CosineAnnealing {
+    fn lr(&self, step: usize) -> f32 {
+        let (step, period) = self.position(step);
+        let frac = step as f32 / period as f32;
+        let cos = (1.0 + (std::f32::consts::PI * frac).cos()) * 0.5;
+        self.min_lr + (self.max_lr - self.min_lr) * cos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+    use std::panic::catch_unwind;
+
+    #[test]
+    fn test_cosine_annealing() {
+        let sched = CosineAnnealing {
+            max_lr: 1e-1,
+            min_lr: 1e-3,
+            period: 4,
+            warm_restarts: None,
+        };
+        let lrs = [0, 1, 2, 3, 4, 5, 100].map(|i| sched.lr(i));
+        assert_close(
+            &lrs,
+            &[0.1, 0.08550253, 0.0505, 0.015497474, 1e-3, 1e-3, 1e-3],
+        );
+    }
+
+    #[test]
+    fn test_cosine_annealing_warm_restarts() {
+        let sched = CosineAnnealing {
+            max_lr: 1.0,
+            min_lr: 0.0,
+            period: 2,
+            warm_restarts: Some(1),
+        };
+        let lrs = [0, 1, 2, 3, 4].map(|i| sched.lr(i));
+        assert_close(&lrs, &[1.0, 0.5, 1.0, 0.5, 1.0]);
+
+        let sched = CosineAnnealing {
+            warm_restarts: Some(2),
+            ..sched
+        };
+        let lrs = [0, 1, 2, 3, 4, 5, 6].map(|i| sched.lr(i));
+        assert_close(&lrs, &[1.0, 0.5, 1.0, 0.8535534, 0.5, 0.14644662, 1.0]);
+    }
+
+    #[test]
+    fn test_cosine_annealing_invalid() {
+        let sched = CosineAnnealing {
+            max_lr: 1.0,
+            min_lr: 0.0,
+            period: 2,
+            warm_restarts: Some(0),
+        };
+        assert!(catch_unwind(|| sched.lr(5)).is_err());
+
+        for warm_restarts in [None, Some(1), Some(2)] {
+            let sched = CosineAnnealing {
+                period: 0,
+                warm_restarts,
+                ..sched
+            };
+            assert!(catch_unwind(|| sched.lr(0)).is_err());
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use super::LrSchedule;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// Multiplies the learning rate by `gamma` every `step_size` steps, starting from `lr`.
+///
+/// **Pytorch equivalent**: `StepLR`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::optim::lr_scheduler::*;
+/// let sched = StepLR {
+///     lr: 1.0,
+///     step_size: 2,
+///     gamma: 0.5,
+/// };
+/// let lrs: Vec<f32> = (0..6).map(|i| sched.lr(i)).collect();
+/// assert_eq!(lrs, [1.0, 1.0, 0.5, 0.5, 0.25, 0.25]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StepLR {
+    /// The starting learning rate.
+    pub lr: f32,
+
+    /// The number of steps between each decay.
+    pub step_size: usize,
+
+    /// The learning rate is multiplied by this every `step_size` steps.
+    pub gamma: f32,
+}
+
+impl LrSchedule for StepLR {
+    fn lr(&self, step: usize) -> f32 {
+        self.lr * self.gamma.powi((step / self.step_size) as i32)
+    }
+}
+
+/// Multiplies the learning rate by `gamma` every step, starting from `lr`.
+///
+/// **Pytorch equivalent**: `ExponentialLR`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::optim::lr_scheduler::*;
+/// let sched = ExponentialLR {
+///     lr: 1.0,
+///     gamma: 0.5,
+/// };
+/// let lrs: Vec<f32> = (0..4).map(|i| sched.lr(i)).collect();
+/// assert_eq!(lrs, [1.0, 0.5, 0.25, 0.125]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExponentialLR {
+    /// The starting learning rate.
+    pub lr: f32,
+
+    /// The learning rate is multiplied by this every step.
+    pub gamma: f32,
+}
+
+impl LrSchedule for ExponentialLR {
+    fn lr(&self, step: usize) -> f32 {
+        self.lr * self.gamma.powi(step as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+
+    #[test]
+    fn test_step_lr() {
+        let sched = StepLR {
+            lr: 1e-1,
+            step_size: 3,
+            gamma: 0.1,
+        };
+        let lrs = [0, 2, 3, 5, 6, 9].map(|i| sched.lr(i));
+        assert_close(&lrs, &[1e-1, 1e-1, 1e-2, 1e-2, 1e-3, 1e-4]);
+    }
+
+    #[test]
+    fn test_exponential_lr() {
+        let sched = ExponentialLR {
+            lr: 1e-1,
+            gamma: 0.9,
+        };
+        let lrs = [0, 1, 2, 10].map(|i| sched.lr(i));
+        assert_close(&lrs, &[1e-1, 0.09, 0.081, 0.034867844]);
+    }
+}
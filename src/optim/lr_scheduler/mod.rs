@@ -0,0 +1,236 @@
+//! Learning rate schedules, and an [LrScheduler] that applies them to an optimizer.
+//!
+//! A schedule is anything that implements [LrSchedule], which gives the learning rate
+//! as a function of the number of steps taken:
+//! - [CosineAnnealing]
+//! - [StepLR] & [ExponentialLR]
+//! - [LinearWarmup], which warms up into another schedule
+//! - [OneCycle], which also schedules momentum with [MomentumSchedule]
+//! - [f32], a constant learning rate
+//!
+//! An [LrScheduler] keeps track of the number of steps, and sets the learning rate of any optimizer that
+//! implements [HasLearningRate] through the [Scheduler] trait. Whether a step is a batch or an epoch is up to you.
+//!
+//! ```rust
+//! # use dfdx::prelude::*;
+//! use dfdx::optim::lr_scheduler::*;
+//! # type Model = Linear<5, 2>;
+//! let mut opt: Sgd<Model> = Default::default();
+//! let mut sched = LrScheduler::new(CosineAnnealing {
+//!     max_lr: 1e-2,
+//!     min_lr: 1e-4,
+//!     period: 100,
+//!     warm_restarts: None,
+//! });
+//! for _ in 0..100 {
+//!     sched.step(&mut opt);
+//!     // -- snip loss computation & opt.update() --
+//! }
+//! ```
+
+mod cosine;
+mod decay;
+mod one_cycle;
+mod warmup;
+
+pub use cosine::*;
+pub use decay::*;
+pub use one_cycle::*;
+pub use warmup::*;
+
+use super::{Adadelta, Adagrad, Adam, Lamb, Lion, Momentum, RAdam, RMSprop, Sgd};
+
+/// An optimizer with a learning rate that can be changed during training.
+pub trait HasLearningRate {
+    /// The current learning rate.
+    fn learning_rate(&self) -> f32;
+
+    /// Sets the learning rate used by the next update.
+    fn set_learning_rate(&mut self, lr: f32);
+}
+
+macro_rules! cfg_lr_impl {
+    ($Opt:ident) => {
+        impl<M> HasLearningRate for $Opt<M> {
+            fn learning_rate(&self) -> f32 {
+                self.cfg.lr
+            }
+            fn set_learning_rate(&mut self, lr: f32) {
+                self.cfg.lr = lr;
+            }
+        }
+    };
+}
+
+cfg_lr_impl!(Sgd);
+cfg_lr_impl!(Adam);
+cfg_lr_impl!(RAdam);
+cfg_lr_impl!(Lamb);
+cfg_lr_impl!(Adagrad);
+cfg_lr_impl!(Adadelta);
+cfg_lr_impl!(Lion);
+cfg_lr_impl!(RMSprop);
+
+/// An optimizer with a momentum that can be changed during training.
+pub trait HasMomentum {
+    /// The current momentum, or `None` if the optimizer isn't using momentum.
+    fn momentum(&self) -> Option<f32>;
+
+    /// Sets the momentum used by the next update. Does nothing if the optimizer isn't using momentum.
+    fn set_momentum(&mut self, momentum: f32);
+}
+
+impl<M> HasMomentum for Sgd<M> {
+    fn momentum(&self) -> Option<f32> {
+        match self.cfg.momentum? {
+            Momentum::Classic(u) | Momentum::Nesterov(u) => Some(u),
+        }
+    }
+    fn set_momentum(&mut self, momentum: f32) {
+        self.cfg.momentum = match self.cfg.momentum {
+            Some(Momentum::Classic(_)) => Some(Momentum::Classic(momentum)),
+            Some(Momentum::Nesterov(_)) => Some(Momentum::Nesterov(momentum)),
+            None => None,
+        };
+    }
+}
+
+impl<M> HasMomentum for RMSprop<M> {
+    fn momentum(&self) -> Option<f32> {
+        self.cfg.momentum
+    }
+    fn set_momentum(&mut self, momentum: f32) {
+        if let Some(u) = self.cfg.momentum.as_mut() {
+            *u = momentum;
+        }
+    }
+}
+
+/// For optimizers whose momentum is the first beta.
+macro_rules! beta_momentum_impl {
+    ($Opt:ident) => {
+        impl<M> HasMomentum for $Opt<M> {
+            fn momentum(&self) -> Option<f32> {
+                Some(self.cfg.betas[0])
+            }
+            fn set_momentum(&mut self, momentum: f32) {
+                self.cfg.betas[0] = momentum;
+            }
+        }
+    };
+}
+
+beta_momentum_impl!(Adam);
+beta_momentum_impl!(RAdam);
+beta_momentum_impl!(Lamb);
+beta_momentum_impl!(Lion);
+
+/// A learning rate as a function of the number of steps taken. See [LrScheduler].
+pub trait LrSchedule {
+    /// The learning rate to use for step `step`, starting at `0`.
+    fn lr(&self, step: usize) -> f32;
+}
+
+/// A momentum as a function of the number of steps taken, for schedules that also change the momentum.
+/// See [LrScheduler::step_with_momentum()].
+pub trait MomentumSchedule {
+    /// The momentum to use for step `step`, starting at `0`, or `None` to leave the momentum unchanged.
+    fn momentum(&self, step: usize) -> Option<f32>;
+}
+
+impl LrSchedule for f32 {
+    fn lr(&self, _: usize) -> f32 {
+        *self
+    }
+}
+
+/// Something that changes the hyperparameters of an optimizer `O` as training progresses.
+///
+/// Example of using any scheduler:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::optim::lr_scheduler::*;
+/// fn train<O, S: Scheduler<O>>(opt: &mut O, sched: &mut S) {
+///     for _ in 0..10 {
+///         sched.step(opt);
+///         // -- snip loss computation & opt.update() --
+///     }
+/// }
+///
+/// let mut opt: Adam<Linear<5, 2>> = Default::default();
+/// train(&mut opt, &mut LrScheduler::new(StepLR { lr: 1e-3, step_size: 5, gamma: 0.1 }));
+/// train(&mut opt, &mut LrScheduler::new(ExponentialLR { lr: 1e-3, gamma: 0.9 }));
+/// ```
+pub trait Scheduler<O> {
+    /// Updates the hyperparameters of `opt` for the current step, and then moves to the next step.
+    /// Call this before the [super::Optimizer::update()] it should apply to.
+    fn step(&mut self, opt: &mut O);
+}
+
+/// Sets the learning rate of an optimizer from an [LrSchedule] every time [Scheduler::step()] is called.
+#[derive(Debug, Clone, Copy)]
+pub struct LrScheduler<S> {
+    /// The schedule to follow
+    pub schedule: S,
+
+    step: usize,
+}
+
+impl<S> LrScheduler<S> {
+    /// Starts following `schedule` from step `0`.
+    pub fn new(schedule: S) -> Self {
+        Self { schedule, step: 0 }
+    }
+
+    /// The number of times [Scheduler::step()] has been called.
+    pub fn num_steps(&self) -> usize {
+        self.step
+    }
+
+    /// Continues following the schedule from step `step`, e.g. when resuming training.
+    pub fn set_num_steps(&mut self, step: usize) {
+        self.step = step;
+    }
+}
+
+impl<S: LrSchedule, O: HasLearningRate> Scheduler<O> for LrScheduler<S> {
+    fn step(&mut self, opt: &mut O) {
+        opt.set_learning_rate(self.schedule.lr(self.step));
+        self.step += 1;
+    }
+}
+
+impl<S: LrSchedule + MomentumSchedule> LrScheduler<S> {
+    /// Same as [Scheduler::step()], but also sets the momentum of `opt`.
+    pub fn step_with_momentum<O: HasLearningRate + HasMomentum>(&mut self, opt: &mut O) {
+        if let Some(momentum) = self.schedule.momentum(self.step) {
+            opt.set_momentum(momentum);
+        }
+        Scheduler::step(self, opt);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::Tensor0D;
+
+    struct Ramp;
+    impl LrSchedule for Ramp {
+        fn lr(&self, step: usize) -> f32 {
+            step as f32
+        }
+    }
+
+    #[test]
+    fn test_scheduler_sets_lr() {
+        let mut opt: Adam<Tensor0D> = Default::default();
+        let mut sched = LrScheduler::new(Ramp);
+        for i in 0..3 {
+            assert_eq!(sched.num_steps(), i);
+            sched.step(&mut opt);
+            assert_eq!(opt.learning_rate(), i as f32);
+            assert_eq!(opt.cfg.lr, i as f32);
+        }
+    }
+}
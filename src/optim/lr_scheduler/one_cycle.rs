@@ -0,0 +1,158 @@
+use super::{LrSchedule, MomentumSchedule};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// The 1cycle policy from
+/// [Super-Convergence: Very Fast Training of Neural Networks Using Large Learning Rates](https://arxiv.org/abs/1708.07120).
+///
+/// Over the first `pct_start` of `total_steps`, the learning rate goes up from `max_lr / div_factor`
+/// to `max_lr`, and then it goes down to `max_lr / (div_factor * final_div_factor)`. Both phases follow
+/// half of a cosine wave.
+///
+/// If `momentum` is `Some([min, max])`, the momentum cycles in the opposite direction, from `max` to `min`
+/// and back to `max`. Use [super::LrScheduler::step_with_momentum()] to apply it.
+///
+/// **Pytorch equivalent**: `OneCycleLR` with `anneal_strategy="cos"`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::optim::lr_scheduler::*;
+/// # type Model = Linear<5, 2>;
+/// let mut opt: Sgd<Model> = Sgd::new(SgdConfig {
+///     momentum: Some(Momentum::Nesterov(0.9)),
+///     ..Default::default()
+/// });
+/// let mut sched = LrScheduler::new(OneCycle::new(1e-1, 100));
+/// for _ in 0..100 {
+///     sched.step_with_momentum(&mut opt);
+///     // -- snip loss computation & opt.update() --
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OneCycle {
+    /// The highest learning rate, reached after `pct_start * total_steps` steps.
+    pub max_lr: f32,
+
+    /// The number of steps in the cycle. The learning rate stays at its minimum afterwards.
+    pub total_steps: usize,
+
+    /// The fraction of the cycle spent increasing the learning rate. Defaults to `0.3`.
+    pub pct_start: f32,
+
+    /// The starting learning rate is `max_lr / div_factor`. Defaults to `25.0`.
+    pub div_factor: f32,
+
+    /// The final learning rate is the starting learning rate divided by `final_div_factor`. Defaults to `1e4`.
+    pub final_div_factor: f32,
+
+    /// The `[min, max]` momentum to cycle between, in the opposite direction to the learning rate.
+    /// Defaults to `Some([0.85, 0.95])`.
+    pub momentum: Option<[f32; 2]>,
+}
+
+impl OneCycle {
+    /// Creates a cycle of `total_steps` steps that peaks at `max_lr`, with the rest of the
+    /// hyperparameters set to their defaults.
+    pub fn new(max_lr: f32, total_steps: usize) -> Self {
+        Self {
+            max_lr,
+            total_steps,
+            pct_start: 0.3,
+            div_factor: 25.0,
+            final_div_factor: 1e4,
+            momentum: Some([0.85, 0.95]),
+        }
+    }
+
+    /// Returns which phase `step` is in (`true` for the first), and how far along that phase it is.
+    fn phase(&self, step: usize) -> (bool, f32) {
+        let step = step as f32;
+        let up_end = self.pct_start * self.total_steps as f32 - 1.0;
+        let down_end = self.total_steps as f32 - 1.0;
+        if step <= up_end {
+            (true, step / up_end)
+        } else {
+            (false, ((step - up_end) / (down_end - up_end)).min(1.0))
+        }
+    }
+}
+
+/// Goes from `start` to `end` along half of a cosine wave as `frac` goes from `0.0` to `1.0`.
+fn cos_anneal(start: f32, end: f32, frac: f32) -> f32 {
+    end + (start - end) * 0.5 * (1.0 + (std::f32::consts::PI * frac).cos())
+}
+
+impl LrSchedule for OneCycle {
+    fn lr(&self, step: usize) -> f32 {
+        let initial_lr = self.max_lr / self.div_factor;
+        let min_lr = initial_lr / self.final_div_factor;
+        match self.phase(step) {
+            (true, frac) => cos_anneal(initial_lr, self.max_lr, frac),
+            (false, frac) => cos_anneal(self.max_lr, min_lr, frac),
+        }
+    }
+}
+
+impl MomentumSchedule for OneCycle {
+    fn momentum(&self, step: usize) -> Option<f32> {
+        let [min, max] = self.momentum?;
+        Some(match self.phase(step) {
+            (true, frac) => cos_anneal(max, min, frac),
+            (false, frac) => cos_anneal(min, max, frac),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optim::lr_scheduler::{HasLearningRate, HasMomentum, LrScheduler};
+    use crate::prelude::*;
+    use crate::tests::assert_close;
+
+    #[test]
+    fn test_one_cycle_lr() {
+        let sched = OneCycle {
+            div_factor: 10.0,
+            final_div_factor: 100.0,
+            ..OneCycle::new(1.0, 10)
+        };
+        let lrs = [0, 1, 2, 5, 9, 10].map(|i| sched.lr(i));
+        assert_close(&lrs, &[0.1, 0.55, 1.0, 0.6116492, 1e-3, 1e-3]);
+    }
+
+    #[test]
+    fn test_one_cycle_momentum() {
+        let sched = OneCycle::new(1.0, 10);
+        let momentums = [0, 2, 9].map(|i| sched.momentum(i).unwrap());
+        assert_close(&momentums, &[0.95, 0.85, 0.95]);
+
+        let sched = OneCycle {
+            momentum: None,
+            ..sched
+        };
+        assert_eq!(sched.momentum(0), None);
+    }
+
+    #[test]
+    fn test_one_cycle_sets_sgd_and_adam() {
+        let mut sgd: Sgd<Tensor0D> = Sgd::new(SgdConfig {
+            momentum: Some(Momentum::Classic(0.5)),
+            ..Default::default()
+        });
+        let mut adam: Adam<Tensor0D> = Default::default();
+        let mut sched = LrScheduler::new(OneCycle::new(1.0, 10));
+        sched.step_with_momentum(&mut sgd);
+        assert_close(&[sgd.learning_rate()], &[0.04]);
+        assert_eq!(sgd.cfg.momentum, Some(Momentum::Classic(0.95)));
+
+        let mut sched = LrScheduler::new(OneCycle::new(1.0, 10));
+        for _ in 0..3 {
+            sched.step_with_momentum(&mut adam);
+        }
+        assert_close(&[adam.learning_rate()], &[1.0]);
+        assert_close(&adam.cfg.betas, &[0.85, 0.999]);
+        assert_eq!(adam.momentum(), Some(adam.cfg.betas[0]));
+    }
+}
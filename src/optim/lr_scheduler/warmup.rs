@@ -0,0 +1,83 @@
+use super::LrSchedule;
+
+/// Linearly increases the learning rate from `lr / warmup_steps` up to the first learning rate of
+/// `schedule` over the first `warmup_steps` steps, and then follows `schedule` starting from its step `0`.
+///
+/// Use a `f32` as `schedule` to warm up to a constant learning rate.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::optim::lr_scheduler::*;
+/// let sched = LinearWarmup {
+///     warmup_steps: 4,
+///     schedule: 1.0,
+/// };
+/// let lrs: Vec<f32> = (0..6).map(|i| sched.lr(i)).collect();
+/// assert_eq!(lrs, [0.25, 0.5, 0.75, 1.0, 1.0, 1.0]);
+/// ```
+///
+/// Warming up before [super::CosineAnnealing]:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::optim::lr_scheduler::*;
+/// let sched = LinearWarmup {
+///     warmup_steps: 1000,
+///     schedule: CosineAnnealing {
+///         max_lr: 1e-3,
+///         min_lr: 1e-5,
+///         period: 10_000,
+///         warm_restarts: None,
+///     },
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearWarmup<S> {
+    /// The number of steps to warm up for.
+    pub warmup_steps: usize,
+
+    /// The schedule to follow after warming up.
+    pub schedule: S,
+}
+
+impl<S: LrSchedule> LrSchedule for LinearWarmup<S> {
+    fn lr(&self, step: usize) -> f32 {
+        if step < self.warmup_steps {
+            self.schedule.lr(0) * (step + 1) as f32 / self.warmup_steps as f32
+        } else {
+            self.schedule.lr(step - self.warmup_steps)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optim::lr_scheduler::CosineAnnealing;
+    use crate::tests::assert_close;
+
+    #[test]
+    fn test_warmup_then_cosine() {
+        let sched = LinearWarmup {
+            warmup_steps: 2,
+            schedule: CosineAnnealing {
+                max_lr: 1.0,
+                min_lr: 0.0,
+                period: 2,
+                warm_restarts: None,
+            },
+        };
+        let lrs = [0, 1, 2, 3, 4].map(|i| sched.lr(i));
+        assert_close(&lrs, &[0.5, 1.0, 1.0, 0.5, 0.0]);
+    }
+
+    #[test]
+    fn test_no_warmup() {
+        let sched = LinearWarmup {
+            warmup_steps: 0,
+            schedule: 1e-3,
+        };
+        assert_eq!(sched.lr(0), 1e-3);
+        assert_eq!(sched.lr(10), 1e-3);
+    }
+}
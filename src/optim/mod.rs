@@ -1,4 +1,4 @@
-//! Optimizers such as [Sgd], [Adam], and [RMSprop] that can optimize neural networks.
+//! Optimizers such as [Sgd], [Adam], [RAdam], [Lamb], [Adagrad], [Adadelta], [Lion], and [RMSprop] that can optimize neural networks.
 //!
 //! # Initializing
 //!
@@ -6,8 +6,19 @@
 //! all the relevant parameters through the corresponding config object:
 //! - [Sgd::new()] with [SgdConfig]
 //! - [Adam::new()] with [AdamConfig]
+//! - [RAdam::new()] with [RAdamConfig]
+//! - [Lamb::new()] with [LambConfig]
+//! - [Adagrad::new()] with [AdagradConfig]
+//! - [Adadelta::new()] with [AdadeltaConfig]
+//! - [Lion::new()] with [LionConfig]
 //! - [RMSprop::new()] with [RMSpropConfig]
 //!
+//! AdamW is available as [Adam] with [WeightDecay::Decoupled]. Every config has a `weight_decay_1d`
+//! field, which can be set to `false` to skip weight decay for biases and normalization layers.
+//!
+//! [Lookahead] and [Sam] wrap any of the above, with [Lookahead::new()] & [LookaheadConfig]
+//! and [Sam::new()] & [SamConfig].
+//!
 //! # Updating network parameters
 //!
 //! This is done via [Optimizer::update()], where you pass in a mutable [crate::nn::Module], and
@@ -25,15 +36,73 @@
 //! let gradients: Gradients = backward(loss);
 //! opt.update(&mut model, gradients);
 //! ```
+//!
+//! # Gradient clipping
+//!
+//! Call [crate::gradients::Gradients::clip_norm()] or [crate::gradients::Gradients::clip_value()]
+//! on the gradients before passing them to [Optimizer::update()].
+//!
+//! # Loss scaling
+//!
+//! [GradScaler] scales the loss before [crate::tensor_ops::backward()], and unscales the gradients in
+//! [GradScaler::step()], skipping updates with `inf` or `nan` gradients.
+//!
+//! # Learning rate schedules
+//!
+//! See [lr_scheduler] for ways to change the learning rate of an optimizer during training.
+//!
+//! # Saving and loading
+//!
+//! With the `numpy` feature, the internal state of an optimizer (like the moments of [Adam])
+//! can be saved alongside the model with [SaveOptimizerToNpz], and loaded with [LoadOptimizerFromNpz]
+//! to resume training.
+//!
+//! [save_checkpoint()] saves the model, the optimizer, the step count, the rng state, and any other
+//! metadata to a single file, and [resume()] loads all of it back.
+//!
+//! # Early stopping
+//!
+//! [EarlyStopping] tracks a validation metric across epochs, decides when to stop training,
+//! and keeps the model from the best epoch.
 
+mod adadelta;
+mod adagrad;
 mod adam;
+#[cfg(feature = "numpy")]
+mod checkpoint;
+mod clip_grad;
+mod early_stopping;
+mod ema;
+mod grad_scaler;
+mod lamb;
+mod lion;
+mod lookahead;
+pub mod lr_scheduler;
+#[cfg(feature = "numpy")]
+mod npz;
 mod optimizer;
+mod radam;
 mod rmsprop;
+mod sam;
 mod sgd;
 mod weight_decay;
 
+pub use adadelta::*;
+pub use adagrad::*;
 pub use adam::*;
+#[cfg(feature = "numpy")]
+pub use checkpoint::*;
+pub use early_stopping::*;
+pub use ema::*;
+pub use grad_scaler::*;
+pub use lamb::*;
+pub use lion::*;
+pub use lookahead::*;
+#[cfg(feature = "numpy")]
+pub use npz::{LoadOptimizerFromNpz, SaveOptimizerToNpz};
 pub use optimizer::*;
+pub use radam::*;
 pub use rmsprop::*;
+pub use sam::*;
 pub use sgd::*;
 pub use weight_decay::*;
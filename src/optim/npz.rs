@@ -0,0 +1,333 @@
+use crate::devices::FillElements;
+use crate::gradients::Gradients;
+use crate::nn::{LoadFromNpz, NpzError, ParamVisitor, ParamVisitorMut, SaveToNpz, VisitParams};
+use crate::numpy::{NumpyDtype, NumpyShape, ReadNumbers, WriteNumbers};
+use crate::prelude::*;
+use std::{
+    format,
+    io::{BufReader, BufWriter, Read, Seek, Write},
+    path::Path,
+};
+use zip::{result::ZipResult, ZipArchive, ZipWriter};
+
+/// Something that can save its internal state for the parameters of a model `M` to a `.npz` file,
+/// so training can be resumed with [LoadOptimizerFromNpz]. This doesn't include the hyperparameters.
+///
+/// Each piece of per parameter state is saved like the model itself would be, with a prefix.
+/// For example the first moment of [Adam] for `0.weight` is saved as `moment1.0.weight.npy`.
+///
+/// All optimizers in optim implement SaveOptimizerToNpz.
+pub trait SaveOptimizerToNpz<M> {
+    /// Save the state of `self` for the parameters of `model` into the `.npz` file located at `path`.
+    ///
+    /// Example:
+    /// ```ignore
+    /// # use dfdx::prelude::*;
+    /// let model: Linear<5, 2> = Default::default();
+    /// let opt: Adam<Linear<5, 2>> = Default::default();
+    /// model.save("model.npz")?;
+    /// opt.save(&model, "opt.npz")?;
+    /// ```
+    fn save<P: AsRef<Path>>(&self, model: &M, path: P) -> ZipResult<()> {
+        let f = std::fs::File::create(path)?;
+        let f = BufWriter::new(f);
+        let mut zip = ZipWriter::new(f);
+        self.write(model, "", &mut zip)?;
+        zip.finish()?;
+        Ok(())
+    }
+
+    /// Write the state of `self` for the parameters of `model` into [ZipWriter] `w`,
+    /// with a base filename of `filename_prefix`.
+    fn write<W>(&self, model: &M, filename_prefix: &str, w: &mut ZipWriter<W>) -> ZipResult<()>
+    where
+        W: Write + Seek;
+}
+
+/// Something that can load its internal state for the parameters of a model `M` from a `.npz` file
+/// written by [SaveOptimizerToNpz].
+///
+/// All optimizers in optim implement LoadOptimizerFromNpz.
+pub trait LoadOptimizerFromNpz<M> {
+    /// Loads the state of `self` for the parameters of `model` from the `.npz` file located at `path`.
+    ///
+    /// Example:
+    /// ```ignore
+    /// # use dfdx::prelude::*;
+    /// let mut model: Linear<5, 2> = Default::default();
+    /// let mut opt: Adam<Linear<5, 2>> = Default::default();
+    /// model.load("model.npz")?;
+    /// opt.load(&model, "opt.npz")?;
+    /// ```
+    fn load<P: AsRef<Path>>(&mut self, model: &M, path: P) -> Result<(), NpzError> {
+        let f = std::fs::File::open(path)?;
+        let f = BufReader::new(f);
+        let mut zip = ZipArchive::new(f)?;
+        self.read(model, "", &mut zip)?;
+        Ok(())
+    }
+
+    /// Reads the state of `self` for the parameters of `model` from [ZipArchive] `r`,
+    /// with a base filename of `filename_prefix`.
+    ///
+    /// Implementations should read everything before modifying `self`, so that `self`
+    /// is left unchanged if this returns an error.
+    fn read<R>(
+        &mut self,
+        model: &M,
+        filename_prefix: &str,
+        r: &mut ZipArchive<R>,
+    ) -> Result<(), NpzError>
+    where
+        R: Read + Seek;
+}
+
+/// Writes the entries of `state` for the parameters of `model`, as if they were the parameters of
+/// `model`, with a base filename of `p`. Parameters without an entry are written as zeros.
+pub(crate) fn write_state<M, W>(
+    state: &Gradients,
+    model: &M,
+    p: &str,
+    w: &mut ZipWriter<W>,
+) -> ZipResult<()>
+where
+    M: VisitParams + SaveToNpz + Clone,
+    W: Write + Seek,
+{
+    let mut copy = model.clone();
+    copy.visit_params_mut(&mut CopyFromState(state));
+    copy.write(p, w)
+}
+
+/// Reads the entries of `state` for the parameters of `model` written by [write_state()].
+pub(crate) fn read_state<M, R>(
+    state: &mut Gradients,
+    model: &M,
+    p: &str,
+    r: &mut ZipArchive<R>,
+) -> Result<(), NpzError>
+where
+    M: VisitParams + LoadFromNpz + Clone,
+    R: Read + Seek,
+{
+    // clones keep the ids of the parameters, so `copy` can be used as the keys into `state`.
+    let mut copy = model.clone();
+    copy.read(p, r)?;
+    copy.visit_params(&mut CopyIntoState(state));
+    Ok(())
+}
+
+/// Writes a single number, like a step count, to `{p}{name}.npy`.
+pub(crate) fn write_scalar<W, T>(w: &mut ZipWriter<W>, p: &str, name: &str, t: &T) -> ZipResult<()>
+where
+    W: Write + Seek,
+    T: NumpyDtype + NumpyShape + WriteNumbers,
+{
+    crate::nn::npz_fwrite(w, format!("{p}{name}.npy"), t)
+}
+
+/// Reads a single number written by [write_scalar()].
+pub(crate) fn read_scalar<R, T>(r: &mut ZipArchive<R>, p: &str, name: &str) -> Result<T, NpzError>
+where
+    R: Read + Seek,
+    T: NumpyDtype + NumpyShape + ReadNumbers + Default,
+{
+    let mut t = Default::default();
+    crate::nn::npz_fread(r, format!("{p}{name}.npy"), &mut t)?;
+    Ok(t)
+}
+
+struct CopyFromState<'a>(&'a Gradients);
+
+impl<'a> ParamVisitorMut for CopyFromState<'a> {
+    fn visit_mut<T: Tensor<Dtype = f32>>(&mut self, _: &str, param: &mut T) {
+        match self.0.try_ref_gradient(param) {
+            Some(s) => *param.mut_data() = s.clone(),
+            None => T::Device::fill(param.mut_data(), &mut |v| *v = 0.0),
+        }
+    }
+}
+
+struct CopyIntoState<'a>(&'a mut Gradients);
+
+impl<'a> ParamVisitor for CopyIntoState<'a> {
+    fn visit<T: Tensor<Dtype = f32>>(&mut self, _: &str, param: &T) {
+        *self.0.mut_gradient(param) = param.data().clone();
+    }
+}
+
+macro_rules! optimizer_npz_impl {
+    ($Opt:ident, [$($step:ident: $StepTy:ty),*], [$($state:ident),+]) => {
+impl<M: VisitParams + SaveToNpz + Clone> SaveOptimizerToNpz<M> for $Opt<M> {
+    fn write<W: Write + Seek>(&self, model: &M, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        $(write_scalar(w, p, stringify!($step), &(self.$step as f64))?;)*
+        $(write_state(&self.$state, model, &format!("{p}{}.", stringify!($state)), w)?;)+
+        Ok(())
+    }
+}
+
+impl<M: VisitParams + LoadFromNpz + Clone> LoadOptimizerFromNpz<M> for $Opt<M> {
+    fn read<R: Read + Seek>(&mut self, model: &M, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        $(let $step = read_scalar::<R, f64>(r, p, stringify!($step))? as $StepTy;)*
+        $(
+            let mut $state = Gradients::default();
+            read_state(&mut $state, model, &format!("{p}{}.", stringify!($state)), r)?;
+        )+
+        $(self.$step = $step;)*
+        $(self.$state = $state;)+
+        Ok(())
+    }
+}
+    };
+}
+
+optimizer_npz_impl!(Sgd, [], [velocity]);
+optimizer_npz_impl!(Adam, [t: i32], [moment1, moment2]);
+optimizer_npz_impl!(RAdam, [t: i32], [moment1, moment2]);
+optimizer_npz_impl!(Lamb, [t: i32], [moment1, moment2]);
+optimizer_npz_impl!(Adagrad, [step: usize], [sum_sq]);
+optimizer_npz_impl!(Adadelta, [], [square_avg, delta_avg]);
+optimizer_npz_impl!(Lion, [], [momentum]);
+optimizer_npz_impl!(RMSprop, [step: usize], [momentums, square_avg, grad_avg]);
+
+impl<M, O> SaveOptimizerToNpz<M> for Lookahead<M, O>
+where
+    M: SaveToNpz,
+    O: SaveOptimizerToNpz<M>,
+{
+    /// Saves the inner optimizer with the prefix `opt.`, and the slow weights with the prefix `slow.`.
+    fn write<W: Write + Seek>(&self, model: &M, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.opt.write(model, &format!("{p}opt."), w)?;
+        write_scalar(w, p, "step", &(self.step as f64))?;
+        match &self.slow {
+            Some(slow) => slow.write(&format!("{p}slow."), w),
+            None => model.write(&format!("{p}slow."), w),
+        }
+    }
+}
+
+impl<M, O> LoadOptimizerFromNpz<M> for Lookahead<M, O>
+where
+    M: LoadFromNpz + Clone,
+    O: LoadOptimizerFromNpz<M>,
+{
+    fn read<R: Read + Seek>(
+        &mut self,
+        model: &M,
+        p: &str,
+        r: &mut ZipArchive<R>,
+    ) -> Result<(), NpzError> {
+        let step = read_scalar::<R, f64>(r, p, "step")? as usize;
+        let mut slow = model.clone();
+        slow.read(&format!("{p}slow."), r)?;
+        self.opt.read(model, &format!("{p}opt."), r)?;
+        self.step = step;
+        self.slow = Some(slow);
+        Ok(())
+    }
+}
+
+impl<M, O: SaveOptimizerToNpz<M>> SaveOptimizerToNpz<M> for Sam<M, O> {
+    /// Saves the inner optimizer, since the rest of [Sam]'s state only lasts for a single update.
+    fn write<W: Write + Seek>(&self, model: &M, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.opt.write(model, p, w)
+    }
+}
+
+impl<M, O: LoadOptimizerFromNpz<M>> LoadOptimizerFromNpz<M> for Sam<M, O> {
+    fn read<R: Read + Seek>(
+        &mut self,
+        model: &M,
+        p: &str,
+        r: &mut ZipArchive<R>,
+    ) -> Result<(), NpzError> {
+        self.opt.read(model, p, r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{prelude::StdRng, SeedableRng};
+    use tempfile::NamedTempFile;
+
+    type Model = (Linear<3, 4>, ReLU, Linear<4, 2>);
+
+    fn train_step<O: Optimizer<Model>>(model: &mut Model, opt: &mut O) {
+        let x: Tensor2D<5, 3> = tensor([[1.0, -2.0, 0.5]; 5]);
+        let loss = mse_loss(model.forward(x.trace()), Tensor2D::ones());
+        opt.update(model, backward(loss)).expect("");
+    }
+
+    fn test_resume<O, F>(new_opt: F)
+    where
+        O: Optimizer<Model> + SaveOptimizerToNpz<Model> + LoadOptimizerFromNpz<Model>,
+        F: Fn() -> O,
+    {
+        let model_file = NamedTempFile::new().expect("failed to create tempfile");
+        let opt_file = NamedTempFile::new().expect("failed to create tempfile");
+
+        let mut model: Model = Default::default();
+        model.reset_params(&mut StdRng::seed_from_u64(0));
+        let mut opt = new_opt();
+        for _ in 0..3 {
+            train_step(&mut model, &mut opt);
+        }
+        model.save(model_file.path()).expect("");
+        opt.save(&model, opt_file.path()).expect("");
+
+        let mut loaded_model: Model = Default::default();
+        let mut loaded_opt = new_opt();
+        loaded_model.load(model_file.path()).expect("");
+        loaded_opt.load(&loaded_model, opt_file.path()).expect("");
+
+        let mut restarted_model = model.clone();
+        let mut restarted_opt = new_opt();
+
+        train_step(&mut model, &mut opt);
+        train_step(&mut loaded_model, &mut loaded_opt);
+        train_step(&mut restarted_model, &mut restarted_opt);
+        assert_eq!(loaded_model.0.weight.data(), model.0.weight.data());
+        assert_eq!(loaded_model.2.bias.data(), model.2.bias.data());
+        assert_ne!(restarted_model.0.weight.data(), model.0.weight.data());
+    }
+
+    #[test]
+    fn test_resume_sgd() {
+        test_resume(|| {
+            Sgd::new(SgdConfig {
+                momentum: Some(Momentum::Nesterov(0.9)),
+                ..Default::default()
+            })
+        });
+    }
+
+    #[test]
+    fn test_resume_adam() {
+        test_resume(Adam::default);
+    }
+
+    #[test]
+    fn test_resume_rmsprop() {
+        test_resume(|| {
+            RMSprop::new(RMSpropConfig {
+                momentum: Some(0.9),
+                centered: true,
+                ..Default::default()
+            })
+        });
+    }
+
+    #[test]
+    fn test_resume_lookahead() {
+        test_resume(|| {
+            Lookahead::new(
+                Adam::default(),
+                LookaheadConfig {
+                    k: 2,
+                    ..Default::default()
+                },
+            )
+        });
+    }
+}
@@ -0,0 +1,261 @@
+use super::weight_decay::weight_decay_for;
+use crate::arrays::HasArrayType;
+use crate::devices::ForEachElement;
+use crate::gradients::{CanUpdateWithGradients, GradientProvider, Gradients};
+use crate::prelude::*;
+use crate::unique_id::HasUniqueId;
+use std::{boxed::Box, marker::PhantomData};
+
+/// An implementation of the RAdam optimizer from
+/// [On the Variance of the Adaptive Learning Rate and Beyond](https://arxiv.org/abs/1908.03265)
+///
+/// RAdam is [Adam] with a rectification term that turns off the adaptive learning rate
+/// while the variance estimate is unreliable, which acts as an automatic learning rate warmup.
+/// During the first few steps parameters are updated with momentum only.
+///
+/// # Example Usage
+///
+/// Constructing using default:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor0D;
+/// let mut opt: RAdam<Model> = Default::default();
+/// ```
+///
+/// Changing using new
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor0D;
+/// let mut opt: RAdam<Model> = RAdam::new(RAdamConfig {
+///     lr: 1e-2,
+///     betas: [0.5, 0.25],
+///     eps: 1e-6,
+///     weight_decay: Some(WeightDecay::Decoupled(1e-2)),
+///     weight_decay_1d: true,
+/// });
+/// ```
+///
+/// See module level documentation at [crate::optim] for examples of how to actually use an optimizer.
+#[derive(Debug)]
+pub struct RAdam<M> {
+    /// Hyperparameter configuration
+    pub cfg: RAdamConfig,
+
+    pub(super) t: i32,
+    gradients: Gradients,
+    pub(super) moment1: Gradients,
+    pub(super) moment2: Gradients,
+
+    marker: PhantomData<*const M>,
+}
+
+/// Configuration of hyperparameters for [RAdam].
+///
+/// Changing all default parameters:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// RAdamConfig {
+///     lr: 1e-2,
+///     betas: [0.1, 0.2],
+///     eps: 1e-6,
+///     weight_decay: Some(WeightDecay::L2(1e-1)),
+///     weight_decay_1d: true,
+/// };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RAdamConfig {
+    /// Learning rate. Defaults to `1e-3`.
+    pub lr: f32,
+
+    /// Betas from Adam paper. Defaults to `[0.9, 0.999]`.
+    pub betas: [f32; 2],
+
+    /// Epsilon for numerical stability. Defaults to `1e-8`.
+    pub eps: f32,
+
+    /// Optional weight decay. Defaults to `None`.
+    pub weight_decay: Option<WeightDecay>,
+
+    /// Whether weight decay is also applied to 1d parameters, like biases and the gains of
+    /// normalization layers. Defaults to `true`.
+    pub weight_decay_1d: bool,
+}
+
+impl Default for RAdamConfig {
+    fn default() -> Self {
+        Self {
+            lr: 1e-3,
+            betas: [0.9, 0.999],
+            eps: 1e-8,
+            weight_decay: None,
+            weight_decay_1d: true,
+        }
+    }
+}
+
+impl<M> Default for RAdam<M> {
+    /// See [RAdamConfig]
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<M> RAdam<M> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(cfg: RAdamConfig) -> Self {
+        Self {
+            cfg,
+            t: 0,
+            gradients: Default::default(),
+            moment1: Default::default(),
+            moment2: Default::default(),
+            marker: PhantomData,
+        }
+    }
+
+    /// The variance rectification term at the current step, or `None` if the
+    /// variance of the adaptive learning rate is intractable and it should not be used.
+    fn rectification(&self) -> Option<f32> {
+        // computed in f64 since `rho_t - 4.0` loses most of its precision near the threshold
+        let b2 = self.cfg.betas[1] as f64;
+        let b2_t = b2.powi(self.t);
+        let rho_inf = 2.0 / (1.0 - b2) - 1.0;
+        let rho_t = rho_inf - 2.0 * self.t as f64 * b2_t / (1.0 - b2_t);
+        (rho_t > 5.0).then(|| {
+            let r = (rho_t - 4.0) * (rho_t - 2.0) * rho_inf
+                / ((rho_inf - 4.0) * (rho_inf - 2.0) * rho_t);
+            r.sqrt() as f32
+        })
+    }
+}
+
+impl<M> GradientProvider for RAdam<M> {
+    fn gradient<P>(&mut self, p: &P) -> Option<Box<P::Array>>
+    where
+        P: HasUniqueId + HasArrayType<Dtype = f32> + HasDevice + HasArrayData,
+    {
+        let mut g_t = self.gradients.remove(p)?;
+        let rect = self.rectification();
+        let m_t = self.moment1.mut_gradient(p);
+        let v_t = self.moment2.mut_gradient(p);
+        let weight_decay = weight_decay_for::<P>(self.cfg.weight_decay, self.cfg.weight_decay_1d);
+        if let Some(WeightDecay::L2(wd)) = weight_decay {
+            P::Device::foreach_mr(g_t.as_mut(), p.data(), &mut |g, p_el| {
+                *g += wd * p_el;
+            });
+        }
+        let [b1, b2] = self.cfg.betas;
+        P::Device::foreach_mmm(g_t.as_mut(), m_t, v_t, &mut |g, m, v| {
+            *m = *m * b1 + *g * (1.0 - b1);
+            *v = *v * b2 + g.powi(2) * (1.0 - b2);
+            let m_hat = *m * (1.0 - b1.powi(self.t)).recip();
+            *g = match rect {
+                Some(r) => {
+                    let v_hat = *v * (1.0 - b2.powi(self.t)).recip();
+                    self.cfg.lr * m_hat * r / (v_hat.sqrt() + self.cfg.eps)
+                }
+                None => self.cfg.lr * m_hat,
+            };
+        });
+        if let Some(WeightDecay::Decoupled(wd)) = weight_decay {
+            P::Device::foreach_mr(g_t.as_mut(), p.data(), &mut |g, p_el| {
+                *g += wd * self.cfg.lr * p_el;
+            });
+        }
+        Some(g_t)
+    }
+}
+
+impl<M: CanUpdateWithGradients> Optimizer<M> for RAdam<M> {
+    fn update(&mut self, module: &mut M, gradients: Gradients) -> Result<(), UnusedParamsError> {
+        self.t = self.t.checked_add(1).unwrap();
+        self.gradients = gradients;
+        let mut unused_tensors = Default::default();
+        module.update(self, &mut unused_tensors);
+        unused_tensors.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+    use rand::{prelude::*, SeedableRng};
+
+    #[test]
+    fn test_radam_rectification() {
+        let mut opt: RAdam<Tensor0D> = RAdam::new(RAdamConfig {
+            betas: [0.9, 0.99],
+            ..Default::default()
+        });
+        for t in 1..=5 {
+            opt.t = t;
+            assert!(opt.rectification().is_none());
+        }
+        opt.t = 6;
+        assert_close(&[opt.rectification().unwrap()], &[0.0816792]);
+        opt.t = 100;
+        assert_close(&[opt.rectification().unwrap()], &[0.6341169]);
+    }
+
+    #[test]
+    fn test_radam_params() {
+        let mut opt: RAdam<Tensor1D<5>> = RAdam::new(RAdamConfig {
+            lr: 1e-2,
+            betas: [0.9, 0.99],
+            ..Default::default()
+        });
+        let mut t: Tensor1D<5> = Tensor1D::ones();
+        let rate = tensor([1e-4, 1e-3, 1e-2, 1e-1, 1e-0]);
+        #[rustfmt::skip]
+        let expected = [
+            [1.0, 1.0, 0.9999996, 0.99996, 0.996],
+            [1.0, 1.0, 0.9999992, 0.99992, 0.99200845],
+            [1.0, 1.0, 0.9999988, 0.99988, 0.98802555],
+            [1.0, 1.0, 0.9999984, 0.99984, 0.9840516],
+            [1.0, 1.0, 0.999998, 0.9998, 0.98008686],
+            [0.99976665, 0.9992031, 0.9991814, 0.9989832, 0.979271],
+            [0.99947035, 0.9981915, 0.99814475, 0.9979463, 0.9782355],
+            [0.99911976, 0.9969942, 0.99691784, 0.9967191, 0.9770102],
+        ];
+
+        for e in expected.iter() {
+            let gradients = backward((t.trace() * rate.clone()).square().mean());
+            opt.update(&mut t, gradients).expect("");
+            assert_close(t.data(), e);
+        }
+    }
+
+    #[test]
+    fn test_radam_changes_all_params() {
+        type Model = (Linear<5, 16>, ReLU, Linear<16, 10>);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut model: Model = Default::default();
+        model.reset_params(&mut rng);
+        let model_0 = model.clone();
+
+        let x: Tensor2D<16, 5> = Tensor2D::rand(&mut rng);
+        let y: Tensor2D<16, 10> = Tensor2D::rand(&mut rng);
+        let mut opt: RAdam<Model> = Default::default();
+
+        let py = model.forward(x.trace());
+        let loss = (py - y).square().mean();
+        let gradients = backward(loss);
+        opt.update(&mut model, gradients).expect("");
+
+        assert!(model_0.0.weight.data() != model.0.weight.data());
+        assert!(model_0.0.bias.data() != model.0.bias.data());
+        assert!(model_0.2.weight.data() != model.2.weight.data());
+        assert!(model_0.2.bias.data() != model.2.bias.data());
+    }
+
+    #[test]
+    fn test_radam_unused_params() {
+        type Model = (Linear<5, 16>, Linear<16, 10>);
+        let mut model: Model = Default::default();
+        let mut opt: RAdam<Model> = Default::default();
+        let y = model.1.forward(Tensor2D::<8, 16>::zeros().trace());
+        let g = backward(y.mean());
+        opt.update(&mut model, g).expect_err("");
+    }
+}
@@ -1,3 +1,4 @@
+use super::weight_decay::weight_decay_for;
 use crate::arrays::HasArrayType;
 use crate::devices::{FillElements, ForEachElement};
 use crate::gradients::{CanUpdateWithGradients, GradientProvider, Gradients};
@@ -36,6 +37,7 @@ use std::{boxed::Box, marker::PhantomData};
 ///     momentum: Some(0.5),
 ///     centered: false,
 ///     weight_decay: Some(WeightDecay::Decoupled(1e-1)),
+///     weight_decay_1d: true,
 /// });
 /// ```
 ///
@@ -45,10 +47,10 @@ pub struct RMSprop<M> {
     /// Hyperparameter configuration
     pub cfg: RMSpropConfig,
 
-    step: usize,
-    momentums: Gradients,
-    square_avg: Gradients,
-    grad_avg: Gradients,
+    pub(super) step: usize,
+    pub(super) momentums: Gradients,
+    pub(super) square_avg: Gradients,
+    pub(super) grad_avg: Gradients,
     gradients: Gradients,
 
     marker: PhantomData<*const M>,
@@ -75,6 +77,10 @@ pub struct RMSpropConfig {
 
     /// Optional weight decay. Defaults to `None`.
     pub weight_decay: Option<WeightDecay>,
+
+    /// Whether weight decay is also applied to 1d parameters, like biases and the gains of
+    /// normalization layers. Defaults to `true`.
+    pub weight_decay_1d: bool,
 }
 
 impl Default for RMSpropConfig {
@@ -86,6 +92,7 @@ impl Default for RMSpropConfig {
             momentum: None,
             centered: false,
             weight_decay: None,
+            weight_decay_1d: true,
         }
     }
 }
@@ -124,7 +131,8 @@ impl<M> GradientProvider for RMSprop<M> {
             P::Device::fill(square_avg, &mut |v| *v = 1.0);
         }
 
-        if let Some(WeightDecay::L2(wd)) = self.cfg.weight_decay {
+        let weight_decay = weight_decay_for::<P>(self.cfg.weight_decay, self.cfg.weight_decay_1d);
+        if let Some(WeightDecay::L2(wd)) = weight_decay {
             P::Device::foreach_mr(g_t.as_mut(), p.data(), &mut |g_i, p_i| {
                 *g_i += wd * p_i;
             });
@@ -166,7 +174,7 @@ impl<M> GradientProvider for RMSprop<M> {
             None => P::Device::foreach_m(g_t.as_mut(), &mut |g| *g *= self.cfg.lr),
         }
 
-        if let Some(WeightDecay::Decoupled(wd)) = self.cfg.weight_decay {
+        if let Some(WeightDecay::Decoupled(wd)) = weight_decay {
             P::Device::foreach_mr(g_t.as_mut(), p.data(), &mut |g_i, p_i| {
                 *g_i += wd * self.cfg.lr * p_i;
             });
@@ -210,6 +218,7 @@ mod tests {
             momentum: None,
             centered: false,
             weight_decay: None,
+            weight_decay_1d: true,
         };
         const EXPECTED: [[f32; 5]; 5] = [
             [0.9997892, 0.98245883, 0.9703907, 0.9683808, 0.96837723],
@@ -230,6 +239,7 @@ mod tests {
             momentum: Some(0.9),
             centered: false,
             weight_decay: None,
+            weight_decay_1d: true,
         };
         const EXPECTED: [[f32; 5]; 5] = [
             [0.9997892, 0.98245883, 0.9703907, 0.9683808, 0.96837723],
@@ -250,6 +260,7 @@ mod tests {
             momentum: None,
             centered: false,
             weight_decay: None,
+            weight_decay_1d: true,
         };
         const EXPECTED: [[f32; 5]; 5] = [
             [0.99971724, 0.9873509, 0.9859671, 0.985858, 0.98585784],
@@ -270,6 +281,7 @@ mod tests {
             momentum: None,
             centered: false,
             weight_decay: None,
+            weight_decay_1d: true,
         };
         const EXPECTED: [[f32; 5]; 5] = [
             [0.9997904, 0.98252594, 0.97041094, 0.9683808, 0.96837723],
@@ -290,6 +302,7 @@ mod tests {
             momentum: None,
             centered: true,
             weight_decay: None,
+            weight_decay_1d: true,
         };
         const EXPECTED: [[f32; 5]; 5] = [
             [0.9997892, 0.98218256, 0.96900064, 0.9666708, 0.9666667],
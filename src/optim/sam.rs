@@ -0,0 +1,192 @@
+use super::lr_scheduler::{HasLearningRate, HasMomentum};
+use super::{Optimizer, UnusedParamsError};
+use crate::devices::ForEachElement;
+use crate::gradients::{CanUpdateWithGradients, Gradients};
+use crate::nn::{zip_params_mut, ParamVisitorMut, VisitParams};
+use crate::prelude::*;
+
+/// Wraps another optimizer `O` with Sharpness-Aware Minimization from
+/// [Sharpness-Aware Minimization for Efficiently Improving Generalization](https://arxiv.org/abs/2010.01412).
+///
+/// SAM needs two forward & backward passes for every update:
+/// 1. Compute gradients at the current parameters, and pass them to [Sam::perturb()]. This moves the parameters
+///    by `rho` in the direction of the gradients, to the nearby point with (approximately) the highest loss.
+/// 2. Compute gradients again at the perturbed parameters, and pass them to [Optimizer::update()]. This restores
+///    the original parameters, and then updates them with the inner optimizer using the new gradients.
+///
+/// Calling [Optimizer::update()] without [Sam::perturb()] is the same as using the inner optimizer.
+///
+/// # Example Usage
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Linear<5, 2>;
+/// let mut model: Model = Default::default();
+/// let mut opt: Sam<Model, Sgd<Model>> = Sam::new(Default::default(), SamConfig { rho: 0.05 });
+/// let x: Tensor2D<3, 5> = TensorCreator::zeros();
+/// let y: Tensor2D<3, 2> = TensorCreator::ones();
+///
+/// // first pass
+/// let loss = mse_loss(model.forward(x.trace()), y.clone());
+/// opt.perturb(&mut model, backward(loss));
+///
+/// // second pass
+/// let loss = mse_loss(model.forward(x.trace()), y.clone());
+/// opt.update(&mut model, backward(loss));
+/// ```
+///
+/// See module level documentation at [crate::optim] for examples of how to actually use an optimizer.
+#[derive(Debug)]
+pub struct Sam<M, O> {
+    /// The inner optimizer
+    pub opt: O,
+
+    /// Hyperparameter configuration
+    pub cfg: SamConfig,
+
+    original: Option<M>,
+}
+
+/// Configuration of hyperparameters for [Sam].
+#[derive(Debug, Clone, Copy)]
+pub struct SamConfig {
+    /// The L2 norm of the perturbation of all the parameters. Defaults to `0.05`.
+    pub rho: f32,
+}
+
+impl Default for SamConfig {
+    fn default() -> Self {
+        Self { rho: 0.05 }
+    }
+}
+
+impl<M, O: Default> Default for Sam<M, O> {
+    /// See [SamConfig]
+    fn default() -> Self {
+        Self::new(Default::default(), Default::default())
+    }
+}
+
+impl<M, O> Sam<M, O> {
+    /// Wraps `opt` using hyperparameters from `cfg`.
+    pub fn new(opt: O, cfg: SamConfig) -> Self {
+        Self {
+            opt,
+            cfg,
+            original: None,
+        }
+    }
+}
+
+impl<M: VisitParams + Clone, O> Sam<M, O> {
+    /// The first step of SAM: moves the parameters of `module` by `rho` in the direction of `gradients`.
+    /// The original parameters are restored by the next [Optimizer::update()].
+    pub fn perturb(&mut self, module: &mut M, mut gradients: Gradients) {
+        if let Some(original) = self.original.as_ref() {
+            zip_params_mut(module, original, |p, o| *p = *o);
+        } else {
+            self.original = Some(module.clone());
+        }
+        let norm = gradients.global_norm(module);
+        module.visit_params_mut(&mut Perturb {
+            grads: &mut gradients,
+            scale: self.cfg.rho / (norm + 1e-12),
+        });
+    }
+}
+
+struct Perturb<'a> {
+    grads: &'a mut Gradients,
+    scale: f32,
+}
+
+impl<'a> ParamVisitorMut for Perturb<'a> {
+    fn visit_mut<T: Tensor<Dtype = f32>>(&mut self, _: &str, param: &mut T) {
+        if let Some(g) = self.grads.try_mut_gradient(param) {
+            T::Device::foreach_mr(param.mut_data(), g, &mut |p, g| *p += self.scale * g);
+        }
+    }
+    fn visit_frozen_mut<T: Tensor<Dtype = f32>>(&mut self, _: &str, _: &mut T) {}
+}
+
+impl<M, O> Optimizer<M> for Sam<M, O>
+where
+    M: CanUpdateWithGradients + VisitParams + Clone,
+    O: Optimizer<M>,
+{
+    fn update(&mut self, module: &mut M, gradients: Gradients) -> Result<(), UnusedParamsError> {
+        if let Some(original) = self.original.take() {
+            zip_params_mut(module, &original, |p, o| *p = *o);
+        }
+        self.opt.update(module, gradients)
+    }
+}
+
+impl<M, O: HasLearningRate> HasLearningRate for Sam<M, O> {
+    fn learning_rate(&self) -> f32 {
+        self.opt.learning_rate()
+    }
+    fn set_learning_rate(&mut self, lr: f32) {
+        self.opt.set_learning_rate(lr);
+    }
+}
+
+impl<M, O: HasMomentum> HasMomentum for Sam<M, O> {
+    fn momentum(&self) -> Option<f32> {
+        self.opt.momentum()
+    }
+    fn set_momentum(&mut self, momentum: f32) {
+        self.opt.set_momentum(momentum);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+
+    #[test]
+    fn test_sam_perturb_then_update() {
+        type Model = Linear<2, 1>;
+        let mut model: Model = Linear {
+            weight: tensor([[1.0, 2.0]]),
+            bias: tensor([0.0]),
+        };
+        let sgd = Sgd::new(SgdConfig {
+            lr: 0.1,
+            ..Default::default()
+        });
+        let mut opt: Sam<Model, Sgd<Model>> = Sam::new(sgd, SamConfig { rho: 0.5 });
+        let x = tensor([3.0, 4.0]);
+
+        // loss = (w . x + b)^2 / 2, so the gradients are (w . x + b) * [x, 1]
+        let y = model.forward(x.trace());
+        opt.perturb(&mut model, backward(y.square().sum() / 2.0));
+
+        // perturbed by 0.5 * [3, 4, 1] / sqrt(26)
+        assert_close(model.weight.data(), &[[1.2941742, 2.3922322]]);
+        assert_close(model.bias.data(), &[0.09805807]);
+
+        let y = model.forward(x.trace());
+        opt.update(&mut model, backward(y.square().sum() / 2.0))
+            .expect("");
+
+        // original parameters updated with the gradients from the perturbed point
+        assert_close(model.weight.data(), &[[-3.064853, -3.419804]]);
+        assert_close(model.bias.data(), &[-1.354951]);
+    }
+
+    #[test]
+    fn test_sam_update_without_perturb() {
+        type Model = Linear<2, 1>;
+        let mut model: Model = Linear {
+            weight: tensor([[1.0, 2.0]]),
+            bias: tensor([0.0]),
+        };
+        let mut opt: Sam<Model, Sgd<Model>> = Default::default();
+        let y = model.forward(tensor([1.0, 1.0]).traced());
+        opt.update(&mut model, backward(y.sum())).expect("");
+        assert_close(model.weight.data(), &[[0.99, 1.99]]);
+        assert_close(model.bias.data(), &[-0.01]);
+    }
+}
@@ -1,3 +1,4 @@
+use super::weight_decay::weight_decay_for;
 use crate::arrays::HasArrayType;
 use crate::devices::ForEachElement;
 use crate::gradients::{CanUpdateWithGradients, GradientProvider, Gradients};
@@ -31,6 +32,7 @@ use std::{boxed::Box, marker::PhantomData};
 ///     lr: 1e-3,
 ///     momentum: Some(Momentum::Classic(0.5)),
 ///     weight_decay: Some(WeightDecay::L2(0.01)),
+///     weight_decay_1d: true,
 /// });
 /// ```
 ///
@@ -40,7 +42,7 @@ pub struct Sgd<M> {
     /// Hyperparameter configuration
     pub cfg: SgdConfig,
 
-    velocity: Gradients,
+    pub(super) velocity: Gradients,
     gradients: Gradients,
 
     marker: PhantomData<*const M>,
@@ -55,6 +57,7 @@ pub struct Sgd<M> {
 ///     lr: 1e-1,
 ///     momentum: None,
 ///     weight_decay: None,
+///     weight_decay_1d: true,
 /// };
 /// ```
 ///
@@ -65,6 +68,7 @@ pub struct Sgd<M> {
 ///     lr: 1e-2,
 ///     momentum: Some(Momentum::Classic(0.5)),
 ///     weight_decay: None,
+///     weight_decay_1d: true,
 /// };
 /// ```
 ///
@@ -75,6 +79,7 @@ pub struct Sgd<M> {
 ///     lr: 1e-3,
 ///     momentum: Some(Momentum::Nesterov(0.25)),
 ///     weight_decay: None,
+///     weight_decay_1d: true,
 /// };
 /// ```
 ///
@@ -85,6 +90,7 @@ pub struct Sgd<M> {
 ///     lr: 1e-3,
 ///     momentum: None,
 ///     weight_decay: Some(WeightDecay::L2(1e-2)),
+///     weight_decay_1d: true,
 /// };
 /// ```
 ///
@@ -95,6 +101,18 @@ pub struct Sgd<M> {
 ///     lr: 1e-3,
 ///     momentum: None,
 ///     weight_decay: Some(WeightDecay::Decoupled(1e-2)),
+///     weight_decay_1d: true,
+/// };
+/// ```
+///
+/// Skipping weight decay for biases and normalization layers:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// SgdConfig {
+///     lr: 1e-3,
+///     momentum: None,
+///     weight_decay: Some(WeightDecay::L2(1e-2)),
+///     weight_decay_1d: false,
 /// };
 /// ```
 #[derive(Debug, Clone, Copy)]
@@ -107,6 +125,10 @@ pub struct SgdConfig {
 
     /// Optional weight decay. Defaults to `None`.
     pub weight_decay: Option<WeightDecay>,
+
+    /// Whether weight decay is also applied to 1d parameters, like biases and the gains of
+    /// normalization layers. Defaults to `true`.
+    pub weight_decay_1d: bool,
 }
 
 impl Default for SgdConfig {
@@ -115,6 +137,7 @@ impl Default for SgdConfig {
             lr: 1e-2,
             momentum: None,
             weight_decay: None,
+            weight_decay_1d: true,
         }
     }
 }
@@ -123,9 +146,15 @@ impl Default for SgdConfig {
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Momentum {
     /// Momentum that is applied to the velocity of a parameter directly.
+    ///
+    /// `v = g + u * v`, then `p -= lr * v`.
     Classic(f32),
 
     /// Momentum that is applied to both velocity and gradients. See [Sgd] nesterov paper for more.
+    ///
+    /// `v = g + u * v`, then `p -= lr * (g + u * v)`. This is the same as evaluating the gradient
+    /// at the lookahead point `p - lr * u * v`, reformulated so only the current parameters are needed,
+    /// and matches pytorch's `nesterov=True`.
     Nesterov(f32),
 }
 
@@ -154,7 +183,8 @@ impl<M> GradientProvider for Sgd<M> {
         P: HasUniqueId + HasArrayType<Dtype = f32> + HasDevice + HasArrayData,
     {
         let mut g_t = self.gradients.remove(p)?;
-        if let Some(WeightDecay::L2(wd)) = self.cfg.weight_decay {
+        let weight_decay = weight_decay_for::<P>(self.cfg.weight_decay, self.cfg.weight_decay_1d);
+        if let Some(WeightDecay::L2(wd)) = weight_decay {
             P::Device::foreach_mr(g_t.as_mut(), p.data(), &mut |g, p_el| {
                 *g += wd * p_el;
             });
@@ -176,7 +206,7 @@ impl<M> GradientProvider for Sgd<M> {
             }
             None => P::Device::foreach_m(g_t.as_mut(), &mut |g| *g *= self.cfg.lr),
         }
-        if let Some(WeightDecay::Decoupled(wd)) = self.cfg.weight_decay {
+        if let Some(WeightDecay::Decoupled(wd)) = weight_decay {
             P::Device::foreach_mr(g_t.as_mut(), p.data(), &mut |g, p_el| {
                 *g += wd * self.cfg.lr * p_el;
             });
@@ -206,6 +236,7 @@ mod tests {
             lr: 1.0,
             momentum: None,
             weight_decay: None,
+            weight_decay_1d: true,
         });
 
         let mut pred: Tensor1D<5> = Tensor1D::zeros();
@@ -246,6 +277,7 @@ mod tests {
             lr: 1e-2,
             momentum: Some(Momentum::Classic(0.5)),
             weight_decay: None,
+            weight_decay_1d: true,
         });
 
         let mut t: Tensor1D<5> = Tensor1D::ones();
@@ -271,6 +303,7 @@ mod tests {
             lr: 1e-2,
             momentum: Some(Momentum::Nesterov(0.5)),
             weight_decay: None,
+            weight_decay_1d: true,
         });
 
         let mut t: Tensor1D<5> = Tensor1D::ones();
@@ -297,11 +330,13 @@ mod tests {
             lr: 1e-2,
             momentum: None,
             weight_decay: Some(WeightDecay::L2(1e-1)),
+            weight_decay_1d: true,
         });
         let mut sgd_decoupled = Sgd::new(SgdConfig {
             lr: 1e-2,
             momentum: None,
             weight_decay: Some(WeightDecay::Decoupled(1e-1)),
+            weight_decay_1d: true,
         });
 
         let mut t: Tensor1D<5> = Tensor1D::ones();
@@ -332,6 +367,7 @@ mod tests {
             lr: 1e-2,
             momentum: Some(Momentum::Classic(0.5)),
             weight_decay: Some(WeightDecay::Decoupled(1e-1)),
+            weight_decay_1d: true,
         });
 
         let mut t: Tensor1D<5> = Tensor1D::ones();
@@ -358,11 +394,13 @@ mod tests {
             lr: 1e-2,
             momentum: Some(Momentum::Classic(0.5)),
             weight_decay: Some(WeightDecay::L2(weight_decay)),
+            weight_decay_1d: true,
         });
         let mut sgd = Sgd::new(SgdConfig {
             lr: 1e-2,
             momentum: Some(Momentum::Classic(0.5)),
             weight_decay: None,
+            weight_decay_1d: true,
         });
 
         let mut t: Tensor1D<5> = Tensor1D::ones();
@@ -393,6 +431,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_sgd_weight_decay_skips_1d_params() {
+        let mut sgd = Sgd::new(SgdConfig {
+            lr: 1.0,
+            momentum: None,
+            weight_decay: Some(WeightDecay::L2(0.5)),
+            weight_decay_1d: false,
+        });
+
+        let mut model: Linear<2, 2> = Default::default();
+        model.reset_params(&mut StdRng::seed_from_u64(0));
+        let model_0 = model.clone();
+
+        let y = model.forward(Tensor1D::<2>::ones().trace());
+        let gradients = backward((y * 0.0).sum());
+        sgd.update(&mut model, gradients).expect("");
+
+        assert_eq!(model.weight.data(), (model_0.weight * 0.5).data());
+        assert_eq!(model.bias.data(), model_0.bias.data());
+    }
+
     #[test]
     fn test_sgd_changes_all_params() {
         type Model = (Linear<5, 16>, ReLU, Linear<16, 16>, ReLU, Linear<16, 10>);
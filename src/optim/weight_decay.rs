@@ -1,3 +1,5 @@
+use crate::arrays::{HasArrayType, HasShape};
+
 /// L2 and decoupled regularization methods
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum WeightDecay {
@@ -8,3 +10,16 @@ pub enum WeightDecay {
     /// See [Decoupled Weight Decay Regularization](https://arxiv.org/abs/1711.05101)
     Decoupled(f32),
 }
+
+/// The weight decay an optimizer should apply to `P`. Parameters with less than 2 dimensions
+/// (biases, and the gains of normalization layers) are only decayed if `decay_1d` is true.
+pub(crate) fn weight_decay_for<P: HasArrayType>(
+    weight_decay: Option<WeightDecay>,
+    decay_1d: bool,
+) -> Option<WeightDecay> {
+    if decay_1d || P::Array::NUM_DIMS >= 2 {
+        weight_decay
+    } else {
+        None
+    }
+}
@@ -0,0 +1,124 @@
+//! Data parallel training on multiple CPU threads.
+//!
+//! Requires the "std" feature.
+
+use crate::devices::ForEachElement;
+use crate::gradients::Gradients;
+use crate::nn::{ParamVisitor, VisitParams};
+use crate::prelude::*;
+use std::vec::Vec;
+
+/// Calls `f` on each of `chunks` on a separate thread, and backprops the losses it returns.
+/// Returns the mean of the losses, along with the mean of the gradients of `model`'s parameters.
+///
+/// `model` is shared by all threads, and only read. If each loss is the mean over an equally sized
+/// chunk of a batch, the result is the same as calling `f` on the whole batch.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::{prelude::*, parallel::parallel_backward};
+/// type Model = (Linear<5, 16>, ReLU, Linear<16, 2>);
+/// let mut model: Model = Default::default();
+/// let mut opt: Sgd<Model> = Default::default();
+/// let chunks: [(Tensor2D<8, 5>, Tensor2D<8, 2>); 4] = Default::default();
+/// let (loss, gradients) = parallel_backward(&model, chunks, |m, (x, y)| {
+///     mse_loss(m.forward(x.traced()), y)
+/// });
+/// opt.update(&mut model, gradients).expect("");
+/// ```
+pub fn parallel_backward<M, X, I, F>(model: &M, chunks: I, f: F) -> (f32, Gradients)
+where
+    M: VisitParams + Sync,
+    X: Send,
+    I: IntoIterator<Item = X>,
+    F: Fn(&M, X) -> Tensor0D<OwnedTape> + Sync,
+{
+    let results: Vec<(f32, Gradients)> = std::thread::scope(|s| {
+        let f = &f;
+        let handles: Vec<_> = chunks
+            .into_iter()
+            .map(|x| {
+                s.spawn(move || {
+                    let loss = f(model, x);
+                    let loss_value = *loss.data();
+                    let mut gradients = backward(loss);
+                    (loss_value, gradients.split_params(model))
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    let scale = 1.0 / results.len() as f32;
+    let mut loss = 0.0;
+    let mut total: Gradients = Default::default();
+    for (chunk_loss, mut gradients) in results {
+        loss += chunk_loss * scale;
+        model.visit_params(&mut AddScaledGrads {
+            src: &mut gradients,
+            dst: &mut total,
+            scale,
+        });
+    }
+    (loss, total)
+}
+
+/// Adds the gradients in `src` multiplied by `scale` to `dst`.
+struct AddScaledGrads<'a> {
+    src: &'a mut Gradients,
+    dst: &'a mut Gradients,
+    scale: f32,
+}
+
+impl<'a> ParamVisitor for AddScaledGrads<'a> {
+    fn visit<T: Tensor<Dtype = f32>>(&mut self, _: &str, param: &T) {
+        if let Some(src) = self.src.remove(param) {
+            let dst = self.dst.mut_gradient(param);
+            T::Device::foreach_mr(dst, src.as_ref(), &mut |d, s| *d += s * self.scale);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+    use rand::{prelude::StdRng, SeedableRng};
+
+    #[test]
+    fn test_parallel_backward_matches_full_batch() {
+        type Model = (Linear<3, 4>, Tanh, Linear<4, 2>);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut model: Model = Default::default();
+        model.reset_params(&mut rng);
+
+        let x: Tensor2D<4, 3> = TensorCreator::randn(&mut rng);
+        let y: Tensor2D<4, 2> = TensorCreator::randn(&mut rng);
+        let full_loss = mse_loss(model.forward(x.trace()), y.clone());
+        let full_loss_value = *full_loss.data();
+        let full = backward(full_loss);
+
+        let chunks: [(Tensor2D<2, 3>, Tensor2D<2, 2>); 2] = [
+            (x.clone().select(&[0, 1]), y.clone().select(&[0, 1])),
+            (x.clone().select(&[2, 3]), y.clone().select(&[2, 3])),
+        ];
+        let (loss, gradients) = parallel_backward(&model, chunks, |m, (x, y)| {
+            mse_loss(m.forward(x.traced()), y)
+        });
+
+        assert_close(&[loss], &[full_loss_value]);
+        assert_close(
+            gradients.ref_gradient(&model.0.weight),
+            full.ref_gradient(&model.0.weight),
+        );
+        assert_close(
+            gradients.ref_gradient(&model.0.bias),
+            full.ref_gradient(&model.0.bias),
+        );
+        assert_close(
+            gradients.ref_gradient(&model.2.weight),
+            full.ref_gradient(&model.2.weight),
+        );
+        assert!(gradients.try_ref_gradient(&x).is_none());
+    }
+}
@@ -0,0 +1,171 @@
+//! Opt-in timing of the operations recorded on a tape, to find what is slow in a model.
+//!
+//! Between [start_profiling()] and [stop_profiling()], every operation that adds a backward op to an
+//! [crate::gradients::OwnedTape] is timed, and grouped by where in dfdx it was called from (e.g.
+//! `src/tensor_ops/matmul.rs:133:5`). The [Profile] returned by [stop_profiling()] can be printed:
+//!
+//! ```rust
+//! # use dfdx::prelude::*;
+//! # use dfdx::profiler::*;
+//! let model: (Linear<5, 3>, ReLU, Linear<3, 2>) = Default::default();
+//! start_profiling();
+//! let y = model.forward(Tensor2D::<4, 5>::zeros().traced());
+//! let gradients = backward(y.mean());
+//! let profile = stop_profiling();
+//! println!("{profile}");
+//! ```
+//!
+//! The forward pass of an operation is not timed by itself. Instead, [OpProfile::since_previous] is the
+//! time from the previous operation being recorded on the same thread to this one being recorded, which
+//! also includes anything else done in between, like loading data or creating tensors without a tape.
+//! It is only close to the forward time of the operation when operations are called back to back.
+//! The backward time is exactly the time its backward op took in [crate::gradients::GradientTape::execute()].
+//! Operations on [crate::gradients::NoneTape] tensors are not recorded.
+//!
+//! When profiling is not started, this only costs an atomic load per operation.
+//!
+//! Requires the "std" feature.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fmt;
+use std::panic::Location;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static RECORDS: Mutex<Option<HashMap<OpLocation, OpProfile>>> = Mutex::new(None);
+
+std::thread_local! {
+    static LAST_OP: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+type OpLocation = (&'static str, u32, u32);
+
+/// The timings of all operations called from one place.
+#[derive(Debug, Clone, Copy)]
+pub struct OpProfile {
+    /// Where the operation was recorded on the tape.
+    pub location: &'static Location<'static>,
+
+    /// The number of times it was called.
+    pub count: usize,
+
+    /// The total time since the previous operation was recorded on the same thread, each
+    /// time it was recorded. This is an upper bound on the time of its forward passes.
+    pub since_previous: Duration,
+
+    /// The total time of its backward ops.
+    pub backward: Duration,
+}
+
+impl OpProfile {
+    /// `self.since_previous + self.backward`
+    pub fn total(&self) -> Duration {
+        self.since_previous + self.backward
+    }
+}
+
+/// The result of [stop_profiling()], with the operations sorted by [OpProfile::total()],
+/// slowest first.
+#[derive(Debug, Clone)]
+pub struct Profile {
+    /// The timings of each place operations were called from.
+    pub ops: Vec<OpProfile>,
+}
+
+/// Starts recording operations. Clears any previous recordings.
+pub fn start_profiling() {
+    *RECORDS.lock().unwrap() = Some(HashMap::new());
+    LAST_OP.with(|last| last.set(Some(Instant::now())));
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Stops recording operations, and returns what was recorded since [start_profiling()].
+pub fn stop_profiling() -> Profile {
+    ENABLED.store(false, Ordering::Relaxed);
+    let records = RECORDS.lock().unwrap().take().unwrap_or_default();
+    let mut ops: Vec<OpProfile> = records.into_values().collect();
+    ops.sort_by_key(|op| std::cmp::Reverse(op.total()));
+    Profile { ops }
+}
+
+pub(crate) fn is_profiling() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Records an operation at `location`, whose forward has just finished.
+pub(crate) fn record_op(location: &'static Location<'static>) {
+    let now = Instant::now();
+    let elapsed = LAST_OP.with(|last| {
+        let elapsed = last.get().map_or(Duration::ZERO, |t| now - t);
+        last.set(Some(now));
+        elapsed
+    });
+    record(location, |op| {
+        op.count += 1;
+        op.since_previous += elapsed;
+    });
+}
+
+/// Records the backward of an operation at `location`.
+pub(crate) fn record_backward(location: &'static Location<'static>, elapsed: Duration) {
+    record(location, |op| op.backward += elapsed);
+}
+
+fn record<F: FnOnce(&mut OpProfile)>(location: &'static Location<'static>, f: F) {
+    if let Some(records) = RECORDS.lock().unwrap().as_mut() {
+        let key = (location.file(), location.line(), location.column());
+        f(records.entry(key).or_insert(OpProfile {
+            location,
+            count: 0,
+            since_previous: Duration::ZERO,
+            backward: Duration::ZERO,
+        }));
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<50} {:>8} {:>20} {:>14}",
+            "Location", "Count", "Since prev op (ms)", "Backward (ms)"
+        )?;
+        for op in self.ops.iter() {
+            writeln!(
+                f,
+                "{:<50} {:>8} {:>20.3} {:>14.3}",
+                std::format!("{}", op.location),
+                op.count,
+                op.since_previous.as_secs_f64() * 1e3,
+                op.backward.as_secs_f64() * 1e3,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_profile_records_ops() {
+        let x: Tensor1D<3> = tensor([1.0, 2.0, 3.0]);
+        start_profiling();
+        let y = x.trace().square().exp().sum();
+        let _ = backward(y);
+        let profile = stop_profiling();
+
+        assert!(profile.ops.len() >= 3);
+        assert!(profile
+            .ops
+            .iter()
+            .any(|op| op.location.file().ends_with("map.rs")));
+        assert!(!is_profiling());
+    }
+}
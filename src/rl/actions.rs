@@ -0,0 +1,174 @@
+use crate::prelude::*;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+use rand::Rng;
+
+/// Arrays of action values or logits, where the last axis is the action. `[f32; A]` picks one
+/// action (a `usize`), and `[[f32; A]; B]` picks one action for each of `B` states (a `[usize; B]`).
+///
+/// **Not intended to be used outside of the crate.**
+pub trait ActionValues {
+    /// The type of the picked actions.
+    type Actions;
+
+    /// Picks an action from each row of action values with `f`.
+    fn pick<F: FnMut(&[f32]) -> usize>(&self, f: F) -> Self::Actions;
+}
+
+impl<const A: usize> ActionValues for [f32; A] {
+    type Actions = usize;
+    fn pick<F: FnMut(&[f32]) -> usize>(&self, mut f: F) -> Self::Actions {
+        f(self)
+    }
+}
+
+impl<const B: usize, const A: usize> ActionValues for [[f32; A]; B] {
+    type Actions = [usize; B];
+    fn pick<F: FnMut(&[f32]) -> usize>(&self, mut f: F) -> Self::Actions {
+        core::array::from_fn(|b| f(&self[b]))
+    }
+}
+
+/// The action with the highest value, e.g. of Q values. For a batch of action values, one
+/// action for each row.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::rl::greedy_actions;
+/// let q_values = tensor([[1.0, 3.0, 2.0], [0.0, -1.0, -2.0]]);
+/// assert_eq!(greedy_actions(&q_values), [1, 0]);
+/// ```
+pub fn greedy_actions<T>(values: &T) -> <T::Array as ActionValues>::Actions
+where
+    T: HasArrayData,
+    T::Array: ActionValues,
+{
+    values.data().pick(argmax)
+}
+
+/// With probability `epsilon` a uniformly random action, otherwise the [greedy_actions()].
+/// Each row of a batch explores independently.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::rl::epsilon_greedy_actions;
+/// # use rand::{rngs::StdRng, SeedableRng};
+/// let mut rng = StdRng::seed_from_u64(0);
+/// let q_values: Tensor2D<4, 3> = TensorCreator::zeros();
+/// let actions: [usize; 4] = epsilon_greedy_actions(&q_values, 0.1, &mut rng);
+/// ```
+pub fn epsilon_greedy_actions<T, R: Rng>(
+    values: &T,
+    epsilon: f32,
+    rng: &mut R,
+) -> <T::Array as ActionValues>::Actions
+where
+    T: HasArrayData,
+    T::Array: ActionValues,
+{
+    values.data().pick(|row| {
+        if rng.gen::<f32>() < epsilon {
+            rng.gen_range(0..row.len())
+        } else {
+            argmax(row)
+        }
+    })
+}
+
+/// Samples actions from `softmax(logits / temperature)`. A higher `temperature` explores more,
+/// and a `temperature` of `0.0` picks the [greedy_actions()].
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::rl::sample_actions;
+/// # use rand::{rngs::StdRng, SeedableRng};
+/// let mut rng = StdRng::seed_from_u64(0);
+/// let logits = tensor([-100.0, 100.0]);
+/// assert_eq!(sample_actions(&logits, 1.0, &mut rng), 1);
+/// ```
+pub fn sample_actions<T, R: Rng>(
+    logits: &T,
+    temperature: f32,
+    rng: &mut R,
+) -> <T::Array as ActionValues>::Actions
+where
+    T: HasArrayData,
+    T::Array: ActionValues,
+{
+    logits.data().pick(|row| {
+        if temperature == 0.0 {
+            return argmax(row);
+        }
+        let max = row.iter().fold(f32::NEG_INFINITY, |a, &b| a.max(b));
+        let weight = |x: f32| ((x - max) / temperature).exp();
+        let total: f32 = row.iter().map(|&x| weight(x)).sum();
+        let mut u = rng.gen::<f32>() * total;
+        for (i, &x) in row.iter().enumerate() {
+            u -= weight(x);
+            if u < 0.0 {
+                return i;
+            }
+        }
+        row.len() - 1
+    })
+}
+
+/// The index of the first largest value.
+pub(crate) fn argmax(row: &[f32]) -> usize {
+    let mut best = 0;
+    for (i, &x) in row.iter().enumerate() {
+        if x > row[best] {
+            best = i;
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_greedy_actions() {
+        assert_eq!(greedy_actions(&tensor([1.0, 5.0, 5.0, -1.0])), 1);
+        let q: Tensor2D<2, 2, OwnedTape> = tensor([[0.0, 1.0], [1.0, 0.0]]).traced();
+        assert_eq!(greedy_actions(&q), [1, 0]);
+    }
+
+    #[test]
+    fn test_epsilon_greedy_actions() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let q = tensor([[0.0, 1.0, 0.0, 0.0]; 1000]);
+        assert_eq!(epsilon_greedy_actions(&q, 0.0, &mut rng), [1; 1000]);
+
+        let actions = epsilon_greedy_actions(&q, 0.4, &mut rng);
+        let counts = [0, 1, 2, 3].map(|a| actions.iter().filter(|&&b| a == b).count());
+        // 60% greedy + 10% random
+        assert!((650..750).contains(&counts[1]), "{counts:?}");
+        assert!(counts.iter().all(|&c| c > 70), "{counts:?}");
+
+        let a = epsilon_greedy_actions(&q, 0.5, &mut StdRng::seed_from_u64(1));
+        let b = epsilon_greedy_actions(&q, 0.5, &mut StdRng::seed_from_u64(1));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_sample_actions() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let logits = tensor([[0.0, 2.0_f32.ln()]; 3000]);
+        assert_eq!(sample_actions(&logits, 0.0, &mut rng), [1; 3000]);
+
+        let actions = sample_actions(&logits, 1.0, &mut rng);
+        let ones = actions.iter().filter(|&&a| a == 1).count();
+        assert!((1900..2100).contains(&ones), "{ones}");
+
+        // a high temperature is close to uniform
+        let actions = sample_actions(&logits, 100.0, &mut rng);
+        let ones = actions.iter().filter(|&&a| a == 1).count();
+        assert!((1400..1600).contains(&ones), "{ones}");
+    }
+}
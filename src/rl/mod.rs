@@ -0,0 +1,12 @@
+//! Utilities for reinforcement learning, such as [ReplayBuffer], [PrioritizedReplayBuffer],
+//! [polyak_update()], [generalized_advantages()], and [epsilon_greedy_actions()].
+
+mod actions;
+mod polyak;
+mod replay;
+mod returns;
+
+pub use actions::*;
+pub use polyak::*;
+pub use replay::*;
+pub use returns::*;
@@ -0,0 +1,50 @@
+use crate::nn::{zip_params_mut, VisitParams};
+
+/// Moves every parameter of `target` towards the corresponding parameter of `online`:
+/// `target = tau * online + (1 - tau) * target`. `tau = 1.0` copies `online` into `target`.
+///
+/// This is the soft target network update of DDPG, TD3, and SAC. Only parameters visited
+/// by [VisitParams] are updated, like [crate::optim::ModelEMA].
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::rl::polyak_update;
+/// type QNetwork = (Linear<4, 32>, ReLU, Linear<32, 2>);
+/// let q_net: QNetwork = Default::default();
+/// let mut target_q_net = q_net.clone();
+/// // -- snip training of q_net --
+/// polyak_update(&mut target_q_net, &q_net, 0.005);
+/// ```
+pub fn polyak_update<M: VisitParams>(target: &mut M, online: &M, tau: f32) {
+    zip_params_mut(target, online, |t, o| *t = *o * tau + *t * (1.0 - tau));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::tests::assert_close;
+
+    #[test]
+    fn test_polyak_update() {
+        let online: (Linear<2, 1>, ReLU) = (
+            Linear {
+                weight: tensor([[1.0, -1.0]]),
+                bias: tensor([2.0]),
+            },
+            ReLU,
+        );
+        let mut target = online.clone();
+        target.0.weight = tensor([[0.0, 0.0]]);
+        target.0.bias = tensor([0.0]);
+
+        polyak_update(&mut target, &online, 0.25);
+        assert_close(target.0.weight.data(), &[[0.25, -0.25]]);
+        assert_close(target.0.bias.data(), &[0.5]);
+
+        polyak_update(&mut target, &online, 1.0);
+        assert_eq!(target.0.weight.data(), online.0.weight.data());
+        assert_eq!(target.0.bias.data(), online.0.bias.data());
+    }
+}
@@ -0,0 +1,348 @@
+use crate::data::{Dataset, Stack};
+use crate::prelude::*;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+use rand::prelude::*;
+use std::vec::Vec;
+
+/// A replay memory that stores the last `capacity` items, like transitions
+/// `(state, action, reward, next_state, done)`, and samples batches of them uniformly.
+/// Batches are stacked with [Stack].
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::rl::ReplayBuffer;
+/// let mut buffer = ReplayBuffer::new(1000, 0);
+/// for i in 0..10 {
+///     let state: Tensor1D<4> = TensorCreator::zeros();
+///     buffer.push((state.clone(), i % 2, 1.0, state, 0.0));
+/// }
+/// let (s, a, r, s_next, done): (Tensor2D<8, 4>, [usize; 8], Tensor1D<8>, Tensor2D<8, 4>, Tensor1D<8>) =
+///     buffer.sample();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReplayBuffer<T> {
+    items: Vec<T>,
+    capacity: usize,
+    next: usize,
+    rng: StdRng,
+}
+
+impl<T> ReplayBuffer<T> {
+    /// An empty buffer of up to `capacity` items, sampling with an rng seeded with `seed`.
+    pub fn new(capacity: usize, seed: u64) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        Self {
+            items: Vec::with_capacity(capacity),
+            capacity,
+            next: 0,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// The number of items stored.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether there are no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The maximum number of items.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Adds `item`, replacing the oldest item if the buffer is full.
+    pub fn push(&mut self, item: T) {
+        push(&mut self.items, &mut self.next, self.capacity, item);
+    }
+
+    /// Samples `B` items uniformly with replacement, and stacks them. Panics if the buffer is empty.
+    pub fn sample<const B: usize>(&mut self) -> T::Stacked
+    where
+        T: Stack<B> + Clone,
+    {
+        assert!(!self.is_empty(), "can't sample from an empty buffer");
+        let len = self.len();
+        let items = core::array::from_fn(|_| self.items[self.rng.gen_range(0..len)].clone());
+        T::stack(items)
+    }
+}
+
+/// The stored items, in the order of the slots they are stored in.
+impl<T: Clone> Dataset for ReplayBuffer<T> {
+    type Item = T;
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+    fn get(&self, index: usize) -> Self::Item {
+        self.items[index].clone()
+    }
+}
+
+/// A [ReplayBuffer] that samples items in proportion to their priority to the power of `alpha`,
+/// as in [Prioritized Experience Replay](https://arxiv.org/abs/1511.05952). New items get the
+/// highest priority seen so far, and [PrioritizedReplayBuffer::update_priorities()] sets the priority of sampled
+/// items, usually to the absolute value of their TD error.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::rl::PrioritizedReplayBuffer;
+/// let mut buffer = PrioritizedReplayBuffer::new(1000, 0.6, 0);
+/// for i in 0..10 {
+///     buffer.push((i as f32, i % 2));
+/// }
+/// let ((x, y), indices, weights): ((Tensor1D<4>, [usize; 4]), _, Tensor1D<4>) = buffer.sample(0.4);
+/// let td_errors = [0.5, 0.1, 2.0, 0.0];
+/// buffer.update_priorities(&indices, &td_errors);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PrioritizedReplayBuffer<T> {
+    items: Vec<T>,
+    capacity: usize,
+    next: usize,
+    tree: SumTree,
+    alpha: f32,
+    max_priority: f32,
+    rng: StdRng,
+}
+
+impl<T> PrioritizedReplayBuffer<T> {
+    /// The smallest priority, so every item can be sampled.
+    const MIN_PRIORITY: f32 = 1e-6;
+
+    /// An empty buffer of up to `capacity` items, sampling with an rng seeded with `seed`.
+    /// `alpha` is how much the priorities matter, `0.0` samples uniformly.
+    pub fn new(capacity: usize, alpha: f32, seed: u64) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        Self {
+            items: Vec::with_capacity(capacity),
+            capacity,
+            next: 0,
+            tree: SumTree::new(capacity),
+            alpha,
+            max_priority: 1.0,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// The number of items stored.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether there are no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The maximum number of items.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Adds `item` with the highest priority so far, replacing the oldest item if the buffer is full.
+    pub fn push(&mut self, item: T) {
+        let index = self.next;
+        push(&mut self.items, &mut self.next, self.capacity, item);
+        self.tree
+            .set(index, self.max_priority.powf(self.alpha) as f64);
+    }
+
+    /// Samples `B` items in proportion to their priority, and stacks them. Also returns the indices
+    /// of the items for [PrioritizedReplayBuffer::update_priorities()], and importance sampling
+    /// weights `(len * P(i))^-beta`, normalized so the largest weight of the batch is `1.0`.
+    ///
+    /// Panics if the buffer is empty.
+    pub fn sample<const B: usize>(&mut self, beta: f32) -> (T::Stacked, [usize; B], Tensor1D<B>)
+    where
+        T: Stack<B> + Clone,
+    {
+        assert!(!self.is_empty(), "can't sample from an empty buffer");
+        // one sample from each of `B` equal segments of the total priority
+        let total = self.tree.total();
+        let segment = total / B as f64;
+        let indices: [usize; B] = core::array::from_fn(|i| {
+            let u = (i as f64 + self.rng.gen::<f64>()) * segment;
+            self.tree.find(u).min(self.len() - 1)
+        });
+
+        let len = self.len() as f32;
+        let mut weights = indices.map(|i| (len * (self.tree.get(i) / total) as f32).powf(-beta));
+        let max = weights.iter().fold(0.0f32, |a, &b| a.max(b));
+        weights.iter_mut().for_each(|w| *w /= max);
+
+        let items = indices.map(|i| self.items[i].clone());
+        (T::stack(items), indices, TensorCreator::new(weights))
+    }
+
+    /// Sets the priorities of the items at `indices`, which are returned by
+    /// [PrioritizedReplayBuffer::sample()].
+    pub fn update_priorities<const B: usize>(
+        &mut self,
+        indices: &[usize; B],
+        priorities: &[f32; B],
+    ) {
+        for (&i, &p) in indices.iter().zip(priorities.iter()) {
+            assert!(i < self.len(), "index out of bounds");
+            let p = p.abs().max(Self::MIN_PRIORITY);
+            self.max_priority = self.max_priority.max(p);
+            self.tree.set(i, p.powf(self.alpha) as f64);
+        }
+    }
+}
+
+fn push<T>(items: &mut Vec<T>, next: &mut usize, capacity: usize, item: T) {
+    if items.len() < capacity {
+        items.push(item);
+    } else {
+        items[*next] = item;
+    }
+    *next = (*next + 1) % capacity;
+}
+
+/// A binary tree where each node is the sum of its children, so an item can be sampled in
+/// proportion to its value in `O(log n)`. The leaves are at `n..2n`.
+#[derive(Debug, Clone)]
+struct SumTree {
+    nodes: Vec<f64>,
+}
+
+impl SumTree {
+    fn new(n: usize) -> Self {
+        Self {
+            nodes: alloc::vec![0.0; 2 * n],
+        }
+    }
+
+    fn n(&self) -> usize {
+        self.nodes.len() / 2
+    }
+
+    fn total(&self) -> f64 {
+        self.nodes[1]
+    }
+
+    fn get(&self, index: usize) -> f64 {
+        self.nodes[self.n() + index]
+    }
+
+    fn set(&mut self, index: usize, value: f64) {
+        let mut i = self.n() + index;
+        self.nodes[i] = value;
+        while i > 1 {
+            i /= 2;
+            self.nodes[i] = self.nodes[2 * i] + self.nodes[2 * i + 1];
+        }
+    }
+
+    /// The index of the leaf where the sum of the leaves before it (in tree order) reaches `u`.
+    fn find(&self, mut u: f64) -> usize {
+        let n = self.n();
+        let mut i = 1;
+        while i < n {
+            let left = self.nodes[2 * i];
+            if u < left || self.nodes[2 * i + 1] == 0.0 {
+                i *= 2;
+            } else {
+                u -= left;
+                i = 2 * i + 1;
+            }
+        }
+        i - n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_buffer_overwrites_oldest() {
+        let mut buffer = ReplayBuffer::new(3, 0);
+        for i in 0..5usize {
+            buffer.push(i);
+        }
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.capacity(), 3);
+        let mut items: Vec<usize> = (0..3).map(|i| buffer.get(i)).collect();
+        items.sort_unstable();
+        assert_eq!(items, [2, 3, 4]);
+
+        let batch: [usize; 100] = buffer.sample();
+        assert!(batch.iter().all(|i| (2..5).contains(i)));
+        assert!((2..5).all(|i| batch.contains(&i)));
+    }
+
+    #[test]
+    fn test_replay_buffer_stacks_transitions() {
+        let mut buffer = ReplayBuffer::new(10, 0);
+        buffer.push((tensor([1.0, 2.0]), 1, 0.5, tensor([3.0, 4.0]), 1.0));
+        let (s, a, r, s_next, done): (
+            Tensor2D<2, 2>,
+            [usize; 2],
+            Tensor1D<2>,
+            Tensor2D<2, 2>,
+            Tensor1D<2>,
+        ) = buffer.sample();
+        assert_eq!(s.data(), &[[1.0, 2.0]; 2]);
+        assert_eq!(a, [1; 2]);
+        assert_eq!(r.data(), &[0.5; 2]);
+        assert_eq!(s_next.data(), &[[3.0, 4.0]; 2]);
+        assert_eq!(done.data(), &[1.0; 2]);
+    }
+
+    #[test]
+    fn test_sum_tree() {
+        let mut tree = SumTree::new(5);
+        for (i, v) in [1.0, 0.0, 2.0, 3.0, 4.0].into_iter().enumerate() {
+            tree.set(i, v);
+        }
+        assert_eq!(tree.total(), 10.0);
+        let mut counts = [0.0; 5];
+        for k in 0..1000 {
+            counts[tree.find(k as f64 / 100.0)] += 0.01;
+        }
+        assert_eq!(counts.map(|c: f64| c.round()), [1.0, 0.0, 2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn test_prioritized_sampling() {
+        let mut buffer = PrioritizedReplayBuffer::new(4, 1.0, 0);
+        for i in 0..4usize {
+            buffer.push(i);
+        }
+        buffer.update_priorities(&[0, 1, 2, 3], &[1.0, 0.0, 3.0, -4.0]);
+
+        let mut counts = [0; 4];
+        for _ in 0..100 {
+            let (batch, indices, _): ([usize; 8], [usize; 8], Tensor1D<8>) = buffer.sample(0.5);
+            assert_eq!(batch, indices);
+            for i in batch {
+                counts[i] += 1;
+            }
+        }
+        assert_eq!(counts[1], 0);
+        assert!((80..120).contains(&counts[0]), "{counts:?}");
+        assert!(counts[3] > counts[2] && counts[2] > counts[0], "{counts:?}");
+
+        let (_, indices, weights): ([usize; 4], [usize; 4], Tensor1D<4>) = buffer.sample(1.0);
+        let expected = indices.map(|i| [1.0, 0.0, 1.0 / 3.0, 0.25][i]);
+        let max = expected.iter().fold(0.0f32, |a, &b| a.max(b));
+        crate::tests::assert_close(weights.data(), &expected.map(|w| w / max));
+    }
+
+    #[test]
+    fn test_prioritized_push_uses_max_priority() {
+        let mut buffer = PrioritizedReplayBuffer::new(2, 1.0, 0);
+        buffer.push(0usize);
+        buffer.update_priorities(&[0], &[5.0]);
+        buffer.push(1);
+        assert_eq!(buffer.tree.get(1), 5.0);
+    }
+}
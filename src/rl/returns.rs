@@ -0,0 +1,195 @@
+use crate::devices::ForEachElement;
+use crate::gradients::Tape;
+use crate::prelude::*;
+use crate::unique_id::HasUniqueId;
+
+/// Arrays whose first axis is time, like the rewards of an episode (`[f32; S]`), or of a
+/// batch of `N` environments (`[[f32; N]; S]`).
+///
+/// **Not intended to be used outside of the crate.**
+pub trait TimeMajor {
+    /// `x[t] += gamma * (1 - dones[t]) * x[t + 1]` from the last step to the first if `reverse`,
+    /// otherwise `x[t] += gamma * (1 - dones[t - 1]) * x[t - 1]` from the first step to the last.
+    fn scan(x: &mut Self, gamma: f32, dones: &Self, reverse: bool);
+}
+
+impl<const S: usize> TimeMajor for [f32; S] {
+    fn scan(x: &mut Self, gamma: f32, dones: &Self, reverse: bool) {
+        if reverse {
+            for t in (0..S.saturating_sub(1)).rev() {
+                x[t] += gamma * (1.0 - dones[t]) * x[t + 1];
+            }
+        } else {
+            for t in 1..S {
+                x[t] += gamma * (1.0 - dones[t - 1]) * x[t - 1];
+            }
+        }
+    }
+}
+
+impl<const S: usize, const N: usize> TimeMajor for [[f32; N]; S] {
+    fn scan(x: &mut Self, gamma: f32, dones: &Self, reverse: bool) {
+        if reverse {
+            for t in (0..S.saturating_sub(1)).rev() {
+                for n in 0..N {
+                    x[t][n] += gamma * (1.0 - dones[t][n]) * x[t + 1][n];
+                }
+            }
+        } else {
+            for t in 1..S {
+                for n in 0..N {
+                    x[t][n] += gamma * (1.0 - dones[t - 1][n]) * x[t - 1][n];
+                }
+            }
+        }
+    }
+}
+
+/// A reverse cumulative sum along the time axis (the first axis), discounted by `gamma`, that
+/// restarts after each step where `dones` is `1.0`:
+/// `y[t] = x[t] + gamma * (1 - dones[t]) * y[t + 1]`.
+///
+/// Implemented for [Tensor1D] (one sequence) and [Tensor2D] (time by a batch of sequences).
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::rl::discounted_cumsum;
+/// let x = tensor([1.0, 2.0, 3.0, 4.0]);
+/// let dones = tensor([0.0, 1.0, 0.0, 0.0]);
+/// let y = discounted_cumsum(x, &dones, 0.5);
+/// assert_eq!(y.data(), &[2.0, 2.0, 5.0, 4.0]);
+/// ```
+pub fn discounted_cumsum<T>(x: T, dones: &T::NoTape, gamma: f32) -> T
+where
+    T: Tensor<Dtype = f32>,
+    T::Array: TimeMajor,
+{
+    let (x, mut tape) = x.split_tape();
+    let mut y = x.data().clone();
+    TimeMajor::scan(&mut y, gamma, dones.data(), true);
+    let result = T::NoTape::new(y);
+    let phantom_result = result.clone();
+    let dones = dones.clone();
+    tape.add_backward_op_with_ids(&[*x.id()], &[*result.id()], move |grads| {
+        let (x_grad, result_grad) = grads.mut_and_ref(&x, &phantom_result);
+        // each x[t] contributes to y[..=t], so its gradient is a forward discounted cumsum
+        let mut g = result_grad.clone();
+        TimeMajor::scan(&mut g, gamma, dones.data(), false);
+        T::Device::foreach_mr(x_grad, &g, &mut |x_grad, g| *x_grad += g);
+    });
+    result.put_tape(tape)
+}
+
+/// The discounted return of each step: `returns[t] = rewards[t] + gamma * (1 - dones[t]) * returns[t + 1]`.
+/// Time is the first axis. See [discounted_cumsum()].
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::rl::discounted_returns;
+/// let rewards = tensor([1.0, 1.0, 1.0]);
+/// let dones = tensor([0.0, 0.0, 1.0]);
+/// let returns = discounted_returns(rewards, &dones, 0.5);
+/// assert_eq!(returns.data(), &[1.75, 1.5, 1.0]);
+/// ```
+pub fn discounted_returns<T>(rewards: T, dones: &T::NoTape, gamma: f32) -> T
+where
+    T: Tensor<Dtype = f32>,
+    T::Array: TimeMajor,
+{
+    discounted_cumsum(rewards, dones, gamma)
+}
+
+/// [Generalized Advantage Estimation](https://arxiv.org/abs/1506.02438) of each step, with time
+/// as the first axis:
+/// 1. `delta[t] = rewards[t] + gamma * (1 - dones[t]) * next_values[t] - values[t]`
+/// 2. `advantages[t] = delta[t] + gamma * lambda * (1 - dones[t]) * advantages[t + 1]`
+///
+/// `next_values[t]` is the value of the state after step `t`, which is `values[t + 1]` except
+/// at the last step. Gradients flow back to `values`, and `values + advantages` are the targets
+/// for the value function.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::rl::generalized_advantages;
+/// let values = tensor([1.0, 2.0]);
+/// let rewards = tensor([0.0, 1.0]);
+/// let next_values = tensor([2.0, 0.0]);
+/// let dones = tensor([0.0, 1.0]);
+/// let advantages = generalized_advantages(values, &rewards, &next_values, &dones, 1.0, 0.5);
+/// assert_eq!(advantages.data(), &[0.5, -1.0]);
+/// ```
+pub fn generalized_advantages<T>(
+    values: T,
+    rewards: &T::NoTape,
+    next_values: &T::NoTape,
+    dones: &T::NoTape,
+    gamma: f32,
+    lambda: f32,
+) -> T
+where
+    T: Tensor<Dtype = f32>,
+    T::Array: TimeMajor,
+{
+    let mut targets = rewards.clone();
+    T::Device::foreach_mrr(
+        targets.mut_data(),
+        next_values.data(),
+        dones.data(),
+        &mut |r, v, d| *r += gamma * (1.0 - d) * v,
+    );
+    let deltas = negate(sub(values, targets));
+    discounted_cumsum(deltas, dones, gamma * lambda)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+
+    #[test]
+    fn test_discounted_cumsum_1d() {
+        let x = tensor([1.0, 2.0, 3.0, 4.0]);
+        let dones = tensor([0.0, 1.0, 0.0, 0.0]);
+        let r = discounted_cumsum(x.trace(), &dones, 0.5);
+        assert_eq!(r.data(), &[2.0, 2.0, 5.0, 4.0]);
+        let g = r.sum().backward();
+        assert_eq!(g.ref_gradient(&x), &[1.0, 1.5, 1.0, 1.5]);
+    }
+
+    #[test]
+    fn test_discounted_cumsum_2d() {
+        let x = tensor([[1.0, 1.0], [1.0, 1.0], [1.0, 1.0]]);
+        let dones = tensor([[0.0, 0.0], [0.0, 1.0], [0.0, 0.0]]);
+        let r = discounted_cumsum(x.trace(), &dones, 0.9);
+        assert_close(r.data(), &[[2.71, 1.9], [1.9, 1.0], [1.0, 1.0]]);
+        let g = (r * tensor([[1.0, 1.0], [0.0, 0.0], [0.0, 1.0]]))
+            .sum::<_, AllAxes>()
+            .backward();
+        assert_close(g.ref_gradient(&x), &[[1.0, 1.0], [0.9, 0.9], [0.81, 1.0]]);
+    }
+
+    #[test]
+    fn test_discounted_returns_empty() {
+        let r = discounted_returns(Tensor1D::<0>::zeros(), &Tensor1D::zeros(), 0.99);
+        assert_eq!(r.data(), &[0.0; 0]);
+    }
+
+    #[test]
+    fn test_generalized_advantages() {
+        let values = tensor([1.0, 2.0]);
+        let r = generalized_advantages(
+            values.trace(),
+            &tensor([0.0, 1.0]),
+            &tensor([2.0, 0.0]),
+            &tensor([0.0, 1.0]),
+            1.0,
+            0.5,
+        );
+        assert_eq!(r.data(), &[0.5, -1.0]);
+        let g = r.sum().backward();
+        assert_eq!(g.ref_gradient(&values), &[-1.0, -1.5]);
+    }
+}
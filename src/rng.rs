@@ -0,0 +1,180 @@
+//! A crate wide seed, for reproducible runs without passing a rng everywhere.
+//!
+//! [default_rng()] returns a new [StdRng] every time it is called, seeded with the value passed to
+//! [manual_seed()] (`0` by default) and the number of times it has been called since. It is used
+//! for the rngs of [crate::nn::Dropout], [crate::nn::DropoutOneIn], and [crate::nn::DropPath],
+//! and can be passed anywhere else a rng is needed:
+//!
+//! ```rust
+//! # use dfdx::prelude::*;
+//! manual_seed(42);
+//! let mut model: (Linear<5, 3>, Dropout) = Default::default();
+//! model.reset_params(&mut default_rng());
+//! let x: Tensor1D<5> = TensorCreator::randn(&mut default_rng());
+//! ```
+//!
+//! So a program that does the same things in the same order after the same [manual_seed()]
+//! gets the same results. All of the CPU kernels in [crate::devices] are single threaded
+//! and visit elements in a fixed order, so reductions (like [crate::tensor_ops::sum()]) are also
+//! reproducible. Note that [crate::tensor_ops::matmul()] uses different SIMD kernels
+//! depending on the CPU, so results may differ in the last bits across machines.
+//!
+//! Calls from multiple threads share the same counter, so the order they call [default_rng()]
+//! determines which rng they get.
+//!
+//! Tensors of random values can be created with [rand()] and [randn()], which use [default_rng()]:
+//!
+//! ```rust
+//! # use dfdx::prelude::*;
+//! manual_seed(0);
+//! let a: Tensor2D<2, 3> = randn();
+//! manual_seed(0);
+//! assert_eq!(randn::<Tensor2D<2, 3>>().data(), a.data());
+//! ```
+
+use crate::tensor::TensorCreator;
+use rand::{distributions::Distribution, rngs::StdRng, SeedableRng};
+use rand_distr::{Standard, StandardNormal};
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+static SEED_LO: AtomicU32 = AtomicU32::new(0);
+static SEED_HI: AtomicU32 = AtomicU32::new(0);
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Held by tests that change the seed, since tests run in parallel.
+#[cfg(test)]
+pub(crate) static TEST_SEED_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Sets the seed of [default_rng()], and restarts its sequence of rngs.
+pub fn manual_seed(seed: u64) {
+    SEED_LO.store(seed as u32, Ordering::Relaxed);
+    SEED_HI.store((seed >> 32) as u32, Ordering::Relaxed);
+    COUNTER.store(0, Ordering::Relaxed);
+}
+
+/// Returns the next [StdRng] in the sequence started by [manual_seed()].
+/// Each call returns a different rng.
+pub fn default_rng() -> StdRng {
+    let i = COUNTER.fetch_add(1, Ordering::Relaxed);
+    nth_rng(seed(), i as u64)
+}
+
+/// Creates a tensor of values sampled uniformly from `[0, 1)` with [default_rng()].
+/// See [TensorCreator::rand()].
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let t: Tensor1D<5> = rand();
+/// assert!(t.data().iter().all(|&x| (0.0..1.0).contains(&x)));
+/// ```
+pub fn rand<T: TensorCreator>() -> T
+where
+    Standard: Distribution<T::Dtype>,
+{
+    T::rand(&mut default_rng())
+}
+
+/// Creates a tensor of values sampled from the standard normal distribution with [default_rng()].
+/// See [TensorCreator::randn()].
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let t: Tensor2D<2, 3> = randn();
+/// ```
+pub fn randn<T: TensorCreator>() -> T
+where
+    StandardNormal: Distribution<T::Dtype>,
+{
+    T::randn(&mut default_rng())
+}
+
+/// Where [default_rng()] is in its sequence of rngs. See [rng_state()].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RngState {
+    /// The value passed to [manual_seed()].
+    pub seed: u64,
+
+    /// The number of times [default_rng()] has been called since.
+    pub counter: usize,
+}
+
+/// Returns where [default_rng()] is in its sequence, so it can be continued later
+/// with [set_rng_state()], e.g. when resuming training.
+pub fn rng_state() -> RngState {
+    RngState {
+        seed: seed(),
+        counter: COUNTER.load(Ordering::Relaxed),
+    }
+}
+
+/// Continues the sequence of [default_rng()] from `state`, which was returned by [rng_state()].
+pub fn set_rng_state(state: RngState) {
+    manual_seed(state.seed);
+    COUNTER.store(state.counter, Ordering::Relaxed);
+}
+
+fn seed() -> u64 {
+    let lo = SEED_LO.load(Ordering::Relaxed) as u64;
+    let hi = SEED_HI.load(Ordering::Relaxed) as u64;
+    (hi << 32) | lo
+}
+
+fn nth_rng(seed: u64, i: u64) -> StdRng {
+    StdRng::seed_from_u64(seed ^ i.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_manual_seed() {
+        let _lock = TEST_SEED_LOCK.lock().unwrap();
+        manual_seed(u64::MAX - 5);
+        assert_eq!(seed(), u64::MAX - 5);
+        manual_seed(0);
+        assert_eq!(seed(), 0);
+    }
+
+    #[test]
+    fn test_set_rng_state() {
+        let _lock = TEST_SEED_LOCK.lock().unwrap();
+        set_rng_state(RngState {
+            seed: 3,
+            counter: 7,
+        });
+        // other tests may call default_rng() in between
+        let state = rng_state();
+        assert_eq!(state.seed, 3);
+        assert!(state.counter >= 7);
+        manual_seed(0);
+        assert_eq!(seed(), 0);
+    }
+
+    #[test]
+    fn test_rand_randn() {
+        let _lock = TEST_SEED_LOCK.lock().unwrap();
+        manual_seed(5);
+        let a: Tensor1D<8> = rand();
+        let b: Tensor1D<8> = randn();
+        // other tests may call default_rng() in between, so check against every rng it could have returned
+        let n = rng_state().counter as u64;
+        assert!((0..n).any(|i| Tensor1D::rand(&mut nth_rng(5, i)).data() == a.data()));
+        assert!((0..n).any(|i| Tensor1D::randn(&mut nth_rng(5, i)).data() == b.data()));
+        assert_ne!(a.data(), b.data());
+        manual_seed(0);
+    }
+
+    #[test]
+    fn test_nth_rng_differs() {
+        let a: u64 = nth_rng(0, 0).gen();
+        assert_eq!(a, nth_rng(0, 0).gen::<u64>());
+        assert_ne!(a, nth_rng(0, 1).gen::<u64>());
+        assert_ne!(a, nth_rng(1, 0).gen::<u64>());
+        assert_ne!(nth_rng(0, 1).gen::<u64>(), nth_rng(1, 1).gen::<u64>());
+    }
+}
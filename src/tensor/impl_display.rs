@@ -0,0 +1,170 @@
+use super::*;
+use crate::arrays::{CountElements, HasArrayData, HasArrayType, HasShape};
+use alloc::format;
+use std::{fmt, string::String, vec::Vec};
+
+/// Tensors with more elements than this are summarized when displayed.
+pub const DISPLAY_THRESHOLD: usize = 1000;
+
+/// The number of items at the start & end of each axis shown when summarizing.
+pub const DISPLAY_EDGE_ITEMS: usize = 3;
+
+/// Writes the name & shape of the tensor, and then its data as nested arrays with
+/// every element aligned to the same width.
+fn display<A>(data: &A, name: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result
+where
+    A: CountElements<Dtype = f32> + HasShape,
+{
+    let shape = A::shape();
+    // the elements of nested arrays are contiguous, so they can be viewed as a flat slice
+    let flat = unsafe { std::slice::from_raw_parts(data.ref_first_elem(), A::NUM_ELEMENTS) };
+    let summarize = !f.alternate() && A::NUM_ELEMENTS > DISPLAY_THRESHOLD;
+    let precision = f.precision().unwrap_or(4);
+
+    let shown: Vec<usize> = shown_indices(&shape, summarize);
+    let elems: Vec<String> = shown
+        .iter()
+        .map(|&i| format!("{:.*}", precision, flat[i]))
+        .collect();
+    let width = elems.iter().map(String::len).max().unwrap_or(0);
+
+    writeln!(f, "{name} {shape:?}")?;
+    let mut elems = elems.iter();
+    write_axis(f, &shape, 0, summarize, &mut || {
+        format!("{:>width$}", elems.next().unwrap())
+    })
+}
+
+/// The flat indices of the elements that are shown, in order.
+fn shown_indices(shape: &[usize], summarize: bool) -> Vec<usize> {
+    let mut indices = alloc::vec![0];
+    for &size in shape {
+        let axis: Vec<usize> = shown_axis(size, summarize).into_iter().flatten().collect();
+        indices = indices
+            .iter()
+            .flat_map(|i| axis.iter().map(move |j| i * size + j))
+            .collect();
+    }
+    indices
+}
+
+/// The indices of an axis of `size` that are shown, where the ellipsis is between the two parts.
+fn shown_axis(size: usize, summarize: bool) -> [core::ops::Range<usize>; 2] {
+    if summarize && size > 2 * DISPLAY_EDGE_ITEMS {
+        [0..DISPLAY_EDGE_ITEMS, size - DISPLAY_EDGE_ITEMS..size]
+    } else {
+        [0..size, size..size]
+    }
+}
+
+fn write_axis<F: FnMut() -> String>(
+    f: &mut fmt::Formatter<'_>,
+    shape: &[usize],
+    depth: usize,
+    summarize: bool,
+    next: &mut F,
+) -> fmt::Result {
+    let Some((&size, inner)) = shape.split_first() else {
+        return write!(f, "{}", next());
+    };
+    let [head, tail] = shown_axis(size, summarize);
+    let truncated = tail.start != head.end;
+    // rows of matrices go on their own lines, and matrices are separated by blank lines
+    let separator = if inner.is_empty() {
+        String::from(", ")
+    } else {
+        format!(
+            ",{}{:depth$}",
+            "\n".repeat(inner.len()),
+            "",
+            depth = depth + 1
+        )
+    };
+    write!(f, "[")?;
+    for i in head.clone() {
+        if i > 0 {
+            write!(f, "{separator}")?;
+        }
+        write_axis(f, inner, depth + 1, summarize, next)?;
+    }
+    if truncated {
+        write!(f, "{separator}...")?;
+    }
+    for i in tail {
+        if i > 0 {
+            write!(f, "{separator}")?;
+        }
+        write_axis(f, inner, depth + 1, summarize, next)?;
+    }
+    write!(f, "]")
+}
+
+macro_rules! tensor_impl {
+    ($typename:ident, [$($Vs:tt),*]) => {
+/// Shows the shape of the tensor & its data, for example:
+/// ```text
+/// Tensor2D [2, 3]
+/// [[ 1.0000, -2.0000,  3.0000],
+///  [ 4.0000,  5.0000,  6.0000]]
+/// ```
+/// The precision (e.g. `{:.2}`) defaults to 4 digits. Tensors with more than [DISPLAY_THRESHOLD]
+/// elements only show the first & last [DISPLAY_EDGE_ITEMS] of each axis, unless the alternate
+/// flag (`{:#}`) is used.
+impl<$(const $Vs: usize, )* H> fmt::Display for $typename<$($Vs, )* H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        display::<<Self as HasArrayType>::Array>(self.data(), stringify!($typename), f)
+    }
+}
+    };
+}
+
+tensor_impl!(Tensor0D, []);
+tensor_impl!(Tensor1D, [M]);
+tensor_impl!(Tensor2D, [M, N]);
+tensor_impl!(Tensor3D, [M, N, O]);
+tensor_impl!(Tensor4D, [M, N, O, P]);
+tensor_impl!(Tensor5D, [M, N, O, P, Q]);
+tensor_impl!(Tensor6D, [M, N, O, P, Q, R]);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_display_small() {
+        assert_eq!(format!("{}", tensor(1.5)), "Tensor0D []\n1.5000");
+        assert_eq!(
+            format!("{:.1}", tensor([1.0, -20.0, 3.25])),
+            "Tensor1D [3]\n[  1.0, -20.0,   3.2]"
+        );
+        assert_eq!(
+            format!("{:.0}", tensor([[1.0, 2.0], [3.0, 4.0]])),
+            "Tensor2D [2, 2]\n[[1, 2],\n [3, 4]]"
+        );
+        let t: Tensor3D<2, 1, 2, OwnedTape> = Tensor3D::zeros().traced();
+        assert_eq!(
+            format!("{:.0}", t),
+            "Tensor3D [2, 1, 2]\n[[[0, 0]],\n\n [[0, 0]]]"
+        );
+    }
+
+    #[test]
+    fn test_display_summarized() {
+        let mut t: Tensor2D<100, 20> = TensorCreator::zeros();
+        for (i, x) in t.mut_data().iter_mut().flatten().enumerate() {
+            *x = i as f32;
+        }
+        let s = format!("{:.0}", t);
+        let lines: Vec<&str> = s.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[0], "Tensor2D [100, 20]");
+        assert_eq!(lines[1], "[[   0,    1,    2, ...,   17,   18,   19],");
+        assert_eq!(lines[4], " ...,");
+        assert_eq!(lines[7], " [1980, 1981, 1982, ..., 1997, 1998, 1999]]");
+
+        let s = format!("{:#.0}", t);
+        assert_eq!(s.lines().count(), 101);
+        assert!(!s.contains("..."));
+    }
+}
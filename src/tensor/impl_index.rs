@@ -0,0 +1,99 @@
+use super::*;
+use crate::arrays::HasArrayData;
+use core::ops::{Index, IndexMut};
+
+macro_rules! index_impl {
+    ($typename:ident, [$($Vs:tt),*], $inner:ty, ($($Idx:ident),*), $elem:ident) => {
+/// Indexes the underlying array along the first axis, like `t.data()[i]`.
+/// Writing through this is like [HasArrayData::mut_data()], so it copies the data
+/// if it is shared with clones of the tensor.
+impl<$(const $Vs: usize, )* H> Index<usize> for $typename<$($Vs, )* H> {
+    type Output = $inner;
+    fn index(&self, i: usize) -> &Self::Output {
+        &self.data()[i]
+    }
+}
+
+impl<$(const $Vs: usize, )* H> IndexMut<usize> for $typename<$($Vs, )* H> {
+    fn index_mut(&mut self, i: usize) -> &mut Self::Output {
+        &mut self.mut_data()[i]
+    }
+}
+
+index_impl!(@elem $typename, [$($Vs),*], ($($Idx),*), $elem);
+    };
+
+    (@elem $typename:ident, [$($Vs:tt),*], ($($Idx:ident),*), true) => {
+/// Indexes a single element, like `t.data()[i][j]` for `t[(i, j)]`.
+impl<$(const $Vs: usize, )* H> Index<($(index_impl!(@usize $Idx)),*)> for $typename<$($Vs, )* H> {
+    type Output = f32;
+    fn index(&self, ($($Idx),*): ($(index_impl!(@usize $Idx)),*)) -> &Self::Output {
+        &self.data()$([$Idx])*
+    }
+}
+
+impl<$(const $Vs: usize, )* H> IndexMut<($(index_impl!(@usize $Idx)),*)> for $typename<$($Vs, )* H> {
+    fn index_mut(&mut self, ($($Idx),*): ($(index_impl!(@usize $Idx)),*)) -> &mut Self::Output {
+        &mut self.mut_data()$([$Idx])*
+    }
+}
+    };
+
+    (@elem $typename:ident, [$($Vs:tt),*], ($($Idx:ident),*), false) => {};
+
+    (@usize $Idx:ident) => { usize };
+}
+
+index_impl!(Tensor1D, [M], f32, (i), false);
+index_impl!(Tensor2D, [M, N], [f32; N], (i, j), true);
+index_impl!(Tensor3D, [M, N, O], [[f32; O]; N], (i, j, k), true);
+index_impl!(
+    Tensor4D,
+    [M, N, O, P],
+    [[[f32; P]; O]; N],
+    (i, j, k, l),
+    true
+);
+index_impl!(
+    Tensor5D,
+    [M, N, O, P, Q],
+    [[[[f32; Q]; P]; O]; N],
+    (i, j, k, l, m),
+    true
+);
+index_impl!(
+    Tensor6D,
+    [M, N, O, P, Q, R],
+    [[[[[f32; R]; Q]; P]; O]; N],
+    (i, j, k, l, m, n),
+    true
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_index() {
+        let t = tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        assert_eq!(t[1], [4.0, 5.0, 6.0]);
+        assert_eq!(t[(0, 2)], 3.0);
+        assert_eq!(t[1][1], 5.0);
+        assert_eq!(tensor([1.0, 2.0])[1], 2.0);
+
+        let t: Tensor4D<2, 3, 4, 5> = TensorCreator::ones();
+        assert_eq!(t[(1, 2, 3, 4)], 1.0);
+        assert_eq!(t[1][2], [[1.0; 5]; 4]);
+    }
+
+    #[test]
+    fn test_index_mut() {
+        let a = tensor([[1.0, 2.0], [3.0, 4.0]]);
+        let mut b = a.clone();
+        b[(0, 1)] = -2.0;
+        b[1] = [0.0; 2];
+        b[1][0] += 5.0;
+        assert_eq!(b.data(), &[[1.0, -2.0], [5.0, 0.0]]);
+        assert_eq!(a.data(), &[[1.0, 2.0], [3.0, 4.0]]);
+    }
+}
@@ -21,7 +21,13 @@ macro_rules! tensor_impl {
 impl<$(const $Vs: usize, )* HIn: Tape, HOut: Tape> PutTape<HOut> for $typename<$($Vs, )* HIn>
 {
     type Output = $typename<$($Vs, )* HOut>;
-    fn put_tape(self, tape: HOut) -> Self::Output {
+    #[track_caller]
+    fn put_tape(self, #[allow(unused_mut)] mut tape: HOut) -> Self::Output {
+        #[cfg(feature = "std")]
+        if HOut::OWNS_TAPE && crate::anomaly::is_detecting_anomalies() {
+            let t = $typename { id: self.id, data: self.data.clone(), tape: crate::gradients::NoneTape };
+            crate::anomaly::check(t, &mut tape);
+        }
         Self::Output { id: self.id, data: self.data, tape }
     }
 }
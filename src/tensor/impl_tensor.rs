@@ -17,7 +17,9 @@ pub trait Tensor:
         + TensorCreator
         // NOTE: Adding this restriction means we can put the tape from Self into the Self::NoTape
         + PutTape<Self::Tape, Output = Self>
-        + Clone;
+        + Clone
+        + Send
+        + Sync;
 
     /// Removes whatever Tape this tensor has and returns itself without a tape.
     fn split_tape(self) -> (Self::NoTape, Self::Tape);
@@ -1,4 +1,5 @@
 use super::*;
+use crate::arrays::CountElements;
 use crate::devices::{AllocateZeros, FillElements};
 use crate::gradients::NoneTape;
 use crate::prelude::*;
@@ -30,6 +31,35 @@ pub trait TensorCreator: Sized + HasDevice {
         Self::new_boxed(Self::Device::filled(&mut |v| *v = One::one()))
     }
 
+    /// Creates a tensor from the elements of `data` in row-major order, which means the last
+    /// axis is contiguous (the same layout as the nested arrays of [crate::arrays::HasArrayData::data()]
+    /// and as numpy & pytorch). Returns an error if `data` doesn't have exactly as many elements
+    /// as the tensor. See [TensorToVec::to_vec()] for the opposite.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// let t = Tensor2D::<2, 3>::try_from_slice(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+    /// assert_eq!(t.data(), &[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    /// assert!(Tensor2D::<2, 3>::try_from_slice(&[1.0, 2.0]).is_err());
+    /// ```
+    fn try_from_slice(data: &[Self::Dtype]) -> Result<Self, SliceLengthError>
+    where
+        Self::Dtype: Clone,
+    {
+        let expected = <Self::Array as CountElements>::NUM_ELEMENTS;
+        if data.len() != expected {
+            return Err(SliceLengthError {
+                expected,
+                found: data.len(),
+            });
+        }
+        let mut data = data.iter();
+        Ok(Self::new_boxed(Self::Device::filled(&mut |v| {
+            *v = data.next().unwrap().clone()
+        })))
+    }
+
     /// Creates a tensor filled with values sampled from [Standard] distribution.
     fn rand<R: rand::Rng>(rng: &mut R) -> Self
     where
@@ -78,6 +108,7 @@ mod tests {
     use crate::unique_id::UniqueId;
     use rand::thread_rng;
     use std::collections::HashSet;
+    use std::vec::Vec;
 
     #[test]
     fn test_id() {
@@ -121,6 +152,22 @@ mod tests {
         assert_eq!(Tensor2D::new(t).data(), &t);
     }
 
+    #[test]
+    fn test_try_from_slice() {
+        let data: Vec<f32> = (0..24).map(|i| i as f32).collect();
+        let t = Tensor3D::<2, 3, 4>::try_from_slice(&data).unwrap();
+        assert_eq!(t.data()[1][2], [20.0, 21.0, 22.0, 23.0]);
+        assert_eq!(t.to_vec(), data);
+        assert_eq!(Tensor0D::try_from_slice(&[2.0]).unwrap().data(), &2.0);
+        assert_eq!(
+            Tensor1D::<4>::try_from_slice(&data[..5]).unwrap_err(),
+            SliceLengthError {
+                expected: 4,
+                found: 5
+            }
+        );
+    }
+
     #[test]
     fn fuzz_test_rand() {
         let mut rng = thread_rng();
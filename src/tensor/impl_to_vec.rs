@@ -0,0 +1,63 @@
+use crate::arrays::{CountElements, HasArrayData};
+use std::vec::Vec;
+
+/// Returned by [crate::tensor::TensorCreator::try_from_slice()] when the slice doesn't have exactly
+/// as many elements as the tensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SliceLengthError {
+    /// The number of elements of the tensor.
+    pub expected: usize,
+    /// The number of elements of the slice.
+    pub found: usize,
+}
+
+impl std::fmt::Display for SliceLengthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Expected a slice with {} elements, but found {}",
+            self.expected, self.found
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SliceLengthError {}
+
+/// Copies the elements of a tensor into a [Vec]. See [TensorToVec::to_vec()].
+pub trait TensorToVec: HasArrayData {
+    /// Copies the elements into a [Vec] in row-major order, which means the last axis
+    /// is contiguous. See [crate::tensor::TensorCreator::try_from_slice()] for the opposite.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// let t = tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    /// assert_eq!(t.to_vec(), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    /// ```
+    fn to_vec(&self) -> Vec<Self::Dtype>
+    where
+        Self::Dtype: Clone,
+    {
+        let n = <Self::Array as CountElements>::NUM_ELEMENTS;
+        // the elements of nested arrays are contiguous, so they can be viewed as a flat slice
+        let data = unsafe { std::slice::from_raw_parts(self.data().ref_first_elem(), n) };
+        data.to_vec()
+    }
+}
+
+impl<T: HasArrayData> TensorToVec for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_to_vec() {
+        assert_eq!(tensor(1.0).to_vec(), [1.0]);
+        let t: Tensor4D<2, 1, 2, 2, OwnedTape> =
+            tensor([[[[1.0, 2.0], [3.0, 4.0]]], [[[5.0, 6.0], [7.0, 8.0]]]]).traced();
+        assert_eq!(t.to_vec(), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+    }
+}
@@ -15,6 +15,71 @@ pub fn tensor<T: IntoTensor>(data: T) -> T::Tensor {
     data.into_tensor()
 }
 
+/// Creates a tensor from a nested array literal, where the rank & shape of the tensor
+/// is inferred from the literal like [tensor()]. Unlike [tensor()], the elements can be
+/// any number that can be cast to `f32` with `as`, so integer literals work too.
+///
+/// There are also two other forms:
+/// 1. `tensor!(value; dims...)` is a tensor with shape `dims` where all elements are `value`.
+/// 2. `tensor!(start..end)` is a [Tensor1D] of `start, start + 1, ..., end - 1`, where `start`
+///    and `end` are integer literals.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let a: Tensor2D<2, 3> = tensor!([[1, 2, 3], [-4, 5, 6]]);
+/// assert_eq!(a.data(), &[[1.0, 2.0, 3.0], [-4.0, 5.0, 6.0]]);
+///
+/// let b: Tensor3D<2, 1, 3> = tensor!(0.5; 2, 1, 3);
+/// assert_eq!(b.data(), &[[[0.5; 3]]; 2]);
+///
+/// let c: Tensor1D<4> = tensor!(2..6);
+/// assert_eq!(c.data(), &[2.0, 3.0, 4.0, 5.0]);
+/// ```
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __tensor {
+    ($start:literal .. $end:literal) => {
+        $crate::tensor::tensor::<[f32; ($end - $start) as usize]>(::core::array::from_fn(|i| {
+            ($start + i as isize) as f32
+        }))
+    };
+    ($value:expr; $($dims:expr),+ $(,)?) => {
+        $crate::tensor::tensor($crate::__tensor_fill!(($value) as f32; $($dims),+))
+    };
+    ($($data:tt)+) => {
+        $crate::tensor::tensor($crate::__tensor_array!($($data)+))
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __tensor_array {
+    ([$([$($inner:tt)*]),+ $(,)?]) => {
+        [$($crate::__tensor_array!([$($inner)*])),+]
+    };
+    ([$($x:expr),* $(,)?]) => {
+        [$(($x) as f32),*]
+    };
+    ($x:expr) => {
+        ($x) as f32
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __tensor_fill {
+    ($value:expr; $dim:expr) => {
+        [$value; $dim]
+    };
+    ($value:expr; $dim:expr, $($dims:expr),+) => {
+        [$crate::__tensor_fill!($value; $($dims),+); $dim]
+    };
+}
+
+#[doc(inline)]
+pub use crate::__tensor as tensor;
+
 /// Enables converting this value into a Tensor. See [tensor()].
 pub trait IntoTensor: Sized {
     /// The type of tensor that this value would be converted into.
@@ -90,6 +155,29 @@ mod tests {
         assert_eq!(a.data(), &arr);
     }
 
+    #[test]
+    fn test_tensor_macro() {
+        assert_eq!(tensor!(2).data(), &2.0);
+        assert_eq!(tensor!(-1.5).data(), &-1.5);
+        assert_eq!(tensor!([1, -2, 3,]).data(), &[1.0, -2.0, 3.0]);
+        let x = 0.25;
+        let t: Tensor2D<2, 2> = tensor!([[x, 2.0 * x], [-x, 1]]);
+        assert_eq!(t.data(), &[[0.25, 0.5], [-0.25, 1.0]]);
+        let t: Tensor4D<1, 2, 1, 2> = tensor!([[[[1, 2]], [[3, 4]]]]);
+        assert_eq!(t.data(), &[[[[1.0, 2.0]], [[3.0, 4.0]]]]);
+    }
+
+    #[test]
+    fn test_tensor_macro_fill_and_range() {
+        let t: Tensor1D<3> = tensor!(1; 3);
+        assert_eq!(t.data(), &[1.0; 3]);
+        let n = 2;
+        let t: Tensor2D<4, 2> = tensor!(n; 4, 2);
+        assert_eq!(t.data(), &[[2.0; 2]; 4]);
+        let t: Tensor1D<5> = tensor!(-2..3);
+        assert_eq!(t.data(), &[-2.0, -1.0, 0.0, 1.0, 2.0]);
+    }
+
     #[test]
     fn test_4d_into_tensor() {
         let arr = [[[[0.0, 1.0, 2.0], [-1.0, -2.0, -3.0]]]; 4];
@@ -16,6 +16,17 @@
 //! let t = tensor([1.0, 2.0, 3.0]);
 //! ```
 //!
+//! ### Use the tensor! macro
+//!
+//! See [tensor!]. It also accepts integers, and has forms for filled tensors and ranges.
+//!
+//! ```rust
+//! # use dfdx::prelude::*;
+//! let t = tensor!([[1, 2], [3, 4]]);
+//! let ones: Tensor2D<2, 3> = tensor!(1; 2, 3);
+//! let range: Tensor1D<5> = tensor!(0..5);
+//! ```
+//!
 //! ### Use the TensorCreator trait
 //!
 //! See [TensorCreator].
@@ -42,6 +53,17 @@
 //! let b = Tensor2D::<4, 3>::randn(&mut rng); // gaussian random data
 //! ```
 //!
+//! 4. From a slice in row-major order with [TensorCreator::try_from_slice()], which checks the length.
+//!
+//! [TensorToVec::to_vec()] does the opposite.
+//! ```rust
+//! # use dfdx::prelude::*;
+//! let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+//! let t = Tensor2D::<3, 2>::try_from_slice(&data).unwrap();
+//! assert_eq!(t.data(), &[[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]);
+//! assert_eq!(t.to_vec(), data);
+//! ```
+//!
 //! # Accessing or modifying underlying data
 //!
 //! Use [crate::arrays::HasArrayData::data()] and [crate::arrays::HasArrayData::mut_data()]
@@ -55,6 +77,33 @@
 //! assert_eq!(t.data(), &[0.0, 2.0, 0.0]);
 //! ```
 //!
+//! Tensors can also be indexed directly, either along the first axis or with
+//! a tuple of indices for a single element. To slice a traced tensor,
+//! use [crate::tensor_ops::slice()] instead, which keeps track of gradients.
+//!
+//! ```rust
+//! # use dfdx::prelude::*;
+//! let mut t = Tensor2D::<2, 3>::zeros();
+//! t[(0, 1)] = 2.0;
+//! t[1] = [3.0; 3];
+//! assert_eq!(t[0], [0.0, 2.0, 0.0]);
+//! assert_eq!(t[(1, 2)], 3.0);
+//! ```
+//!
+//! # Printing
+//!
+//! Tensors implement [std::fmt::Display], which shows their shape and data. Large tensors
+//! only show the start & end of each axis, and the precision can be set with e.g. `{:.2}`.
+//!
+//! ```rust
+//! # use dfdx::prelude::*;
+//! let t = tensor([[1.0, -2.0, 3.0], [4.0, 5.0, 6.0]]);
+//! assert_eq!(
+//!     format!("{:.1}", t),
+//!     "Tensor2D [2, 3]\n[[ 1.0, -2.0,  3.0],\n [ 4.0,  5.0,  6.0]]"
+//! );
+//! ```
+//!
 //! # Tracking gradients
 //!
 //! Use the [trace()] or [traced()] methods to add [crate::gradients::OwnedTape] to the [Tensor].
@@ -71,19 +120,23 @@
 //! ```
 
 mod impl_default;
+mod impl_display;
 mod impl_has_array;
 mod impl_has_device;
 mod impl_has_unique_id;
+mod impl_index;
 mod impl_put_tape;
 mod impl_randomize;
 mod impl_tensor;
 mod impl_tensor_creator;
+mod impl_to_vec;
 mod impl_trace;
 mod impl_update_with_grads;
 mod into_tensor;
 mod structs;
 
 pub use impl_default::*;
+pub use impl_display::*;
 pub use impl_has_array::*;
 pub use impl_has_device::*;
 pub use impl_has_unique_id::*;
@@ -91,6 +144,7 @@ pub use impl_put_tape::*;
 pub use impl_randomize::*;
 pub use impl_tensor::*;
 pub use impl_tensor_creator::*;
+pub use impl_to_vec::*;
 pub use impl_trace::*;
 pub use impl_update_with_grads::*;
 pub use into_tensor::*;
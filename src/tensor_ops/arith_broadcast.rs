@@ -0,0 +1,138 @@
+use crate::arrays::{AllAxes, Axes2, Axes3, Axis};
+use crate::gradients::{Merge, Tape};
+use crate::prelude::*;
+use std::ops::{Add, Div, Mul, Sub};
+
+macro_rules! broadcast_op_impl {
+    ($Trait:ident, $method:ident, $Big:ident, [$($BigDims:tt),*], $Small:ident, [$($SmallDims:tt),*], $Axes:ty, {$($Vs:tt),*}) => {
+impl<$(const $Vs: usize, )* TapeL: Tape, TapeR: Tape> $Trait<$Small<$($SmallDims, )* TapeR>> for $Big<$($BigDims, )* TapeL>
+where
+    TapeL: Merge<TapeR>,
+{
+    type Output = $Big<$($BigDims, )* TapeL>;
+    /// Broadcasts `rhs` along the leading axes of `self`, and then calls the element wise op.
+    fn $method(self, rhs: $Small<$($SmallDims, )* TapeR>) -> Self::Output {
+        // the broadcast is recorded on the merged tape, so `rhs` gets a gradient even without a tape
+        let (lhs, tape) = self.split_tape();
+        let (rhs, rhs_tape) = rhs.split_tape();
+        let rhs: $Big<$($BigDims, )* TapeL> = BroadcastTo::<_, $Axes>::broadcast(rhs.put_tape(tape.merge(rhs_tape)));
+        let (rhs, tape) = rhs.split_tape();
+        $method(lhs.put_tape(tape), rhs)
+    }
+}
+
+impl<$(const $Vs: usize, )* TapeL: Tape, TapeR: Tape> $Trait<$Big<$($BigDims, )* TapeR>> for $Small<$($SmallDims, )* TapeL>
+where
+    TapeL: Merge<TapeR>,
+{
+    type Output = $Big<$($BigDims, )* TapeL>;
+    /// Broadcasts `self` along the leading axes of `rhs`, and then calls the element wise op.
+    fn $method(self, rhs: $Big<$($BigDims, )* TapeR>) -> Self::Output {
+        let (lhs, tape) = self.split_tape();
+        let (rhs, rhs_tape) = rhs.split_tape();
+        let lhs: $Big<$($BigDims, )* TapeL> = BroadcastTo::<_, $Axes>::broadcast(lhs.put_tape(tape.merge(rhs_tape)));
+        $method(lhs, rhs)
+    }
+}
+    };
+}
+
+macro_rules! broadcast_ops_impl {
+    ($Big:ident, [$($BigDims:tt),*], $Small:ident, [$($SmallDims:tt),*], $Axes:ty, {$($Vs:tt),*}) => {
+        broadcast_op_impl!(Add, add, $Big, [$($BigDims),*], $Small, [$($SmallDims),*], $Axes, {$($Vs),*});
+        broadcast_op_impl!(Sub, sub, $Big, [$($BigDims),*], $Small, [$($SmallDims),*], $Axes, {$($Vs),*});
+        broadcast_op_impl!(Mul, mul, $Big, [$($BigDims),*], $Small, [$($SmallDims),*], $Axes, {$($Vs),*});
+        broadcast_op_impl!(Div, div, $Big, [$($BigDims),*], $Small, [$($SmallDims),*], $Axes, {$($Vs),*});
+    };
+}
+
+broadcast_ops_impl!(Tensor1D, [M], Tensor0D, [], AllAxes, { M });
+broadcast_ops_impl!(Tensor2D, [M, N], Tensor0D, [], AllAxes, {M, N});
+broadcast_ops_impl!(Tensor3D, [M, N, O], Tensor0D, [], AllAxes, {M, N, O});
+broadcast_ops_impl!(Tensor4D, [M, N, O, P], Tensor0D, [], AllAxes, {M, N, O, P});
+broadcast_ops_impl!(Tensor2D, [M, N], Tensor1D, [N], Axis<0>, {M, N});
+broadcast_ops_impl!(Tensor3D, [M, N, O], Tensor1D, [O], Axes2<0, 1>, {M, N, O});
+broadcast_ops_impl!(Tensor4D, [M, N, O, P], Tensor1D, [P], Axes3<0, 1, 2>, {M, N, O, P});
+broadcast_ops_impl!(Tensor3D, [M, N, O], Tensor2D, [N, O], Axis<0>, {M, N, O});
+broadcast_ops_impl!(Tensor4D, [M, N, O, P], Tensor2D, [O, P], Axes2<0, 1>, {M, N, O, P});
+broadcast_ops_impl!(Tensor4D, [M, N, O, P], Tensor3D, [N, O, P], Axis<0>, {M, N, O, P});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+
+    #[test]
+    fn test_broadcast_add_2d_1d() {
+        let x = tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let b = tensor([1.0, -1.0, 0.5]);
+        let r = x.trace() + b.trace();
+        assert_eq!(r.data(), &[[2.0, 1.0, 3.5], [5.0, 4.0, 6.5]]);
+        let g = r.square().sum::<_, AllAxes>().backward();
+        assert_eq!(g.ref_gradient(&x), &[[4.0, 2.0, 7.0], [10.0, 8.0, 13.0]]);
+        assert_eq!(g.ref_gradient(&b), &[14.0, 10.0, 20.0]);
+    }
+
+    #[test]
+    fn test_broadcast_gradient_without_tape() {
+        let x = tensor([[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]);
+        let b = tensor([1.0, -1.0]);
+        let r = x.trace() * b.clone();
+        let g = r.sum::<_, AllAxes>().backward();
+        assert_eq!(g.ref_gradient(&b), &[9.0, 12.0]);
+        assert_eq!(g.ref_gradient(&x), &[[1.0, -1.0]; 3]);
+
+        let r = b.trace() - x.clone();
+        let g = r.sum::<_, AllAxes>().backward();
+        assert_eq!(g.ref_gradient(&b), &[3.0, 3.0]);
+        assert_eq!(g.ref_gradient(&x), &[[-1.0; 2]; 3]);
+    }
+
+    #[test]
+    fn test_broadcast_sub_div_both_sides() {
+        let x = tensor([[1.0, 2.0], [4.0, 8.0]]);
+        let y = tensor([1.0, 2.0]);
+        let r = y.trace() - x.clone();
+        assert_eq!(r.data(), &[[0.0, 0.0], [-3.0, -6.0]]);
+        assert_eq!(
+            r.sum::<_, AllAxes>().backward().ref_gradient(&y),
+            &[2.0, 2.0]
+        );
+
+        let r = x.trace() / y.clone();
+        assert_eq!(r.data(), &[[1.0, 1.0], [4.0, 4.0]]);
+        let g = r.sum::<_, AllAxes>().backward();
+        assert_eq!(g.ref_gradient(&x), &[[1.0, 0.5], [1.0, 0.5]]);
+
+        let r = y.trace() / x.clone();
+        assert_eq!(r.data(), &[[1.0, 1.0], [0.25, 0.25]]);
+        let g = r.sum::<_, AllAxes>().backward();
+        assert_eq!(g.ref_gradient(&y), &[1.25, 0.625]);
+    }
+
+    #[test]
+    fn test_broadcast_mul_scalar_tensor() {
+        let x = tensor([[1.0, 2.0], [3.0, 4.0]]);
+        let s = tensor(2.0);
+        let r = x.trace() * s.trace();
+        assert_eq!(r.data(), &[[2.0, 4.0], [6.0, 8.0]]);
+        let g = r.sum::<_, AllAxes>().backward();
+        assert_eq!(g.ref_gradient(&s), &10.0);
+        assert_eq!(g.ref_gradient(&x), &[[2.0; 2]; 2]);
+    }
+
+    #[test]
+    fn test_broadcast_4d_3d() {
+        let x: Tensor4D<2, 3, 4, 5> = TensorCreator::ones();
+        let y: Tensor3D<3, 4, 5> = TensorCreator::ones();
+        let r = x.trace() - y.trace() * 2.0;
+        assert_eq!(r.data(), &[[[[-1.0; 5]; 4]; 3]; 2]);
+        let g = r.mean::<_, AllAxes>().backward();
+        assert_close(g.ref_gradient(&y), &[[[-2.0 / 60.0; 5]; 4]; 3]);
+
+        let z: Tensor2D<4, 5> = TensorCreator::ones();
+        let r = y.trace() + z.trace();
+        let g = r.sum::<_, AllAxes>().backward();
+        assert_eq!(g.ref_gradient(&z), &[[3.0; 5]; 4]);
+    }
+}
@@ -1,10 +1,8 @@
-use super::utils::move_tape_and_add_backward_op;
-use crate::gradients::Tape;
-use crate::{
-    devices::{Device, ForEachElement},
-    prelude::*,
-};
-use std::ops::{Add, Div, Mul, Sub};
+use super::utils::{map_df_uses_fx, move_tape_and_add_backward_op};
+use crate::devices::{Device, ForEachElement};
+use crate::gradients::{NoneTape, Tape};
+use crate::prelude::*;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
 
 /// `t + val`. `val` is used for all elements of `t`.
 ///
@@ -16,13 +14,7 @@ use std::ops::{Add, Div, Mul, Sub};
 /// assert_eq!(r.data(), &[1.5, 2.5, -2.5]);
 /// ```
 pub fn add_scalar<T: Tensor<Dtype = f32>>(t: T, val: T::Dtype) -> T {
-    let result = T::NoTape::new_boxed(T::Device::map(t.data(), |x| x + val));
-    move_tape_and_add_backward_op(t, result, move |t, result, grads| {
-        let (t_grad, result_grad) = grads.mut_and_ref(&t, &result);
-        T::Device::foreach_mr(t_grad, result_grad, &mut |t, r| {
-            *t += r;
-        });
-    })
+    map_df_uses_fx(t, |x| x + val, |_| 1.0)
 }
 
 /// `t - val`. `val` is used for all elements of `t`.
@@ -35,13 +27,7 @@ pub fn add_scalar<T: Tensor<Dtype = f32>>(t: T, val: T::Dtype) -> T {
 /// assert_eq!(r.data(), &[0.5, 1.5, -3.5]);
 /// ```
 pub fn sub_scalar<T: Tensor<Dtype = f32>>(t: T, val: T::Dtype) -> T {
-    let result = T::NoTape::new_boxed(T::Device::map(t.data(), |x| x - val));
-    move_tape_and_add_backward_op(t, result, move |t, result, grads| {
-        let (t_grad, result_grad) = grads.mut_and_ref(&t, &result);
-        T::Device::foreach_mr(t_grad, result_grad, &mut |t, r| {
-            *t += r;
-        });
-    })
+    map_df_uses_fx(t, |x| x - val, |_| 1.0)
 }
 
 /// `t * val`. `val` is used for all elements of `t`.
@@ -54,13 +40,7 @@ pub fn sub_scalar<T: Tensor<Dtype = f32>>(t: T, val: T::Dtype) -> T {
 /// assert_eq!(r.data(), &[0.5, 1.0, -1.5]);
 /// ```
 pub fn mul_scalar<T: Tensor<Dtype = f32>>(t: T, val: T::Dtype) -> T {
-    let result = T::NoTape::new_boxed(T::Device::map(t.data(), |x| x * val));
-    move_tape_and_add_backward_op(t, result, move |t, result, grads| {
-        let (t_grad, result_grad) = grads.mut_and_ref(&t, &result);
-        T::Device::foreach_mr(t_grad, result_grad, &mut |t, r| {
-            *t += r * val;
-        });
-    })
+    map_df_uses_fx(t, |x| x * val, move |_| val)
 }
 
 /// `t / val`. `val` is used for all elements of `t`.
@@ -139,6 +119,34 @@ impl<$(const $Vs: usize, )* H: Tape> Div<f32> for $typename<$($Vs, )* H> {
         div_scalar(self, rhs)
     }
 }
+
+impl<$(const $Vs: usize, )*> AddAssign<f32> for $typename<$($Vs, )* NoneTape> {
+    /// Adds `rhs` to every element in place - implements `T += f32`
+    fn add_assign(&mut self, rhs: f32) {
+        <Self as HasDevice>::Device::foreach_m(self.mut_data(), &mut |x| *x += rhs);
+    }
+}
+
+impl<$(const $Vs: usize, )*> SubAssign<f32> for $typename<$($Vs, )* NoneTape> {
+    /// Subtracts `rhs` from every element in place - implements `T -= f32`
+    fn sub_assign(&mut self, rhs: f32) {
+        <Self as HasDevice>::Device::foreach_m(self.mut_data(), &mut |x| *x -= rhs);
+    }
+}
+
+impl<$(const $Vs: usize, )*> MulAssign<f32> for $typename<$($Vs, )* NoneTape> {
+    /// Multiplies every element by `rhs` in place - implements `T *= f32`
+    fn mul_assign(&mut self, rhs: f32) {
+        <Self as HasDevice>::Device::foreach_m(self.mut_data(), &mut |x| *x *= rhs);
+    }
+}
+
+impl<$(const $Vs: usize, )*> DivAssign<f32> for $typename<$($Vs, )* NoneTape> {
+    /// Divides every element by `rhs` in place - implements `T /= f32`
+    fn div_assign(&mut self, rhs: f32) {
+        <Self as HasDevice>::Device::foreach_m(self.mut_data(), &mut |x| *x /= rhs);
+    }
+}
     };
 }
 
@@ -262,4 +270,19 @@ mod tests {
         let gradients = backward(r.exp().sum());
         assert_eq!(gradients.ref_gradient(&x), &[[0.8243606; 2]; 3]);
     }
+
+    #[test]
+    fn test_scalar_assign() {
+        let mut x = tensor([1.0, 2.0, 3.0]);
+        let x_0 = x.clone();
+        x += 1.0;
+        assert_eq!(x.data(), &[2.0, 3.0, 4.0]);
+        x -= 0.5;
+        assert_eq!(x.data(), &[1.5, 2.5, 3.5]);
+        x *= 2.0;
+        assert_eq!(x.data(), &[3.0, 5.0, 7.0]);
+        x /= 4.0;
+        assert_eq!(x.data(), &[0.75, 1.25, 1.75]);
+        assert_eq!(x_0.data(), &[1.0, 2.0, 3.0]);
+    }
 }
@@ -0,0 +1,165 @@
+use super::utils::merge_tapes_and_add_backward_binop;
+use crate::arrays::{Axis, CountElements};
+use crate::devices::{Cpu, ForEachElement};
+use crate::gradients::{Merge, Tape};
+use crate::prelude::*;
+
+/// **Requires Nightly** Concatenates two tensors along `Axes`. All other axes must be the same size.
+///
+/// Example:
+/// ```ignore
+/// # use dfdx::prelude::*;
+/// let a: Tensor2D<2, 3> = TensorCreator::zeros();
+/// let b: Tensor2D<2, 4> = TensorCreator::ones();
+/// let c: Tensor2D<2, 7> = concat::<_, _, Axis<1>>(a, b);
+/// assert_eq!(c.data(), &[[0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0]; 2]);
+/// ```
+pub fn concat<Lhs: Concat<Rhs, Axes>, Rhs, Axes>(lhs: Lhs, rhs: Rhs) -> Lhs::Output {
+    lhs.concat(rhs)
+}
+
+/// **Requires Nightly** Concatenates `Self` with `Rhs` along `Axes`. See [concat()].
+pub trait Concat<Rhs, Axes> {
+    type Output;
+    fn concat(self, rhs: Rhs) -> Self::Output;
+}
+
+fn concat_fwd<E: Clone, const M: usize, const N: usize>(
+    lhs: &[E; M],
+    rhs: &[E; N],
+    out: &mut [E; M + N],
+) {
+    out[..M].clone_from_slice(lhs);
+    out[M..].clone_from_slice(rhs);
+}
+
+fn concat_bwd_lhs<E: CountElements<Dtype = f32>, const M: usize, const N: usize>(
+    lhs_grad: &mut [E; M],
+    out_grad: &[E; M + N],
+) where
+    Cpu: ForEachElement<E>,
+{
+    for (l, o) in lhs_grad.iter_mut().zip(out_grad[..M].iter()) {
+        Cpu::foreach_mr(l, o, &mut |l, o| *l += o);
+    }
+}
+
+fn concat_bwd_rhs<E: CountElements<Dtype = f32>, const M: usize, const N: usize>(
+    rhs_grad: &mut [E; N],
+    out_grad: &[E; M + N],
+) where
+    Cpu: ForEachElement<E>,
+{
+    for (r, o) in rhs_grad.iter_mut().zip(out_grad[M..].iter()) {
+        Cpu::foreach_mr(r, o, &mut |r, o| *r += o);
+    }
+}
+
+macro_rules! nest_fwd {
+    ([], $l:expr, $r:expr, $o:expr) => {
+        concat_fwd($l, $r, $o)
+    };
+    ([$head:ident $(, $tail:ident)*], $l:expr, $r:expr, $o:expr) => {
+        for ((l, r), o) in $l.iter().zip($r.iter()).zip($o.iter_mut()) {
+            nest_fwd!([$($tail),*], l, r, o);
+        }
+    };
+}
+
+macro_rules! nest_bwd {
+    ([], $f:expr, $g:expr, $o:expr) => {
+        $f($g, $o)
+    };
+    ([$head:ident $(, $tail:ident)*], $f:expr, $g:expr, $o:expr) => {
+        for (g, o) in $g.iter_mut().zip($o.iter()) {
+            nest_bwd!([$($tail),*], $f, g, o);
+        }
+    };
+}
+
+macro_rules! concat_impl {
+    ($typename:ident, [$($Pre:ident),*], [$($Post:ident),*], $Ax:literal) => {
+impl<$(const $Pre: usize, )* const M: usize, const N: usize, $(const $Post: usize, )* LhsTape: Tape, RhsTape: Tape>
+    Concat<$typename<$($Pre, )* N, $($Post, )* RhsTape>, Axis<$Ax>> for $typename<$($Pre, )* M, $($Post, )* LhsTape>
+where
+    LhsTape: Merge<RhsTape>,
+    [(); M + N]:,
+{
+    type Output = $typename<$($Pre, )* { M + N }, $($Post, )* LhsTape>;
+    fn concat(self, rhs: $typename<$($Pre, )* N, $($Post, )* RhsTape>) -> Self::Output {
+        let mut result: $typename<$($Pre, )* { M + N }, $($Post, )* NoneTape> = TensorCreator::zeros();
+        nest_fwd!([$($Pre),*], self.data(), rhs.data(), result.mut_data());
+        merge_tapes_and_add_backward_binop::<_, _, Self::Output, _>(self, rhs, result, move |lhs, rhs, result, grads| {
+            let (lhs_grad, result_grad) = grads.mut_and_ref(&lhs, &result);
+            nest_bwd!([$($Pre),*], concat_bwd_lhs::<_, M, N>, lhs_grad, result_grad);
+            let (rhs_grad, result_grad) = grads.mut_and_ref(&rhs, &result);
+            nest_bwd!([$($Pre),*], concat_bwd_rhs::<_, M, N>, rhs_grad, result_grad);
+        })
+    }
+}
+    };
+}
+
+concat_impl!(Tensor1D, [], [], 0);
+concat_impl!(Tensor2D, [], [B], 0);
+concat_impl!(Tensor2D, [A], [], 1);
+concat_impl!(Tensor3D, [], [B, C], 0);
+concat_impl!(Tensor3D, [A], [C], 1);
+concat_impl!(Tensor3D, [A, B], [], 2);
+concat_impl!(Tensor4D, [], [B, C, D], 0);
+concat_impl!(Tensor4D, [A], [C, D], 1);
+concat_impl!(Tensor4D, [A, B], [D], 2);
+concat_impl!(Tensor4D, [A, B, C], [], 3);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+
+    #[test]
+    fn test_concat_1d() {
+        let a = tensor([1.0, 2.0]);
+        let b = tensor([3.0, 4.0, 5.0]);
+        let r: Tensor1D<5, _> = concat::<_, _, Axis<0>>(a.trace(), b.trace());
+        assert_eq!(r.data(), &[1.0, 2.0, 3.0, 4.0, 5.0]);
+        let g = backward(r.square().sum());
+        assert_eq!(g.ref_gradient(&a), &[2.0, 4.0]);
+        assert_eq!(g.ref_gradient(&b), &[6.0, 8.0, 10.0]);
+    }
+
+    #[test]
+    fn test_concat_2d() {
+        let a = tensor([[1.0, 2.0], [3.0, 4.0]]);
+        let b = tensor([[5.0], [6.0]]);
+        let r: Tensor2D<2, 3, _> = concat::<_, _, Axis<1>>(a.trace(), b.clone());
+        assert_eq!(r.data(), &[[1.0, 2.0, 5.0], [3.0, 4.0, 6.0]]);
+        let g = backward(r.exp().mean());
+        assert_close(
+            g.ref_gradient(&a),
+            &[[0.45304698, 1.2315093], [3.3475895, 9.099692]],
+        );
+
+        let c = tensor([[7.0, 8.0]]);
+        let r: Tensor2D<3, 2, _> = concat::<_, _, Axis<0>>(c.trace(), a.clone());
+        assert_eq!(r.data(), &[[7.0, 8.0], [1.0, 2.0], [3.0, 4.0]]);
+        let g = backward(r.sum());
+        assert_eq!(g.ref_gradient(&c), &[[1.0; 2]]);
+    }
+
+    #[test]
+    fn test_concat_4d() {
+        let a: Tensor4D<2, 3, 4, 5> = TensorCreator::zeros();
+        let b: Tensor4D<2, 1, 4, 5> = TensorCreator::ones();
+        let r: Tensor4D<2, 4, 4, 5, _> = concat::<_, _, Axis<1>>(a.trace(), b.clone());
+        assert_eq!(
+            r.data(),
+            &[[[[0.0; 5]; 4], [[0.0; 5]; 4], [[0.0; 5]; 4], [[1.0; 5]; 4]]; 2]
+        );
+        let g = backward(r.sum());
+        assert_eq!(g.ref_gradient(&a), &[[[[1.0; 5]; 4]; 3]; 2]);
+
+        let c: Tensor4D<2, 3, 4, 2> = TensorCreator::ones();
+        let r: Tensor4D<2, 3, 4, 7> = concat::<_, _, Axis<3>>(a, c);
+        assert_eq!(r.data()[1][2][3], [0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0]);
+    }
+}
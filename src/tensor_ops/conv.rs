@@ -1,6 +1,7 @@
-use crate::devices::{Cpu, DeviceConv2D};
+use crate::devices::{Cpu, DeviceConv2D, PaddingMode, ZeroPadding};
 use crate::gradients::Tape;
 use crate::prelude::*;
+use crate::unique_id::HasUniqueId;
 
 impl<const C: usize, const H: usize, const W: usize, T: Tape> Tensor3D<C, H, W, T> {
     /// **Requires Nightly** Perform a 2d convolution
@@ -10,23 +11,81 @@ impl<const C: usize, const H: usize, const W: usize, T: Tape> Tensor3D<C, H, W,
         self,
         filters: &Tensor4D<O, C, K, K>,
         bias: &Tensor1D<O>,
+    ) -> Tensor3D<O, { (H + 2 * P - K) / S + 1 }, { (W + 2 * P - K) / S + 1 }, T> {
+        self.padded_conv2d::<O, K, S, P, ZeroPadding>(filters, bias)
+    }
+
+    /// **Requires Nightly** Perform a 2d convolution, where the image is padded using [PaddingMode] `M`.
+    pub fn padded_conv2d<
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        M: PaddingMode,
+    >(
+        self,
+        filters: &Tensor4D<O, C, K, K>,
+        bias: &Tensor1D<O>,
+    ) -> Tensor3D<O, { (H + 2 * P - K) / S + 1 }, { (W + 2 * P - K) / S + 1 }, T> {
+        self.conv2d_with_bias::<O, K, S, P, M>(filters, Some(bias))
+    }
+
+    /// **Requires Nightly** Same as [Self::padded_conv2d()], but without a bias.
+    pub fn padded_conv2d_unbiased<
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        M: PaddingMode,
+    >(
+        self,
+        filters: &Tensor4D<O, C, K, K>,
+    ) -> Tensor3D<O, { (H + 2 * P - K) / S + 1 }, { (W + 2 * P - K) / S + 1 }, T> {
+        self.conv2d_with_bias::<O, K, S, P, M>(filters, None)
+    }
+
+    fn conv2d_with_bias<
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        M: PaddingMode,
+    >(
+        self,
+        filters: &Tensor4D<O, C, K, K>,
+        bias: Option<&Tensor1D<O>>,
     ) -> Tensor3D<O, { (H + 2 * P - K) / S + 1 }, { (W + 2 * P - K) / S + 1 }, T> {
         let mut result = Tensor3D::zeros();
-        <Cpu as DeviceConv2D<S, P>>::conv_forward(
+        <Cpu as DeviceConv2D<S, P, M>>::conv_forward(
             self.data(),
             filters.data(),
-            bias.data(),
+            bias.map(|b| b.data()),
             result.mut_data(),
         );
 
         let f = filters.clone();
         let (x, mut tape) = self.split_tape();
         let phf = filters.clone();
-        let phb = bias.clone();
+        let phb = bias.cloned();
         let phr = result.clone();
-        tape.add_backward_op(move |grads| {
-            let (fg, bg, ig, rg) = grads.muts_and_ref(&phf, &phb, &x, &phr);
-            <Cpu as DeviceConv2D<S, P>>::conv_backward(x.data(), f.data(), rg, ig, fg, bg);
+        let mut inputs = std::vec![*x.id(), *phf.id()];
+        inputs.extend(phb.as_ref().map(|b| *b.id()));
+        tape.add_backward_op_with_ids(&inputs, &[*phr.id()], move |grads| match &phb {
+            Some(phb) => {
+                let (fg, bg, ig, rg) = grads.muts_and_ref(&phf, phb, &x, &phr);
+                <Cpu as DeviceConv2D<S, P, M>>::conv_backward(
+                    x.data(),
+                    f.data(),
+                    rg,
+                    ig,
+                    fg,
+                    Some(bg),
+                );
+            }
+            None => {
+                let (fg, ig, rg) = grads.muts2_and_ref(&phf, &x, &phr);
+                <Cpu as DeviceConv2D<S, P, M>>::conv_backward(x.data(), f.data(), rg, ig, fg, None);
+            }
         });
         result.put_tape(tape)
     }
@@ -42,23 +101,82 @@ impl<const B: usize, const C: usize, const H: usize, const W: usize, T: Tape>
         self,
         filters: &Tensor4D<O, C, K, K>,
         bias: &Tensor1D<O>,
+    ) -> Tensor4D<B, O, { (H + 2 * P - K) / S + 1 }, { (W + 2 * P - K) / S + 1 }, T> {
+        self.padded_conv2d::<O, K, S, P, ZeroPadding>(filters, bias)
+    }
+
+    /// **Requires Nightly** Perform a batched 2d convolution, where the images are padded using [PaddingMode] `M`.
+    pub fn padded_conv2d<
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        M: PaddingMode,
+    >(
+        self,
+        filters: &Tensor4D<O, C, K, K>,
+        bias: &Tensor1D<O>,
+    ) -> Tensor4D<B, O, { (H + 2 * P - K) / S + 1 }, { (W + 2 * P - K) / S + 1 }, T> {
+        self.conv2d_with_bias::<O, K, S, P, M>(filters, Some(bias))
+    }
+
+    /// **Requires Nightly** Same as [Self::padded_conv2d()], but without a bias.
+    pub fn padded_conv2d_unbiased<
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        M: PaddingMode,
+    >(
+        self,
+        filters: &Tensor4D<O, C, K, K>,
+    ) -> Tensor4D<B, O, { (H + 2 * P - K) / S + 1 }, { (W + 2 * P - K) / S + 1 }, T> {
+        self.conv2d_with_bias::<O, K, S, P, M>(filters, None)
+    }
+
+    fn conv2d_with_bias<
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        M: PaddingMode,
+    >(
+        self,
+        filters: &Tensor4D<O, C, K, K>,
+        bias: Option<&Tensor1D<O>>,
     ) -> Tensor4D<B, O, { (H + 2 * P - K) / S + 1 }, { (W + 2 * P - K) / S + 1 }, T> {
         let mut result = Tensor4D::zeros();
         for (x_i, r_i) in self.data().iter().zip(result.mut_data().iter_mut()) {
-            <Cpu as DeviceConv2D<S, P>>::conv_forward(x_i, filters.data(), bias.data(), r_i);
+            let b = bias.map(|b| b.data());
+            <Cpu as DeviceConv2D<S, P, M>>::conv_forward(x_i, filters.data(), b, r_i);
         }
 
         let f = filters.clone();
 
         let (x, mut tape) = self.split_tape();
         let phf = filters.clone();
-        let phb = bias.clone();
+        let phb = bias.cloned();
         let phr = result.clone();
-        tape.add_backward_op(move |grads| {
-            let (fg, bg, ig, r_grad) = grads.muts_and_ref(&phf, &phb, &x, &phr);
+        let mut inputs = std::vec![*x.id(), *phf.id()];
+        inputs.extend(phb.as_ref().map(|b| *b.id()));
+        tape.add_backward_op_with_ids(&inputs, &[*phr.id()], move |grads| {
             let f = f.data();
-            for ((x_i, rg_i), ig_i) in x.data().iter().zip(r_grad.iter()).zip(ig.iter_mut()) {
-                <Cpu as DeviceConv2D<S, P>>::conv_backward(x_i, f, rg_i, ig_i, fg, bg);
+            match &phb {
+                Some(phb) => {
+                    let (fg, bg, ig, r_grad) = grads.muts_and_ref(&phf, phb, &x, &phr);
+                    for ((x_i, rg_i), ig_i) in x.data().iter().zip(r_grad.iter()).zip(ig.iter_mut())
+                    {
+                        let bg = Some(&mut *bg);
+                        <Cpu as DeviceConv2D<S, P, M>>::conv_backward(x_i, f, rg_i, ig_i, fg, bg);
+                    }
+                }
+                None => {
+                    let (fg, ig, r_grad) = grads.muts2_and_ref(&phf, &x, &phr);
+                    for ((x_i, rg_i), ig_i) in x.data().iter().zip(r_grad.iter()).zip(ig.iter_mut())
+                    {
+                        <Cpu as DeviceConv2D<S, P, M>>::conv_backward(x_i, f, rg_i, ig_i, fg, None);
+                    }
+                }
             }
         });
         result.put_tape(tape)
@@ -293,4 +411,22 @@ mod tests {
             &[0.55381978, 0.55677116, 0.30686682],
         );
     }
+
+    #[test]
+    fn test_conv2d_unbiased() {
+        let weight = tensor([[[[1.0, -1.0], [0.5, 2.0]]], [[[0.0, 1.0], [-2.0, 0.5]]]]);
+        let x = tensor([[[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]]; 2]);
+        let bias = Tensor1D::zeros();
+        let y = x
+            .trace()
+            .padded_conv2d_unbiased::<2, 2, 1, 0, ZeroPadding>(&weight);
+        let expected = x.trace().conv2d::<2, 2, 1, 0>(&weight, &bias);
+        assert_eq!(y.data(), expected.data());
+
+        let g = backward(y.exp().mean());
+        let expected = backward(expected.exp().mean());
+        assert_eq!(g.ref_gradient(&x), expected.ref_gradient(&x));
+        assert_eq!(g.ref_gradient(&weight), expected.ref_gradient(&weight));
+        assert!(g.try_ref_gradient(&bias).is_none());
+    }
 }
@@ -1,5 +1,6 @@
 use super::utils::binary_map;
-use crate::gradients::{Merge, Tape};
+use crate::devices::ForEachElement;
+use crate::gradients::{Merge, NoneTape, Tape};
 use crate::prelude::*;
 
 /// Element wise addition.
@@ -33,6 +34,13 @@ where
         add(self, rhs)
     }
 }
+
+impl<$(const $Vs: usize, )*> std::ops::AddAssign<&$typename<$($Vs, )* NoneTape>> for $typename<$($Vs, )* NoneTape> {
+    /// Adds `rhs` to `self` element wise in place - implements `T += &T`
+    fn add_assign(&mut self, rhs: &$typename<$($Vs, )* NoneTape>) {
+        <Self as HasDevice>::Device::foreach_mr(self.mut_data(), rhs.data(), &mut |l, r| *l += r);
+    }
+}
     };
 }
 
@@ -45,6 +53,7 @@ binary_ops_impl!(Tensor4D, [M, N, O, P]);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::unique_id::HasUniqueId;
 
     #[test]
     fn test_add_0d() {
@@ -84,4 +93,22 @@ mod tests {
         assert_eq!(gradients.ref_gradient(&a), &[[1.0 / 6.0; 3]; 2]);
         assert_eq!(gradients.ref_gradient(&b), &[[1.0 / 6.0; 3]; 2]);
     }
+
+    #[test]
+    fn test_add_no_tape_keeps_shared_data() {
+        let a = tensor([1.0, 2.0, 3.0]);
+        let r = a.clone() + a.clone();
+        assert_eq!(r.data(), &[2.0, 4.0, 6.0]);
+        assert_eq!(a.data(), &[1.0, 2.0, 3.0]);
+        assert_ne!(r.id(), a.id());
+    }
+
+    #[test]
+    fn test_add_assign() {
+        let mut a = tensor([[1.0, 2.0], [3.0, 4.0]]);
+        let b = a.clone();
+        a += &tensor([[1.0, -1.0], [0.5, 0.0]]);
+        assert_eq!(a.data(), &[[2.0, 1.0], [3.5, 4.0]]);
+        assert_eq!(b.data(), &[[1.0, 2.0], [3.0, 4.0]]);
+    }
 }
@@ -1,6 +1,8 @@
 use crate::devices::{Cpu, FillElements};
-use crate::gradients::{Gradients, Tape};
+use crate::gradients::{Gradients, Merge, Tape};
 use crate::prelude::*;
+use crate::unique_id::{HasUniqueId, UniqueId};
+use std::collections::HashSet;
 
 /// Runs backprop algorithm with all operations contained in the tape that `t` has.
 ///
@@ -8,15 +10,259 @@ use crate::prelude::*;
 ///
 /// Note that `t` is required to have [OwnedTape], which means it currently owns the [crate::gradients::GradientTape].
 pub fn backward(t: Tensor0D<OwnedTape>) -> Gradients {
+    let mut gradients: Gradients = Default::default();
+    backward_into(t, &mut gradients);
+    gradients
+}
+
+/// Same as [backward()], but stores the gradients in `gradients`. This first calls
+/// [Gradients::clear()], so arrays allocated by a previous backward are re-used.
+///
+/// Use [Gradients::split_params()] to pass only the gradients of a model's parameters to an optimizer,
+/// so the rest stay around for the next call:
+/// ```rust
+/// # use dfdx::{prelude::*, gradients::Gradients};
+/// let mut model: (Linear<5, 3>, ReLU, Linear<3, 2>) = Default::default();
+/// let mut opt: Sgd<_> = Default::default();
+/// let mut gradients: Gradients = Default::default();
+/// for _ in 0..3 {
+///     let y = model.forward(Tensor2D::<4, 5>::zeros().traced());
+///     backward_into(y.mean(), &mut gradients);
+///     let params = gradients.split_params(&model);
+///     opt.update(&mut model, params).expect("");
+/// }
+/// ```
+pub fn backward_into(t: Tensor0D<OwnedTape>, gradients: &mut Gradients) {
+    gradients.clear();
     let (t, mut tape) = t.split_tape();
     tape.add_backward_op(move |grads| {
         Cpu::fill(grads.mut_gradient(&t), &mut |v| *v = 1.0);
     });
-    tape.0.execute()
+    tape.0.execute_into(gradients);
+}
+
+/// Same as [backward()], but only returns the gradients of the tensors in `wrt`, e.g. of the input
+/// of a frozen model to compute a saliency map or an adversarial example.
+///
+/// Backward operations that can't change the gradients of `wrt` are skipped, e.g. the ones
+/// that only compute the gradients of a model's parameters. Gradient checkpointing
+/// ([crate::nn::Checkpoint]) and [crate::anomaly] record operations that don't say which tensors
+/// they use, so if the tape has any of those, every operation is run.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let model: Linear<3, 2> = Default::default();
+/// let x = tensor([1.0, 2.0, 3.0]);
+/// let gradients = backward_wrt(model.forward(x.trace()).sum(), &[&x]);
+/// assert!(gradients.try_ref_gradient(&x).is_some());
+/// assert!(gradients.try_ref_gradient(&model.weight).is_none());
+/// ```
+pub fn backward_wrt(t: Tensor0D<OwnedTape>, wrt: &[&dyn HasUniqueId]) -> Gradients {
+    let mut gradients: Gradients = Default::default();
+    let ids: HashSet<UniqueId> = wrt.iter().map(|t| *t.id()).collect();
+    let (t, mut tape) = t.split_tape();
+    let seed_id = *t.id();
+    tape.add_backward_op_with_ids(&[seed_id], &[], move |grads| {
+        Cpu::fill(grads.mut_gradient(&t), &mut |v| *v = 1.0);
+    });
+    tape.0.execute_wrt(&mut gradients, &ids);
+    gradients.retain_ids(&ids);
+    gradients
+}
+
+/// Runs backprop separately from each of `heads` through a single forward pass, and returns
+/// the [Gradients] of each head in the same order. This is useful when each loss needs its own
+/// gradients, e.g. the generator and discriminator terms of a GAN.
+///
+/// The heads can share part of the forward pass, as long as only one head holds the tape
+/// of the shared part, and other heads start from a new tape (e.g. with [Tensor::with_empty_tape()]).
+/// The heads can be in any order.
+///
+/// Each head seeds its own gradient with `1.0` and every other head's with `0.0`, so this runs
+/// the backward operations of all heads `N` times.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let w = tensor([1.0, 2.0]);
+/// let y = w.trace() * 3.0;
+/// let a = y.with_empty_tape().sum();
+/// let b = y.square().sum();
+/// let [grads_a, _] = backward_heads([a, b]);
+/// assert_eq!(grads_a.ref_gradient(&w), &[3.0, 3.0]);
+/// ```
+pub fn backward_heads<const N: usize>(heads: [Tensor0D<OwnedTape>; N]) -> [Gradients; N] {
+    let mut tape = OwnedTape::default();
+    let heads = heads.map(|head| {
+        let (head, head_tape) = head.split_tape();
+        tape = std::mem::take(&mut tape).merge(head_tape);
+        head
+    });
+    core::array::from_fn(|i| {
+        let mut gradients: Gradients = Default::default();
+        for (j, head) in heads.iter().enumerate() {
+            let g = gradients.mut_gradient(head);
+            *g = if i == j { 1.0 } else { 0.0 };
+        }
+        tape.0.execute_retained(&mut gradients);
+        gradients
+    })
 }
 
 impl Tensor0D<OwnedTape> {
     pub fn backward(self) -> Gradients {
         backward(self)
     }
+
+    /// Calls [backward_into()]
+    pub fn backward_into(self, gradients: &mut Gradients) {
+        backward_into(self, gradients)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_backward_into_reuses_arrays() {
+        let x: Tensor1D<3> = tensor([1.0, 2.0, 3.0]);
+        let mut gradients: Gradients = Default::default();
+
+        backward_into(x.trace().sum(), &mut gradients);
+        assert_eq!(gradients.ref_gradient(&x), &[1.0; 3]);
+        let ptr = gradients.ref_gradient(&x) as *const [f32; 3];
+
+        backward_into(x.trace().sum(), &mut gradients);
+        assert_eq!(gradients.ref_gradient(&x), &[1.0; 3]);
+        assert_eq!(gradients.ref_gradient(&x) as *const [f32; 3], ptr);
+    }
+
+    #[test]
+    fn test_split_params() {
+        let model: Linear<2, 1> = Default::default();
+        let x: Tensor1D<2> = tensor([1.0, 2.0]);
+        let mut gradients: Gradients = Default::default();
+        backward_into(model.forward(x.trace()).sum(), &mut gradients);
+
+        let params = gradients.split_params(&model);
+        assert_eq!(params.ref_gradient(&model.weight), &[[1.0, 2.0]]);
+        assert_eq!(params.ref_gradient(&model.bias), &[1.0]);
+        assert!(gradients.try_ref_gradient(&model.weight).is_none());
+        assert_eq!(gradients.ref_gradient(&x), &[0.0; 2]);
+    }
+
+    #[test]
+    fn test_backward_heads() {
+        let model: Linear<2, 3> = Linear {
+            weight: tensor([[1.0, -1.0], [0.5, 2.0], [-0.5, 0.0]]),
+            bias: tensor([0.1, 0.2, 0.3]),
+        };
+        let x = tensor([1.0, 2.0]);
+        let loss_a = |h: Tensor1D<3, OwnedTape>| h.square().mean::<_, AllAxes>();
+        let loss_b = |h: Tensor1D<3, OwnedTape>| h.tanh().sum();
+
+        let h = model.forward(x.trace());
+        let b = loss_b(h.with_empty_tape());
+        let [grads_a, grads_b] = backward_heads([loss_a(h), b]);
+
+        let h = model.forward(x.trace());
+        let b = loss_b(h.with_empty_tape());
+        let [grads_b2, grads_a2] = backward_heads([b, loss_a(h)]);
+
+        let expected_a = loss_a(model.forward(x.trace())).backward();
+        let expected_b = loss_b(model.forward(x.trace())).backward();
+        for (grads, expected) in [
+            (&grads_a, &expected_a),
+            (&grads_b, &expected_b),
+            (&grads_a2, &expected_a),
+            (&grads_b2, &expected_b),
+        ] {
+            assert_eq!(grads.ref_gradient(&x), expected.ref_gradient(&x));
+            assert_eq!(
+                grads.ref_gradient(&model.weight),
+                expected.ref_gradient(&model.weight)
+            );
+            assert_eq!(
+                grads.ref_gradient(&model.bias),
+                expected.ref_gradient(&model.bias)
+            );
+        }
+    }
+
+    #[test]
+    fn test_backward_wrt() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut model: (Linear<3, 4>, Tanh, Linear<4, 2>) = Default::default();
+        model.reset_params(&mut rng);
+        let x: Tensor2D<2, 3> = TensorCreator::randn(&mut rng);
+        let expected = model
+            .forward(x.trace())
+            .square()
+            .mean::<_, AllAxes>()
+            .backward();
+
+        let g = backward_wrt(
+            model.forward(x.trace()).square().mean::<_, AllAxes>(),
+            &[&x],
+        );
+        assert_eq!(g.ref_gradient(&x), expected.ref_gradient(&x));
+        assert!(g.try_ref_gradient(&model.0.weight).is_none());
+        assert!(g.try_ref_gradient(&model.2.bias).is_none());
+
+        let g = backward_wrt(
+            model.forward(x.trace()).square().mean::<_, AllAxes>(),
+            &[&model.0.weight, &model.2.bias],
+        );
+        assert!(g.try_ref_gradient(&x).is_none());
+        assert_eq!(
+            g.ref_gradient(&model.0.weight),
+            expected.ref_gradient(&model.0.weight)
+        );
+        assert_eq!(
+            g.ref_gradient(&model.2.bias),
+            expected.ref_gradient(&model.2.bias)
+        );
+    }
+
+    #[test]
+    fn test_backward_wrt_skips_unused_ops() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let x = tensor([1.0, 2.0]);
+        let w = tensor([3.0, 4.0]);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let f = |calls: &Arc<AtomicUsize>| {
+            let calls = calls.clone();
+            let w2 = crate::tensor_ops::utils::map(
+                w.trace(),
+                |w| w * w,
+                move |w| {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    2.0 * w
+                },
+            );
+            add(x.trace(), w2).sum()
+        };
+
+        let g = backward_wrt(f(&calls), &[&x]);
+        assert_eq!(g.ref_gradient(&x), &[1.0; 2]);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        let g = backward_wrt(f(&calls), &[&w]);
+        assert_eq!(g.ref_gradient(&w), &[6.0, 8.0]);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_backward_wrt_through_split_tape() {
+        let x = tensor([[1.0, -2.0], [3.0, 4.0]]);
+        let w = tensor([[0.5, -1.0], [2.0, 1.0]]);
+        let (h, tape) = x.trace().relu().split_tape();
+        let g = backward_wrt(matmul(w.put_tape(tape), h).sum(), &[&x]);
+        assert_eq!(g.ref_gradient(&x), &[[2.5, 0.0], [0.0, 0.0]]);
+    }
 }
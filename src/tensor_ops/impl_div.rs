@@ -1,5 +1,6 @@
 use super::utils::binary_map;
-use crate::gradients::{Merge, Tape};
+use crate::devices::ForEachElement;
+use crate::gradients::{Merge, NoneTape, Tape};
 use crate::prelude::*;
 
 /// Element wise division.
@@ -37,6 +38,12 @@ where
     }
 }
 
+impl<$(const $Vs: usize, )*> std::ops::DivAssign<&$typename<$($Vs, )* NoneTape>> for $typename<$($Vs, )* NoneTape> {
+    /// Divides `self` by `rhs` element wise in place - implements `T /= &T`
+    fn div_assign(&mut self, rhs: &$typename<$($Vs, )* NoneTape>) {
+        <Self as HasDevice>::Device::foreach_mr(self.mut_data(), rhs.data(), &mut |l, r| *l /= r);
+    }
+}
     };
 }
 
@@ -106,4 +113,11 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_div_assign() {
+        let mut a = tensor([1.0, 2.0, 3.0]);
+        a /= &tensor([1.0, -1.0, 0.5]);
+        assert_eq!(a.data(), &[1.0, -2.0, 6.0]);
+    }
 }
@@ -1,3 +1,5 @@
+use super::utils::move_tape_and_add_backward_op;
+use crate::devices::{Device, ForEachElement};
 use crate::gradients::Tape;
 use crate::prelude::*;
 use rand::{rngs::StdRng, Rng, SeedableRng};
@@ -38,26 +40,25 @@ pub fn dropout<T: Tensor<Dtype = f32>, R: Rng>(t: T, p: f32, rng: &mut R) -> T {
         // `t` owns the tape in this branch, so apply dropout randomly.
         let seed: u64 = rng.gen();
         let mut fwd_rng = StdRng::seed_from_u64(seed);
-        let mut bwd_rng = StdRng::seed_from_u64(seed);
-        crate::tensor_ops::utils::map(
-            t,
-            move |x| {
-                let val: f32 = fwd_rng.sample(Standard);
-                if val < p {
-                    0.0
-                } else {
-                    x / (1.0 - p)
-                }
-            },
-            move |_| {
+        let result = T::NoTape::new_boxed(T::Device::map(t.data(), |x| {
+            let val: f32 = fwd_rng.sample(Standard);
+            if val < p {
+                0.0
+            } else {
+                x / (1.0 - p)
+            }
+        }));
+        move_tape_and_add_backward_op(t, result, move |t, result, grads| {
+            // re-seeded on every run, since the tape can be executed more than once
+            let mut bwd_rng = StdRng::seed_from_u64(seed);
+            let (t_grad, result_grad) = grads.mut_and_ref(&t, &result);
+            T::Device::foreach_mr(t_grad, result_grad, &mut |g, r| {
                 let val: f32 = bwd_rng.sample(Standard);
-                if val < p {
-                    0.0
-                } else {
-                    1.0 / (1.0 - p)
+                if val >= p {
+                    *g += (1.0 / (1.0 - p)) * r;
                 }
-            },
-        )
+            });
+        })
     }
 }
 
@@ -0,0 +1,136 @@
+use crate::arrays::HasArrayType;
+use crate::devices::{Device, ForEachElement};
+use crate::gradients::Tape;
+use crate::prelude::*;
+use crate::unique_id::internal::ResetId;
+use crate::unique_id::HasUniqueId;
+
+/// Identity in the forward pass. In the backward pass, `hook` is called with the gradient
+/// of the result before it is added to the gradient of `t`, so it can observe or rewrite it.
+/// Does nothing if `t` doesn't own a tape.
+///
+/// This can be used for gradient reversal, per-layer gradient clipping, or debugging.
+///
+/// **Pytorch equivalent**: `t.register_hook(hook)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let t = tensor([1.0, 2.0, 3.0]);
+/// let r = t.trace().hook_gradient(|g: &mut [f32; 3]| g[1] = 0.0);
+/// let gradients = r.sum().backward();
+/// assert_eq!(gradients.ref_gradient(&t), &[1.0, 0.0, 1.0]);
+/// ```
+pub fn hook_gradient<T, F>(t: T, mut hook: F) -> T
+where
+    T: Tensor<Dtype = f32>,
+    F: 'static + Send + FnMut(&mut T::Array),
+{
+    if !T::Tape::OWNS_TAPE {
+        return t;
+    }
+    let (t, mut tape) = t.split_tape();
+    let mut result = t.clone();
+    result.reset_id();
+    let phantom_result = result.clone();
+    tape.add_backward_op_with_ids(&[*t.id()], &[*result.id()], move |grads| {
+        let (t_grad, result_grad) = grads.mut_and_ref(&t, &phantom_result);
+        let mut g = T::Device::map(result_grad, |g| *g);
+        hook(g.as_mut());
+        T::Device::foreach_mr(t_grad, g.as_ref(), &mut |t, g| *t += g);
+    });
+    result.put_tape(tape)
+}
+
+/// Identity in the forward pass, and multiplies the gradient by `factor` in the backward pass.
+/// A `factor` of `-1.0` is a gradient reversal layer, as used in
+/// [domain-adversarial training](https://arxiv.org/abs/1505.07818), and a `factor` between
+/// `0.0` and `1.0` partially stops the gradient.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let t = tensor([1.0, 2.0, 3.0]);
+/// let r = t.trace().scale_gradient(-0.5);
+/// assert_eq!(r.data(), &[1.0, 2.0, 3.0]);
+/// let gradients = r.sum().backward();
+/// assert_eq!(gradients.ref_gradient(&t), &[-0.5; 3]);
+/// ```
+pub fn scale_gradient<T: Tensor<Dtype = f32>>(t: T, factor: f32) -> T {
+    hook_gradient(t, move |g| T::Device::foreach_m(g, &mut |g| *g *= factor))
+}
+
+macro_rules! tensor_impl {
+    ($typename:ident, [$($Vs:tt),*]) => {
+impl<$(const $Vs: usize, )* H: Tape> $typename<$($Vs, )* H> {
+    /// Calls [hook_gradient()] on `self`.
+    pub fn hook_gradient<F>(self, hook: F) -> Self
+    where
+        F: 'static + Send + FnMut(&mut <Self as HasArrayType>::Array),
+    {
+        hook_gradient(self, hook)
+    }
+
+    /// Calls [scale_gradient()] on `self`.
+    pub fn scale_gradient(self, factor: f32) -> Self {
+        scale_gradient(self, factor)
+    }
+}
+    };
+}
+
+tensor_impl!(Tensor0D, []);
+tensor_impl!(Tensor1D, [M]);
+tensor_impl!(Tensor2D, [M, N]);
+tensor_impl!(Tensor3D, [M, N, O]);
+tensor_impl!(Tensor4D, [M, N, O, P]);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unique_id::HasUniqueId;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_hook_gradient_observes() {
+        let seen = Arc::new(Mutex::new(None));
+        let recorder = seen.clone();
+        let t = tensor([[1.0, 2.0], [3.0, 4.0]]);
+        let r = t
+            .trace()
+            .square()
+            .hook_gradient(move |g: &mut [[f32; 2]; 2]| *recorder.lock().unwrap() = Some(*g));
+        assert_eq!(r.data(), &[[1.0, 4.0], [9.0, 16.0]]);
+        let gradients = r.sum::<_, AllAxes>().backward();
+        assert_eq!(*seen.lock().unwrap(), Some([[1.0; 2]; 2]));
+        assert_eq!(gradients.ref_gradient(&t), &[[2.0, 4.0], [6.0, 8.0]]);
+    }
+
+    #[test]
+    fn test_hook_gradient_rewrites() {
+        let t = tensor([1.0, -2.0, 3.0]);
+        let r = (t.trace() * 2.0).hook_gradient(|g: &mut [f32; 3]| {
+            g.iter_mut().for_each(|g| *g = -g.clamp(-0.5, 0.5));
+        });
+        let gradients = r.square().sum().backward();
+        assert_eq!(gradients.ref_gradient(&t), &[-1.0, 1.0, -1.0]);
+    }
+
+    #[test]
+    fn test_scale_gradient() {
+        let x = tensor([[1.0, 2.0], [3.0, 4.0]]);
+        let y = x.trace().square();
+        let a = y.with_empty_tape().sum::<_, AllAxes>();
+        let b = y.scale_gradient(-2.0).sum::<_, AllAxes>();
+        assert_eq!(b.data(), &30.0);
+        let gradients = (b + a).backward();
+        assert_eq!(gradients.ref_gradient(&x), &[[-2.0, -4.0], [-6.0, -8.0]]);
+    }
+
+    #[test]
+    fn test_hook_gradient_no_tape() {
+        let t = tensor([1.0, 2.0]);
+        let r = t.clone().hook_gradient(|_: &mut [f32; 2]| panic!());
+        assert_eq!(r.id(), t.id());
+    }
+}
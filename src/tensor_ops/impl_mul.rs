@@ -1,5 +1,6 @@
 use super::utils::binary_map;
-use crate::gradients::{Merge, Tape};
+use crate::devices::ForEachElement;
+use crate::gradients::{Merge, NoneTape, Tape};
 use crate::prelude::*;
 
 /// Element wise multiplication.
@@ -33,6 +34,13 @@ where
         mul(self, rhs)
     }
 }
+
+impl<$(const $Vs: usize, )*> std::ops::MulAssign<&$typename<$($Vs, )* NoneTape>> for $typename<$($Vs, )* NoneTape> {
+    /// Multiplies `self` by `rhs` element wise in place - implements `T *= &T`
+    fn mul_assign(&mut self, rhs: &$typename<$($Vs, )* NoneTape>) {
+        <Self as HasDevice>::Device::foreach_mr(self.mut_data(), rhs.data(), &mut |l, r| *l *= r);
+    }
+}
     };
 }
 
@@ -99,4 +107,11 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_mul_assign() {
+        let mut a = tensor([1.0, 2.0, 3.0]);
+        a *= &tensor([1.0, -1.0, 0.5]);
+        assert_eq!(a.data(), &[1.0, -2.0, 1.5]);
+    }
 }
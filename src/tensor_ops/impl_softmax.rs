@@ -1,6 +1,10 @@
-use crate::devices::{Device, DeviceReduce, MaxAccum, SubAccum};
+use super::utils::move_tape_and_add_backward_op;
+use crate::devices::{
+    AddAccum, AllocateZeros, CopyAccum, Device, DeviceReduce, ForEachElement, MaxAccum, SubAccum,
+};
 use crate::gradients::Tape;
 use crate::prelude::*;
+use std::boxed::Box;
 
 /// Computes the [LogSumExp](https://en.wikipedia.org/wiki/LogSumExp) function across
 /// `Axes`
@@ -79,6 +83,73 @@ pub fn softmax<T: Reduce<Axes>, Axes>(t: T) -> T {
     exp(log_softmax(t))
 }
 
+/// The entropy of the distribution `softmax(t)` across `Axes`, computed from the logits `t` in a
+/// single numerically stable op: `-(softmax(t) * log_softmax(t)).sum(Axes)`.
+///
+/// This records one op on the tape, instead of the several ops of the composed version.
+///
+/// **Pytorch equivalent**: `torch.distributions.Categorical(logits=t).entropy()`
+///
+/// **Related functions**: [log_softmax()], [softmax()]
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let logits: Tensor2D<2, 4> = tensor([[0.0; 4], [0.0, 0.0, -f32::INFINITY, -f32::INFINITY]]);
+/// let r: Tensor1D<2> = logits.entropy_with_logits();
+/// assert_eq!(r.data(), &[4.0f32.ln(), 2.0f32.ln()]);
+/// ```
+pub fn entropy_with_logits<T: Reduce<Axes>, Axes>(t: T) -> T::Reduced {
+    let (t, tape) = t.split_tape();
+
+    // log_probs = t - max - ln(sum(exp(t - max)))
+    let max = T::DeviceR::reduce::<MaxAccum>(t.data());
+    let mut log_probs = t.clone();
+    T::DeviceR::broadcast_into_no_reset::<SubAccum>(log_probs.mut_data(), max.as_ref());
+    let probs = T::Device::map(log_probs.data(), |x| x.exp());
+    let mut lse = T::DeviceR::reduce::<AddAccum>(probs.as_ref());
+    <T::Reduced as HasDevice>::Device::foreach_m(lse.as_mut(), &mut |x| *x = x.ln());
+    T::DeviceR::broadcast_into_no_reset::<SubAccum>(log_probs.mut_data(), lse.as_ref());
+
+    // p * log(p) is 0 where p is 0
+    let p_log_p = T::Device::map(log_probs.data(), |l| {
+        let p = l.exp();
+        if p > 0.0 {
+            -p * l
+        } else {
+            0.0
+        }
+    });
+    let mut result = <T::Reduced as Tensor>::NoTape::zeros();
+    T::DeviceR::reduce_into::<AddAccum>(result.mut_data(), p_log_p.as_ref());
+
+    move_tape_and_add_backward_op::<T, T::Reduced, _>(
+        t.put_tape(tape),
+        result,
+        move |t, result, grads| {
+            let (t_grad, result_grad) = grads.mut_and_ref(&t, &result);
+            // d(entropy)/dt = -p * (log(p) + entropy)
+            let mut scale: Box<T::Array> = T::Device::zeros();
+            T::DeviceR::broadcast_into_no_reset::<CopyAccum>(scale.as_mut(), result.data());
+            T::Device::add(scale.as_mut(), log_probs.data());
+            let mut g: Box<T::Array> = T::Device::zeros();
+            T::DeviceR::broadcast_into_no_reset::<CopyAccum>(g.as_mut(), result_grad);
+            T::Device::foreach_mr(scale.as_mut(), g.as_ref(), &mut |s, g| *s *= g);
+            T::Device::foreach_mrr(
+                t_grad,
+                log_probs.data(),
+                scale.as_ref(),
+                &mut |t_grad, l, s| {
+                    let p = l.exp();
+                    if p > 0.0 {
+                        *t_grad -= p * s;
+                    }
+                },
+            );
+        },
+    )
+}
+
 macro_rules! tensor_impl {
     ($typename:ident, [$($Vs:tt),*]) => {
 impl<$(const $Vs: usize, )* H: Tape> $typename<$($Vs, )* H> {
@@ -97,6 +168,11 @@ impl<$(const $Vs: usize, )* H: Tape> $typename<$($Vs, )* H> {
     {
         softmax(self)
     }
+    /// Calls [entropy_with_logits()] on `self` with `Axes`
+    pub fn entropy_with_logits<T, Axes>(self) -> T where Self: ReduceTo<T, Axes>
+    {
+        entropy_with_logits(self)
+    }
 }
     };
 }
@@ -292,4 +368,39 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn test_entropy_with_logits_1d() {
+        let a = tensor([1.0, 2.0, 3.0]);
+        let r = a.trace().entropy_with_logits();
+        assert_close(&[*r.data()], &[0.8323956]);
+        let gradients = r.backward();
+        assert_close(
+            gradients.ref_gradient(&a),
+            &[0.1418171, 0.14077036, -0.28258745],
+        );
+    }
+
+    #[test]
+    fn test_entropy_with_logits_matches_composed() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let t: Tensor3D<2, 3, 4> = TensorCreator::randn(&mut rng);
+        let r: Tensor2D<2, 3, OwnedTape> = t.trace().entropy_with_logits::<_, Axis<2>>();
+        let log_probs = t.trace().log_softmax::<Axis<2>>();
+        let probs = log_probs.with_empty_tape().exp();
+        let expected: Tensor2D<2, 3, OwnedTape> = -(log_probs * probs).sum::<_, Axis<2>>();
+        assert_close(r.data(), expected.data());
+        let g1 = r.mean::<_, AllAxes>().backward();
+        let g2 = expected.mean::<_, AllAxes>().backward();
+        assert_close(g1.ref_gradient(&t), g2.ref_gradient(&t));
+    }
+
+    #[test]
+    fn test_entropy_with_logits_zero_probs() {
+        let a = tensor([[0.0, -f32::INFINITY], [1000.0, 0.0]]);
+        let r: Tensor1D<2, OwnedTape> = a.trace().entropy_with_logits::<_, Axis<1>>();
+        assert_eq!(r.data(), &[0.0; 2]);
+        let gradients = r.sum().backward();
+        assert_eq!(gradients.ref_gradient(&a), &[[0.0; 2]; 2]);
+    }
 }
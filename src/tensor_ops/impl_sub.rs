@@ -1,5 +1,6 @@
 use super::utils::binary_map;
-use crate::gradients::{Merge, Tape};
+use crate::devices::ForEachElement;
+use crate::gradients::{Merge, NoneTape, Tape};
 use crate::prelude::*;
 
 /// Element wise subtraction.
@@ -33,6 +34,13 @@ where
         sub(self, rhs)
     }
 }
+
+impl<$(const $Vs: usize, )*> std::ops::SubAssign<&$typename<$($Vs, )* NoneTape>> for $typename<$($Vs, )* NoneTape> {
+    /// Subtracts `rhs` from `self` element wise in place - implements `T -= &T`
+    fn sub_assign(&mut self, rhs: &$typename<$($Vs, )* NoneTape>) {
+        <Self as HasDevice>::Device::foreach_mr(self.mut_data(), rhs.data(), &mut |l, r| *l -= r);
+    }
+}
     };
 }
 
@@ -87,4 +95,11 @@ mod tests {
         assert_eq!(gradients.ref_gradient(&a), &[[-1.0 / 6.0; 3]; 2]);
         assert_eq!(gradients.ref_gradient(&b), &[[1.0 / 6.0; 3]; 2]);
     }
+
+    #[test]
+    fn test_sub_assign() {
+        let mut a = tensor([1.0, 2.0, 3.0]);
+        a -= &tensor([1.0, -1.0, 0.5]);
+        assert_eq!(a.data(), &[0.0, 3.0, 2.5]);
+    }
 }
@@ -0,0 +1,124 @@
+use crate::prelude::*;
+
+/// The Jacobian of `f` at `x`, where `jacobian(f, x)[i][j]` is the derivative of
+/// output `i` with respect to input `j`.
+///
+/// This runs `f` and [backward()] once per output, so it is intended for small problems.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let x = tensor([1.0, 2.0]);
+/// let j: Tensor2D<2, 2> = jacobian(|x| square(x) * 3.0, &x);
+/// assert_eq!(j.data(), &[[6.0, 0.0], [0.0, 12.0]]);
+/// ```
+pub fn jacobian<F, const M: usize, const N: usize>(mut f: F, x: &Tensor1D<N>) -> Tensor2D<M, N>
+where
+    F: FnMut(Tensor1D<N, OwnedTape>) -> Tensor1D<M, OwnedTape>,
+{
+    let mut j = [[0.0; N]; M];
+    for (i, row) in j.iter_mut().enumerate() {
+        let y: Tensor0D<OwnedTape> = f(x.trace()).select(&i);
+        if let Some(g) = y.backward().try_ref_gradient(x) {
+            *row = *g;
+        }
+    }
+    Tensor2D::new(j)
+}
+
+/// The gradient of the scalar function `f` at `x`.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let g = gradient(|x| square(x).sum(), &tensor([1.0, -2.0]));
+/// assert_eq!(g.data(), &[2.0, -4.0]);
+/// ```
+pub fn gradient<F, const N: usize>(mut f: F, x: &Tensor1D<N>) -> Tensor1D<N>
+where
+    F: FnMut(Tensor1D<N, OwnedTape>) -> Tensor0D<OwnedTape>,
+{
+    let g = f(x.trace()).backward();
+    Tensor1D::new(g.try_ref_gradient(x).copied().unwrap_or([0.0; N]))
+}
+
+/// An approximation of the Hessian of the scalar function `f` at `x`, where
+/// `hessian(f, x)[i][j]` is the second derivative with respect to inputs `i` and `j`.
+///
+/// The tape can't differentiate a backward pass, so each column is a central difference
+/// of two exact [gradient()]s, and the result is symmetrized. The step for input `j` is
+/// `cbrt(f32::EPSILON) * max(1, |x[j]|)`, which gives roughly 4 significant digits for
+/// smooth functions. This runs `f` and [backward()] `2 * N` times.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let h: Tensor2D<2, 2> = hessian(|x| square(x).sum(), &tensor([1.0, 2.0]));
+/// assert!((h.data()[0][0] - 2.0).abs() < 1e-2);
+/// assert!(h.data()[0][1].abs() < 1e-2);
+/// ```
+pub fn hessian<F, const N: usize>(mut f: F, x: &Tensor1D<N>) -> Tensor2D<N, N>
+where
+    F: FnMut(Tensor1D<N, OwnedTape>) -> Tensor0D<OwnedTape>,
+{
+    let x = *x.data();
+    // dh[j] is the derivative of the gradient with respect to input j
+    let dh: [[f32; N]; N] = core::array::from_fn(|j| {
+        let step = f32::EPSILON.cbrt() * x[j].abs().max(1.0);
+        let mut x_fwd = x;
+        x_fwd[j] += step;
+        let mut x_bwd = x;
+        x_bwd[j] -= step;
+        let g_fwd = gradient(&mut f, &Tensor1D::new(x_fwd));
+        let g_bwd = gradient(&mut f, &Tensor1D::new(x_bwd));
+        let step = x_fwd[j] - x_bwd[j];
+        core::array::from_fn(|i| (g_fwd.data()[i] - g_bwd.data()[i]) / step)
+    });
+    let h = core::array::from_fn(|i| core::array::from_fn(|j| 0.5 * (dh[i][j] + dh[j][i])));
+    Tensor2D::new(h)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+
+    #[test]
+    fn test_jacobian() {
+        let x = tensor([1.0, 2.0]);
+        let w = tensor([[1.0, 2.0, 3.0], [-1.0, 0.5, 0.0]]);
+        let j: Tensor2D<3, 2> = jacobian(|x| sin(vecmat_mul(x, w.clone())), &x);
+        let z = [-1.0f32, 3.0, 3.0];
+        assert_close(
+            j.data(),
+            &[
+                [z[0].cos(), -z[0].cos()],
+                [2.0 * z[1].cos(), 0.5 * z[1].cos()],
+                [3.0 * z[2].cos(), 0.0],
+            ],
+        );
+
+        let j: Tensor2D<1, 2> = jacobian(|x| Tensor1D::new([1.0]).put_tape(x.split_tape().1), &x);
+        assert_eq!(j.data(), &[[0.0, 0.0]]);
+    }
+
+    #[test]
+    fn test_gradient() {
+        let x = tensor([1.0, 2.0, 3.0]);
+        let g = gradient(|x| powi(x, 3).sum(), &x);
+        assert_eq!(g.data(), &[3.0, 12.0, 27.0]);
+    }
+
+    #[test]
+    fn test_hessian() {
+        let x = tensor([1.0, -2.0, 3.0]);
+        let h = hessian(
+            |x| square(x.with_empty_tape().sum::<_, AllAxes>()) + powi(x, 3).sum(),
+            &x,
+        );
+        let expected = [[8.0, 2.0, 2.0], [2.0, -10.0, 2.0], [2.0, 2.0, 20.0]];
+        for (a, b) in h.data().iter().flatten().zip(expected.iter().flatten()) {
+            assert!((a - b).abs() < 1e-2, "{:?}", h.data());
+        }
+    }
+}
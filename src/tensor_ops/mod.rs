@@ -74,6 +74,22 @@
 //! add(a, big);
 //! ```
 //!
+//! The arithmetic operators (`+`, `-`, `*` and `/`) broadcast implicitly between tensors of different
+//! ranks, like in numpy & pytorch. The smaller tensor is broadcast along the leading axes of the
+//! larger one, so its shape has to match the trailing axes of the larger one:
+//! ```rust
+//! # use dfdx::prelude::*;
+//! let x = tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+//! let b = tensor([0.5, 0.0, -0.5]);
+//! let r = x.trace() + b.trace(); // same as `add(x, b.broadcast())`
+//! assert_eq!(r.data(), &[[1.5, 2.0, 2.5], [4.5, 5.0, 5.5]]);
+//! let gradients = r.sum::<_, AllAxes>().backward();
+//! assert_eq!(gradients.ref_gradient(&b), &[2.0; 3]);
+//!
+//! let r = tensor(1.0) / x;
+//! assert_eq!(r.data()[1], [0.25, 0.2, 1.0 / 6.0]);
+//! ```
+//!
 //! # Permutating axes
 //!
 //! Permutating axes is done via [PermuteTo], and similar to braodcasting/reducing,
@@ -131,7 +147,47 @@
 //! let b: Tensor2D<2, 2> = t.select(&[[0, 2], [1, 1]]); // select multiple from the last axis
 //! assert_eq!(b.data(), &[[1.0, 3.0], [5.0, 5.0]]);
 //! ```
+//!
+//! A contiguous range of an axis can be taken with [slice()], where the size of the range
+//! comes from the result type:
+//! ```rust
+//! # use dfdx::prelude::*;
+//! let t = tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+//! let a: Tensor2D<2, 2> = slice::<_, _, Axis<1>>(t, 1..3); // t[:, 1:3] in pytorch
+//! assert_eq!(a.data(), &[[2.0, 3.0], [5.0, 6.0]]);
+//! ```
+//!
+//! # In-place operations
+//!
+//! Tensors share their data when cloned, and most element wise operations on tensors without a tape
+//! re-use the data of their input when nothing else shares it. So on inference paths,
+//! `t.relu()` and `a + b` don't allocate any new data (the result of `a + b` is written into `a`).
+//!
+//! Tensors without a tape also implement `+=`, `-=`, `*=`, and `/=` with either another tensor or an `f32`:
+//! ```rust
+//! # use dfdx::prelude::*;
+//! let mut a = tensor([1.0, 2.0, 3.0]);
+//! a += &tensor([1.0, 1.0, 1.0]);
+//! a *= 2.0;
+//! assert_eq!(a.data(), &[4.0, 6.0, 8.0]);
+//! ```
+//!
+//! With a tape, some of the inputs are needed for backprop, so only the operations that can
+//! compute their derivative from their output are done in place, like [relu()], [exp()], [sigmoid()],
+//! and [add_scalar()].
+//!
+//! # Custom operations
+//!
+//! New differentiable operations can be implemented outside of dfdx with the functions in [utils],
+//! which are what all the operations here are built with:
+//! ```rust
+//! # use dfdx::prelude::*;
+//! fn softsign<T: Tensor<Dtype = f32>>(t: T) -> T {
+//!     dfdx::tensor_ops::utils::map(t, |x| x / (1.0 + x.abs()), |x| 1.0 / (1.0 + x.abs()).powi(2))
+//! }
+//! ```
 
+mod arith_broadcast;
 mod arith_scalar;
 mod impl_add;
 mod impl_backward;
@@ -139,6 +195,7 @@ mod impl_broadcast_reduce;
 mod impl_clamp;
 mod impl_div;
 mod impl_dropout;
+mod impl_hook_gradient;
 mod impl_mask;
 mod impl_max;
 mod impl_maximum;
@@ -153,11 +210,13 @@ mod impl_softmax;
 mod impl_stddev;
 mod impl_sub;
 mod impl_sum;
+mod jacobian;
 mod map;
 mod matmul;
 mod permute;
 mod select;
-pub(crate) mod utils;
+mod slice;
+pub mod utils;
 
 pub use arith_scalar::*;
 pub use impl_add::*;
@@ -166,6 +225,7 @@ pub use impl_broadcast_reduce::*;
 pub use impl_clamp::*;
 pub use impl_div::*;
 pub use impl_dropout::*;
+pub use impl_hook_gradient::*;
 pub use impl_mask::*;
 pub use impl_max::*;
 pub use impl_maximum::*;
@@ -180,21 +240,31 @@ pub use impl_softmax::*;
 pub use impl_stddev::*;
 pub use impl_sub::*;
 pub use impl_sum::*;
+pub use jacobian::*;
 pub use map::*;
 pub use matmul::*;
 pub use permute::*;
 pub use select::SelectTo;
+pub use slice::*;
 
 #[cfg(feature = "nightly")]
 mod impl_reshape;
 #[cfg(feature = "nightly")]
 pub use impl_reshape::*;
 
+#[cfg(feature = "nightly")]
+mod concat;
+#[cfg(feature = "nightly")]
+pub use concat::*;
+
 #[cfg(feature = "nightly")]
 mod conv;
 #[cfg(feature = "nightly")]
 pub use conv::*;
 
+#[cfg(feature = "nightly")]
+mod pixel_shuffle;
+
 #[cfg(feature = "nightly")]
 mod pool2d;
 #[cfg(feature = "nightly")]
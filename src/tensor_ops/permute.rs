@@ -22,9 +22,9 @@ pub trait PermuteTo<T, Axes> {
 macro_rules! axis { (0) => { M }; (1) => { N }; (2) => { O }; (3) => { P }; }
 
 /// Helper macro that creates a tensor based on axes passed in.
-/// E.g. `tensor!(2, 0, 1)` returns `Tensor3D<O, M, N, H>`
+/// E.g. `permuted!(2, 0, 1)` returns `Tensor3D<O, M, N, H>`
 #[rustfmt::skip]
-macro_rules! tensor {
+macro_rules! permuted {
     ($Ax0:tt) => { Tensor1D<axis!($Ax0), H> };
     ($Ax0:tt, $Ax1:tt) => { Tensor2D<axis!($Ax0), axis!($Ax1), H> };
     ($Ax0:tt, $Ax1:tt, $Ax2:tt) => { Tensor3D<axis!($Ax0), axis!($Ax1), axis!($Ax2), H> };
@@ -37,10 +37,10 @@ macro_rules! tensor {
 macro_rules! impl_permute {
     ($Ax0:tt, $Ax1:tt) => {
 impl<const M: usize, const N: usize, H: Tape>
-PermuteTo<tensor!($Ax0, $Ax1), Axes2<$Ax0, $Ax1>> for tensor!(0, 1)
+PermuteTo<permuted!($Ax0, $Ax1), Axes2<$Ax0, $Ax1>> for permuted!(0, 1)
 {
-    fn permute(self) -> tensor!($Ax0, $Ax1) {
-        let mut result: <tensor!($Ax0, $Ax1) as Tensor>::NoTape = TensorCreator::zeros();
+    fn permute(self) -> permuted!($Ax0, $Ax1) {
+        let mut result: <permuted!($Ax0, $Ax1) as Tensor>::NoTape = TensorCreator::zeros();
         <Cpu as DevicePermute<_, _, Axes2<$Ax0, $Ax1>>>::permute(self.data(), result.mut_data());
         move_tape_and_add_backward_op(self, result, move |mut t, result, grads| {
             let (t_grad, result_grad) = grads.mut_and_ref(&t, &result);
@@ -52,10 +52,10 @@ PermuteTo<tensor!($Ax0, $Ax1), Axes2<$Ax0, $Ax1>> for tensor!(0, 1)
     };
     ($Ax0:tt, $Ax1:tt, $Ax2:tt) => {
 impl<const M: usize, const N: usize, const O: usize, H: Tape>
-PermuteTo<tensor!($Ax0, $Ax1, $Ax2), Axes3<$Ax0, $Ax1, $Ax2>> for tensor!(0, 1, 2)
+PermuteTo<permuted!($Ax0, $Ax1, $Ax2), Axes3<$Ax0, $Ax1, $Ax2>> for permuted!(0, 1, 2)
 {
-    fn permute(self) -> tensor!($Ax0, $Ax1, $Ax2) {
-        let mut result: <tensor!($Ax0, $Ax1, $Ax2) as Tensor>::NoTape = TensorCreator::zeros();
+    fn permute(self) -> permuted!($Ax0, $Ax1, $Ax2) {
+        let mut result: <permuted!($Ax0, $Ax1, $Ax2) as Tensor>::NoTape = TensorCreator::zeros();
         <Cpu as DevicePermute<_, _, Axes3<$Ax0, $Ax1, $Ax2>>>::permute(self.data(), result.mut_data());
         move_tape_and_add_backward_op(self, result, move |mut t, result, grads| {
             let (t_grad, result_grad) = grads.mut_and_ref(&t, &result);
@@ -67,10 +67,10 @@ PermuteTo<tensor!($Ax0, $Ax1, $Ax2), Axes3<$Ax0, $Ax1, $Ax2>> for tensor!(0, 1,
     };
     ($Ax0:tt, $Ax1:tt, $Ax2:tt, $Ax3:tt) => {
 impl<const M: usize, const N: usize, const O: usize, const P: usize, H: Tape>
-PermuteTo<tensor!($Ax0, $Ax1, $Ax2, $Ax3), Axes4<$Ax0, $Ax1, $Ax2, $Ax3>> for tensor!(0, 1, 2, 3)
+PermuteTo<permuted!($Ax0, $Ax1, $Ax2, $Ax3), Axes4<$Ax0, $Ax1, $Ax2, $Ax3>> for permuted!(0, 1, 2, 3)
 {
-    fn permute(self) -> tensor!($Ax0, $Ax1, $Ax2, $Ax3) {
-        let mut result: <tensor!($Ax0, $Ax1, $Ax2, $Ax3) as Tensor>::NoTape = TensorCreator::zeros();
+    fn permute(self) -> permuted!($Ax0, $Ax1, $Ax2, $Ax3) {
+        let mut result: <permuted!($Ax0, $Ax1, $Ax2, $Ax3) as Tensor>::NoTape = TensorCreator::zeros();
         <Cpu as DevicePermute<_, _, Axes4<$Ax0, $Ax1, $Ax2, $Ax3>>>::permute(self.data(), result.mut_data());
         move_tape_and_add_backward_op(self, result, move |mut t, result, grads| {
             let (t_grad, result_grad) = grads.mut_and_ref(&t, &result);
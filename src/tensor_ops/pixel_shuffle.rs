@@ -0,0 +1,81 @@
+use super::utils::move_tape_and_add_backward_op;
+use crate::arrays::HasArrayData;
+use crate::devices::{Cpu, DevicePixelShuffle};
+use crate::gradients::Tape;
+use crate::tensor::*;
+use crate::unique_id::HasUniqueId;
+use crate::{Assert, ConstTrue};
+
+impl<const C: usize, const H: usize, const W: usize, T: Tape> Tensor3D<C, H, W, T> {
+    /// **Requires Nightly** Rearranges a single image of shape `(C * R * R, H, W)` into `(C, H * R, W * R)`,
+    /// as described in [Real-Time Single Image and Video Super-Resolution Using an Efficient
+    /// Sub-Pixel Convolutional Neural Network](https://arxiv.org/abs/1609.05158).
+    ///
+    /// `R` is the upscale factor. `C` must be divisible by `R * R`.
+    pub fn pixel_shuffle<const R: usize>(self) -> Tensor3D<{ C / (R * R) }, { H * R }, { W * R }, T>
+    where
+        Assert<{ C.is_multiple_of(R * R) }>: ConstTrue,
+    {
+        let mut result = Tensor3D::zeros();
+        <Cpu as DevicePixelShuffle<R>>::shuffle_forward(self.data(), result.mut_data());
+        move_tape_and_add_backward_op(self, result, move |x, r, grads| {
+            let (xg, rg) = grads.mut_and_ref(&x, &r);
+            <Cpu as DevicePixelShuffle<R>>::shuffle_backward(rg, xg);
+        })
+    }
+}
+
+impl<const B: usize, const C: usize, const H: usize, const W: usize, T: Tape>
+    Tensor4D<B, C, H, W, T>
+{
+    /// **Requires Nightly** Rearranges a batch of images of shape `(B, C * R * R, H, W)` into
+    /// `(B, C, H * R, W * R)`. See [Tensor3D::pixel_shuffle()].
+    pub fn pixel_shuffle<const R: usize>(
+        self,
+    ) -> Tensor4D<B, { C / (R * R) }, { H * R }, { W * R }, T>
+    where
+        Assert<{ C.is_multiple_of(R * R) }>: ConstTrue,
+    {
+        let mut result = Tensor4D::zeros();
+        for (x_i, r_i) in self.data().iter().zip(result.mut_data().iter_mut()) {
+            <Cpu as DevicePixelShuffle<R>>::shuffle_forward(x_i, r_i);
+        }
+        let (x, mut tape) = self.split_tape();
+        let r = result.clone();
+        tape.add_backward_op_with_ids(&[*x.id()], &[*r.id()], move |grads| {
+            let (xg, rg) = grads.mut_and_ref(&x, &r);
+            for (rg_i, xg_i) in rg.iter().zip(xg.iter_mut()) {
+                <Cpu as DevicePixelShuffle<R>>::shuffle_backward(rg_i, xg_i);
+            }
+        });
+        result.put_tape(tape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tensor_ops::backward;
+    use crate::tests::assert_close;
+
+    #[test]
+    fn test_3d_pixel_shuffle() {
+        let x = tensor([[[1.0, 2.0]], [[3.0, 4.0]], [[5.0, 6.0]], [[7.0, 8.0]]]);
+        let r: Tensor3D<1, 2, 4, _> = x.trace().pixel_shuffle::<2>();
+        assert_eq!(r.data(), &[[[1.0, 3.0, 2.0, 4.0], [5.0, 7.0, 6.0, 8.0]]]);
+        let g = backward(r.square().mean());
+        assert_close(
+            g.ref_gradient(&x),
+            &[[[0.25, 0.5]], [[0.75, 1.0]], [[1.25, 1.5]], [[1.75, 2.0]]],
+        );
+    }
+
+    #[test]
+    fn test_4d_pixel_shuffle() {
+        let x: Tensor4D<2, 8, 3, 3> = TensorCreator::ones();
+        let r: Tensor4D<2, 2, 6, 6, _> = x.trace().pixel_shuffle::<2>();
+        assert_eq!(r.data(), &[[[[1.0; 6]; 6]; 2]; 2]);
+        let g = backward(r.sum());
+        assert_eq!(g.ref_gradient(&x), &[[[[1.0; 3]; 3]; 8]; 2]);
+    }
+}
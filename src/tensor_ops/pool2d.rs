@@ -3,6 +3,7 @@ use crate::arrays::HasArrayData;
 use crate::devices::{Cpu, DevicePool2D, PoolAvg, PoolMax, PoolMin};
 use crate::gradients::Tape;
 use crate::tensor::*;
+use crate::unique_id::HasUniqueId;
 
 impl<const C: usize, const H: usize, const W: usize, T: Tape> Tensor3D<C, H, W, T> {
     /// Avg pool on a single image. `K` is kernel size, `S` is stride, `P` is padding.
@@ -77,7 +78,7 @@ impl<const B: usize, const C: usize, const H: usize, const W: usize, T: Tape>
         }
         let (x, mut tape) = self.split_tape();
         let r = result.clone();
-        tape.add_backward_op(move |grads| {
+        tape.add_backward_op_with_ids(&[*x.id()], &[*r.id()], move |grads| {
             let (xg, rg) = grads.mut_and_ref(&x, &r);
             for ((x_i, rg_i), xg_i) in x.data().iter().zip(rg.iter()).zip(xg.iter_mut()) {
                 Cpu::pool_backward(x_i, rg_i, xg_i);
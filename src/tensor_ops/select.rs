@@ -117,7 +117,7 @@ impl_select!(Axis<0>, BSelectAx1, Tensor3D<M, N, O, H>, [[usize; Z]; B], Tensor4
 pub(crate) fn select<T, I, R, Mode>(t: T, indices: &I) -> R
 where
     T: Tensor<Dtype = f32>,
-    I: 'static + Clone,
+    I: 'static + Send + Clone,
     R: Tensor<Dtype = f32, Tape = T::Tape>,
     <T as HasDevice>::Device: DeviceSelect<T::Array, I, Mode, Result = R::Array>,
 {
@@ -0,0 +1,167 @@
+use super::utils::move_tape_and_add_backward_op;
+use crate::arrays::{Axis, CountElements};
+use crate::devices::{Cpu, ForEachElement};
+use crate::gradients::Tape;
+use crate::prelude::*;
+use core::ops::Range;
+
+/// Slices `range` of `Axes`, which has to be as long as that axis of the result.
+/// All other axes are kept. In the backward pass the gradient of the result
+/// is added to the sliced part of the gradient of `t`.
+///
+/// Panics if `range` is not as long as the axis of the result, or ends after the axis of `t`.
+///
+/// **Pytorch equivalent**: `t[:, 1:3]` is `slice::<_, _, Axis<1>>(t, 1..3)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let t = tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+/// let r: Tensor2D<2, 2> = slice::<_, _, Axis<1>>(t.clone(), 1..3);
+/// assert_eq!(r.data(), &[[2.0, 3.0], [5.0, 6.0]]);
+/// let r: Tensor2D<1, 3> = slice::<_, _, Axis<0>>(t, 1..2);
+/// assert_eq!(r.data(), &[[4.0, 5.0, 6.0]]);
+/// ```
+///
+/// When there is only one axis, it can be inferred:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let r: Tensor1D<2> = tensor([1.0, 2.0, 3.0]).slice(0..2);
+/// assert_eq!(r.data(), &[1.0, 2.0]);
+/// ```
+pub fn slice<T: SliceTo<R, Axes>, R, Axes>(t: T, range: Range<usize>) -> R {
+    t.slice(range)
+}
+
+/// Slices `Self` along `Axes` resulting in `T`. See [slice()].
+pub trait SliceTo<T, Axes> {
+    fn slice(self, range: Range<usize>) -> T;
+}
+
+fn check_range(range: &Range<usize>, src: usize, dst: usize) {
+    assert!(
+        range.len() == dst && range.end <= src,
+        "Slicing {range:?} into an axis of size {dst}, from an axis of size {src}"
+    );
+}
+
+fn slice_fwd<E: Clone, const M: usize, const N: usize>(t: &[E; M], out: &mut [E; N], start: usize) {
+    out.clone_from_slice(&t[start..start + N]);
+}
+
+fn slice_bwd<E: CountElements<Dtype = f32>, const M: usize, const N: usize>(
+    t_grad: &mut [E; M],
+    out_grad: &[E; N],
+    start: usize,
+) where
+    Cpu: ForEachElement<E>,
+{
+    for (t, o) in t_grad[start..start + N].iter_mut().zip(out_grad.iter()) {
+        Cpu::foreach_mr(t, o, &mut |t, o| *t += o);
+    }
+}
+
+macro_rules! nest {
+    ([], $f:expr, $a:expr, $b:expr, $start:expr) => {
+        $f($a, $b, $start)
+    };
+    ([$head:ident $(, $tail:ident)*], $f:expr, $a:expr, $b:expr, $start:expr) => {
+        for (a, b) in $a.iter_mut().zip($b.iter()) {
+            nest!([$($tail),*], $f, a, b, $start);
+        }
+    };
+}
+
+macro_rules! slice_impl {
+    ($typename:ident, [$($Pre:ident),*], [$($Post:ident),*], $Ax:literal) => {
+impl<$(const $Pre: usize, )* const M: usize, const N: usize, $(const $Post: usize, )* H: Tape>
+    SliceTo<$typename<$($Pre, )* N, $($Post, )* H>, Axis<$Ax>> for $typename<$($Pre, )* M, $($Post, )* H>
+{
+    fn slice(self, range: Range<usize>) -> $typename<$($Pre, )* N, $($Post, )* H> {
+        check_range(&range, M, N);
+        let start = range.start;
+        let mut result: $typename<$($Pre, )* N, $($Post, )* NoneTape> = TensorCreator::zeros();
+        nest!([$($Pre),*], |o, t, s| slice_fwd::<_, M, N>(t, o, s), result.mut_data(), self.data(), start);
+        move_tape_and_add_backward_op::<_, $typename<$($Pre, )* N, $($Post, )* H>, _>(self, result, move |t, result, grads| {
+            let (t_grad, result_grad) = grads.mut_and_ref(&t, &result);
+            nest!([$($Pre),*], slice_bwd::<_, M, N>, t_grad, result_grad, start);
+        })
+    }
+}
+    };
+}
+
+slice_impl!(Tensor1D, [], [], 0);
+slice_impl!(Tensor2D, [], [B], 0);
+slice_impl!(Tensor2D, [A], [], 1);
+slice_impl!(Tensor3D, [], [B, C], 0);
+slice_impl!(Tensor3D, [A], [C], 1);
+slice_impl!(Tensor3D, [A, B], [], 2);
+slice_impl!(Tensor4D, [], [B, C, D], 0);
+slice_impl!(Tensor4D, [A], [C, D], 1);
+slice_impl!(Tensor4D, [A, B], [D], 2);
+slice_impl!(Tensor4D, [A, B, C], [], 3);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_close;
+
+    #[test]
+    fn test_slice_1d() {
+        let t = tensor([1.0, 2.0, 3.0, 4.0]);
+        let r: Tensor1D<2, _> = t.trace().slice(1..3);
+        assert_eq!(r.data(), &[2.0, 3.0]);
+        let g = backward(r.square().sum());
+        assert_eq!(g.ref_gradient(&t), &[0.0, 4.0, 6.0, 0.0]);
+    }
+
+    #[test]
+    fn test_slice_2d() {
+        let t = tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let r: Tensor2D<2, 2, _> = slice::<_, _, Axis<1>>(t.trace(), 0..2);
+        assert_eq!(r.data(), &[[1.0, 2.0], [4.0, 5.0]]);
+        let g = backward(r.exp().mean());
+        assert_close(
+            g.ref_gradient(&t),
+            &[[0.67957044, 1.847264, 0.0], [13.649538, 37.10329, 0.0]],
+        );
+
+        let r: Tensor2D<1, 3, _> = slice::<_, _, Axis<0>>(t.trace(), 1..2);
+        assert_eq!(r.data(), &[[4.0, 5.0, 6.0]]);
+        let g = backward(r.sum());
+        assert_eq!(g.ref_gradient(&t), &[[0.0; 3], [1.0; 3]]);
+    }
+
+    #[test]
+    fn test_slice_4d() {
+        let mut t: Tensor4D<2, 3, 4, 5> = TensorCreator::zeros();
+        for (i, x) in t
+            .mut_data()
+            .iter_mut()
+            .flatten()
+            .flatten()
+            .flatten()
+            .enumerate()
+        {
+            *x = i as f32;
+        }
+        let r: Tensor4D<2, 3, 2, 5, _> = slice::<_, _, Axis<2>>(t.trace(), 2..4);
+        assert_eq!(r.data()[1][2], [t.data()[1][2][2], t.data()[1][2][3]]);
+        let g = backward(r.sum());
+        let expected = [[[[0.0; 5], [0.0; 5], [1.0; 5], [1.0; 5]]; 3]; 2];
+        assert_eq!(g.ref_gradient(&t), &expected);
+
+        let r: Tensor4D<2, 3, 4, 1, _> = slice::<_, _, Axis<3>>(t.trace(), 4..5);
+        assert_eq!(r.data()[0][1][2], [t.data()[0][1][2][4]]);
+    }
+
+    #[test]
+    fn test_slice_bad_range() {
+        let t = tensor([1.0, 2.0, 3.0]);
+        let r = std::panic::catch_unwind(|| {
+            let _: Tensor1D<2> = t.clone().slice(2..4);
+        });
+        assert!(r.is_err());
+    }
+}
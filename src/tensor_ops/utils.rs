@@ -1,4 +1,7 @@
-//! Some utilities for moving tape between tensors & adding backward op during that movement.
+//! Utilities for implementing differentiable operations, which move the tape from the inputs to the output,
+//! and add a backward op to it during that movement. All the operations in dfdx are built with these,
+//! and they are the supported way to write custom operations outside of dfdx.
+//!
 //! These are provided because:
 //!
 //! 1. .split_tape() & put_tape() are very repetitive
@@ -6,22 +9,56 @@
 //! 3. Forces a more standard way in all the operations of doing the above.
 //! 4. You can't really separate these operations since they are very inter-dependent. So it makes
 //!    sense to have a single unit for doing it.
+//!
+//! From simplest to most flexible:
+//! 1. [map()] for element wise functions of one tensor, given the derivative.
+//! 2. [binary_map()] for element wise functions of two tensors, given the partial derivatives.
+//! 3. [move_tape_and_add_backward_op()] and [merge_tapes_and_add_backward_binop()] for anything else,
+//!    where you compute the output yourself, and write its backward op with [Gradients].
+//!
+//! A backward op can be run more than once (see [crate::tensor_ops::backward_heads()]), so it should
+//! not change anything it captured.
+//!
+//! See `examples/12-custom-op.rs` for a full example.
 
 use crate::devices::{Device, ForEachElement};
 use crate::gradients::{Gradients, Merge, Tape};
 use crate::prelude::*;
-use crate::unique_id::internal::ResetId;
+use crate::unique_id::{internal::ResetId, HasUniqueId};
 
 /// `f(t)`. Applies a function `f` to every element of the [Tensor]. The derivative
 /// `df` must also be provided.
 ///
 /// This is primarily used to implement standard functions such as [relu()], [exp()], etc.
 /// But users can also implement their own activations with this.
-pub(crate) fn map<T: Tensor<Dtype = f32>, F, Df>(t: T, f: F, mut df: Df) -> T
+///
+/// If `t` doesn't own a tape, `f` is applied in place, so no new data is allocated
+/// unless `t` shares its data with another tensor.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// use dfdx::tensor_ops::utils::map;
+/// fn softsign<T: Tensor<Dtype = f32>>(t: T) -> T {
+///     map(t, |x| x / (1.0 + x.abs()), |x| 1.0 / (1.0 + x.abs()).powi(2))
+/// }
+/// let t = tensor([-1.0, 0.0, 3.0]);
+/// let r = softsign(t.trace());
+/// assert_eq!(r.data(), &[-0.5, 0.0, 0.75]);
+/// let gradients = r.sum().backward();
+/// assert_eq!(gradients.ref_gradient(&t), &[0.25, 1.0, 0.0625]);
+/// ```
+#[track_caller]
+pub fn map<T: Tensor<Dtype = f32>, F, Df>(mut t: T, mut f: F, mut df: Df) -> T
 where
     F: 'static + FnMut(&f32) -> f32,
-    Df: 'static + FnMut(&f32) -> f32,
+    Df: 'static + Send + FnMut(&f32) -> f32,
 {
+    if !<T::Tape as Tape>::OWNS_TAPE {
+        T::Device::foreach_m(t.mut_data(), &mut |x| *x = f(x)); // clones if there is more than 1 reference to t
+        t.reset_id();
+        return t;
+    }
     let result = T::NoTape::new_boxed(T::Device::map(t.data(), f));
     move_tape_and_add_backward_op(t, result, move |t, result, grads| {
         let (t_grad, result_grad) = grads.mut_and_ref(&t, &result);
@@ -32,17 +69,18 @@ where
 }
 
 /// Same as [map()], but calls `df` with the result of `f(x)`. This can potentially remove an allocation.
-pub(crate) fn map_df_uses_fx<T: Tensor<Dtype = f32>, F, Df>(mut t: T, mut f: F, mut df: Df) -> T
+#[track_caller]
+pub fn map_df_uses_fx<T: Tensor<Dtype = f32>, F, Df>(mut t: T, mut f: F, mut df: Df) -> T
 where
     F: FnMut(&f32) -> f32,
-    Df: 'static + FnMut(&f32) -> f32,
+    Df: 'static + Send + FnMut(&f32) -> f32,
 {
     T::Device::foreach_m(t.mut_data(), &mut |x| *x = f(x)); // clones if there is more than 1 reference to t
     let (t, mut tape) = t.split_tape();
     let mut result = t.clone(); // inc t's reference count
     result.reset_id(); // ensure there are two differet nodes in the graph
     let phantom_result = result.clone();
-    tape.add_backward_op(move |grads| {
+    tape.add_backward_op_with_ids(&[*t.id()], &[*result.id()], move |grads| {
         let (t_grad, result_grad) = grads.mut_and_ref(&t, &phantom_result);
         T::Device::foreach_mrr(t_grad, t.data(), result_grad, &mut |g, fx, r| {
             *g += df(fx) * r;
@@ -55,7 +93,24 @@ where
 /// to a pair of [Tensor]s `lhs` and `rhs.
 ///
 /// This is primarily used to implement [add()], [sub()], [mul()], and [div()].
-pub(crate) fn binary_map<Lhs, Rhs, F, Dfdx, Dfdy>(
+///
+/// If neither `lhs` nor `rhs` own a tape, the result is written into `lhs`.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// use dfdx::tensor_ops::utils::binary_map;
+/// fn squared_difference<T: Tensor<Dtype = f32>>(a: T, b: T::NoTape) -> T {
+///     binary_map(a, b, |a, b| (a - b).powi(2), |a, b| 2.0 * (a - b), |a, b| 2.0 * (b - a))
+/// }
+/// let a = tensor([1.0, 2.0]);
+/// let r = squared_difference(a.trace(), tensor([0.0, 4.0]));
+/// assert_eq!(r.data(), &[1.0, 4.0]);
+/// let gradients = r.sum().backward();
+/// assert_eq!(gradients.ref_gradient(&a), &[2.0, -4.0]);
+/// ```
+#[track_caller]
+pub fn binary_map<Lhs, Rhs, F, Dfdx, Dfdy>(
     mut lhs: Lhs,
     mut rhs: Rhs,
     mut f: F,
@@ -70,16 +125,17 @@ where
     Dfdx: FnMut(&f32, &f32) -> f32,
     Dfdy: FnMut(&f32, &f32) -> f32,
 {
-    let mut result: Lhs::NoTape = TensorCreator::zeros();
-
     if !<Lhs::Tape as Tape>::OWNS_TAPE && !<Rhs::Tape as Tape>::OWNS_TAPE {
-        let (lhs, lhs_tape) = lhs.split_tape();
+        let (mut lhs, lhs_tape) = lhs.split_tape();
         let (rhs, rhs_tape) = rhs.split_tape();
-        Lhs::Device::foreach_mrr(result.mut_data(), lhs.data(), rhs.data(), &mut |o, l, r| {
-            *o = f(l, r);
+        Lhs::Device::foreach_mr(lhs.mut_data(), rhs.data(), &mut |l, r| {
+            *l = f(l, r);
         });
-        result.put_tape(lhs_tape.merge(rhs_tape))
+        lhs.reset_id();
+        lhs.put_tape(lhs_tape.merge(rhs_tape))
     } else {
+        let mut result: Lhs::NoTape = TensorCreator::zeros();
+
         // compute result & derivatives
         Lhs::Device::foreach_mmm(
             result.mut_data(),
@@ -103,25 +159,48 @@ where
     }
 }
 
-/// Moves tape from `inp` to `out`, and does `tape.add_backward_op()` with `f`
-pub(super) fn move_tape_and_add_backward_op<Inp, Out, F>(
-    inp: Inp,
-    out: Out::NoTape,
-    mut f: F,
-) -> Out
+/// Moves tape from `inp` to `out`, and does `tape.add_backward_op()` with `f`, which is
+/// called with `inp` and `out` without tapes. `f` should add the gradient of `inp` into `grads`,
+/// using the gradient of `out`.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// use dfdx::devices::{Device, ForEachElement};
+/// use dfdx::tensor_ops::utils::move_tape_and_add_backward_op;
+/// fn double<T: Tensor<Dtype = f32>>(t: T) -> T {
+///     let result = T::NoTape::new_boxed(T::Device::map(t.data(), |x| 2.0 * x));
+///     move_tape_and_add_backward_op(t, result, |t, result, grads| {
+///         let (t_grad, result_grad) = grads.mut_and_ref(&t, &result);
+///         T::Device::foreach_mr(t_grad, result_grad, &mut |g, r| *g += 2.0 * r);
+///     })
+/// }
+/// let t = tensor([1.0, 2.0]);
+/// let r = double(t.trace());
+/// assert_eq!(r.data(), &[2.0, 4.0]);
+/// let gradients = r.sum().backward();
+/// assert_eq!(gradients.ref_gradient(&t), &[2.0, 2.0]);
+/// ```
+#[track_caller]
+pub fn move_tape_and_add_backward_op<Inp, Out, F>(inp: Inp, out: Out::NoTape, mut f: F) -> Out
 where
     Inp: Tensor,
     Out: Tensor<Tape = Inp::Tape>,
-    F: 'static + FnMut(Inp::NoTape, Out::NoTape, &mut Gradients),
+    F: 'static + Send + FnMut(Inp::NoTape, Out::NoTape, &mut Gradients),
 {
     let phantom_out = out.clone();
     let (t, mut tape) = inp.split_tape();
-    tape.add_backward_op(move |grads| f(t, phantom_out, grads));
+    let ids = ([*t.id()], [*out.id()]);
+    tape.add_backward_op_with_ids(&ids.0, &ids.1, move |grads| {
+        f(t.clone(), phantom_out.clone(), grads)
+    });
     out.put_tape(tape)
 }
 
-/// Moves tape from `lhs` to `out`, and does `tape.add_backward_op()` with `f`
-pub(super) fn merge_tapes_and_add_backward_binop<Lhs, Rhs, Out, F>(
+/// Merges the tape of `rhs` into the tape of `lhs`, moves it to `out`, and does `tape.add_backward_op()`
+/// with `f`, which is called with `lhs`, `rhs` and `out` without tapes. See [move_tape_and_add_backward_op()].
+#[track_caller]
+pub fn merge_tapes_and_add_backward_binop<Lhs, Rhs, Out, F>(
     lhs: Lhs,
     rhs: Rhs,
     out: Out::NoTape,
@@ -132,12 +211,15 @@ where
     Rhs: Tensor,
     Out: Tensor<Tape = Lhs::Tape>,
     Lhs::Tape: Merge<Rhs::Tape>,
-    F: 'static + FnMut(Lhs::NoTape, Rhs::NoTape, Out::NoTape, &mut Gradients),
+    F: 'static + Send + FnMut(Lhs::NoTape, Rhs::NoTape, Out::NoTape, &mut Gradients),
 {
     let phantom_out = out.clone();
     let (lhs, lhs_tape) = lhs.split_tape();
     let (rhs, rhs_tape) = rhs.split_tape();
     let mut tape = lhs_tape.merge(rhs_tape);
-    tape.add_backward_op(move |grads| f(lhs, rhs, phantom_out, grads));
+    let ids = ([*lhs.id(), *rhs.id()], [*out.id()]);
+    tape.add_backward_op_with_ids(&ids.0, &ids.1, move |grads| {
+        f(lhs.clone(), rhs.clone(), phantom_out.clone(), grads)
+    });
     out.put_tape(tape)
 }
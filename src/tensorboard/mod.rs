@@ -0,0 +1,352 @@
+//! Writing TensorBoard event files, to monitor training with standard tooling.
+//!
+//! [SummaryWriter] writes scalars, histograms, and images to a log directory, which can be viewed
+//! with `tensorboard --logdir runs`:
+//!
+//! ```rust
+//! # use dfdx::prelude::*;
+//! # use dfdx::tensorboard::SummaryWriter;
+//! # let dir = tempfile::tempdir().unwrap();
+//! # let logdir = dir.path().join("runs/example");
+//! type Model = (Linear<5, 3>, ReLU, Linear<3, 2>);
+//! let mut model: Model = Default::default();
+//! let mut opt: Sgd<Model> = Default::default();
+//! let mut writer = SummaryWriter::new(logdir).unwrap();
+//! for step in 0..10 {
+//!     let y = model.forward(Tensor2D::<4, 5>::zeros().traced());
+//!     let loss = mse_loss(y, Tensor2D::ones());
+//!     writer.add_scalar("loss", *loss.data(), step).unwrap();
+//!     let gradients = backward(loss);
+//!     writer.add_gradient_histograms(&model, &gradients, step).unwrap();
+//!     opt.update(&mut model, gradients).expect("");
+//!     writer.add_param_histograms(&model, step).unwrap();
+//! }
+//! ```
+//!
+//! Requires the "tensorboard" feature.
+
+mod png;
+mod proto;
+
+use crate::arrays::CountElements;
+use crate::gradients::Gradients;
+use crate::nn::{ParamVisitor, VisitParams};
+use crate::prelude::*;
+use proto::{record, Message};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{format, string::String, vec::Vec};
+
+/// The number of equal width buckets in histograms.
+const NUM_BUCKETS: usize = 30;
+
+/// Writes events to a new event file in a log directory.
+///
+/// **Pytorch equivalent**: `torch.utils.tensorboard.SummaryWriter`
+#[derive(Debug)]
+pub struct SummaryWriter {
+    path: PathBuf,
+    file: BufWriter<File>,
+}
+
+impl SummaryWriter {
+    /// Creates `log_dir` if it doesn't exist, and a new event file in it.
+    pub fn new<P: AsRef<Path>>(log_dir: P) -> io::Result<Self> {
+        let log_dir = log_dir.as_ref();
+        std::fs::create_dir_all(log_dir)?;
+        let secs = wall_time() as u64;
+        let pid = std::process::id();
+        let path = log_dir.join(format!("events.out.tfevents.{secs}.dfdx.{pid}"));
+        let file = BufWriter::new(File::create(&path)?);
+        let mut writer = Self { path, file };
+        let mut event = Message::default();
+        event.double(1, wall_time()).bytes(3, b"brain.Event:2");
+        writer.write_event(&event)?;
+        writer.flush()?;
+        Ok(writer)
+    }
+
+    /// The path of the event file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Adds `value` to the plot named `tag`.
+    pub fn add_scalar(&mut self, tag: &str, value: f32, step: usize) -> io::Result<()> {
+        let mut value_msg = Message::default();
+        value_msg.bytes(1, tag.as_bytes()).float(2, value);
+        self.write_summary(&value_msg, step)
+    }
+
+    /// Adds a histogram of `values` to the plot named `tag`.
+    pub fn add_histogram(&mut self, tag: &str, values: &[f32], step: usize) -> io::Result<()> {
+        let mut value_msg = Message::default();
+        value_msg
+            .bytes(1, tag.as_bytes())
+            .message(5, &histogram(values));
+        self.write_summary(&value_msg, step)
+    }
+
+    /// Adds a histogram of each parameter of `model`, named by the names of the parameters
+    /// (e.g. `"0.weight"`).
+    pub fn add_param_histograms<M: VisitParams>(
+        &mut self,
+        model: &M,
+        step: usize,
+    ) -> io::Result<()> {
+        let mut visitor = Histograms {
+            writer: self,
+            gradients: None,
+            step,
+            result: Ok(()),
+        };
+        model.visit_params(&mut visitor);
+        visitor.result
+    }
+
+    /// Adds a histogram of the gradient of each parameter of `model` in `gradients`,
+    /// named `"grad/"` followed by the name of the parameter (e.g. `"grad/0.weight"`).
+    /// Parameters without a gradient are skipped.
+    pub fn add_gradient_histograms<M: VisitParams>(
+        &mut self,
+        model: &M,
+        gradients: &Gradients,
+        step: usize,
+    ) -> io::Result<()> {
+        let mut visitor = Histograms {
+            writer: self,
+            gradients: Some(gradients),
+            step,
+            result: Ok(()),
+        };
+        model.visit_params(&mut visitor);
+        visitor.result
+    }
+
+    /// Adds an image with `C` channels, `H` rows, and `W` columns, with values between `0.0` and `1.0`.
+    /// `C` must be 1 (grayscale), 3 (RGB), or 4 (RGBA).
+    pub fn add_image<const C: usize, const H: usize, const W: usize>(
+        &mut self,
+        tag: &str,
+        image: &Tensor3D<C, H, W>,
+        step: usize,
+    ) -> io::Result<()> {
+        let data = image.data();
+        let mut pixels = Vec::with_capacity(C * H * W);
+        for h in 0..H {
+            for w in 0..W {
+                for c in data.iter() {
+                    pixels.push((c[h][w].clamp(0.0, 1.0) * 255.0).round() as u8);
+                }
+            }
+        }
+        let mut image_msg = Message::default();
+        image_msg
+            .int(1, H as i64)
+            .int(2, W as i64)
+            .int(3, C as i64)
+            .bytes(4, &png::encode(W, H, C, &pixels));
+        let mut value_msg = Message::default();
+        value_msg.bytes(1, tag.as_bytes()).message(4, &image_msg);
+        self.write_summary(&value_msg, step)
+    }
+
+    /// Writes buffered events to the file.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+
+    fn write_summary(&mut self, value: &Message, step: usize) -> io::Result<()> {
+        let mut summary = Message::default();
+        summary.message(1, value);
+        let mut event = Message::default();
+        event
+            .double(1, wall_time())
+            .int(2, step as i64)
+            .message(5, &summary);
+        self.write_event(&event)
+    }
+
+    fn write_event(&mut self, event: &Message) -> io::Result<()> {
+        self.file.write_all(&record(&event.0))
+    }
+}
+
+fn wall_time() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |d| d.as_secs_f64())
+}
+
+/// Encodes a `HistogramProto` of `values` with [NUM_BUCKETS] equal width buckets.
+fn histogram(values: &[f32]) -> Message {
+    let min = values.iter().fold(f64::INFINITY, |m, v| m.min(*v as f64));
+    let max = values
+        .iter()
+        .fold(f64::NEG_INFINITY, |m, v| m.max(*v as f64));
+    let sum: f64 = values.iter().map(|v| *v as f64).sum();
+    let sum_squares: f64 = values.iter().map(|v| (*v as f64).powi(2)).sum();
+
+    let (limits, counts) = if values.is_empty() {
+        (Vec::new(), Vec::new())
+    } else if min == max {
+        (alloc::vec![max], alloc::vec![values.len() as f64])
+    } else {
+        let width = (max - min) / NUM_BUCKETS as f64;
+        let limits: Vec<f64> = (1..=NUM_BUCKETS).map(|i| min + width * i as f64).collect();
+        let mut counts = alloc::vec![0.0; NUM_BUCKETS];
+        for v in values {
+            let i = ((*v as f64 - min) / width) as usize;
+            counts[i.min(NUM_BUCKETS - 1)] += 1.0;
+        }
+        (limits, counts)
+    };
+
+    let mut m = Message::default();
+    m.double(1, if values.is_empty() { 0.0 } else { min })
+        .double(2, if values.is_empty() { 0.0 } else { max })
+        .double(3, values.len() as f64)
+        .double(4, sum)
+        .double(5, sum_squares)
+        .packed_doubles(6, &limits)
+        .packed_doubles(7, &counts);
+    m
+}
+
+/// Adds a histogram of every parameter, or of their gradients.
+struct Histograms<'a> {
+    writer: &'a mut SummaryWriter,
+    gradients: Option<&'a Gradients>,
+    step: usize,
+    result: io::Result<()>,
+}
+
+impl<'a> ParamVisitor for Histograms<'a> {
+    fn visit<T: Tensor<Dtype = f32>>(&mut self, name: &str, param: &T) {
+        if self.result.is_err() {
+            return;
+        }
+        let (tag, data) = match self.gradients {
+            None => (String::from(name), param.data()),
+            Some(gradients) => match gradients.try_ref_gradient(param) {
+                Some(g) => (format!("grad/{name}"), g),
+                None => return,
+            },
+        };
+        let values: &[f32] = match T::Array::NUM_ELEMENTS {
+            0 => &[],
+            // the elements of nested arrays are contiguous, so they can be viewed as a flat slice
+            n => unsafe { std::slice::from_raw_parts(data.ref_first_elem(), n) },
+        };
+        self.result = self.writer.add_histogram(&tag, values, self.step);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Splits an event file into its records, checking the crcs.
+    fn read_records(path: &Path) -> Vec<Vec<u8>> {
+        let bytes = std::fs::read(path).unwrap();
+        let mut records = Vec::new();
+        let mut rest = &bytes[..];
+        while !rest.is_empty() {
+            let len = u64::from_le_bytes(rest[..8].try_into().unwrap()) as usize;
+            let data = &rest[12..12 + len];
+            assert_eq!(record(data), &rest[..16 + len]);
+            records.push(data.to_vec());
+            rest = &rest[16 + len..];
+        }
+        records
+    }
+
+    #[test]
+    fn test_add_scalar() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = SummaryWriter::new(dir.path().join("run")).unwrap();
+        writer.add_scalar("loss", 0.5, 3).unwrap();
+        writer.flush().unwrap();
+        assert!(writer
+            .path()
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .starts_with("events.out.tfevents."));
+
+        let records = read_records(writer.path());
+        assert_eq!(records.len(), 2);
+        assert!(records[0].ends_with(b"\x1a\x0dbrain.Event:2"));
+
+        // wall_time, then step 3, then the summary with a single value
+        let event = &records[1];
+        assert_eq!(event[0], 0x09);
+        assert_eq!(&event[9..11], &[0x10, 3]);
+        let mut value = Message::default();
+        value.bytes(1, b"loss").float(2, 0.5);
+        let mut summary = Message::default();
+        summary.message(1, &value);
+        let mut expected = Message::default();
+        expected.message(5, &summary);
+        assert_eq!(&event[11..], &expected.0[..]);
+    }
+
+    #[test]
+    fn test_histogram() {
+        let h = histogram(&[0.0, 1.0, 3.0]);
+        let mut expected = Message::default();
+        let width = 3.0 / NUM_BUCKETS as f64;
+        let limits: Vec<f64> = (1..=NUM_BUCKETS).map(|i| width * i as f64).collect();
+        let mut counts = alloc::vec![0.0; NUM_BUCKETS];
+        counts[0] = 1.0;
+        counts[10] = 1.0;
+        counts[NUM_BUCKETS - 1] = 1.0;
+        expected
+            .double(1, 0.0)
+            .double(2, 3.0)
+            .double(3, 3.0)
+            .double(4, 4.0)
+            .double(5, 10.0)
+            .packed_doubles(6, &limits)
+            .packed_doubles(7, &counts);
+        assert_eq!(h.0, expected.0);
+    }
+
+    #[test]
+    fn test_param_and_gradient_histograms() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = SummaryWriter::new(dir.path()).unwrap();
+        let model: (Linear<2, 2>, ReLU, Linear<2, 1>) = Default::default();
+        writer.add_param_histograms(&model, 0).unwrap();
+        let y = model.0.forward(Tensor1D::<2>::ones().traced());
+        let gradients = backward(y.sum());
+        writer
+            .add_gradient_histograms(&model, &gradients, 0)
+            .unwrap();
+        writer.flush().unwrap();
+
+        let records = read_records(writer.path());
+        let contains = |r: &[u8], tag: &[u8]| r.windows(tag.len()).any(|w| w == tag);
+        assert_eq!(records.len(), 1 + 4 + 2);
+        assert!(contains(&records[1], b"0.weight"));
+        assert!(contains(&records[4], b"2.bias"));
+        assert!(contains(&records[5], b"grad/0.weight"));
+        assert!(contains(&records[6], b"grad/0.bias"));
+    }
+
+    #[test]
+    fn test_add_image() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = SummaryWriter::new(dir.path()).unwrap();
+        let image: Tensor3D<3, 1, 2> = tensor([[[1.0, 0.0]], [[0.0, 0.0]], [[0.0, 2.0]]]);
+        writer.add_image("image", &image, 1).unwrap();
+        writer.flush().unwrap();
+
+        let records = read_records(writer.path());
+        let png = png::encode(2, 1, 3, &[255, 0, 0, 0, 0, 255]);
+        assert!(records[1].windows(png.len()).any(|w| w == png));
+    }
+}
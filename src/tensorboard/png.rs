@@ -0,0 +1,111 @@
+//! An uncompressed png encoder for images logged to TensorBoard.
+
+use super::proto::crc32;
+use std::vec::Vec;
+
+/// Encodes 8 bit pixels as a png. `channels` is 1 for grayscale, 3 for RGB, or 4 for RGBA,
+/// and `pixels` is in row major order with the channels of each pixel next to each other.
+pub(super) fn encode(width: usize, height: usize, channels: usize, pixels: &[u8]) -> Vec<u8> {
+    assert_eq!(pixels.len(), width * height * channels);
+    let color_type = match channels {
+        1 => 0,
+        3 => 2,
+        4 => 6,
+        _ => panic!("png images must have 1, 3, or 4 channels, found {channels}"),
+    };
+
+    let mut png = alloc::vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+    let mut ihdr = Vec::new();
+    ihdr.extend_from_slice(&(width as u32).to_be_bytes());
+    ihdr.extend_from_slice(&(height as u32).to_be_bytes());
+    ihdr.extend_from_slice(&[8, color_type, 0, 0, 0]);
+    chunk(&mut png, b"IHDR", &ihdr);
+
+    // every row starts with filter type 0 (none)
+    let mut raw = Vec::with_capacity(height * (1 + width * channels));
+    for row in pixels.chunks(width * channels) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+    chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+    chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(0xEDB8_8320, &png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Wraps `data` in a zlib stream of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = alloc::vec![0x78, 0x01];
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        out.push(last as u8);
+        let len = block.len() as u16;
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for x in data {
+        a = (a + *x as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adler32() {
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+
+    #[test]
+    fn test_encode_png() {
+        let png = encode(2, 1, 3, &[255, 0, 0, 0, 0, 255]);
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], &[0, 0, 0, 2, 0, 0, 0, 1]);
+        assert_eq!(png[25], 2);
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+
+        // IDAT after the 25 bytes of IHDR
+        let idat_len = u32::from_be_bytes([png[33], png[34], png[35], png[36]]) as usize;
+        assert_eq!(&png[37..41], b"IDAT");
+        let zlib = &png[41..41 + idat_len];
+        assert_eq!(&zlib[..7], &[0x78, 0x01, 1, 7, 0, !7, 0xff]);
+        assert_eq!(&zlib[7..14], &[0, 255, 0, 0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_zlib_stored_blocks() {
+        let data = alloc::vec![7; 70000];
+        let zlib = zlib_stored(&data);
+        assert_eq!(zlib.len(), 2 + 2 * 5 + 70000 + 4);
+        assert_eq!(zlib[2], 0);
+        assert_eq!(zlib[2 + 5 + 65535], 1);
+        assert_eq!(
+            zlib_stored(&[]),
+            [0x78, 0x01, 1, 0, 0, 0xff, 0xff, 0, 0, 0, 1]
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Just enough protobuf & TFRecord encoding to write the `Event` messages of TensorBoard.
+
+use std::vec::Vec;
+
+/// An encoded protobuf message.
+#[derive(Debug, Default, Clone)]
+pub(super) struct Message(pub Vec<u8>);
+
+impl Message {
+    fn key(&mut self, field: u32, wire_type: u32) {
+        self.varint(((field << 3) | wire_type) as u64);
+    }
+
+    fn varint(&mut self, mut v: u64) {
+        while v >= 0x80 {
+            self.0.push((v as u8) | 0x80);
+            v >>= 7;
+        }
+        self.0.push(v as u8);
+    }
+
+    pub fn int(&mut self, field: u32, v: i64) -> &mut Self {
+        self.key(field, 0);
+        self.varint(v as u64);
+        self
+    }
+
+    pub fn double(&mut self, field: u32, v: f64) -> &mut Self {
+        self.key(field, 1);
+        self.0.extend_from_slice(&v.to_le_bytes());
+        self
+    }
+
+    pub fn float(&mut self, field: u32, v: f32) -> &mut Self {
+        self.key(field, 5);
+        self.0.extend_from_slice(&v.to_le_bytes());
+        self
+    }
+
+    pub fn bytes(&mut self, field: u32, v: &[u8]) -> &mut Self {
+        self.key(field, 2);
+        self.varint(v.len() as u64);
+        self.0.extend_from_slice(v);
+        self
+    }
+
+    pub fn message(&mut self, field: u32, m: &Message) -> &mut Self {
+        self.bytes(field, &m.0)
+    }
+
+    pub fn packed_doubles(&mut self, field: u32, v: &[f64]) -> &mut Self {
+        let bytes: Vec<u8> = v.iter().flat_map(|x| x.to_le_bytes()).collect();
+        self.bytes(field, &bytes)
+    }
+}
+
+/// Frames `data` as a TFRecord: its length, the crc of the length, the data, and the crc of the data.
+pub(super) fn record(data: &[u8]) -> Vec<u8> {
+    let len = (data.len() as u64).to_le_bytes();
+    let mut out = Vec::with_capacity(data.len() + 16);
+    out.extend_from_slice(&len);
+    out.extend_from_slice(&masked_crc32c(&len).to_le_bytes());
+    out.extend_from_slice(data);
+    out.extend_from_slice(&masked_crc32c(data).to_le_bytes());
+    out
+}
+
+fn masked_crc32c(data: &[u8]) -> u32 {
+    let crc = crc32(0x82F6_3B78, data);
+    crc.rotate_right(15).wrapping_add(0xa282_ead8)
+}
+
+/// The reflected crc32 of `data` with the polynomial `poly`. `0xEDB88320` is the crc used
+/// by png & zip, and `0x82F63B78` is crc32c.
+pub(super) fn crc32(poly: u32, data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for b in data {
+        crc ^= *b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ poly
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(0xEDB8_8320, b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(0x82F6_3B78, b"123456789"), 0xE306_9283);
+    }
+
+    #[test]
+    fn test_encode_message() {
+        let mut m = Message::default();
+        m.int(1, 150).bytes(2, b"testing").float(3, 1.0);
+        assert_eq!(
+            m.0,
+            [
+                0x08, 0x96, 0x01, 0x12, 0x07, b't', b'e', b's', b't', b'i', b'n', b'g', 0x1d, 0x00,
+                0x00, 0x80, 0x3f
+            ]
+        );
+    }
+
+    #[test]
+    fn test_record() {
+        let r = record(b"abc");
+        assert_eq!(r.len(), 8 + 4 + 3 + 4);
+        assert_eq!(&r[..8], &3u64.to_le_bytes());
+        assert_eq!(&r[12..15], b"abc");
+        assert_eq!(&r[15..], &masked_crc32c(b"abc").to_le_bytes());
+    }
+}
@@ -0,0 +1,467 @@
+//! Loading PyTorch checkpoints (`.pt`/`.pth` files saved with `torch.save()`) directly.
+//!
+//! [StateDict::load()] reads every tensor in a file, and [StateDict::load_params()] copies them
+//! into the parameters of a module:
+//!
+//! ```ignore
+//! # use dfdx::prelude::*;
+//! # use dfdx::torch::StateDict;
+//! // torch.save(torch.nn.Sequential(Linear(5, 3), ReLU(), Linear(3, 2)).state_dict(), "model.pt")
+//! let mut model: (Linear<5, 3>, ReLU, Linear<3, 2>) = Default::default();
+//! StateDict::load("model.pt")?.load_params(&mut model)?;
+//! ```
+//!
+//! Parameters are looked up by their names from [crate::nn::VisitParams], which are the same as the
+//! keys of `torch.nn.Sequential` state dicts for tuples of modules. When the names differ, use
+//! [StateDict::load_params_with()] to map them. Nested dictionaries (e.g. `{"model": state_dict}`)
+//! are flattened, so their tensors have keys like `"model.0.weight"`.
+//!
+//! Only the zip based format of `torch.save()` (the default since PyTorch 1.6) is supported. Tensors
+//! of type `float32`, `float64`, `float16`, and `bfloat16` are converted to `f32`, other types
+//! are skipped. Like [crate::nn::VisitParams], this does not load the running statistics of
+//! [crate::nn::BatchNorm2D]. Parameters inside of [crate::nn::Frozen] are loaded like any other.
+//!
+//! Requires the "torch" feature.
+
+mod pickle;
+
+use crate::arrays::HasShape;
+use crate::devices::FillElements;
+use crate::nn::{ParamVisitorMut, VisitParams};
+use crate::tensor::Tensor;
+use pickle::{TensorRef, Value};
+use std::collections::HashMap;
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+use std::{format, string::String, vec::Vec};
+use zip::{result::ZipError, ZipArchive};
+
+/// A tensor loaded from a PyTorch checkpoint, converted to `f32`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TorchTensor {
+    /// The size of each dimension, which is empty for scalars.
+    pub shape: Vec<usize>,
+
+    /// The elements in row major order.
+    pub data: Vec<f32>,
+}
+
+/// All the tensors in a PyTorch checkpoint, by their keys.
+#[derive(Debug, Clone, Default)]
+pub struct StateDict {
+    /// The tensors, by their keys in the checkpoint.
+    pub tensors: HashMap<String, TorchTensor>,
+}
+
+/// Error that can happen while loading a PyTorch checkpoint.
+#[derive(Debug)]
+pub enum TorchError {
+    /// Something went wrong with reading from the `.zip` archive.
+    Zip(ZipError),
+
+    /// Error from opening a file, reading values, etc.
+    IoError(std::io::Error),
+
+    /// The pickled data uses something that isn't supported.
+    Pickle(String),
+
+    /// A parameter has no tensor in the checkpoint.
+    MissingTensor(String),
+
+    /// A tensor in the checkpoint has a different shape than its parameter.
+    ShapeMismatch {
+        name: String,
+        expected: Vec<usize>,
+        found: Vec<usize>,
+    },
+}
+
+impl std::fmt::Display for TorchError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TorchError::Zip(err) => write!(fmt, "{}", err),
+            TorchError::IoError(err) => write!(fmt, "{}", err),
+            TorchError::Pickle(msg) => write!(fmt, "error while unpickling: {}", msg),
+            TorchError::MissingTensor(name) => write!(fmt, "no tensor named {}", name),
+            TorchError::ShapeMismatch {
+                name,
+                expected,
+                found,
+            } => write!(
+                fmt,
+                "expected shape {:?} for {}, found {:?}",
+                expected, name, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TorchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TorchError::Zip(err) => Some(err),
+            TorchError::IoError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ZipError> for TorchError {
+    fn from(e: ZipError) -> Self {
+        Self::Zip(e)
+    }
+}
+
+impl From<std::io::Error> for TorchError {
+    fn from(e: std::io::Error) -> Self {
+        Self::IoError(e)
+    }
+}
+
+impl StateDict {
+    /// Loads all the tensors of the checkpoint at `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TorchError> {
+        let f = std::fs::File::open(path)?;
+        Self::read(BufReader::new(f))
+    }
+
+    /// Reads all the tensors of a checkpoint from `r`.
+    pub fn read<R: Read + Seek>(r: R) -> Result<Self, TorchError> {
+        let mut zip = ZipArchive::new(r)?;
+        let pkl = zip
+            .file_names()
+            .find(|name| name.ends_with("data.pkl"))
+            .map(String::from)
+            .ok_or_else(|| TorchError::Pickle("missing data.pkl".into()))?;
+        let prefix = &pkl[..pkl.len() - "data.pkl".len()];
+        let big_endian = match zip.by_name(&format!("{prefix}byteorder")) {
+            Ok(mut f) => {
+                let mut order = String::new();
+                f.read_to_string(&mut order)?;
+                order == "big"
+            }
+            Err(_) => false,
+        };
+
+        let value = pickle::unpickle(&mut zip.by_name(&pkl)?)?;
+        let mut refs = Vec::new();
+        flatten("", value, &mut refs);
+
+        let mut storages: HashMap<String, Vec<f32>> = HashMap::new();
+        let mut tensors = HashMap::new();
+        for (name, t) in refs {
+            if !storages.contains_key(&t.key) {
+                let mut f = zip.by_name(&format!("{prefix}data/{}", t.key))?;
+                match read_storage(&mut f, &t.dtype, big_endian)? {
+                    Some(storage) => storages.insert(t.key.clone(), storage),
+                    None => continue,
+                };
+            }
+            let tensor = gather(&name, &storages[&t.key], &t)?;
+            tensors.insert(name, tensor);
+        }
+        Ok(Self { tensors })
+    }
+
+    /// Copies the tensor with the same name into each parameter of `model`.
+    pub fn load_params<M: VisitParams>(&self, model: &mut M) -> Result<(), TorchError> {
+        self.load_params_with(model, |name| name.into())
+    }
+
+    /// Copies the tensor named `name_map(name)` into each parameter `name` of `model`.
+    ///
+    /// Example:
+    /// ```ignore
+    /// # use dfdx::prelude::*;
+    /// # use dfdx::torch::StateDict;
+    /// let mut model: (Linear<5, 3>, ReLU, Linear<3, 2>) = Default::default();
+    /// // the checkpoint has keys like "encoder.fc.0.weight"
+    /// StateDict::load("model.pt")?.load_params_with(&mut model, |name| format!("encoder.fc.{name}"))?;
+    /// ```
+    pub fn load_params_with<M: VisitParams, F: FnMut(&str) -> String>(
+        &self,
+        model: &mut M,
+        name_map: F,
+    ) -> Result<(), TorchError> {
+        let mut visitor = LoadParams {
+            tensors: &self.tensors,
+            name_map,
+            result: Ok(()),
+        };
+        model.visit_params_mut(&mut visitor);
+        visitor.result
+    }
+}
+
+/// Collects every tensor in `value`, naming the tensors in dictionaries by their keys.
+fn flatten(prefix: &str, value: Value, out: &mut Vec<(String, TensorRef)>) {
+    match value {
+        Value::Tensor(t) => out.push((String::from(prefix), t)),
+        Value::Dict(items) => {
+            for (key, value) in items {
+                let key = match key {
+                    Value::String(key) => key,
+                    Value::Int(i) => format!("{i}"),
+                    _ => continue,
+                };
+                let name = if prefix.is_empty() {
+                    key
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(&name, value, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Reads a whole storage file as `f32`s. Returns `None` for unsupported dtypes.
+fn read_storage<R: Read>(
+    r: &mut R,
+    dtype: &str,
+    big_endian: bool,
+) -> Result<Option<Vec<f32>>, TorchError> {
+    let mut bytes = Vec::new();
+    r.read_to_end(&mut bytes)?;
+    let data = match dtype {
+        "FloatStorage" => bytes
+            .chunks_exact(4)
+            .map(|b| {
+                let b = [b[0], b[1], b[2], b[3]];
+                match big_endian {
+                    true => f32::from_be_bytes(b),
+                    false => f32::from_le_bytes(b),
+                }
+            })
+            .collect(),
+        "DoubleStorage" => bytes
+            .chunks_exact(8)
+            .map(|b| {
+                let b = [b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]];
+                match big_endian {
+                    true => f64::from_be_bytes(b) as f32,
+                    false => f64::from_le_bytes(b) as f32,
+                }
+            })
+            .collect(),
+        "HalfStorage" | "BFloat16Storage" => bytes
+            .chunks_exact(2)
+            .map(|b| {
+                let b = [b[0], b[1]];
+                let bits = match big_endian {
+                    true => u16::from_be_bytes(b),
+                    false => u16::from_le_bytes(b),
+                };
+                match dtype {
+                    "HalfStorage" => f16_to_f32(bits),
+                    _ => f32::from_bits((bits as u32) << 16),
+                }
+            })
+            .collect(),
+        _ => return Ok(None),
+    };
+    Ok(Some(data))
+}
+
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exp = ((bits >> 10) & 0x1f) as i32;
+    let frac = (bits & 0x3ff) as f32;
+    match exp {
+        0 => sign * frac * 2f32.powi(-24),
+        0x1f if frac == 0.0 => sign * f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => sign * (1.0 + frac / 1024.0) * 2f32.powi(exp - 15),
+    }
+}
+
+/// Copies the elements of `t` out of `storage` in row major order, following its strides.
+fn gather(name: &str, storage: &[f32], t: &TensorRef) -> Result<TorchTensor, TorchError> {
+    let out_of_bounds = || TorchError::Pickle(format!("{name} is out of bounds of its storage"));
+    let numel = t
+        .shape
+        .iter()
+        .try_fold(1usize, |n, &d| n.checked_mul(d))
+        .ok_or_else(out_of_bounds)?;
+    if numel > storage.len() {
+        return Err(out_of_bounds());
+    }
+    let mut data = Vec::with_capacity(numel);
+    let mut index: Vec<usize> = alloc::vec![0; t.shape.len()];
+    for _ in 0..numel {
+        let i = index
+            .iter()
+            .zip(t.strides.iter())
+            .try_fold(t.offset, |acc, (i, s)| acc.checked_add(i.checked_mul(*s)?))
+            .ok_or_else(out_of_bounds)?;
+        data.push(*storage.get(i).ok_or_else(out_of_bounds)?);
+        for (i, n) in index.iter_mut().zip(t.shape.iter()).rev() {
+            *i += 1;
+            if *i < *n {
+                break;
+            }
+            *i = 0;
+        }
+    }
+    Ok(TorchTensor {
+        shape: t.shape.clone(),
+        data,
+    })
+}
+
+/// Copies tensors into parameters, keeping the first error.
+struct LoadParams<'a, F> {
+    tensors: &'a HashMap<String, TorchTensor>,
+    name_map: F,
+    result: Result<(), TorchError>,
+}
+
+impl<'a, F: FnMut(&str) -> String> ParamVisitorMut for LoadParams<'a, F> {
+    fn visit_mut<T: Tensor<Dtype = f32>>(&mut self, name: &str, param: &mut T) {
+        if self.result.is_err() {
+            return;
+        }
+        let name = (self.name_map)(name);
+        let tensor = match self.tensors.get(&name) {
+            Some(tensor) => tensor,
+            None => {
+                self.result = Err(TorchError::MissingTensor(name));
+                return;
+            }
+        };
+        let expected = T::Array::shape();
+        if tensor.shape != expected {
+            self.result = Err(TorchError::ShapeMismatch {
+                name,
+                expected,
+                found: tensor.shape.clone(),
+            });
+            return;
+        }
+        let mut data = tensor.data.iter();
+        T::Device::fill(param.mut_data(), &mut |v| *v = *data.next().unwrap());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::io::{Cursor, Write};
+    use zip::ZipWriter;
+
+    /// `torch.save(state_dict, "archive.pt")` of a state dict with a `(2, 3)` tensor with strides
+    /// `(1, 2)` named `"0.weight"` in storage `"0"`, and a `(2,)` tensor named `"0.bias"`
+    /// in storage `"1"`.
+    const DATA_PKL: &[u8] = b"\x80\x02ccollections\x0aOrderedDict\x0aq\x00)Rq\x01(X\x08\x00\x00\x000.weightq\x02ctorch._utils\x0a_rebuild_tensor_v2\x0aq\x03((X\x07\x00\x00\x00storageq\x04ctorch\x0aFloatStorage\x0aq\x05X\x01\x00\x00\x000q\x06X\x03\x00\x00\x00cpuq\x07K\x06tq\x08QK\x00K\x02K\x03\x86q\x09K\x01K\x02\x86q\x0a\x89h\x00)Rq\x0btq\x0cRq\x0dX\x06\x00\x00\x000.biasq\x0eh\x03((h\x04h\x05X\x01\x00\x00\x001q\x0fh\x07K\x02tq\x10QK\x00K\x02\x85q\x11K\x01\x85q\x12\x89h\x00)Rq\x13tq\x14Rq\x15u}q\x16X\x09\x00\x00\x00_metadataq\x17h\x00)Rq\x18X\x00\x00\x00\x00q\x19}q\x1aX\x07\x00\x00\x00versionq\x1bK\x01sssb.";
+
+    fn write_checkpoint() -> Cursor<Vec<u8>> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let mut add = |name: &str, bytes: &[u8]| {
+            zip.start_file(name, Default::default()).unwrap();
+            zip.write_all(bytes).unwrap();
+        };
+        add("archive/data.pkl", DATA_PKL);
+        add("archive/byteorder", b"little");
+        let weight: Vec<u8> = [1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0]
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect();
+        add("archive/data/0", &weight);
+        let bias: Vec<u8> = [-1.0f32, 1.0]
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect();
+        add("archive/data/1", &bias);
+        add("archive/version", b"3\n");
+        let mut f = zip.finish().unwrap();
+        f.set_position(0);
+        f
+    }
+
+    #[test]
+    fn test_read_state_dict() {
+        let state_dict = StateDict::read(write_checkpoint()).unwrap();
+        assert_eq!(state_dict.tensors.len(), 2);
+        assert_eq!(
+            state_dict.tensors["0.weight"],
+            TorchTensor {
+                shape: alloc::vec![2, 3],
+                data: alloc::vec![1.0, 3.0, 5.0, 2.0, 4.0, 6.0],
+            }
+        );
+        assert_eq!(state_dict.tensors["0.bias"].data, [-1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_load_params() {
+        let state_dict = StateDict::read(write_checkpoint()).unwrap();
+
+        let mut model: (Linear<3, 2>, ReLU) = Default::default();
+        state_dict.load_params(&mut model).unwrap();
+        assert_eq!(model.0.weight.data(), &[[1.0, 3.0, 5.0], [2.0, 4.0, 6.0]]);
+        assert_eq!(model.0.bias.data(), &[-1.0, 1.0]);
+
+        let mut model: (ReLU, Linear<3, 2>) = Default::default();
+        assert!(matches!(
+            state_dict.load_params(&mut model),
+            Err(TorchError::MissingTensor(name)) if name == "1.weight"
+        ));
+        state_dict
+            .load_params_with(&mut model, |name| name.replacen('1', "0", 1))
+            .unwrap();
+        assert_eq!(model.1.bias.data(), &[-1.0, 1.0]);
+
+        let mut model: Linear<2, 3> = Default::default();
+        let result = state_dict.load_params_with(&mut model, |name| format!("0.{name}"));
+        assert!(matches!(
+            result,
+            Err(TorchError::ShapeMismatch { name, expected, found })
+                if name == "0.weight" && expected == [3, 2] && found == [2, 3]
+        ));
+    }
+
+    #[test]
+    fn test_gather_out_of_bounds() {
+        let storage = [1.0, 2.0, 3.0, 4.0];
+        let t = |shape: Vec<usize>, strides: Vec<usize>| TensorRef {
+            dtype: "FloatStorage".into(),
+            key: "0".into(),
+            offset: 0,
+            shape,
+            strides,
+        };
+
+        let tensor = gather("a", &storage, &t(alloc::vec![2, 2], alloc::vec![1, 2])).unwrap();
+        assert_eq!(tensor.data, [1.0, 3.0, 2.0, 4.0]);
+
+        let too_many = t(alloc::vec![2, 3], alloc::vec![3, 1]);
+        assert!(matches!(
+            gather("a", &storage, &too_many),
+            Err(TorchError::Pickle(_))
+        ));
+
+        let overflow = t(alloc::vec![usize::MAX, 2], alloc::vec![2, 1]);
+        assert!(matches!(
+            gather("a", &storage, &overflow),
+            Err(TorchError::Pickle(_))
+        ));
+
+        let bad_stride = t(alloc::vec![2], alloc::vec![usize::MAX]);
+        assert!(matches!(
+            gather("a", &storage, &bad_stride),
+            Err(TorchError::Pickle(_))
+        ));
+    }
+
+    #[test]
+    fn test_f16_to_f32() {
+        assert_eq!(f16_to_f32(0x3c00), 1.0);
+        assert_eq!(f16_to_f32(0xc000), -2.0);
+        assert_eq!(f16_to_f32(0x3555), 0.33325195);
+        assert_eq!(f16_to_f32(0x0001), 5.9604645e-8);
+        assert_eq!(f16_to_f32(0x7c00), f32::INFINITY);
+        assert!(f16_to_f32(0x7e00).is_nan());
+    }
+}
@@ -0,0 +1,490 @@
+//! A minimal unpickler that understands the subset of python's pickle format that `torch.save()`
+//! uses for state dicts.
+
+use super::TorchError;
+use std::collections::HashMap;
+use std::io::Read;
+use std::{boxed::Box, string::String, vec::Vec};
+
+/// A python object reconstructed from a pickle.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    None,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    Tuple(Vec<Value>),
+    List(Vec<Value>),
+    Dict(Vec<(Value, Value)>),
+
+    /// A reference to `module.name`, e.g. `torch.FloatStorage`.
+    Global(String, String),
+
+    /// A storage that is saved in a separate file of the archive.
+    Storage {
+        dtype: String,
+        key: String,
+    },
+
+    /// A view into a [Value::Storage].
+    Tensor(TensorRef),
+
+    /// Result of calling a [Value::Global] that isn't understood, with its arguments.
+    Object(Box<Value>, Box<Value>),
+}
+
+/// The arguments of `torch._utils._rebuild_tensor_v2()`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TensorRef {
+    pub dtype: String,
+    pub key: String,
+    pub offset: usize,
+    pub shape: Vec<usize>,
+    pub strides: Vec<usize>,
+}
+
+/// Items on the stack are either values or the position of a `MARK` opcode.
+enum Item {
+    Value(Value),
+    Mark,
+}
+
+struct Unpickler<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    stack: Vec<Item>,
+    memo: HashMap<u32, Value>,
+}
+
+/// Unpickles `r` until the `STOP` opcode.
+pub(crate) fn unpickle<R: Read>(r: &mut R) -> Result<Value, TorchError> {
+    let mut buf = Vec::new();
+    r.read_to_end(&mut buf)?;
+    Unpickler {
+        buf: &buf,
+        pos: 0,
+        stack: Vec::new(),
+        memo: HashMap::new(),
+    }
+    .run()
+}
+
+fn error<T>(msg: impl Into<String>) -> Result<T, TorchError> {
+    Err(TorchError::Pickle(msg.into()))
+}
+
+impl<'a> Unpickler<'a> {
+    fn run(&mut self) -> Result<Value, TorchError> {
+        loop {
+            let op = self.read_u8()?;
+            match op {
+                0x80 => {
+                    // PROTO
+                    self.read_u8()?;
+                }
+                0x95 => {
+                    // FRAME
+                    self.read_bytes(8)?;
+                }
+                b'.' => return self.pop(),
+                b'(' => self.stack.push(Item::Mark),
+                b'N' => self.push(Value::None),
+                0x88 => self.push(Value::Bool(true)),
+                0x89 => self.push(Value::Bool(false)),
+                b'K' => {
+                    let v = self.read_u8()?;
+                    self.push(Value::Int(v as i64));
+                }
+                b'M' => {
+                    let v = u16::from_le_bytes(self.read_array()?);
+                    self.push(Value::Int(v as i64));
+                }
+                b'J' => {
+                    let v = i32::from_le_bytes(self.read_array()?);
+                    self.push(Value::Int(v as i64));
+                }
+                0x8a => {
+                    // LONG1
+                    let n = self.read_u8()? as usize;
+                    let bytes = self.read_bytes(n)?;
+                    if n > 8 {
+                        return error("integer too large");
+                    }
+                    let fill = if matches!(bytes.last(), Some(b) if b & 0x80 != 0) {
+                        0xff
+                    } else {
+                        0
+                    };
+                    let mut le = [fill; 8];
+                    le[..n].copy_from_slice(bytes);
+                    self.push(Value::Int(i64::from_le_bytes(le)));
+                }
+                b'G' => {
+                    let v = f64::from_be_bytes(self.read_array()?);
+                    self.push(Value::Float(v));
+                }
+                b'X' => {
+                    let n = u32::from_le_bytes(self.read_array()?) as usize;
+                    let s = self.read_string(n)?;
+                    self.push(Value::String(s));
+                }
+                0x8c => {
+                    // SHORT_BINUNICODE
+                    let n = self.read_u8()? as usize;
+                    let s = self.read_string(n)?;
+                    self.push(Value::String(s));
+                }
+                b'U' => {
+                    // SHORT_BINSTRING
+                    let n = self.read_u8()? as usize;
+                    let s = self.read_string(n)?;
+                    self.push(Value::String(s));
+                }
+                b'T' => {
+                    // BINSTRING
+                    let n = u32::from_le_bytes(self.read_array()?) as usize;
+                    let s = self.read_string(n)?;
+                    self.push(Value::String(s));
+                }
+                b'C' => {
+                    // SHORT_BINBYTES
+                    let n = self.read_u8()? as usize;
+                    let b = self.read_bytes(n)?.to_vec();
+                    self.push(Value::Bytes(b));
+                }
+                b'B' => {
+                    // BINBYTES
+                    let n = u32::from_le_bytes(self.read_array()?) as usize;
+                    let b = self.read_bytes(n)?.to_vec();
+                    self.push(Value::Bytes(b));
+                }
+                b')' => self.push(Value::Tuple(Vec::new())),
+                b']' => self.push(Value::List(Vec::new())),
+                b'}' => self.push(Value::Dict(Vec::new())),
+                b't' => {
+                    let items = self.pop_mark()?;
+                    self.push(Value::Tuple(items));
+                }
+                0x85 => {
+                    let a = self.pop()?;
+                    self.push(Value::Tuple(alloc::vec![a]));
+                }
+                0x86 => {
+                    let b = self.pop()?;
+                    let a = self.pop()?;
+                    self.push(Value::Tuple(alloc::vec![a, b]));
+                }
+                0x87 => {
+                    let c = self.pop()?;
+                    let b = self.pop()?;
+                    let a = self.pop()?;
+                    self.push(Value::Tuple(alloc::vec![a, b, c]));
+                }
+                b'l' => {
+                    // LIST
+                    let items = self.pop_mark()?;
+                    self.push(Value::List(items));
+                }
+                b'a' => {
+                    let item = self.pop()?;
+                    match self.top()? {
+                        Value::List(list) => list.push(item),
+                        _ => return error("APPEND to a non list"),
+                    }
+                }
+                b'e' => {
+                    let items = self.pop_mark()?;
+                    match self.top()? {
+                        Value::List(list) => list.extend(items),
+                        _ => return error("APPENDS to a non list"),
+                    }
+                }
+                b's' => {
+                    let v = self.pop()?;
+                    let k = self.pop()?;
+                    self.set_items(alloc::vec![k, v])?;
+                }
+                b'u' => {
+                    let items = self.pop_mark()?;
+                    self.set_items(items)?;
+                }
+                b'c' => {
+                    let module = self.read_line()?;
+                    let name = self.read_line()?;
+                    self.push(Value::Global(module, name));
+                }
+                0x93 => {
+                    // STACK_GLOBAL
+                    let name = self.pop()?;
+                    let module = self.pop()?;
+                    match (module, name) {
+                        (Value::String(module), Value::String(name)) => {
+                            self.push(Value::Global(module, name))
+                        }
+                        _ => return error("STACK_GLOBAL of non strings"),
+                    }
+                }
+                b'q' => {
+                    let i = self.read_u8()? as u32;
+                    self.memoize(i)?;
+                }
+                b'r' => {
+                    let i = u32::from_le_bytes(self.read_array()?);
+                    self.memoize(i)?;
+                }
+                0x94 => {
+                    // MEMOIZE
+                    let i = self.memo.len() as u32;
+                    self.memoize(i)?;
+                }
+                b'h' => {
+                    let i = self.read_u8()? as u32;
+                    self.get(i)?;
+                }
+                b'j' => {
+                    let i = u32::from_le_bytes(self.read_array()?);
+                    self.get(i)?;
+                }
+                b'Q' => {
+                    // BINPERSID
+                    let pid = self.pop()?;
+                    let storage = persistent_load(pid)?;
+                    self.push(storage);
+                }
+                b'R' => {
+                    let args = self.pop()?;
+                    let func = self.pop()?;
+                    let value = reduce(func, args)?;
+                    self.push(value);
+                }
+                b'b' => {
+                    // BUILD, the state is only attributes like `_metadata`, which aren't needed.
+                    self.pop()?;
+                }
+                op => return error(std::format!("unsupported opcode 0x{op:02x}")),
+            }
+        }
+    }
+
+    fn read_u8(&mut self) -> Result<u8, TorchError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], TorchError> {
+        let mut out = [0; N];
+        out.copy_from_slice(self.read_bytes(N)?);
+        Ok(out)
+    }
+
+    fn read_bytes(&mut self, n: usize) -> Result<&'a [u8], TorchError> {
+        let buf: &'a [u8] = self.buf;
+        match buf.get(self.pos..self.pos + n) {
+            Some(bytes) => {
+                self.pos += n;
+                Ok(bytes)
+            }
+            None => error("unexpected end of pickle"),
+        }
+    }
+
+    fn read_string(&mut self, n: usize) -> Result<String, TorchError> {
+        let bytes = self.read_bytes(n)?;
+        String::from_utf8(bytes.to_vec()).or_else(|_| error("invalid utf8"))
+    }
+
+    fn read_line(&mut self) -> Result<String, TorchError> {
+        let rest = &self.buf[self.pos..];
+        match rest.iter().position(|&b| b == b'\n') {
+            Some(n) => {
+                let line = self.read_string(n)?;
+                self.pos += 1;
+                Ok(line)
+            }
+            None => error("unexpected end of pickle"),
+        }
+    }
+
+    fn push(&mut self, value: Value) {
+        self.stack.push(Item::Value(value));
+    }
+
+    fn pop(&mut self) -> Result<Value, TorchError> {
+        match self.stack.pop() {
+            Some(Item::Value(value)) => Ok(value),
+            _ => error("stack underflow"),
+        }
+    }
+
+    fn top(&mut self) -> Result<&mut Value, TorchError> {
+        match self.stack.last_mut() {
+            Some(Item::Value(value)) => Ok(value),
+            _ => error("stack underflow"),
+        }
+    }
+
+    /// Pops everything above the last `MARK`, and the mark itself.
+    fn pop_mark(&mut self) -> Result<Vec<Value>, TorchError> {
+        let mut items = Vec::new();
+        loop {
+            match self.stack.pop() {
+                Some(Item::Value(value)) => items.push(value),
+                Some(Item::Mark) => break,
+                None => return error("missing MARK"),
+            }
+        }
+        items.reverse();
+        Ok(items)
+    }
+
+    fn set_items(&mut self, items: Vec<Value>) -> Result<(), TorchError> {
+        let dict = match self.top()? {
+            Value::Dict(dict) => dict,
+            _ => return error("SETITEMS on a non dict"),
+        };
+        let mut items = items.into_iter();
+        while let Some(k) = items.next() {
+            match items.next() {
+                Some(v) => dict.push((k, v)),
+                None => return error("odd number of items for SETITEMS"),
+            }
+        }
+        Ok(())
+    }
+
+    fn memoize(&mut self, i: u32) -> Result<(), TorchError> {
+        let value = self.top()?.clone();
+        self.memo.insert(i, value);
+        Ok(())
+    }
+
+    fn get(&mut self, i: u32) -> Result<(), TorchError> {
+        match self.memo.get(&i) {
+            Some(value) => {
+                let value = value.clone();
+                self.push(value);
+                Ok(())
+            }
+            None => error(std::format!("missing memo entry {i}")),
+        }
+    }
+}
+
+/// Loads `('storage', storage_type, key, location, numel)`.
+fn persistent_load(pid: Value) -> Result<Value, TorchError> {
+    match pid {
+        Value::Tuple(items) => match items.as_slice() {
+            [Value::String(kind), Value::Global(_, dtype), Value::String(key), ..]
+                if kind == "storage" =>
+            {
+                Ok(Value::Storage {
+                    dtype: dtype.clone(),
+                    key: key.clone(),
+                })
+            }
+            _ => error("unsupported persistent id"),
+        },
+        _ => error("unsupported persistent id"),
+    }
+}
+
+fn reduce(func: Value, args: Value) -> Result<Value, TorchError> {
+    let (module, name) = match &func {
+        Value::Global(module, name) => (module.as_str(), name.as_str()),
+        _ => return Ok(Value::Object(Box::new(func), Box::new(args))),
+    };
+    let args = match args {
+        Value::Tuple(args) => args,
+        args => return Ok(Value::Object(Box::new(func), Box::new(args))),
+    };
+    match (module, name) {
+        ("collections", "OrderedDict") => Ok(Value::Dict(Vec::new())),
+        ("torch._utils", "_rebuild_tensor_v2") | ("torch._utils", "_rebuild_tensor") => {
+            match args.as_slice() {
+                [Value::Storage { dtype, key }, Value::Int(offset), Value::Tuple(shape), Value::Tuple(strides), ..] => {
+                    Ok(Value::Tensor(TensorRef {
+                        dtype: dtype.clone(),
+                        key: key.clone(),
+                        offset: *offset as usize,
+                        shape: to_usizes(shape)?,
+                        strides: to_usizes(strides)?,
+                    }))
+                }
+                _ => error("unexpected arguments to _rebuild_tensor_v2"),
+            }
+        }
+        ("torch._utils", "_rebuild_parameter") => match args.into_iter().next() {
+            Some(tensor @ Value::Tensor(_)) => Ok(tensor),
+            _ => error("unexpected arguments to _rebuild_parameter"),
+        },
+        _ => Ok(Value::Object(Box::new(func), Box::new(Value::Tuple(args)))),
+    }
+}
+
+fn to_usizes(values: &[Value]) -> Result<Vec<usize>, TorchError> {
+    values
+        .iter()
+        .map(|v| match v {
+            Value::Int(i) if *i >= 0 => Ok(*i as usize),
+            _ => error("expected a non negative integer"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unpickle_bytes(bytes: &[u8]) -> Value {
+        unpickle(&mut &bytes[..]).unwrap()
+    }
+
+    #[test]
+    fn test_unpickle_primitives() {
+        // pickle.dumps((1, -2, 300, 1.5, 'ab', None, True), protocol=2)
+        let value = unpickle_bytes(
+            b"\x80\x02(K\x01J\xfe\xff\xff\xffM,\x01G?\xf8\x00\x00\x00\x00\x00\x00X\x02\x00\x00\x00abq\x00N\x88tq\x01.",
+        );
+        assert_eq!(
+            value,
+            Value::Tuple(alloc::vec![
+                Value::Int(1),
+                Value::Int(-2),
+                Value::Int(300),
+                Value::Float(1.5),
+                Value::String("ab".into()),
+                Value::None,
+                Value::Bool(true),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_unpickle_containers() {
+        // pickle.dumps({'a': [1, 2], 'b': (-129,)}, protocol=2), with -129 as a LONG1
+        let value = unpickle_bytes(
+            b"\x80\x02}q\x00(X\x01\x00\x00\x00aq\x01]q\x02(K\x01K\x02eX\x01\x00\x00\x00bq\x03\x8a\x02\x7f\xff\x85q\x04u.",
+        );
+        assert_eq!(
+            value,
+            Value::Dict(alloc::vec![
+                (
+                    Value::String("a".into()),
+                    Value::List(alloc::vec![Value::Int(1), Value::Int(2)])
+                ),
+                (
+                    Value::String("b".into()),
+                    Value::Tuple(alloc::vec![Value::Int(-129)])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_unpickle_errors() {
+        assert!(unpickle(&mut &b"\x80\x02K"[..]).is_err());
+        assert!(unpickle(&mut &b"\x80\x02\xff."[..]).is_err());
+        assert!(unpickle(&mut &b"\x80\x02h\x00."[..]).is_err());
+    }
+}